@@ -0,0 +1,74 @@
+//! Timings for the hot paths of a long simulation: building a shoe, shuffling,
+//! dealing, poker evaluation and whole high card rounds.
+//!
+//! Run with `cargo bench`. Criterion warms each benchmark up, samples it until
+//! the timing settles and reports how it changed since the last run.
+
+use card_game::cards::{Card, DeckBuilder, Hand, Rank, Suit};
+use card_game::game::GameBuilder;
+use card_game::poker::evaluate_hand;
+use card_game::strategy::Random;
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::hint::black_box;
+
+fn shoe(c: &mut Criterion) {
+    c.bench_function("build 8 deck shoe", |b| {
+        b.iter(|| DeckBuilder::new().decks(8).build().unwrap())
+    });
+
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut shoe = DeckBuilder::new().decks(8).build().unwrap();
+    c.bench_function("shuffle 8 deck shoe", |b| {
+        b.iter(|| {
+            shoe.shuffle(&mut rng);
+            black_box(&shoe);
+        })
+    });
+
+    let mut hand = Hand::new();
+    c.bench_function("deal 8 deck shoe in fives", |b| {
+        b.iter(|| {
+            shoe.reset();
+            while shoe.remaining() >= 5 {
+                hand.drain();
+                shoe.draw_into(&mut hand, 5);
+                black_box(&hand);
+            }
+        })
+    });
+}
+
+fn poker(c: &mut Criterion) {
+    let seven = [
+        Card::new(Suit::Spades, Rank::Ace),
+        Card::new(Suit::Hearts, Rank::King),
+        Card::new(Suit::Spades, Rank::Queen),
+        Card::new(Suit::Clubs, Rank::Seven),
+        Card::new(Suit::Spades, Rank::Jack),
+        Card::new(Suit::Diamonds, Rank::Two),
+        Card::new(Suit::Spades, Rank::Ten),
+    ];
+    c.bench_function("evaluate 5 card hand", |b| {
+        b.iter(|| evaluate_hand(black_box(&seven[..5])))
+    });
+    c.bench_function("evaluate 7 card hand", |b| {
+        b.iter(|| evaluate_hand(black_box(&seven)))
+    });
+}
+
+fn simulation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simulation");
+    group.sample_size(10);
+    group.bench_function("simulate 10,000 rounds", |b| {
+        b.iter(|| {
+            let mut game = GameBuilder::new().with_seed(1).spawn().unwrap();
+            game.simulate(10_000, &mut Random::new(1))
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, shoe, poker, simulation);
+criterion_main!(benches);
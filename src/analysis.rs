@@ -0,0 +1,173 @@
+//! This module provides the odds of each card in a hand being the high card.
+//!
+//! Only what a player can see is used: the face up cards, and the cards not yet
+//! seen, which are the rest of the deck plus the face down cards in the hand.
+//! Each face down card is equally likely to be any of the unseen cards, so the
+//! chance of a face up card being high is the chance every face down card turns
+//! out lower. What's left over is shared equally between the face down cards.
+
+use crate::cards::set::CardSet;
+use crate::cards::{Card, Suit};
+use crate::scoring::ScoringStrategy;
+
+/// The chance, from 0 to 1, of each card in `hand` being the high card under
+/// `scoring` when the face down cards are drawn from `unseen`. As with
+/// `Hand::high_card_with`, the first of two equal scores is the high card.
+pub fn high_card_odds<S: ScoringStrategy + ?Sized>(
+    hand: &[Card],
+    unseen: &CardSet,
+    scoring: &S,
+) -> Vec<f64> {
+    let hidden: Vec<usize> = (0..hand.len())
+        .filter(|&idx| !hand[idx].state.is_face_up())
+        .collect();
+    let mut odds: Vec<f64> = hand
+        .iter()
+        .enumerate()
+        .map(|(idx, card)| match card.state.is_face_up() {
+            true => face_up_odds(hand, idx, &hidden, unseen, scoring),
+            false => 0.0,
+        })
+        .collect();
+    if !hidden.is_empty() {
+        let left = (1.0 - odds.iter().sum::<f64>()).max(0.0);
+        for &idx in &hidden {
+            odds[idx] = left / hidden.len() as f64;
+        }
+    }
+    odds
+}
+
+/// The chance the face up card at `idx` is high: it has to beat the face up
+/// cards already, and every face down card has to be drawn lower.
+fn face_up_odds<S: ScoringStrategy + ?Sized>(
+    hand: &[Card],
+    idx: usize,
+    hidden: &[usize],
+    unseen: &CardSet,
+    scoring: &S,
+) -> f64 {
+    let score = scoring.score(&hand[idx]);
+    // Cards before it have to score less, cards after it no more.
+    let beats = |other: usize, other_score: u32| {
+        if other < idx {
+            other_score < score
+        } else {
+            other_score <= score
+        }
+    };
+    let beats_visible = hand.iter().enumerate().all(|(other, card)| {
+        other == idx || !card.state.is_face_up() || beats(other, scoring.score(card))
+    });
+    if !beats_visible {
+        return 0.0;
+    }
+
+    let below = unseen
+        .iter()
+        .filter(|card| scoring.score(card) < score)
+        .count();
+    let at_most = unseen
+        .iter()
+        .filter(|card| scoring.score(card) <= score)
+        .count();
+    let before = hidden.iter().filter(|&&other| other < idx).count();
+    let after = hidden.len() - before;
+    // Draw the face down cards before it from the lower cards, then the ones
+    // after it from whatever's left that's no higher.
+    let mut chance = 1.0;
+    for drawn in 0..before {
+        chance *= below.saturating_sub(drawn) as f64 / (unseen.len() - drawn).max(1) as f64;
+    }
+    for drawn in 0..after {
+        let pool = at_most.saturating_sub(before + drawn);
+        chance *= pool as f64 / (unseen.len() - before - drawn).max(1) as f64;
+    }
+    chance
+}
+
+/// How many of `cards` there are of each suit.
+pub fn suit_counts(cards: &CardSet) -> Vec<(Suit, usize)> {
+    Suit::iter()
+        .map(|suit| (suit, cards.intersection(CardSet::of_suit(suit)).len()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{high_card_odds, suit_counts};
+    use crate::cards::set::CardSet;
+    use crate::cards::{Card, CardState, Rank, Suit};
+    use crate::rules::Ruleset;
+    use crate::scoring::HighCardScoring;
+
+    fn face_up(suit: Suit, rank: Rank) -> Card {
+        let mut card = Card::new(suit, rank);
+        card.state = CardState::Visible;
+        card
+    }
+
+    #[test]
+    /// Tests face down cards share the odds equally when nothing is showing.
+    fn test_all_face_down() {
+        let unseen = CardSet::standard();
+        let hand = vec![Card::new(Suit::Spades, Rank::Two); 4];
+        let odds = high_card_odds(&hand, &unseen, &Ruleset::high_card());
+        assert!(odds.iter().all(|odds| (odds - 0.25).abs() < 1e-9));
+    }
+
+    #[test]
+    /// Tests a face up card is high as often as the face down cards are lower.
+    fn test_face_up_odds() {
+        // Six unseen cards: three lower than the queen, one tying it and two above.
+        let unseen: CardSet = [
+            Card::new(Suit::Hearts, Rank::Two),
+            Card::new(Suit::Hearts, Rank::Five),
+            Card::new(Suit::Hearts, Rank::Nine),
+            Card::new(Suit::Hearts, Rank::Queen),
+            Card::new(Suit::Hearts, Rank::King),
+            Card::new(Suit::Hearts, Rank::Ace),
+        ]
+        .iter()
+        .collect();
+        let queen = face_up(Suit::Spades, Rank::Queen);
+        let hidden = Card::new(Suit::Clubs, Rank::Two);
+
+        // Drawn after the queen a tie still loses to it: 4 of 6 cards.
+        let odds = high_card_odds(&[queen, hidden], &unseen, &HighCardScoring);
+        assert!((odds[0] - 4.0 / 6.0).abs() < 1e-9);
+        assert!((odds[1] - 2.0 / 6.0).abs() < 1e-9);
+
+        // Before it only the three lower cards do, and two face down cards
+        // both have to be lower: 3/6 * 2/5.
+        let odds = high_card_odds(&[hidden, hidden, queen], &unseen, &HighCardScoring);
+        assert!((odds[2] - 0.2).abs() < 1e-9);
+        assert!((odds[0] - 0.4).abs() < 1e-9 && (odds[1] - 0.4).abs() < 1e-9);
+
+        // A face up card beaten by another face up card never wins.
+        let ace = face_up(Suit::Clubs, Rank::Ace);
+        let odds = high_card_odds(&[queen, ace], &unseen, &HighCardScoring);
+        assert_eq!(odds, vec![0.0, 1.0]);
+    }
+
+    #[test]
+    /// Tests cards are counted by suit.
+    fn test_suit_counts() {
+        let cards: CardSet = [
+            Card::new(Suit::Hearts, Rank::Two),
+            Card::new(Suit::Hearts, Rank::Five),
+            Card::new(Suit::Clubs, Rank::Nine),
+        ]
+        .iter()
+        .collect();
+        assert_eq!(
+            suit_counts(&cards),
+            vec![
+                (Suit::Spades, 0),
+                (Suit::Diamonds, 0),
+                (Suit::Hearts, 2),
+                (Suit::Clubs, 1)
+            ]
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! This module provides short sound effects for the high card game.
+//!
+//! Built with the `audio` feature. A game given `Sounds` plays an effect when a
+//! hand is dealt, when it's turned over and when the round is won or lost. Each
+//! effect is a few square wave tones played with `rodio` on the default output
+//! device, which is kept open on a thread of its own so the game never waits on
+//! it. Without an output device the game is silent.
+
+use crate::game::state::Event;
+use rodio::buffer::SamplesBuffer;
+use rodio::{OutputStream, Sink};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+/// Samples per second in the generated tones.
+const SAMPLE_RATE: u32 = 8_000;
+
+/// How loud the tones are, from 0 to 1.
+const VOLUME: f32 = 0.3;
+
+/// A sound the game can make.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Effect {
+    Deal,
+    Flip,
+    Win,
+    Lose,
+}
+
+impl Effect {
+    /// What `event` sounds like, in the order the effects are played.
+    pub fn from_event(event: &Event) -> Vec<Effect> {
+        match event {
+            Event::Dealt => vec![Effect::Deal],
+            Event::Revealed(result) if result.won => vec![Effect::Flip, Effect::Win],
+            Event::Revealed(_) => vec![Effect::Flip, Effect::Lose],
+            _ => Vec::new(),
+        }
+    }
+
+    /// The tones making up the effect, as a frequency in hertz and a length in
+    /// milliseconds. A frequency of zero is a rest.
+    pub fn tones(self) -> &'static [(u32, u32)] {
+        match self {
+            Effect::Deal => &[(1_200, 25), (0, 15), (1_200, 25)],
+            Effect::Flip => &[(900, 30)],
+            Effect::Win => &[(523, 90), (659, 90), (784, 160)],
+            Effect::Lose => &[(392, 140), (262, 220)],
+        }
+    }
+}
+
+/// The effect as mono samples at `SAMPLE_RATE`.
+pub fn samples(effect: Effect) -> Vec<f32> {
+    let mut samples = Vec::new();
+    for &(frequency, millis) in effect.tones() {
+        let count = SAMPLE_RATE * millis / 1000;
+        for idx in 0..count {
+            samples.push(match frequency {
+                0 => 0.0,
+                _ if (idx * frequency * 2 / SAMPLE_RATE).is_multiple_of(2) => VOLUME,
+                _ => -VOLUME,
+            });
+        }
+    }
+    samples
+}
+
+/// Plays effects on the default output device.
+#[derive(Debug, Clone)]
+pub struct Sounds {
+    effects: Sender<Vec<Effect>>,
+}
+
+impl Sounds {
+    /// Opens the default output device on a thread that plays whatever effects
+    /// it's sent, or returns None if there's no device to play them on.
+    pub fn detect() -> Option<Sounds> {
+        let (effects, queue) = mpsc::channel::<Vec<Effect>>();
+        let (opened, ready) = mpsc::sync_channel(1);
+        thread::spawn(move || {
+            // The stream stops playing when it's dropped, so it lives as long
+            // as the thread does.
+            let output = OutputStream::try_default()
+                .ok()
+                .and_then(|(stream, handle)| Some((stream, Sink::try_new(&handle).ok()?)));
+            let _ = opened.send(output.is_some());
+            if let Some((_stream, sink)) = output {
+                for effect in queue.into_iter().flatten() {
+                    sink.append(SamplesBuffer::new(1, SAMPLE_RATE, samples(effect)));
+                }
+                sink.sleep_until_end();
+            }
+        });
+        match ready.recv() {
+            Ok(true) => Some(Sounds { effects }),
+            _ => None,
+        }
+    }
+
+    /// Plays `effects` one after another in the background.
+    pub fn play(&self, effects: &[Effect]) {
+        if !effects.is_empty() {
+            // The thread only stops if the device went away, and then the game
+            // carries on in silence.
+            let _ = self.effects.send(effects.to_vec());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{samples, Effect, VOLUME};
+    use crate::game::state::Input;
+    use crate::game::GameBuilder;
+
+    #[test]
+    /// Tests deals, reveals and results map to their effects.
+    fn test_from_event() {
+        let mut game = GameBuilder::new().with_seed(3).spawn().unwrap();
+        assert_eq!(
+            Effect::from_event(&game.advance(Input::Deal)),
+            [Effect::Deal]
+        );
+        assert!(Effect::from_event(&game.advance(Input::Choose(0))).is_empty());
+        let effects = Effect::from_event(&game.advance(Input::Reveal));
+        assert_eq!(effects[0], Effect::Flip);
+        assert!(effects[1] == Effect::Win || effects[1] == Effect::Lose);
+    }
+
+    #[test]
+    /// Tests the generated tones are as long as the effect and only rest or
+    /// swing between the two levels of a square wave.
+    fn test_samples() {
+        let lose = samples(Effect::Lose);
+        assert_eq!(lose.len(), 8 * (140 + 220));
+        assert!(lose.iter().all(|&sample| sample.abs() == VOLUME));
+
+        let deal = samples(Effect::Deal);
+        assert_eq!(deal.len(), 8 * (25 + 15 + 25));
+        assert!(deal[8 * 25..8 * 40].iter().all(|&sample| sample == 0.0));
+    }
+}
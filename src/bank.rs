@@ -0,0 +1,152 @@
+//! This module provides a bankroll of chips for betting on rounds.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// Why a wager was refused.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BankError {
+    /// Wagers must be at least one chip.
+    ZeroWager,
+    /// The wager is more than the chips left.
+    InsufficientChips { wager: u64, chips: u64 },
+}
+
+impl Display for BankError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            BankError::ZeroWager => write!(formatter, "wager at least one chip"),
+            BankError::InsufficientChips { wager, chips } => {
+                write!(formatter, "can't wager {} with {} chips", wager, chips)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BankError {}
+
+/// The player's chips and the wager on the current round.
+///
+/// A winning wager pays `payout` chips for every chip staked, and a losing wager is
+/// lost. The default 2 to 1 is a fair price for picking one of three cards.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bank {
+    chips: u64,
+    payout: u64,
+    wager: u64,
+}
+
+impl Bank {
+    /// Construct a bank holding `chips` and paying 2 to 1.
+    pub fn new(chips: u64) -> Bank {
+        Bank {
+            chips,
+            payout: 2,
+            wager: 0,
+        }
+    }
+
+    /// Option to change the chips paid per chip staked on a win.
+    pub fn payout(mut self, payout: u64) -> Bank {
+        self.payout = payout;
+        self
+    }
+
+    /// Stakes `amount` chips on the current round, replacing any earlier wager.
+    pub fn wager(&mut self, amount: u64) -> Result<(), BankError> {
+        if amount == 0 {
+            return Err(BankError::ZeroWager);
+        }
+        if amount > self.chips {
+            return Err(BankError::InsufficientChips {
+                wager: amount,
+                chips: self.chips,
+            });
+        }
+        self.wager = amount;
+        Ok(())
+    }
+
+    /// Pays out or takes the current wager and returns the change in chips.
+    pub fn settle(&mut self, won: bool) -> i64 {
+        let wager = self.wager;
+        self.wager = 0;
+        if won {
+            let winnings = wager * self.payout;
+            self.chips += winnings;
+            winnings as i64
+        } else {
+            self.chips -= wager;
+            -(wager as i64)
+        }
+    }
+
+    /// Puts up to `amount` chips into a shared pot and returns how many went in.
+    /// A player short of chips puts in everything they have left.
+    pub fn pay_in(&mut self, amount: u64) -> u64 {
+        let paid = amount.min(self.chips);
+        self.chips -= paid;
+        paid
+    }
+
+    /// Adds chips won from a shared pot.
+    pub fn pay_out(&mut self, amount: u64) {
+        self.chips += amount;
+    }
+
+    pub fn chips(&self) -> u64 {
+        self.chips
+    }
+
+    pub fn current_wager(&self) -> u64 {
+        self.wager
+    }
+
+    pub fn payout_ratio(&self) -> u64 {
+        self.payout
+    }
+
+    /// Returns true once every chip is gone.
+    pub fn is_bankrupt(&self) -> bool {
+        self.chips == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bank, BankError};
+
+    #[test]
+    /// Tests wagers are paid out on a win and lost on a loss.
+    fn test_wager_and_settle() {
+        let mut bank = Bank::new(10).payout(3);
+        assert_eq!(bank.wager(0), Err(BankError::ZeroWager));
+        assert_eq!(
+            bank.wager(11),
+            Err(BankError::InsufficientChips {
+                wager: 11,
+                chips: 10
+            })
+        );
+
+        bank.wager(4).unwrap();
+        assert_eq!(bank.settle(true), 12);
+        assert_eq!(bank.chips(), 22);
+        assert_eq!(bank.current_wager(), 0);
+
+        bank.wager(22).unwrap();
+        assert_eq!(bank.settle(false), -22);
+        assert!(bank.is_bankrupt());
+    }
+
+    #[test]
+    /// Tests paying into a pot never takes more than the chips left.
+    fn test_pot() {
+        let mut bank = Bank::new(10);
+        assert_eq!(bank.pay_in(4), 4);
+        assert_eq!(bank.pay_in(20), 6);
+        assert!(bank.is_bankrupt());
+        bank.pay_out(15);
+        assert_eq!(bank.chips(), 15);
+    }
+}
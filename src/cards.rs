@@ -1,9 +1,26 @@
-use ansi_term::Colour;
+use crate::error::GameConfigError;
+use crate::scoring::{ScoringStrategy, SuitWeightedScoring};
+#[cfg(feature = "color")]
+use crate::style::Color;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "terminal")]
+pub mod card_printer;
+#[cfg(test)]
+mod properties;
+pub mod set;
+pub mod shoe;
+
 /// This module provides abstractions and methods for building and interacting with a Standard 52-card deck.
+use std::cmp::Ordering;
+use std::collections::VecDeque;
 use std::fmt::{self, Display, Formatter};
+use std::iter::FromIterator;
+use std::str::FromStr;
 
 /// Represent Card Suits
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Suit {
     Spades = 1,
     Diamonds = 2,
@@ -11,21 +28,85 @@ pub enum Suit {
     Clubs = 4,
 }
 
-/// Displays Card Suits with symbols.
+/// The colour a suit is printed in on a standard deck.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SuitColor {
+    Black,
+    Red,
+}
+
+impl Suit {
+    /// Every suit, lowest point number first.
+    pub const ALL: [Suit; 4] = [Suit::Spades, Suit::Diamonds, Suit::Hearts, Suit::Clubs];
+
+    /// Iterates over every suit, lowest point number first.
+    pub fn iter() -> impl Iterator<Item = Suit> {
+        Suit::ALL.iter().copied()
+    }
+
+    /// Whether the suit is black or red.
+    pub fn color(self) -> SuitColor {
+        match self {
+            Suit::Spades | Suit::Clubs => SuitColor::Black,
+            Suit::Diamonds | Suit::Hearts => SuitColor::Red,
+        }
+    }
+
+    /// The suit's name, e.g. `Spades`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Suit::Spades => "Spades",
+            Suit::Diamonds => "Diamonds",
+            Suit::Hearts => "Hearts",
+            Suit::Clubs => "Clubs",
+        }
+    }
+
+    /// The suit's symbol without any colour, e.g. `♠`.
+    pub fn symbol(self) -> char {
+        match self {
+            Suit::Spades => '♠',
+            Suit::Diamonds => '♦',
+            Suit::Hearts => '♥',
+            Suit::Clubs => '♣',
+        }
+    }
+
+    /// The first letter of the suit's name, e.g. `S`.
+    pub fn letter(self) -> char {
+        match self {
+            Suit::Spades => 'S',
+            Suit::Diamonds => 'D',
+            Suit::Hearts => 'H',
+            Suit::Clubs => 'C',
+        }
+    }
+}
+
+/// Displays Card Suits with symbols, coloured with the `color` feature: red
+/// suits in red and black suits in white, as `SuitColors::default()` paints them.
+/// A console that can't take escape codes gets the plain symbol.
 impl Display for Suit {
+    #[cfg(feature = "color")]
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
-        let s = match self {
-            Suit::Spades => Colour::White.paint("♠"),
-            Suit::Diamonds => Colour::Red.paint("♦"),
-            Suit::Hearts => Colour::Red.paint("♥"),
-            Suit::Clubs => Colour::White.paint("♣"),
+        if !crate::style::enable_ansi() {
+            return write!(formatter, "{}", self.symbol());
+        }
+        let colour = match self.color() {
+            SuitColor::Red => Color::Red,
+            SuitColor::Black => Color::White,
         };
-        write!(formatter, "{}", s)
+        write!(formatter, "{}", colour.paint(self.symbol()))
+    }
+
+    #[cfg(not(feature = "color"))]
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.symbol())
     }
 }
 
 /// Represent Card Ranks
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Rank {
     Two,
     Three,
@@ -40,11 +121,65 @@ pub enum Rank {
     Queen,
     King,
     Ace,
+    /// Jokers aren't part of a standard deck, see `DeckBuilder::with_jokers`.
+    /// They're worth nothing and rank above the Ace, and aren't wild.
+    Joker,
 }
 
-/// Convert Rank to integer values.
 impl Rank {
-    fn value(&self) -> u32 {
+    /// Every rank in a standard deck, Two first. Jokers aren't included.
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    /// Iterates over every rank in a standard deck, Two first.
+    pub fn iter() -> impl Iterator<Item = Rank> {
+        Rank::ALL.iter().copied()
+    }
+
+    /// The rank's name, e.g. `Queen`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Rank::Two => "Two",
+            Rank::Three => "Three",
+            Rank::Four => "Four",
+            Rank::Five => "Five",
+            Rank::Six => "Six",
+            Rank::Seven => "Seven",
+            Rank::Eight => "Eight",
+            Rank::Nine => "Nine",
+            Rank::Ten => "Ten",
+            Rank::Jack => "Jack",
+            Rank::Queen => "Queen",
+            Rank::King => "King",
+            Rank::Ace => "Ace",
+            Rank::Joker => "Joker",
+        }
+    }
+
+    /// Where the rank falls from lowest to highest: number cards at face value,
+    /// then 11 for a Jack up to 14 for an Ace, and 15 for a Joker. Use this to
+    /// compare ranks.
+    pub fn ordinal(&self) -> u32 {
+        *self as u32 + 2
+    }
+
+    /// What the rank is worth when cards are counted up, as in blackjack: number
+    /// cards at face value, face cards 10 and aces 11. Jacks, Queens and Kings
+    /// are all worth the same, so this can't tell them apart; see `ordinal`.
+    pub fn value(&self) -> u32 {
         match self {
             Rank::Two => 2,
             Rank::Three => 3,
@@ -59,6 +194,7 @@ impl Rank {
             Rank::Queen => 10,
             Rank::King => 10,
             Rank::Ace => 11,
+            Rank::Joker => 0,
         }
     }
 }
@@ -80,39 +216,32 @@ impl Display for Rank {
             Rank::Queen => "Q",
             Rank::King => "K",
             Rank::Ace => "A",
+            Rank::Joker => "JK",
         };
         write!(formatter, "{}", s)
     }
 }
 
-/// Static helper for easy and cheap iteration over suits.
-static SUITS: [Suit; 4] = [Suit::Spades, Suit::Diamonds, Suit::Hearts, Suit::Clubs];
-
-/// Static helper for easy and cheap iteration over ranks.
-static RANKS: [Rank; 13] = [
-    Rank::Two,
-    Rank::Three,
-    Rank::Four,
-    Rank::Five,
-    Rank::Six,
-    Rank::Seven,
-    Rank::Eight,
-    Rank::Nine,
-    Rank::Ten,
-    Rank::Jack,
-    Rank::Queen,
-    Rank::King,
-    Rank::Ace,
-];
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum CardState {
     Visible,
     Hidden,
+    /// Face up and picked by the player, so it's drawn highlighted.
+    Selected,
+}
+
+impl CardState {
+    /// Whether the card's face is showing.
+    pub fn is_face_up(self) -> bool {
+        self != CardState::Hidden
+    }
 }
 
 /// Represets a single card with a suit and rank.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+///
+/// Cards are ordered by suit, then rank, then state. To sort the way a player
+/// would, use `cmp_by_rank` or `cmp_by_value`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Card {
     pub suit: Suit,
     pub rank: Rank,
@@ -122,7 +251,7 @@ pub struct Card {
 impl Card {
     pub fn toggle(&mut self) {
         match self.state {
-            CardState::Visible => self.state = CardState::Hidden,
+            CardState::Visible | CardState::Selected => self.state = CardState::Hidden,
             CardState::Hidden => self.state = CardState::Visible,
         }
     }
@@ -145,7 +274,10 @@ impl Card {
     /// Ace of Spades
     ///
     pub fn nomenclature(&self) -> String {
-        format!("{:?}\tof {:?}\t", self.rank, self.suit)
+        match self.rank {
+            Rank::Joker => format!("{}\t\t", self.rank.name()),
+            _ => format!("{}\tof {}\t", self.rank.name(), self.suit.name()),
+        }
     }
 
     /// Value of card.
@@ -154,69 +286,1071 @@ impl Card {
     pub fn value(&self) -> u32 {
         (self.suit as u32) * self.rank.value()
     }
+
+    /// The card's number from 0 to 51, counting up by rank and then by suit in
+    /// the order `Deck::new` deals them: 0 is the Two of Spades, 1 the Two of
+    /// Diamonds and 51 the Ace of Clubs. Jokers are 52 for black and 53 for
+    /// red. Whether the card is face up isn't part of the number.
+    pub fn id(&self) -> u8 {
+        let suit = self.suit as u8 - 1;
+        match self.rank {
+            Rank::Joker if self.suit.color() == SuitColor::Black => 52,
+            Rank::Joker => 53,
+            rank => rank as u8 * 4 + suit,
+        }
+    }
+
+    /// The face down card numbered `id`, see `Card::id`, or None past 53.
+    pub fn from_id(id: u8) -> Option<Card> {
+        match id {
+            0..=51 => Some(Card::new(
+                Suit::ALL[usize::from(id % 4)],
+                Rank::ALL[usize::from(id / 4)],
+            )),
+            52 => Some(Card::new(Suit::Spades, Rank::Joker)),
+            53 => Some(Card::new(Suit::Hearts, Rank::Joker)),
+            _ => None,
+        }
+    }
+
+    /// The card's code in plain ASCII, e.g. `Td` for the Ten of Diamonds, as
+    /// poker software writes cards. The code parses back into the same card.
+    pub fn ascii(&self) -> String {
+        let rank = match self.rank {
+            Rank::Ten => "T".to_string(),
+            rank => rank.to_string(),
+        };
+        let suit = self.suit.letter().to_ascii_lowercase();
+        format!("{}{}", rank, suit)
+    }
+
+    /// Compares by rank, then by suit between cards of the same rank.
+    pub fn cmp_by_rank(&self, other: &Card) -> Ordering {
+        (self.rank, self.suit).cmp(&(other.rank, other.suit))
+    }
+
+    /// Compares by suit, then by rank within a suit. Unlike `cmp` this ignores
+    /// whether the cards are face up.
+    pub fn cmp_by_suit_then_rank(&self, other: &Card) -> Ordering {
+        (self.suit, self.rank).cmp(&(other.suit, other.rank))
+    }
+
+    /// Compares the cards' scores under `scoring`.
+    pub fn cmp_by_value<S: ScoringStrategy + ?Sized>(&self, other: &Card, scoring: &S) -> Ordering {
+        scoring.score(self).cmp(&scoring.score(other))
+    }
+}
+
+/// Displays the card's short code, e.g. `A♠` or `10♥`, without colour and
+/// whichever way up it is. The code parses back into the same card.
+impl Display for Card {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.pad(&format!("{}{}", self.rank, self.suit.symbol()))
+    }
+}
+
+/// Error returned when a suit, rank or card can't be parsed from a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCardError {
+    /// The suit part wasn't recognised.
+    Suit(String),
+    /// The rank part wasn't recognised.
+    Rank(String),
+    /// The string wasn't shaped like a card at all.
+    Card(String),
+}
+
+impl Display for ParseCardError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            ParseCardError::Suit(input) => write!(formatter, "unknown suit {:?}", input),
+            ParseCardError::Rank(input) => write!(formatter, "unknown rank {:?}", input),
+            ParseCardError::Card(input) => write!(formatter, "not a card: {:?}", input),
+        }
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+/// Error returned when a deck spec isn't one of each card in a standard deck.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeckSpecError {
+    /// The spec started like a JSON list but wasn't a list of strings.
+    Json(String),
+    /// One of the codes wasn't a card.
+    Card(ParseCardError),
+    /// The card was listed more than once.
+    Duplicate(Card),
+    /// The card isn't in a standard deck, as with jokers.
+    Extra(Card),
+    /// The cards the spec left out.
+    Missing(Vec<Card>),
+}
+
+impl Display for DeckSpecError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            DeckSpecError::Json(err) => write!(formatter, "not a list of card codes: {}", err),
+            DeckSpecError::Card(err) => write!(formatter, "{}", err),
+            DeckSpecError::Duplicate(card) => write!(formatter, "{} is listed twice", card),
+            DeckSpecError::Extra(card) => {
+                write!(formatter, "{} isn't in a standard deck", card)
+            }
+            DeckSpecError::Missing(cards) => {
+                let codes: Vec<String> = cards.iter().map(|card| card.to_string()).collect();
+                write!(
+                    formatter,
+                    "{} cards are missing: {}",
+                    cards.len(),
+                    codes.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DeckSpecError {}
+
+impl From<ParseCardError> for DeckSpecError {
+    fn from(err: ParseCardError) -> DeckSpecError {
+        DeckSpecError::Card(err)
+    }
+}
+
+/// Parses a suit from its letter, symbol or name, e.g. `S`, `♠` or `spades`.
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    fn from_str(input: &str) -> Result<Suit, ParseCardError> {
+        match input.trim().to_lowercase().as_str() {
+            "s" | "♠" | "♤" | "spade" | "spades" => Ok(Suit::Spades),
+            "d" | "♦" | "♢" | "diamond" | "diamonds" => Ok(Suit::Diamonds),
+            "h" | "♥" | "♡" | "heart" | "hearts" => Ok(Suit::Hearts),
+            "c" | "♣" | "♧" | "club" | "clubs" => Ok(Suit::Clubs),
+            _ => Err(ParseCardError::Suit(input.to_string())),
+        }
+    }
+}
+
+/// Parses a rank from its short code or name, e.g. `10`, `T`, `Q` or `queen`.
+impl FromStr for Rank {
+    type Err = ParseCardError;
+
+    fn from_str(input: &str) -> Result<Rank, ParseCardError> {
+        match input.trim().to_lowercase().as_str() {
+            "2" | "two" => Ok(Rank::Two),
+            "3" | "three" => Ok(Rank::Three),
+            "4" | "four" => Ok(Rank::Four),
+            "5" | "five" => Ok(Rank::Five),
+            "6" | "six" => Ok(Rank::Six),
+            "7" | "seven" => Ok(Rank::Seven),
+            "8" | "eight" => Ok(Rank::Eight),
+            "9" | "nine" => Ok(Rank::Nine),
+            "10" | "t" | "ten" => Ok(Rank::Ten),
+            "j" | "jack" => Ok(Rank::Jack),
+            "q" | "queen" => Ok(Rank::Queen),
+            "k" | "king" => Ok(Rank::King),
+            "a" | "ace" => Ok(Rank::Ace),
+            "jk" | "joker" => Ok(Rank::Joker),
+            _ => Err(ParseCardError::Rank(input.to_string())),
+        }
+    }
+}
+
+/// Parses a face down card from a short code such as `AS` or `10♥`, or a long
+/// name such as `Queen of Clubs`.
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(input: &str) -> Result<Card, ParseCardError> {
+        let trimmed = input.trim();
+        let lower = trimmed.to_lowercase();
+        let (rank, suit) = match lower.find(" of ") {
+            Some(idx) => (&trimmed[..idx], &trimmed[idx + 4..]),
+            None => match trimmed.char_indices().last() {
+                Some((idx, _)) if idx > 0 => trimmed.split_at(idx),
+                _ => return Err(ParseCardError::Card(input.to_string())),
+            },
+        };
+        Ok(Card::new(suit.parse()?, rank.parse()?))
+    }
+}
+
+/// Represents the cards held by a player.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hand {
+    cards: Vec<Card>,
+}
+
+impl Hand {
+    /// Construct a new empty hand.
+    pub fn new() -> Hand {
+        Hand { cards: Vec::new() }
+    }
+
+    /// Construct a hand holding the given cards.
+    pub fn from_cards(cards: Vec<Card>) -> Hand {
+        Hand { cards }
+    }
+
+    /// Adds a card to the hand.
+    pub fn push(&mut self, card: Card) {
+        self.cards.push(card);
+    }
+
+    /// Cards in the hand.
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Iterates over the cards in the hand.
+    pub fn iter(&self) -> std::slice::Iter<'_, Card> {
+        self.cards.iter()
+    }
+
+    /// Iterates mutably over the cards in the hand, e.g. to flip them.
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Card> {
+        self.cards.iter_mut()
+    }
+
+    /// Number of cards in the hand.
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Returns true if the hand holds no cards.
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Removes every card from the hand, e.g. to discard them.
+    pub fn drain(&mut self) -> std::vec::Drain<'_, Card> {
+        self.cards.drain(..)
+    }
+
+    /// Sum of the values of every card in the hand.
+    pub fn total_value(&self) -> u32 {
+        self.total_value_with(&SuitWeightedScoring)
+    }
+
+    /// Sum of the scores of every card in the hand under `scoring`.
+    pub fn total_value_with<S: ScoringStrategy + ?Sized>(&self, scoring: &S) -> u32 {
+        self.cards.iter().map(|card| scoring.score(card)).sum()
+    }
+
+    /// Index of the highest valued card. The first card wins ties.
+    pub fn high_card(&self) -> Option<usize> {
+        self.high_card_with(&SuitWeightedScoring)
+    }
+
+    /// Index of the highest scoring card under `scoring`. The first card wins ties.
+    pub fn high_card_with<S: ScoringStrategy + ?Sized>(&self, scoring: &S) -> Option<usize> {
+        let mut high: Option<(usize, u32)> = None;
+        for (idx, card) in self.cards.iter().enumerate() {
+            let score = scoring.score(card);
+            match high {
+                Some((_, value)) if score <= value => {}
+                _ => high = Some((idx, score)),
+            }
+        }
+        high.map(|(idx, _)| idx)
+    }
+
+    /// Flips the card at `idx` over.
+    pub fn toggle(&mut self, idx: usize) {
+        self.cards[idx].toggle();
+    }
+
+    /// Swaps the positions of two cards.
+    pub fn swap(&mut self, first: usize, second: usize) {
+        self.cards.swap(first, second);
+    }
+
+    /// Turns the card at `idx` face up, leaving it highlighted if it's selected.
+    pub fn reveal(&mut self, idx: usize) {
+        if self.cards[idx].state == CardState::Hidden {
+            self.cards[idx].state = CardState::Visible;
+        }
+    }
+
+    /// Turns the card at `idx` face down.
+    pub fn hide(&mut self, idx: usize) {
+        self.cards[idx].state = CardState::Hidden;
+    }
+
+    /// Turns the card at `idx` face up and highlights it as picked.
+    pub fn select(&mut self, idx: usize) {
+        self.cards[idx].state = CardState::Selected;
+    }
+
+    /// Turns every card face up, leaving a selected card highlighted.
+    pub fn reveal_all(&mut self) {
+        for card in self.cards.iter_mut() {
+            if card.state == CardState::Hidden {
+                card.state = CardState::Visible;
+            }
+        }
+    }
+
+    /// Turns every card face down.
+    pub fn hide_all(&mut self) {
+        for card in self.cards.iter_mut() {
+            card.state = CardState::Hidden;
+        }
+    }
+
+    /// Sorts the hand lowest first with `compare`.
+    pub fn sort_by<F: FnMut(&Card, &Card) -> Ordering>(&mut self, compare: F) {
+        self.cards.sort_by(compare);
+    }
+
+    /// Sorts the hand from the lowest rank to the highest, see `Card::cmp_by_rank`.
+    pub fn sort_by_rank(&mut self) {
+        self.sort_by(Card::cmp_by_rank);
+    }
+
+    /// Sorts the hand into suits, each from its lowest rank to the highest.
+    pub fn sort_by_suit(&mut self) {
+        self.sort_by(Card::cmp_by_suit_then_rank);
+    }
+
+    /// Sorts the hand from the lowest score under `scoring` to the highest. Cards
+    /// with the same score keep their order.
+    pub fn sort_by_value<S: ScoringStrategy + ?Sized>(&mut self, scoring: &S) {
+        self.sort_by(|a, b| a.cmp_by_value(b, scoring));
+    }
+}
+
+impl Hand {
+    /// Writes the hand as comma separated short codes with `##` for face down
+    /// cards, e.g. `A♠, ##, 10♥`.
+    fn write_codes(&self, formatter: &mut Formatter) -> fmt::Result {
+        for (idx, card) in self.cards.iter().enumerate() {
+            if idx > 0 {
+                write!(formatter, ", ")?;
+            }
+            if card.state.is_face_up() {
+                write!(formatter, "{}", card)?;
+            } else {
+                write!(formatter, "##")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Displays the hand as card boxes using `card_printer`. The alternate form
+/// `{:#}`, and every form without the terminal front end, is a line of short
+/// codes instead.
+impl Display for Hand {
+    #[cfg(feature = "terminal")]
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        if formatter.alternate() {
+            self.write_codes(formatter)
+        } else {
+            card_printer::write_hand(formatter, &self.cards, false)
+        }
+    }
+
+    #[cfg(not(feature = "terminal"))]
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.write_codes(formatter)
+    }
+}
+
+impl IntoIterator for Hand {
+    type Item = Card;
+    type IntoIter = std::vec::IntoIter<Card>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cards.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Hand {
+    type Item = &'a Card;
+    type IntoIter = std::slice::Iter<'a, Card>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cards.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Hand {
+    type Item = &'a mut Card;
+    type IntoIter = std::slice::IterMut<'a, Card>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cards.iter_mut()
+    }
+}
+
+impl FromIterator<Card> for Hand {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Hand {
+        Hand::from_cards(iter.into_iter().collect())
+    }
+}
+
+impl Extend<Card> for Hand {
+    fn extend<I: IntoIterator<Item = Card>>(&mut self, iter: I) {
+        self.cards.extend(iter);
+    }
 }
 
 /// DeckBuilder is used to create a new Deck of Cards.
 ///
-pub struct DeckBuilder;
+/// Example:
+/// ```
+/// use card_game::cards::DeckBuilder;
+///
+/// let shoe = DeckBuilder::new().decks(2).with_jokers().build().unwrap();
+/// assert_eq!(shoe.remaining(), 108);
+/// ```
+pub struct DeckBuilder {
+    decks: usize,
+    jokers: bool,
+    ranks: Vec<Rank>,
+    suits: Vec<Suit>,
+}
 
 impl DeckBuilder {
-    pub fn new() -> Vec<Card> {
-        let mut deck: Vec<Card> = vec![];
+    /// DeckBuilder Constructor. Builds a single 52-card deck by default.
+    pub fn new() -> DeckBuilder {
+        DeckBuilder {
+            decks: 1,
+            jokers: false,
+            ranks: Rank::ALL.to_vec(),
+            suits: Suit::ALL.to_vec(),
+        }
+    }
+
+    /// The 32-card piquet deck, Seven through Ace.
+    pub fn piquet() -> DeckBuilder {
+        DeckBuilder::new().without_ranks(&Rank::ALL[..5])
+    }
+
+    /// The 48-card pinochle deck, two of each card from Nine through Ace.
+    pub fn pinochle() -> DeckBuilder {
+        DeckBuilder::new().decks(2).without_ranks(&Rank::ALL[..7])
+    }
 
-        for rank in RANKS.iter() {
-            for suit in SUITS.iter() {
-                deck.push(Card::new(*suit, *rank))
+    /// Builds a standard deck in the exact order given by `spec`, top card first.
+    /// The spec is either a JSON list of card codes, as in `["AS", "KH"]`, or
+    /// codes separated by spaces, commas or new lines, with `#` starting a
+    /// comment. Every card has to be listed exactly once.
+    pub fn from_spec(spec: &str) -> Result<Deck, DeckSpecError> {
+        let codes: Vec<String> = if spec.trim_start().starts_with('[') {
+            serde_json::from_str(spec).map_err(|err| DeckSpecError::Json(err.to_string()))?
+        } else {
+            spec.lines()
+                .map(|line| line.split('#').next().unwrap_or(""))
+                .flat_map(|line| line.split(|c: char| c == ',' || c.is_whitespace()))
+                .filter(|code| !code.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+
+        let mut unlisted: Vec<Card> = Deck::new().into_iter().collect();
+        let mut cards = Vec::with_capacity(codes.len());
+        for code in &codes {
+            let card: Card = code.parse()?;
+            match unlisted.iter().position(|other| *other == card) {
+                Some(idx) => {
+                    unlisted.remove(idx);
+                }
+                None if cards.contains(&card) => return Err(DeckSpecError::Duplicate(card)),
+                None => return Err(DeckSpecError::Extra(card)),
             }
+            cards.push(card);
+        }
+        if !unlisted.is_empty() {
+            return Err(DeckSpecError::Missing(unlisted));
+        }
+        Ok(Deck::from_cards(cards))
+    }
+
+    /// Option to combine several standard decks into one shoe.
+    pub fn decks(mut self, decks: usize) -> DeckBuilder {
+        self.decks = decks;
+        self
+    }
+
+    /// Option to strip the given ranks from every deck.
+    pub fn without_ranks(mut self, ranks: &[Rank]) -> DeckBuilder {
+        self.ranks.retain(|rank| !ranks.contains(rank));
+        self
+    }
+
+    /// Option to strip the given suits from every deck.
+    pub fn without_suits(mut self, suits: &[Suit]) -> DeckBuilder {
+        self.suits.retain(|suit| !suits.contains(suit));
+        self
+    }
+
+    /// Option to add a black and a red joker to each deck.
+    pub fn with_jokers(mut self) -> DeckBuilder {
+        self.jokers = true;
+        self
+    }
+
+    /// Builds the deck, each standard deck in order followed by its jokers. Fails
+    /// if there are no decks or every rank or suit was stripped.
+    pub fn build(self) -> Result<Deck, GameConfigError> {
+        if self.decks == 0 {
+            return Err(GameConfigError::NoDecks);
+        }
+        if self.ranks.is_empty() || self.suits.is_empty() {
+            return Err(GameConfigError::EmptyDeck);
+        }
+
+        let per_deck = self.ranks.len() * self.suits.len() + if self.jokers { 2 } else { 0 };
+        let mut cards: Vec<Card> = Vec::with_capacity(per_deck * self.decks);
+
+        for _ in 0..self.decks {
+            for rank in self.ranks.iter() {
+                for suit in self.suits.iter() {
+                    cards.push(Card::new(*suit, *rank))
+                }
+            }
+            if self.jokers {
+                cards.push(Card::new(Suit::Spades, Rank::Joker));
+                cards.push(Card::new(Suit::Hearts, Rank::Joker));
+            }
+        }
+
+        Ok(Deck::from_cards(cards))
+    }
+}
+
+impl Default for DeckBuilder {
+    fn default() -> DeckBuilder {
+        DeckBuilder::new()
+    }
+}
+
+/// Represents a deck of cards.
+///
+/// The top of the deck is the front of the inner vector. Cards which have been played
+/// can be placed on the discard pile and are restored with `reset`, or shuffled
+/// back in with `reshuffle_discards`.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deck {
+    /// A ring buffer so drawing from the top and placing at the bottom are both
+    /// cheap, however large the shoe.
+    cards: VecDeque<Card>,
+    discarded: Vec<Card>,
+    initial: Vec<Card>,
+}
+
+impl Deck {
+    /// Construct a new standard 52-card deck.
+    pub fn new() -> Deck {
+        DeckBuilder::new()
+            .build()
+            .expect("a standard deck always has cards")
+    }
+
+    /// Construct a deck from a list of cards, top card first.
+    pub fn from_cards(cards: Vec<Card>) -> Deck {
+        Deck {
+            initial: cards.clone(),
+            cards: cards.into(),
+            discarded: Vec::new(),
+        }
+    }
+
+    /// Randomly shuffles the cards remaining in the deck.
+    pub fn shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.cards.make_contiguous().shuffle(rng);
+    }
+
+    /// Draws up to `count` cards from the top of the deck.
+    pub fn draw(&mut self, count: usize) -> Vec<Card> {
+        let count = count.min(self.cards.len());
+        self.cards.drain(..count).collect()
+    }
+
+    /// Draws up to `count` cards from the top of the deck, turned face up.
+    pub fn deal_face_up(&mut self, count: usize) -> Vec<Card> {
+        let mut cards = self.draw(count);
+        for card in &mut cards {
+            card.state = CardState::Visible;
         }
+        cards
+    }
+
+    /// Draws up to `count` cards from the top of the deck into `hand`, reusing the
+    /// room the hand already has.
+    pub fn draw_into(&mut self, hand: &mut Hand, count: usize) {
+        let count = count.min(self.cards.len());
+        hand.extend(self.cards.drain(..count));
+    }
+
+    /// Looks at the top card without drawing it.
+    pub fn peek(&self) -> Option<&Card> {
+        self.cards.front()
+    }
+
+    /// Places a card at the bottom of the deck.
+    pub fn place_bottom(&mut self, card: Card) {
+        self.cards.push_back(card);
+    }
+
+    /// Places a card on top of the deck, to be drawn next.
+    pub fn place_top(&mut self, card: Card) {
+        self.cards.push_front(card);
+    }
+
+    /// Takes the first card in the deck with the same suit and rank as `card`
+    /// out of it, wherever it is.
+    pub fn take(&mut self, card: &Card) -> Option<Card> {
+        let idx = self
+            .cards
+            .iter()
+            .position(|other| other.suit == card.suit && other.rank == card.rank)?;
+        self.cards.remove(idx)
+    }
+
+    /// Places a played card on the discard pile.
+    pub fn discard(&mut self, card: Card) {
+        self.discarded.push(card);
+    }
+
+    /// Cards on the discard pile, oldest first.
+    pub fn discarded(&self) -> &[Card] {
+        &self.discarded
+    }
+
+    /// Shuffles the discard pile and puts it under the cards left to draw, so
+    /// the cards already in the deck still come up first.
+    pub fn reshuffle_discards<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.discarded.shuffle(rng);
+        self.cards.extend(self.discarded.drain(..));
+    }
+
+    /// Number of cards left to draw.
+    pub fn remaining(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Returns true if there are no cards left to draw.
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Iterates over the cards left in the deck, top card first.
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<'_, Card> {
+        self.cards.iter()
+    }
+
+    /// Sorts the cards left to draw with `compare`, so the lowest is on top.
+    pub fn sort_by<F: FnMut(&Card, &Card) -> Ordering>(&mut self, compare: F) {
+        self.cards.make_contiguous().sort_by(compare);
+    }
+
+    /// Sorts the cards left to draw from the lowest rank to the highest.
+    pub fn sort_by_rank(&mut self) {
+        self.sort_by(Card::cmp_by_rank);
+    }
+
+    /// Sorts the cards left to draw into suits, each from its lowest rank to the
+    /// highest.
+    pub fn sort_by_suit(&mut self) {
+        self.sort_by(Card::cmp_by_suit_then_rank);
+    }
+
+    /// Sorts the cards left to draw from the lowest score under `scoring` to the
+    /// highest.
+    pub fn sort_by_value<S: ScoringStrategy + ?Sized>(&mut self, scoring: &S) {
+        self.sort_by(|a, b| a.cmp_by_value(b, scoring));
+    }
+
+    /// Restores the deck to its original order and empties the discard pile.
+    pub fn reset(&mut self) {
+        self.cards.clear();
+        self.cards.extend(self.initial.iter().copied());
+        self.discarded.clear();
+    }
+}
+
+impl Default for Deck {
+    fn default() -> Deck {
+        Deck::new()
+    }
+}
+
+/// Yields the cards left to draw, top card first.
+impl IntoIterator for Deck {
+    type Item = Card;
+    type IntoIter = std::collections::vec_deque::IntoIter<Card>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cards.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Deck {
+    type Item = &'a Card;
+    type IntoIter = std::collections::vec_deque::Iter<'a, Card>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cards.iter()
+    }
+}
+
+/// Collects cards into a deck, the first card on top.
+impl FromIterator<Card> for Deck {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> Deck {
+        Deck::from_cards(iter.into_iter().collect())
+    }
+}
 
-        deck
+/// Places the cards at the bottom of the deck in order.
+impl Extend<Card> for Deck {
+    fn extend<I: IntoIterator<Item = Card>>(&mut self, iter: I) {
+        self.cards.extend(iter);
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Card, DeckBuilder, Rank, Suit};
-    use insta;
+    use super::{
+        Card, CardState, Deck, DeckBuilder, DeckSpecError, Hand, ParseCardError, Rank, Suit,
+        SuitColor,
+    };
+    use crate::error::GameConfigError;
+    use crate::scoring::{HighCardScoring, SuitWeightedScoring};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::cmp::Ordering;
 
     #[test]
     /// Tests sorting.
     fn test_deck_sorts() {
         //(Spades, 2), (Diamonds, 5), (Spades, King), (Hearts, 3), (Clubs, Ace)
-        let mut unsorted_deck: Vec<Card> = Vec::new();
-        unsorted_deck.push(Card::new(Suit::Spades, Rank::Two));
-        unsorted_deck.push(Card::new(Suit::Diamonds, Rank::Five));
-        unsorted_deck.push(Card::new(Suit::Spades, Rank::King));
-        unsorted_deck.push(Card::new(Suit::Hearts, Rank::Three));
-        unsorted_deck.push(Card::new(Suit::Clubs, Rank::Ace));
+        let mut unsorted_deck: Vec<Card> = vec![
+            Card::new(Suit::Spades, Rank::Two),
+            Card::new(Suit::Diamonds, Rank::Five),
+            Card::new(Suit::Spades, Rank::King),
+            Card::new(Suit::Hearts, Rank::Three),
+            Card::new(Suit::Clubs, Rank::Ace),
+        ];
 
         unsorted_deck.sort();
 
         //(Spades, 2), (Spades, King), (Diamonds, 5), (Hearts, 3), (Clubs, Ace)
-        let mut sorted_deck: Vec<Card> = Vec::new();
-        sorted_deck.push(Card::new(Suit::Spades, Rank::Two));
-        sorted_deck.push(Card::new(Suit::Spades, Rank::King));
-        sorted_deck.push(Card::new(Suit::Diamonds, Rank::Five));
-        sorted_deck.push(Card::new(Suit::Hearts, Rank::Three));
-        sorted_deck.push(Card::new(Suit::Clubs, Rank::Ace));
+        let sorted_deck: Vec<Card> = vec![
+            Card::new(Suit::Spades, Rank::Two),
+            Card::new(Suit::Spades, Rank::King),
+            Card::new(Suit::Diamonds, Rank::Five),
+            Card::new(Suit::Hearts, Rank::Three),
+            Card::new(Suit::Clubs, Rank::Ace),
+        ];
 
         assert_eq!(unsorted_deck, sorted_deck);
     }
 
+    #[test]
+    /// Tests hands and decks sort by rank, by suit or by score.
+    fn test_sort_helpers() {
+        let cards = vec![
+            Card::new(Suit::Clubs, Rank::Three),
+            Card::new(Suit::Spades, Rank::King),
+            Card::new(Suit::Hearts, Rank::Three),
+            Card::new(Suit::Spades, Rank::Ace),
+        ];
+        let mut hand = Hand::from_cards(cards.clone());
+        let codes = |hand: &Hand| -> Vec<String> {
+            hand.iter()
+                .map(|card| format!("{}{:?}", card.rank, card.suit))
+                .collect()
+        };
+
+        hand.sort_by_rank();
+        assert_eq!(codes(&hand), ["3Hearts", "3Clubs", "KSpades", "ASpades"]);
+        hand.sort_by_suit();
+        assert_eq!(codes(&hand), ["KSpades", "ASpades", "3Hearts", "3Clubs"]);
+        hand.sort_by_value(&SuitWeightedScoring);
+        assert_eq!(codes(&hand), ["3Hearts", "KSpades", "ASpades", "3Clubs"]);
+
+        let mut deck = Deck::from_cards(cards);
+        deck.sort_by_rank();
+        assert_eq!(deck.peek(), Some(&Card::new(Suit::Hearts, Rank::Three)));
+        deck.sort_by_value(&HighCardScoring);
+        assert_eq!(
+            deck.iter().last(),
+            Some(&Card::new(Suit::Spades, Rank::Ace))
+        );
+
+        let (two, king) = (
+            Card::new(Suit::Clubs, Rank::Two),
+            Card::new(Suit::Spades, Rank::King),
+        );
+        assert_eq!(two.cmp(&king), Ordering::Greater);
+        assert_eq!(two.cmp_by_rank(&king), Ordering::Less);
+        assert_eq!(
+            two.cmp_by_value(&king, &SuitWeightedScoring),
+            Ordering::Less
+        );
+    }
+
     #[test]
     /// Tests 52 cards are created.
     fn test_deck_builder_length() {
-        let test_deck = DeckBuilder::new();
-        assert_eq!(test_deck.len(), 52);
+        let test_deck = DeckBuilder::new().build().unwrap();
+        assert_eq!(test_deck.remaining(), 52);
     }
 
     #[test]
-    /// Tests nomenclatures of each card
-    fn test_nomenclature() {
-        let nomenclatures: Vec<_> = DeckBuilder::new()
+    /// Tests building a shoe of several decks with jokers.
+    fn test_deck_builder_decks_and_jokers() {
+        let shoe = DeckBuilder::new().decks(2).with_jokers().build().unwrap();
+        assert_eq!(shoe.remaining(), 108);
+
+        let jokers: Vec<&Card> = shoe
             .iter()
-            .map(|card| card.nomenclature())
+            .filter(|card| card.rank == Rank::Joker)
             .collect();
+        assert_eq!(jokers.len(), 4);
+        assert!(jokers.iter().all(|joker| joker.value() == 0));
+        assert_eq!(shoe.iter().nth(52).unwrap().rank, Rank::Joker);
+        assert_eq!(jokers[0].nomenclature(), "Joker\t\t");
+    }
+
+    #[test]
+    /// Tests decks and hands work with standard iterator adapters.
+    fn test_iterators() {
+        let mut deck: Deck = Deck::new()
+            .into_iter()
+            .filter(|card| card.suit == Suit::Hearts)
+            .collect();
+        assert_eq!(deck.remaining(), 13);
+        assert_eq!((&deck).into_iter().count(), 13);
+
+        let mut hand: Hand = deck.draw(2).into_iter().collect();
+        hand.extend(deck.draw(1));
+        for card in &mut hand {
+            card.toggle();
+        }
+        assert!(hand.iter().all(|card| card.state == CardState::Visible));
+        assert_eq!(
+            hand.iter().map(|card| card.rank).collect::<Vec<_>>(),
+            [Rank::Two, Rank::Three, Rank::Four]
+        );
+
+        deck.extend(hand);
+        assert_eq!(deck.remaining(), 13);
+        assert_eq!(deck.iter().last().unwrap().rank, Rank::Four);
+    }
+
+    #[test]
+    /// Tests suits and ranks list themselves in order with their names and colours.
+    fn test_suit_and_rank_helpers() {
+        assert_eq!(Suit::iter().count(), 4);
+        assert!(Suit::ALL.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(Suit::Hearts.color(), SuitColor::Red);
+        assert_eq!(Suit::Clubs.color(), SuitColor::Black);
+        assert_eq!(Suit::Diamonds.name(), "Diamonds");
+
+        assert_eq!(Rank::iter().next(), Some(Rank::Two));
+        assert_eq!(Rank::ALL.last(), Some(&Rank::Ace));
+        assert!(!Rank::ALL.contains(&Rank::Joker));
+        assert_eq!(Rank::Queen.name(), "Queen");
+        for suit in Suit::iter() {
+            assert_eq!(suit.name().parse(), Ok(suit));
+        }
+        for rank in Rank::iter() {
+            assert_eq!(rank.name().parse(), Ok(rank));
+        }
+    }
+
+    #[test]
+    /// Tests card numbers follow the order of a new deck.
+    fn test_card_ids() {
+        let ids: Vec<u8> = Deck::new().iter().map(Card::id).collect();
+        assert_eq!(ids, (0..52).collect::<Vec<u8>>());
+        assert_eq!(Card::from_id(0), Some(Card::new(Suit::Spades, Rank::Two)));
+        assert_eq!(Card::from_id(51), Some(Card::new(Suit::Clubs, Rank::Ace)));
+        assert_eq!(Card::new(Suit::Clubs, Rank::Joker).id(), 52);
+        assert_eq!(
+            Card::from_id(53),
+            Some(Card::new(Suit::Hearts, Rank::Joker))
+        );
+        assert_eq!(Card::from_id(54), None);
+    }
+
+    #[test]
+    /// Tests parsing cards from short codes and long names.
+    fn test_parse_cards() {
+        assert_eq!("AS".parse(), Ok(Card::new(Suit::Spades, Rank::Ace)));
+        assert_eq!("10♥".parse(), Ok(Card::new(Suit::Hearts, Rank::Ten)));
+        assert_eq!("td".parse(), Ok(Card::new(Suit::Diamonds, Rank::Ten)));
+        let ten = Card::new(Suit::Diamonds, Rank::Ten);
+        assert_eq!(ten.ascii(), "Td");
+        assert_eq!(ten.ascii().parse(), Ok(ten));
+        assert_eq!(
+            "Queen of Clubs".parse(),
+            Ok(Card::new(Suit::Clubs, Rank::Queen))
+        );
+        assert_eq!(
+            " seven OF hearts ".parse(),
+            Ok(Card::new(Suit::Hearts, Rank::Seven))
+        );
+
+        assert_eq!(
+            "1S".parse::<Card>(),
+            Err(ParseCardError::Rank("1".to_string()))
+        );
+        assert_eq!(
+            "AX".parse::<Card>(),
+            Err(ParseCardError::Suit("X".to_string()))
+        );
+        assert_eq!(
+            "A".parse::<Card>(),
+            Err(ParseCardError::Card("A".to_string()))
+        );
+        assert_eq!(
+            "Knight".parse::<Rank>().unwrap_err().to_string(),
+            "unknown rank \"Knight\""
+        );
+    }
+
+    #[test]
+    /// Tests decks are built in the order a spec lists them, and specs that
+    /// aren't one of each card are refused.
+    fn test_deck_from_spec() {
+        let codes: Vec<String> = Deck::new()
+            .iter()
+            .rev()
+            .map(|card| card.to_string())
+            .collect();
+        let text = format!("# Reversed\n{}\n", codes.join(", "));
+        let deck = DeckBuilder::from_spec(&text).unwrap();
+        assert_eq!(deck.peek(), Some(&Card::new(Suit::Clubs, Rank::Ace)));
+        let json = serde_json::to_string(&codes).unwrap();
+        assert_eq!(DeckBuilder::from_spec(&json).unwrap(), deck);
+
+        let mut listed = codes.clone();
+        listed[51] = "KC".to_string();
+        assert_eq!(
+            DeckBuilder::from_spec(&listed.join(" ")),
+            Err(DeckSpecError::Duplicate(Card::new(Suit::Clubs, Rank::King)))
+        );
+        assert_eq!(
+            DeckBuilder::from_spec(&codes[1..].join(" ")),
+            Err(DeckSpecError::Missing(vec![Card::new(
+                Suit::Clubs,
+                Rank::Ace
+            )]))
+        );
+        assert!(matches!(
+            DeckBuilder::from_spec("AS XX"),
+            Err(DeckSpecError::Card(_))
+        ));
+        assert!(matches!(
+            DeckBuilder::from_spec("[1, 2]"),
+            Err(DeckSpecError::Json(_))
+        ));
+    }
+
+    #[test]
+    /// Tests stripping ranks and suits for piquet and pinochle decks.
+    fn test_deck_builder_composition() {
+        let piquet = DeckBuilder::piquet().build().unwrap();
+        assert_eq!(piquet.remaining(), 32);
+        assert!(piquet.iter().all(|card| card.rank >= Rank::Seven));
+
+        let pinochle = DeckBuilder::pinochle().build().unwrap();
+        assert_eq!(pinochle.remaining(), 48);
+        assert!(pinochle.iter().all(|card| card.rank >= Rank::Nine));
+
+        let red = DeckBuilder::new()
+            .without_suits(&[Suit::Spades, Suit::Clubs])
+            .without_ranks(&[Rank::Jack, Rank::Queen, Rank::King])
+            .build()
+            .unwrap();
+        assert_eq!(red.remaining(), 20);
+        assert!(red
+            .iter()
+            .all(|card| card.suit == Suit::Hearts || card.suit == Suit::Diamonds));
+
+        assert_eq!(
+            DeckBuilder::new().decks(0).build(),
+            Err(GameConfigError::NoDecks)
+        );
+        let no_suits = DeckBuilder::new().without_suits(&Suit::ALL);
+        assert_eq!(no_suits.build(), Err(GameConfigError::EmptyDeck));
+    }
+
+    #[test]
+    /// Tests drawing, discarding and resetting a deck.
+    fn test_deck_draw_discard_reset() {
+        let mut deck = Deck::new();
+        let top = *deck.peek().unwrap();
+
+        let drawn = deck.draw(3);
+        assert_eq!(drawn.len(), 3);
+        assert_eq!(drawn[0], top);
+        assert_eq!(deck.remaining(), 49);
+
+        for card in drawn {
+            deck.discard(card);
+        }
+        assert_eq!(deck.discarded().len(), 3);
+
+        assert_eq!(deck.draw(100).len(), 49);
+        assert!(deck.is_empty());
+        assert_eq!(deck.peek(), None);
+
+        deck.reset();
+        assert_eq!(deck, Deck::new());
+    }
+
+    #[test]
+    /// Tests discards are shuffled back in under the cards left.
+    fn test_reshuffle_discards() {
+        let mut deck = Deck::new();
+        let drawn = deck.draw(50);
+        let left: Vec<Card> = deck.iter().copied().collect();
+        for card in drawn {
+            deck.discard(card);
+        }
+        deck.reshuffle_discards(&mut StdRng::seed_from_u64(1));
+        assert_eq!(deck.remaining(), 52);
+        assert!(deck.discarded().is_empty());
+        assert_eq!(deck.draw(2), left);
+        assert_ne!(deck.draw(50), Deck::new().draw(50));
+    }
+
+    #[test]
+    /// Tests dealing face up and turning single cards over.
+    fn test_face_up_and_reveal() {
+        let mut deck = Deck::new();
+        let mut hand = Hand::from_cards(deck.deal_face_up(3));
+        assert!(hand.iter().all(|card| card.state == CardState::Visible));
+        assert_eq!(deck.deal_face_up(100).len(), 49);
+
+        hand.hide(1);
+        assert_eq!(hand.cards()[1].state, CardState::Hidden);
+        hand.hide_all();
+        hand.reveal(2);
+        hand.reveal(2);
+        assert_eq!(hand.cards()[2].state, CardState::Visible);
+        assert_eq!(hand.cards()[0].state, CardState::Hidden);
+
+        hand.select(0);
+        hand.reveal(0);
+        hand.reveal_all();
+        assert_eq!(hand.cards()[0].state, CardState::Selected);
+        assert!(hand.iter().all(|card| card.state.is_face_up()));
+    }
+
+    #[test]
+    /// Tests nomenclatures of each card
+    fn test_nomenclature() {
+        let nomenclatures: Vec<_> = Deck::new().iter().map(|card| card.nomenclature()).collect();
 
         insta::assert_debug_snapshot!(nomenclatures);
     }
@@ -224,139 +1358,83 @@ mod tests {
     #[test]
     /// Tests card values
     fn test_card_value() {
-        let values: Vec<_> = DeckBuilder::new().iter().map(|card| card.value()).collect();
+        let values: Vec<_> = Deck::new().iter().map(|card| card.value()).collect();
 
         insta::assert_debug_snapshot!(values);
     }
 
     #[test]
+    #[cfg(feature = "color")]
     /// Tests card displays
     fn test_rank_suit_display() {
-        let cards: Vec<_> = DeckBuilder::new()
+        let cards: Vec<_> = Deck::new()
             .iter()
             .map(|card| format!("{} {}", card.suit, card.rank))
             .collect();
         insta::assert_debug_snapshot!(cards);
     }
-}
-
-/// This module provides functions for creating displayable cards.
-///
-/// Example:
-/// ```text
-/// *---------* *---------* *---------*
-/// | K       | | 4       | | 8       |
-/// |         | |         | |         |
-/// |    ♠    | |    ♥    | |    ♣    |
-/// |         | |         | |         |
-/// |       K | |       4 | |       8 |
-/// *---------* *---------* *---------*
-/// ```
-///
-pub mod card_printer {
-    use super::{Card, CardState, Rank};
 
-    fn print_end(hand: &Vec<Card>) {
-        for _ in 0..hand.len() {
-            print!("*---------*");
-            print!(" ")
-        }
-        println!();
-    }
+    #[test]
+    /// Tests cards and decks round-trip through JSON.
+    fn test_serde_round_trip() {
+        let card = Card::new(Suit::Hearts, Rank::Queen);
+        let json = serde_json::to_string(&card).unwrap();
+        assert_eq!(json, r#"{"suit":"Hearts","rank":"Queen","state":"Hidden"}"#);
+        assert_eq!(serde_json::from_str::<Card>(&json).unwrap(), card);
 
-    fn print_empty_section(hand: &Vec<Card>) {
-        for card in hand {
-            match card.state {
-                CardState::Hidden => {
-                    print!("|#########|");
-                    print!(" ")
-                }
-                CardState::Visible => {
-                    print!("|         |");
-                    print!(" ")
-                }
-            }
-        }
-        println!();
+        let mut deck = Deck::new();
+        let drawn = deck.draw(5);
+        deck.discard(drawn[0]);
+        let json = serde_json::to_string(&deck).unwrap();
+        assert_eq!(serde_json::from_str::<Deck>(&json).unwrap(), deck);
     }
 
-    fn print_left_rank(hand: &Vec<Card>) {
-        for card in hand {
-            match card.state {
-                CardState::Hidden => {
-                    print!("|#########|");
-                    print!(" ")
-                }
+    #[test]
+    /// Tests hand scoring and the high card.
+    fn test_hand_value_and_high_card() {
+        let mut hand = Hand::new();
+        assert_eq!(hand.high_card(), None);
 
-                CardState::Visible => {
-                    if let Rank::Ten = card.rank {
-                        print!("| {}      |", card.rank);
-                    } else {
-                        print!("| {}       |", card.rank);
-                    }
-                    print!(" ")
-                }
-            }
-        }
-        println!();
-    }
+        hand.push(Card::new(Suit::Diamonds, Rank::Five));
+        hand.push(Card::new(Suit::Clubs, Rank::Three));
+        hand.push(Card::new(Suit::Spades, Rank::Ace));
 
-    fn print_right_rank(hand: &Vec<Card>) {
-        for card in hand {
-            match card.state {
-                CardState::Hidden => {
-                    print!("|#########|");
-                    print!(" ")
-                }
-                CardState::Visible => {
-                    if let Rank::Ten = card.rank {
-                        print!("|      {} |", card.rank);
-                    } else {
-                        print!("|       {} |", card.rank);
-                    }
-                    print!(" ")
-                }
-            }
-        }
-        println!();
+        assert_eq!(hand.total_value(), 10 + 12 + 11);
+        assert_eq!(hand.high_card(), Some(1));
     }
 
-    fn print_suit(hand: &Vec<Card>) {
-        for card in hand {
-            match card.state {
-                CardState::Hidden => {
-                    print!("|#########|");
-                    print!(" ")
-                }
+    #[test]
+    #[cfg(feature = "terminal")]
+    /// Tests hand displays
+    fn test_hand_display() {
+        let mut hand = Hand::from_cards(vec![
+            Card::new(Suit::Spades, Rank::Ten),
+            Card::new(Suit::Hearts, Rank::Queen),
+        ]);
+        let hidden = format!("{}", hand);
 
-                CardState::Visible => {
-                    print!("|    {}    |", card.suit);
-                    print!(" ")
-                }
-            }
-        }
-        println!();
-    }
+        hand.reveal_all();
+        hand.hide_all();
+        assert_eq!(format!("{}", hand), hidden);
 
-    fn print_index(hand: &Vec<Card>) {
-        for (idx, _) in hand.iter().enumerate() {
-            print!("    [{}]    ", idx);
-            print!(" ")
-        }
-        println!();
+        insta::assert_snapshot!(hidden);
     }
 
-    pub fn display_hand(hand: &Vec<Card>, show_index: bool) {
-        print_end(&hand);
-        print_left_rank(&hand);
-        print_empty_section(&hand);
-        print_suit(&hand);
-        print_empty_section(&hand);
-        print_right_rank(&hand);
-        print_end(&hand);
+    #[test]
+    /// Tests cards display as short codes and hands as a line of them.
+    fn test_card_and_hand_codes() {
+        let mut hand = Hand::from_cards(vec![
+            Card::new(Suit::Spades, Rank::Ace),
+            Card::new(Suit::Hearts, Rank::Ten),
+            Card::new(Suit::Clubs, Rank::Joker),
+        ]);
+        assert_eq!(hand.cards()[0].to_string(), "A♠");
+        assert_eq!(format!("{:#}", hand), "##, ##, ##");
 
-        if show_index {
-            print_index(&hand);
-        }
+        hand.reveal(0);
+        hand.select(1);
+        assert_eq!(format!("{:#}", hand), "A♠, 10♥, ##");
+        assert_eq!(Suit::Diamonds.symbol(), '♦');
+        assert_eq!(Suit::Diamonds.letter(), 'D');
     }
 }
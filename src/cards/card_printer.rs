@@ -0,0 +1,1358 @@
+//! This module provides functions for creating displayable cards.
+//!
+//! Example:
+//! ```text
+//! *---------* *---------* *---------*
+//! | K       | | 4       | | 8       |
+//! |         | |         | |         |
+//! |    ♠    | |    ♥    | |    ♣    |
+//! |         | |         | |         |
+//! |       K | |       4 | |       8 |
+//! *---------* *---------* *---------*
+//! ```
+//!
+//! Every line inside a box is measured with `width` and padded to fit, so ranks,
+//! theme patterns and borders of any display width keep the boxes aligned.
+//!
+//! `animation` deals and turns over hands frame by frame in a terminal.
+
+pub mod animation;
+pub mod width;
+
+use super::{Card, CardState, Rank, Suit, SuitColor};
+use crate::error::GameError;
+use crate::style::{self, Color, Style};
+use console::Term;
+use std::env;
+use std::fmt::{self, Write};
+use std::io::{self, Write as _};
+use width::{fill, pad, Align};
+
+/// Inner width of a card box.
+const CARD_WIDTH: usize = 9;
+
+/// Lines in a small card box, borders included.
+const CARD_HEIGHT: usize = 7;
+
+/// Lines of a small card left showing when another card is stacked on it.
+const STACKED_HEIGHT: usize = 2;
+
+/// Inner width of a large card box.
+const LARGE_CARD_WIDTH: usize = 11;
+
+/// Rows of pips between the ranks on a large card.
+const PIP_ROWS: usize = 7;
+
+/// Controls how cards are drawn: the pattern on card backs, the border
+/// characters, the suit colors, the colour a selected card is outlined in and
+/// the space between cards.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardTheme {
+    pub back: String,
+    pub top_left: char,
+    pub top_right: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+    pub horizontal: char,
+    pub vertical: char,
+    pub suit_colors: SuitColors,
+    pub highlight: Color,
+    pub padding: usize,
+}
+
+/// The colour each suit is painted in.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SuitColors {
+    pub spades: Color,
+    pub hearts: Color,
+    pub diamonds: Color,
+    pub clubs: Color,
+}
+
+impl SuitColors {
+    /// Hearts and diamonds in `red`, spades and clubs in `black`.
+    pub fn two_color(red: Color, black: Color) -> SuitColors {
+        SuitColors {
+            spades: black,
+            hearts: red,
+            diamonds: red,
+            clubs: black,
+        }
+    }
+
+    /// A four colour deck: blue diamonds and green clubs, so no two suits match.
+    pub fn four_color() -> SuitColors {
+        SuitColors {
+            diamonds: Color::Blue,
+            clubs: Color::Green,
+            ..SuitColors::default()
+        }
+    }
+
+    /// Looks up `two-color` or `four-color`, or reads four colour names for
+    /// spades, hearts, diamonds and clubs, e.g. `"white red yellow cyan"`.
+    pub fn named(name: &str) -> Option<SuitColors> {
+        match name {
+            "two-color" => return Some(SuitColors::default()),
+            "four-color" => return Some(SuitColors::four_color()),
+            _ => {}
+        }
+        let colours = name
+            .split_whitespace()
+            .map(Color::named)
+            .collect::<Option<Vec<_>>>()?;
+        match colours[..] {
+            [spades, hearts, diamonds, clubs] => Some(SuitColors {
+                spades,
+                hearts,
+                diamonds,
+                clubs,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The colour `suit` is painted in.
+    pub fn of(&self, suit: Suit) -> Color {
+        match suit {
+            Suit::Spades => self.spades,
+            Suit::Hearts => self.hearts,
+            Suit::Diamonds => self.diamonds,
+            Suit::Clubs => self.clubs,
+        }
+    }
+}
+
+impl Default for SuitColors {
+    /// Red hearts and diamonds, with spades and clubs in white so they show up
+    /// on a dark terminal.
+    fn default() -> SuitColors {
+        SuitColors::two_color(Color::Red, Color::White)
+    }
+}
+
+impl CardTheme {
+    /// The original look with `#` card backs.
+    pub fn classic() -> CardTheme {
+        CardTheme {
+            back: "#".to_string(),
+            top_left: '*',
+            top_right: '*',
+            bottom_left: '*',
+            bottom_right: '*',
+            horizontal: '-',
+            vertical: '|',
+            suit_colors: SuitColors::default(),
+            highlight: Color::Yellow,
+            padding: 1,
+        }
+    }
+
+    /// Rounded box drawing borders with a shaded back.
+    pub fn rounded() -> CardTheme {
+        CardTheme {
+            back: "░".to_string(),
+            top_left: '╭',
+            top_right: '╮',
+            bottom_left: '╰',
+            bottom_right: '╯',
+            horizontal: '─',
+            vertical: '│',
+            ..CardTheme::classic()
+        }
+    }
+
+    /// Double line borders with a lattice back, like a casino deck.
+    pub fn casino() -> CardTheme {
+        CardTheme {
+            back: "╳".to_string(),
+            top_left: '╔',
+            top_right: '╗',
+            bottom_left: '╚',
+            bottom_right: '╝',
+            horizontal: '═',
+            vertical: '║',
+            suit_colors: SuitColors::two_color(Color::Red, Color::Blue),
+            highlight: Color::Yellow,
+            padding: 2,
+        }
+    }
+
+    /// Plain ASCII with a sparse back and no gap between cards.
+    pub fn minimal() -> CardTheme {
+        CardTheme {
+            back: ". ".to_string(),
+            top_left: '+',
+            top_right: '+',
+            bottom_left: '+',
+            bottom_right: '+',
+            padding: 0,
+            ..CardTheme::classic()
+        }
+    }
+
+    /// Looks up a built-in theme by name.
+    pub fn named(name: &str) -> Option<CardTheme> {
+        match name {
+            "classic" => Some(CardTheme::classic()),
+            "rounded" => Some(CardTheme::rounded()),
+            "casino" => Some(CardTheme::casino()),
+            "minimal" => Some(CardTheme::minimal()),
+            _ => None,
+        }
+    }
+
+    /// The back pattern repeated across `width` columns.
+    fn back_row(&self, width: usize) -> String {
+        fill(&self.back, width)
+    }
+
+    /// The top or bottom border of a box `width` columns wide inside.
+    fn border(&self, top: bool, width: usize) -> String {
+        let (left, right) = if top {
+            (self.top_left, self.top_right)
+        } else {
+            (self.bottom_left, self.bottom_right)
+        };
+        let line = fill(&self.horizontal.to_string(), width);
+        format!("{}{}{}", left, line, right)
+    }
+
+    /// The sides, top and bottom of a box `width` columns wide inside around
+    /// `card`. A selected card is outlined in the highlight colour, or with `=`
+    /// borders when suits aren't coloured.
+    fn outline(&self, card: &Card, color: ColorMode, width: usize) -> [String; 3] {
+        let selected = card.state == CardState::Selected;
+        let colored = matches!(color, ColorMode::Auto | ColorMode::Always);
+        let (top, bottom) = if selected && !colored {
+            let line = fill("=", width);
+            (
+                format!("{}{}{}", self.top_left, line, self.top_right),
+                format!("{}{}{}", self.bottom_left, line, self.bottom_right),
+            )
+        } else {
+            (self.border(true, width), self.border(false, width))
+        };
+        let paint = |text: String| {
+            if selected && colored {
+                self.highlight.bold().paint(text)
+            } else {
+                text
+            }
+        };
+        [paint(self.vertical.to_string()), paint(top), paint(bottom)]
+    }
+
+    /// The same theme with suits painted in `suit_colors`.
+    pub fn suit_colors(mut self, suit_colors: SuitColors) -> CardTheme {
+        self.suit_colors = suit_colors;
+        self
+    }
+
+    /// Paints `text` in the suit's colour, or bold for high contrast.
+    fn paint(&self, suit: Suit, text: &str, color: ColorMode) -> String {
+        match color {
+            ColorMode::Auto | ColorMode::Always => self.suit_colors.of(suit).paint(text),
+            ColorMode::Never => text.to_string(),
+            ColorMode::HighContrast => Style::new().bold().paint(text),
+        }
+    }
+
+    /// The suit's symbol or letter painted for `color`.
+    fn suit(&self, suit: Suit, color: ColorMode, suits: SuitStyle) -> String {
+        let symbol = match (suits, suit, color) {
+            (SuitStyle::Letters, _, _) | (SuitStyle::Words, _, _) => suit.letter(),
+            // Red suits are outlined instead of coloured.
+            (_, Suit::Diamonds, ColorMode::HighContrast) => '♢',
+            (_, Suit::Hearts, ColorMode::HighContrast) => '♡',
+            (_, suit, _) => suit.symbol(),
+        };
+        self.paint(suit, &symbol.to_string(), color)
+    }
+}
+
+impl Default for CardTheme {
+    fn default() -> CardTheme {
+        CardTheme::classic()
+    }
+}
+
+/// Writes one row of every card followed by the gap between cards.
+/// Writes one line across a row of `hand`, drawing each card's part of it with
+/// `row` given the card's position.
+fn write_row<W: Write, F>(out: &mut W, hand: &[Card], theme: &CardTheme, row: F) -> fmt::Result
+where
+    F: Fn(usize) -> String,
+{
+    for idx in 0..hand.len() {
+        write!(out, "{}", row(idx))?;
+        write!(out, "{}", " ".repeat(theme.padding))?;
+    }
+    writeln!(out)
+}
+
+/// The lines of one small card box, top border first. Hidden cards show the
+/// back pattern inside the border.
+fn small_lines(card: &Card, options: &RenderOptions) -> Vec<String> {
+    let (theme, color) = (&options.theme, options.color);
+    let suit = match options.suits {
+        SuitStyle::Words => theme.paint(card.suit, card.suit.name(), color),
+        suits => theme.suit(card.suit, color, suits),
+    };
+    let back = theme.back_row(CARD_WIDTH);
+    let [side, top, bottom] = theme.outline(card, color, CARD_WIDTH);
+    let face = |inner: String| match card.state {
+        CardState::Hidden => format!("{}{}{}", side, back, side),
+        CardState::Visible | CardState::Selected => format!("{}{}{}", side, inner, side),
+    };
+    let rank = card.rank.to_string();
+    let empty = " ".repeat(CARD_WIDTH);
+    vec![
+        top,
+        face(pad(&format!(" {}", rank), CARD_WIDTH, Align::Left)),
+        face(empty.clone()),
+        face(pad(&suit, CARD_WIDTH, Align::Center)),
+        face(empty),
+        face(pad(&format!("{} ", rank), CARD_WIDTH, Align::Right)),
+        bottom,
+    ]
+}
+
+fn write_index<W: Write>(
+    out: &mut W,
+    hand: &[Card],
+    theme: &CardTheme,
+    width: usize,
+    first: usize,
+) -> fmt::Result {
+    for (idx, _) in hand.iter().enumerate() {
+        write!(out, "{:^1$}", format!("[{}]", first + idx), width + 2)?;
+        write!(out, "{}", " ".repeat(theme.padding))?;
+    }
+    writeln!(out)
+}
+
+/// Pip positions on a large card as (row, column) pairs, with columns
+/// 0, 1 and 2 being the left, middle and right of the card.
+fn pip_layout(rank: Rank) -> &'static [(usize, usize)] {
+    match rank {
+        Rank::Ace => &[(3, 1)],
+        Rank::Two => &[(0, 1), (6, 1)],
+        Rank::Three => &[(0, 1), (3, 1), (6, 1)],
+        Rank::Four => &[(0, 0), (0, 2), (6, 0), (6, 2)],
+        Rank::Five => &[(0, 0), (0, 2), (3, 1), (6, 0), (6, 2)],
+        Rank::Six => &[(0, 0), (0, 2), (3, 0), (3, 2), (6, 0), (6, 2)],
+        Rank::Seven => &[(0, 0), (0, 2), (1, 1), (3, 0), (3, 2), (6, 0), (6, 2)],
+        Rank::Eight => &[
+            (0, 0),
+            (0, 2),
+            (1, 1),
+            (3, 0),
+            (3, 2),
+            (5, 1),
+            (6, 0),
+            (6, 2),
+        ],
+        Rank::Nine => &[
+            (0, 0),
+            (0, 2),
+            (2, 0),
+            (2, 2),
+            (3, 1),
+            (4, 0),
+            (4, 2),
+            (6, 0),
+            (6, 2),
+        ],
+        Rank::Ten => &[
+            (0, 0),
+            (0, 2),
+            (1, 1),
+            (2, 0),
+            (2, 2),
+            (4, 0),
+            (4, 2),
+            (5, 1),
+            (6, 0),
+            (6, 2),
+        ],
+        Rank::Jack | Rank::Queen | Rank::King | Rank::Joker => &[],
+    }
+}
+
+/// Letter art drawn in the middle of large face cards.
+fn face_art(rank: Rank) -> Option<[&'static str; 5]> {
+    match rank {
+        Rank::Jack => Some(["  JJJ", "    J", "    J", "J   J", " JJJ "]),
+        Rank::Queen => Some([" QQQ ", "Q   Q", "Q Q Q", "Q  Q ", " QQ Q"]),
+        Rank::King => Some(["K   K", "K  K ", "KKK  ", "K  K ", "K   K"]),
+        Rank::Joker => Some(["  *  ", " *** ", "*****", " *** ", "  *  "]),
+        _ => None,
+    }
+}
+
+/// The inner rows of a face up large card between its two rank rows.
+fn large_face(card: &Card, options: &RenderOptions) -> Vec<String> {
+    let suit = options.theme.suit(card.suit, options.color, options.suits);
+    if let Some(art) = face_art(card.rank) {
+        let mut rows = vec![pad(&format!(" {}", suit), LARGE_CARD_WIDTH, Align::Left)];
+        rows.extend(
+            art.iter()
+                .map(|line| pad(line, LARGE_CARD_WIDTH, Align::Center)),
+        );
+        rows.push(pad(&format!("{} ", suit), LARGE_CARD_WIDTH, Align::Right));
+        return rows;
+    }
+
+    let pips = pip_layout(card.rank);
+    (0..PIP_ROWS)
+        .map(|row| {
+            let mut line = String::new();
+            for column in 0..3 {
+                line.push_str("  ");
+                if pips.contains(&(row, column)) {
+                    line.push_str(&suit);
+                } else {
+                    line.push(' ');
+                }
+            }
+            line.push_str("  ");
+            line
+        })
+        .collect()
+}
+
+/// Writes full-size cards with pips for number cards and letter art for faces.
+fn write_large<W: Write>(
+    out: &mut W,
+    hand: &[Card],
+    options: &RenderOptions,
+    first: usize,
+) -> fmt::Result {
+    let theme = &options.theme;
+    let back = theme.back_row(LARGE_CARD_WIDTH);
+    let faces: Vec<Vec<String>> = hand.iter().map(|card| large_face(card, options)).collect();
+    let rank_row = |card: &Card, top: bool| {
+        if top {
+            pad(&format!(" {}", card.rank), LARGE_CARD_WIDTH, Align::Left)
+        } else {
+            pad(&format!("{} ", card.rank), LARGE_CARD_WIDTH, Align::Right)
+        }
+    };
+    let outlines: Vec<[String; 3]> = hand
+        .iter()
+        .map(|card| theme.outline(card, options.color, LARGE_CARD_WIDTH))
+        .collect();
+    let wrap = |idx: usize, inner: String| {
+        let side = &outlines[idx][0];
+        match hand[idx].state {
+            CardState::Hidden => format!("{}{}{}", side, back, side),
+            CardState::Visible | CardState::Selected => format!("{}{}{}", side, inner, side),
+        }
+    };
+
+    write_row(out, hand, theme, |idx| outlines[idx][1].clone())?;
+    write_row(out, hand, theme, |idx| {
+        wrap(idx, rank_row(&hand[idx], true))
+    })?;
+    (0..PIP_ROWS).try_for_each(|row| {
+        write_row(out, hand, theme, |idx| wrap(idx, faces[idx][row].clone()))
+    })?;
+    write_row(out, hand, theme, |idx| {
+        wrap(idx, rank_row(&hand[idx], false))
+    })?;
+    write_row(out, hand, theme, |idx| outlines[idx][2].clone())?;
+
+    if options.show_index {
+        write_index(out, hand, theme, LARGE_CARD_WIDTH, first)?;
+    }
+    Ok(())
+}
+
+/// Unicode glyph shown for a face down card.
+pub const CARD_BACK_GLYPH: char = '\u{1F0A0}';
+
+/// The Unicode playing card glyph for a card, e.g. 🂡 for the Ace of Spades.
+/// Hidden cards aren't taken into account, see `CARD_BACK_GLYPH`.
+pub fn glyph(card: &Card) -> char {
+    if let Rank::Joker = card.rank {
+        return match card.suit.color() {
+            SuitColor::Red => '\u{1F0BF}',
+            SuitColor::Black => '\u{1F0CF}',
+        };
+    }
+    let suit_base = match card.suit {
+        Suit::Spades => 0x1F0A0,
+        Suit::Hearts => 0x1F0B0,
+        Suit::Diamonds => 0x1F0C0,
+        Suit::Clubs => 0x1F0D0,
+    };
+    // The block has a Knight between the Jack and the Queen, which is skipped.
+    let offset = match card.rank {
+        Rank::Ace => 1,
+        Rank::Jack => 11,
+        Rank::Queen => 13,
+        Rank::King => 14,
+        rank => rank as u32 + 2,
+    };
+    std::char::from_u32(suit_base + offset).expect("playing card glyphs are valid chars")
+}
+
+/// How each card is drawn.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum RenderStyle {
+    /// ASCII-art boxes several lines tall.
+    #[default]
+    Boxes,
+    /// One Unicode playing card glyph per card, so a hand fits on a single line.
+    Compact,
+    /// A plain sentence per card and no drawing at all, for screen readers.
+    Narrated,
+}
+
+/// How big the boxes drawn by `RenderStyle::Boxes` are.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum CardSize {
+    /// Rank in the corners and one suit symbol in the middle.
+    #[default]
+    Small,
+    /// Full-size cards with a pip for each point and letter art on face cards.
+    Large,
+}
+
+/// Whether cards are drawn with ANSI colours.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Colour when printing to a terminal that supports it and `NO_COLOR`
+    /// isn't set. Rendering to a string always colours.
+    #[default]
+    Auto,
+    /// Always colour suits red and white.
+    Always,
+    /// Never write ANSI escape codes.
+    Never,
+    /// Tell suits apart by shape instead of colour: red suits are outlined
+    /// (♡ ♢) and black suits filled (♠ ♣), all drawn in bold.
+    HighContrast,
+}
+
+impl ColorMode {
+    /// Turns `Auto` into `Always` or `Never` for output to stdout. A console
+    /// that can't take escape codes, such as an old Windows console or a CI log
+    /// that isn't a terminal, gets `Never`.
+    pub fn resolve(self) -> ColorMode {
+        match self {
+            ColorMode::Auto if env::var_os("NO_COLOR").is_some() => ColorMode::Never,
+            ColorMode::Auto if console::colors_enabled() && style::enable_ansi() => {
+                ColorMode::Always
+            }
+            ColorMode::Auto => ColorMode::Never,
+            mode => mode,
+        }
+    }
+
+    /// Looks up a mode by name, as in `high-contrast`.
+    pub fn named(name: &str) -> Option<ColorMode> {
+        match name {
+            "auto" => Some(ColorMode::Auto),
+            "always" => Some(ColorMode::Always),
+            "never" => Some(ColorMode::Never),
+            "high-contrast" => Some(ColorMode::HighContrast),
+            _ => None,
+        }
+    }
+}
+
+/// How suits are written on cards.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum SuitStyle {
+    /// Symbols when printing to stdout with a locale that can show them, and
+    /// letters otherwise. Rendering to a string always uses symbols.
+    #[default]
+    Auto,
+    /// ♠ ♦ ♥ ♣
+    Symbols,
+    /// S D H C, for terminals and fonts without the suit symbols.
+    Letters,
+    /// The suit's name in the middle of small cards, and letters where only one
+    /// character fits.
+    Words,
+}
+
+impl SuitStyle {
+    /// Turns `Auto` into `Symbols` or `Letters` for output to stdout, going by
+    /// the locale in `LC_ALL`, `LC_CTYPE` or `LANG`. A Windows console has to
+    /// take the UTF-8 code page too.
+    pub fn resolve(self) -> SuitStyle {
+        match self {
+            SuitStyle::Auto => {
+                let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+                    .iter()
+                    .filter_map(|name| env::var(name).ok())
+                    .find(|value| !value.is_empty());
+                if supports_unicode(locale.as_deref()) && style::enable_unicode() {
+                    SuitStyle::Symbols
+                } else {
+                    SuitStyle::Letters
+                }
+            }
+            style => style,
+        }
+    }
+
+    /// Looks up a style by name, as in `letters`.
+    pub fn named(name: &str) -> Option<SuitStyle> {
+        match name {
+            "auto" => Some(SuitStyle::Auto),
+            "symbols" => Some(SuitStyle::Symbols),
+            "letters" => Some(SuitStyle::Letters),
+            "words" => Some(SuitStyle::Words),
+            _ => None,
+        }
+    }
+}
+
+/// Whether `locale` can show the suit symbols. Only a UTF-8 locale can, but
+/// with none set at all the terminal is assumed to manage.
+fn supports_unicode(locale: Option<&str>) -> bool {
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        }
+        None => true,
+    }
+}
+
+/// Options controlling how a hand is rendered.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderOptions {
+    pub show_index: bool,
+    pub theme: CardTheme,
+    pub style: RenderStyle,
+    pub size: CardSize,
+    pub color: ColorMode,
+    pub suits: SuitStyle,
+}
+
+/// Builds render options using the builder pattern.
+impl RenderOptions {
+    /// RenderOptions Constructor. Draws the classic theme without indices.
+    pub fn new() -> RenderOptions {
+        RenderOptions::default()
+    }
+
+    /// Option to print each card's index under the hand.
+    pub fn show_index(mut self, show_index: bool) -> RenderOptions {
+        self.show_index = show_index;
+        self
+    }
+
+    /// Option to change the theme cards are drawn with.
+    pub fn theme(mut self, theme: CardTheme) -> RenderOptions {
+        self.theme = theme;
+        self
+    }
+
+    /// Option to change how each card is drawn.
+    pub fn style(mut self, style: RenderStyle) -> RenderOptions {
+        self.style = style;
+        self
+    }
+
+    /// Option to draw small or large card boxes.
+    pub fn size(mut self, size: CardSize) -> RenderOptions {
+        self.size = size;
+        self
+    }
+
+    /// Option to turn colour off or use high contrast suits.
+    pub fn color(mut self, color: ColorMode) -> RenderOptions {
+        self.color = color;
+        self
+    }
+
+    /// Option to write suits as letters or words instead of symbols.
+    pub fn suits(mut self, suits: SuitStyle) -> RenderOptions {
+        self.suits = suits;
+        self
+    }
+
+    /// The same options with `Auto` colour and suits settled for output to
+    /// stdout.
+    pub fn resolve(&self) -> RenderOptions {
+        self.clone()
+            .color(self.color.resolve())
+            .suits(self.suits.resolve())
+    }
+}
+
+/// Renders the card boxes for a hand to a string.
+pub fn render_hand(hand: &[Card], options: &RenderOptions) -> String {
+    let mut out = String::new();
+    write_hand_with(&mut out, hand, options).expect("writing to a String cannot fail");
+    out
+}
+
+/// Writes the card boxes for a hand into any `fmt::Write` target.
+pub fn write_hand<W: Write>(out: &mut W, hand: &[Card], show_index: bool) -> fmt::Result {
+    write_hand_with(out, hand, &RenderOptions::new().show_index(show_index))
+}
+
+/// Writes the card boxes for a hand drawn with `options`.
+pub fn write_hand_with<W: Write>(
+    out: &mut W,
+    hand: &[Card],
+    options: &RenderOptions,
+) -> fmt::Result {
+    write_cards(out, hand, options, 0)
+}
+
+/// Writes a label under each card of a hand drawn with `options`, such as the
+/// card's score. Boxes get their label centred underneath; other styles list
+/// the labels on one line after each card's index.
+pub fn write_labels<W: Write>(
+    out: &mut W,
+    labels: &[String],
+    options: &RenderOptions,
+) -> fmt::Result {
+    let width = match (options.style, options.size) {
+        (RenderStyle::Boxes, CardSize::Small) => CARD_WIDTH,
+        (RenderStyle::Boxes, CardSize::Large) => LARGE_CARD_WIDTH,
+        _ => {
+            let labels: Vec<String> = labels
+                .iter()
+                .enumerate()
+                .map(|(idx, label)| format!("[{}] {}", idx, label))
+                .collect();
+            return writeln!(out, "{}", labels.join("  "));
+        }
+    };
+    for label in labels {
+        write!(out, "{:^1$}", label, width + 2)?;
+        write!(out, "{}", " ".repeat(options.theme.padding))?;
+    }
+    writeln!(out)
+}
+
+/// Writes `cards` in rows of `columns`, numbering them from the top left when
+/// `options` shows the index.
+pub fn write_grid<W: Write>(
+    out: &mut W,
+    cards: &[Card],
+    columns: usize,
+    options: &RenderOptions,
+) -> fmt::Result {
+    for (row, cards) in cards.chunks(columns.max(1)).enumerate() {
+        write_cards(out, cards, options, row * columns)?;
+    }
+    Ok(())
+}
+
+/// Writes `stacks` side by side as columns, each card overlapping the one
+/// below it so only its top edge and rank show. Empty stacks are drawn as an
+/// outline. Indexes label the columns from the top when `options` shows them.
+/// Large cards are drawn small, and the compact style puts each stack on a line.
+pub fn write_stacks<W: Write>(
+    out: &mut W,
+    stacks: &[&[Card]],
+    options: &RenderOptions,
+) -> fmt::Result {
+    let theme = &options.theme;
+    if let RenderStyle::Compact = options.style {
+        for (idx, stack) in stacks.iter().enumerate() {
+            if options.show_index {
+                write!(out, "[{}] ", idx)?;
+            }
+            write_compact(out, stack, &options.clone().show_index(false), 0)?;
+        }
+        return Ok(());
+    }
+    if let RenderStyle::Narrated = options.style {
+        for (idx, stack) in stacks.iter().enumerate() {
+            let pile = if options.show_index { idx } else { idx + 1 };
+            if stack.is_empty() {
+                writeln!(out, "Pile {} is empty.", pile)?;
+            } else {
+                write!(out, "Pile {}. ", pile)?;
+                write_narrated(out, stack, &options.clone().show_index(false), 0)?;
+            }
+        }
+        return Ok(());
+    }
+
+    if options.show_index {
+        for idx in 0..stacks.len() {
+            write!(out, "{:^1$}", format!("[{}]", idx), CARD_WIDTH + 2)?;
+            write!(out, "{}", " ".repeat(theme.padding))?;
+        }
+        writeln!(out)?;
+    }
+    let columns: Vec<Vec<String>> = stacks
+        .iter()
+        .map(|stack| stack_lines(stack, options))
+        .collect();
+    let height = columns.iter().map(Vec::len).max().unwrap_or(0);
+    let blank = " ".repeat(CARD_WIDTH + 2);
+    for row in 0..height {
+        for lines in &columns {
+            let line = lines.get(row).unwrap_or(&blank);
+            write!(out, "{}{}", line, " ".repeat(theme.padding))?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// The lines of a stack drawn top to bottom, or an outline if it's empty.
+fn stack_lines(stack: &[Card], options: &RenderOptions) -> Vec<String> {
+    let theme = &options.theme;
+    let (last, covered) = match stack.split_last() {
+        Some(split) => split,
+        None => {
+            let mut outline = small_lines(&Card::new(Suit::Spades, Rank::Ace), options);
+            let empty = format!(
+                "{}{}{}",
+                theme.vertical,
+                " ".repeat(CARD_WIDTH),
+                theme.vertical
+            );
+            for line in &mut outline[1..CARD_HEIGHT - 1] {
+                *line = empty.clone();
+            }
+            return outline;
+        }
+    };
+    let mut lines = Vec::new();
+    for card in covered {
+        lines.extend(small_lines(card, options).into_iter().take(STACKED_HEIGHT));
+    }
+    lines.extend(small_lines(last, options));
+    lines
+}
+
+/// Writes a row of cards whose index labels start at `first`.
+fn write_cards<W: Write>(
+    out: &mut W,
+    hand: &[Card],
+    options: &RenderOptions,
+    first: usize,
+) -> fmt::Result {
+    match options.style {
+        RenderStyle::Compact => return write_compact(out, hand, options, first),
+        RenderStyle::Narrated => return write_narrated(out, hand, options, first),
+        RenderStyle::Boxes => {}
+    }
+    if let CardSize::Large = options.size {
+        return write_large(out, hand, options, first);
+    }
+
+    let theme = &options.theme;
+    let boxes: Vec<Vec<String>> = hand.iter().map(|card| small_lines(card, options)).collect();
+    for row in 0..CARD_HEIGHT {
+        for lines in &boxes {
+            write!(out, "{}{}", lines[row], " ".repeat(theme.padding))?;
+        }
+        writeln!(out)?;
+    }
+
+    if options.show_index {
+        write_index(out, hand, theme, CARD_WIDTH, first)?;
+    }
+    Ok(())
+}
+
+/// Writes a hand as a single line of glyphs, each prefixed by its index if shown.
+fn write_compact<W: Write>(
+    out: &mut W,
+    hand: &[Card],
+    options: &RenderOptions,
+    first: usize,
+) -> fmt::Result {
+    let theme = &options.theme;
+    for (idx, card) in hand.iter().enumerate() {
+        if idx > 0 {
+            write!(out, " {}", " ".repeat(theme.padding))?;
+        }
+        if options.show_index {
+            write!(out, "[{}]", first + idx)?;
+        }
+        // Glyphs are symbols too, so without symbols the short code is written.
+        let (glyph, back) = match options.suits {
+            SuitStyle::Auto | SuitStyle::Symbols => {
+                (glyph(card).to_string(), CARD_BACK_GLYPH.to_string())
+            }
+            SuitStyle::Letters | SuitStyle::Words => (
+                format!("{}{}", card.rank, card.suit.letter()),
+                "##".to_string(),
+            ),
+        };
+        match (card.state, options.color) {
+            (CardState::Hidden, _) => write!(out, "{}", back)?,
+            (CardState::Visible, color) => {
+                write!(out, "{}", theme.paint(card.suit, &glyph, color))?
+            }
+            // A selected glyph is underlined, or starred when suits aren't coloured.
+            (CardState::Selected, ColorMode::Auto | ColorMode::Always) => {
+                let style = theme.suit_colors.of(card.suit).underline();
+                write!(out, "{}", style.paint(glyph))?
+            }
+            (CardState::Selected, color) => {
+                write!(out, "{}*", theme.paint(card.suit, &glyph, color))?
+            }
+        }
+    }
+    writeln!(out)
+}
+
+/// Writes a hand as one line of sentences, such as `Card 1 is hidden. Card 2 is
+/// the Queen of Hearts.` Cards are numbered as their index when it's shown, so
+/// the number heard is the one to type, and counted from 1 otherwise.
+fn write_narrated<W: Write>(
+    out: &mut W,
+    hand: &[Card],
+    options: &RenderOptions,
+    first: usize,
+) -> fmt::Result {
+    for (idx, card) in hand.iter().enumerate() {
+        if idx > 0 {
+            write!(out, " ")?;
+        }
+        let number = first + idx + if options.show_index { 0 } else { 1 };
+        let name = match card.rank {
+            Rank::Joker => "a Joker".to_string(),
+            rank => format!("the {} of {}", rank.name(), card.suit.name()),
+        };
+        match card.state {
+            CardState::Hidden => write!(out, "Card {} is hidden.", number)?,
+            CardState::Visible => write!(out, "Card {} is {}.", number, name)?,
+            CardState::Selected => write!(out, "Card {}, your pick, is {}.", number, name)?,
+        }
+    }
+    if hand.is_empty() {
+        write!(out, "No cards.")?;
+    }
+    writeln!(out)
+}
+
+/// Prints a hand to stdout.
+pub fn display_hand(hand: &[Card], show_index: bool) -> Result<(), GameError> {
+    display_hand_with(hand, &RenderOptions::new().show_index(show_index))
+}
+
+/// Prints a hand drawn with `options` to stdout, leaving out colour in
+/// `ColorMode::Auto` when stdout isn't a terminal. A hand too wide for the
+/// terminal wraps onto as many rows as it needs, numbered on from the row above.
+pub fn display_hand_with(hand: &[Card], options: &RenderOptions) -> Result<(), GameError> {
+    let columns = match Term::stdout().size_checked() {
+        Some((_, width)) => columns_for(width as usize, options),
+        None => hand.len(),
+    };
+    display_grid(hand, columns.min(hand.len()), options)
+}
+
+/// How many cards drawn with `options` fit side by side in `width` columns,
+/// always at least one.
+pub fn columns_for(width: usize, options: &RenderOptions) -> usize {
+    let padding = options.theme.padding;
+    let card = match (options.style, options.size) {
+        // A glyph can be two columns wide, plus the space between glyphs.
+        (RenderStyle::Compact, _) if options.show_index => "[10]".len() + 3 + padding,
+        (RenderStyle::Compact, _) => 3 + padding,
+        // Sentences wrap by themselves, so a row holds every card.
+        (RenderStyle::Narrated, _) => return usize::MAX,
+        (RenderStyle::Boxes, CardSize::Small) => CARD_WIDTH + 2 + padding,
+        (RenderStyle::Boxes, CardSize::Large) => LARGE_CARD_WIDTH + 2 + padding,
+    };
+    (width / card).max(1)
+}
+
+/// Which of `cards` written in rows of `columns` with `options` is drawn at
+/// `column` and `row`, counted from the top left of the first row. Gaps between
+/// boxes, index lines and anything past the last card hit nothing, and only
+/// boxes can be hit at all.
+pub fn card_at(
+    cards: usize,
+    columns: usize,
+    options: &RenderOptions,
+    column: usize,
+    row: usize,
+) -> Option<usize> {
+    let (width, height) = match (options.style, options.size) {
+        (RenderStyle::Boxes, CardSize::Small) => (CARD_WIDTH + 2, CARD_HEIGHT),
+        (RenderStyle::Boxes, CardSize::Large) => (LARGE_CARD_WIDTH + 2, PIP_ROWS + 4),
+        _ => return None,
+    };
+    let columns = columns.max(1);
+    let stride = width + options.theme.padding;
+    let row_height = height + usize::from(options.show_index);
+    if column % stride >= width || row % row_height >= height || column / stride >= columns {
+        return None;
+    }
+    let idx = row / row_height * columns + column / stride;
+    Some(idx).filter(|&idx| idx < cards)
+}
+
+/// Prints stacks of overlapping cards to stdout, see `write_stacks`.
+pub fn display_stacks(stacks: &[&[Card]], options: &RenderOptions) -> Result<(), GameError> {
+    let options = options.resolve();
+    let mut out = String::new();
+    write_stacks(&mut out, stacks, &options)?;
+    let mut stdout = io::stdout();
+    stdout.write_all(out.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Prints cards in rows of `columns` to stdout, see `write_grid`.
+pub fn display_grid(
+    cards: &[Card],
+    columns: usize,
+    options: &RenderOptions,
+) -> Result<(), GameError> {
+    let options = options.resolve();
+    let mut out = String::new();
+    if cards.is_empty() {
+        write_hand_with(&mut out, cards, &options)?;
+    } else {
+        write_grid(&mut out, cards, columns, &options)?;
+    }
+    let mut stdout = io::stdout();
+    stdout.write_all(out.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        card_at, columns_for, glyph, render_hand, supports_unicode, width, write_grid,
+        write_labels, write_stacks, CardSize, CardTheme, ColorMode, RenderOptions, RenderStyle,
+        SuitColors, SuitStyle,
+    };
+    use crate::cards::{Card, CardState, Rank, Suit};
+    use crate::style::Color;
+
+    fn hand() -> Vec<Card> {
+        let mut hand = vec![
+            Card::new(Suit::Clubs, Rank::Ten),
+            Card::new(Suit::Hearts, Rank::Seven),
+            Card::new(Suit::Spades, Rank::Ace),
+        ];
+        hand[0].state = CardState::Visible;
+        hand[2].state = CardState::Visible;
+        hand
+    }
+
+    #[test]
+    /// Tests suits are written as letters or words for terminals without the
+    /// symbols, and the locale decides which `Auto` uses.
+    fn test_suit_styles() {
+        let options = RenderOptions::new().color(ColorMode::Never);
+        let rendered: Vec<String> = [SuitStyle::Letters, SuitStyle::Words]
+            .iter()
+            .map(|&suits| render_hand(&hand(), &options.clone().suits(suits)))
+            .collect();
+        assert!(rendered.iter().all(|hand| !hand.contains('♠')));
+        insta::assert_debug_snapshot!(rendered);
+
+        let compact = options
+            .clone()
+            .style(RenderStyle::Compact)
+            .suits(SuitStyle::Letters);
+        assert_eq!(render_hand(&hand(), &compact), "10C  ##  AS\n");
+        let large = options.size(CardSize::Large).suits(SuitStyle::Words);
+        assert!(render_hand(&hand()[2..], &large).contains(" S "));
+
+        assert!(supports_unicode(Some("en_US.UTF-8")));
+        assert!(supports_unicode(Some("C.utf8")));
+        assert!(!supports_unicode(Some("C")));
+        assert!(supports_unicode(None));
+        assert_eq!(SuitStyle::named("words"), Some(SuitStyle::Words));
+        assert_eq!(SuitStyle::Letters.resolve(), SuitStyle::Letters);
+    }
+
+    #[test]
+    /// Tests labels are centred under boxes and listed by index otherwise.
+    fn test_write_labels() {
+        let labels = vec!["= 42".to_string(), "= 7".to_string()];
+        let mut boxes = String::new();
+        write_labels(&mut boxes, &labels, &RenderOptions::new()).unwrap();
+        assert_eq!(boxes, "   = 42         = 7     \n");
+
+        let mut compact = String::new();
+        let options = RenderOptions::new().style(RenderStyle::Compact);
+        write_labels(&mut compact, &labels, &options).unwrap();
+        assert_eq!(compact, "[0] = 42  [1] = 7\n");
+    }
+
+    #[test]
+    /// Tests the narrated style describes each card in a sentence.
+    fn test_render_narrated() {
+        let mut cards = hand();
+        cards[2].state = CardState::Selected;
+        let options = RenderOptions::new().style(RenderStyle::Narrated);
+        assert_eq!(
+            render_hand(&cards, &options),
+            "Card 1 is the Ten of Clubs. Card 2 is hidden. \
+             Card 3, your pick, is the Ace of Spades.\n"
+        );
+        let indexed = options.clone().show_index(true);
+        assert!(render_hand(&cards, &indexed).starts_with("Card 0 is the Ten of Clubs."));
+        assert_eq!(render_hand(&[], &options), "No cards.\n");
+
+        let stacks: [&[Card]; 2] = [&[], &cards[..1]];
+        let mut out = String::new();
+        write_stacks(&mut out, &stacks, &options).unwrap();
+        assert_eq!(
+            out,
+            "Pile 1 is empty.\nPile 2. Card 1 is the Ten of Clubs.\n"
+        );
+        assert_eq!(columns_for(80, &options), usize::MAX);
+    }
+
+    #[test]
+    /// Tests each built-in theme draws a face up and a face down card.
+    fn test_themes() {
+        let rendered: Vec<String> = ["classic", "rounded", "casino", "minimal"]
+            .iter()
+            .map(|name| {
+                let options = RenderOptions::new()
+                    .show_index(true)
+                    .theme(CardTheme::named(name).unwrap());
+                render_hand(&hand()[..2], &options)
+            })
+            .collect();
+
+        insta::assert_debug_snapshot!(rendered);
+    }
+
+    #[test]
+    /// Tests rendering with the default options.
+    fn test_render_hand() {
+        insta::assert_snapshot!(render_hand(&hand(), &RenderOptions::new()));
+    }
+
+    #[test]
+    /// Tests rendering indices under the hand.
+    fn test_render_hand_with_index() {
+        insta::assert_snapshot!(render_hand(&hand(), &RenderOptions::new().show_index(true)));
+    }
+
+    #[test]
+    /// Tests cards map to the Unicode playing card block.
+    fn test_glyph() {
+        assert_eq!(glyph(&Card::new(Suit::Spades, Rank::Ace)), '🂡');
+        assert_eq!(glyph(&Card::new(Suit::Hearts, Rank::Ten)), '🂺');
+        assert_eq!(glyph(&Card::new(Suit::Diamonds, Rank::Queen)), '🃍');
+        assert_eq!(glyph(&Card::new(Suit::Clubs, Rank::King)), '🃞');
+        assert_eq!(glyph(&Card::new(Suit::Clubs, Rank::Two)), '🃒');
+        assert_eq!(glyph(&Card::new(Suit::Hearts, Rank::Joker)), '🂿');
+    }
+
+    #[test]
+    /// Tests the compact style renders a single line.
+    fn test_render_compact() {
+        let options = RenderOptions::new().style(RenderStyle::Compact);
+        insta::assert_debug_snapshot!(vec![
+            render_hand(&hand(), &options),
+            render_hand(&hand(), &options.show_index(true)),
+        ]);
+    }
+
+    #[test]
+    /// Tests large cards draw pips for number cards and letter art for faces.
+    fn test_render_large() {
+        let mut hand = vec![
+            Card::new(Suit::Hearts, Rank::Seven),
+            Card::new(Suit::Spades, Rank::Ten),
+            Card::new(Suit::Diamonds, Rank::Queen),
+            Card::new(Suit::Clubs, Rank::Ace),
+        ];
+        for card in &mut hand[..3] {
+            card.state = CardState::Visible;
+        }
+        let options = RenderOptions::new()
+            .size(CardSize::Large)
+            .show_index(true)
+            .theme(
+                CardTheme::classic().suit_colors(SuitColors::two_color(Color::White, Color::White)),
+            );
+        insta::assert_snapshot!(render_hand(&hand, &options));
+    }
+
+    #[test]
+    /// Tests colour can be turned off or swapped for outlined red suits.
+    fn test_color_modes() {
+        let never = render_hand(&hand(), &RenderOptions::new().color(ColorMode::Never));
+        assert!(!never.contains('\u{1b}'));
+        assert_eq!(ColorMode::Never.resolve(), ColorMode::Never);
+        assert_eq!(
+            ColorMode::named("high-contrast"),
+            Some(ColorMode::HighContrast)
+        );
+
+        let mut hearts = hand();
+        hearts[1].state = CardState::Visible;
+        let options = RenderOptions::new()
+            .style(RenderStyle::Compact)
+            .color(ColorMode::HighContrast);
+        insta::assert_snapshot!(render_hand(
+            &hearts,
+            &options.clone().style(RenderStyle::Boxes)
+        ));
+        assert!(render_hand(&hearts, &options).contains("\u{1b}[1m"));
+    }
+
+    #[test]
+    /// Tests a selected card is outlined apart from the rest of the hand.
+    fn test_render_selected() {
+        let mut cards = hand();
+        cards[1].state = CardState::Selected;
+        for size in [CardSize::Small, CardSize::Large] {
+            let plain = RenderOptions::new().size(size).color(ColorMode::Never);
+            let lines: Vec<String> = render_hand(&cards, &plain)
+                .lines()
+                .map(String::from)
+                .collect();
+            assert!(!lines[0].contains("*-*"));
+            assert!(lines[0].contains("*=========") && lines[0].contains("-*"));
+            assert!(lines[1].contains('7'));
+            assert_eq!(width::width(&lines[0]), width::width(&lines[2]));
+
+            let colored = plain.color(ColorMode::Always);
+            let highlight = Color::Yellow.bold().prefix();
+            assert!(render_hand(&cards, &colored).contains(&highlight));
+            assert!(!render_hand(&hand(), &colored).contains(&highlight));
+        }
+        let compact = RenderOptions::new()
+            .style(RenderStyle::Compact)
+            .color(ColorMode::Never);
+        assert_eq!(render_hand(&cards, &compact), "🃚  🂷*  🂡\n");
+    }
+
+    #[test]
+    /// Tests suit colours can be picked by name or spelled out per suit.
+    fn test_suit_colors() {
+        let four = SuitColors::named("four-color").unwrap();
+        assert_eq!(four.of(Suit::Spades), Color::White);
+        assert_eq!(four.of(Suit::Hearts), Color::Red);
+        assert_eq!(four.of(Suit::Diamonds), Color::Blue);
+        assert_eq!(four.of(Suit::Clubs), Color::Green);
+        assert_eq!(SuitColors::named("two-color"), Some(SuitColors::default()));
+        assert_eq!(
+            SuitColors::named("black red yellow cyan").map(|colors| colors.of(Suit::Clubs)),
+            Some(Color::Cyan)
+        );
+        assert_eq!(SuitColors::named("red red red"), None);
+        for suit in [Suit::Spades, Suit::Hearts] {
+            let painted = SuitColors::default().of(suit).paint(suit.symbol());
+            assert_eq!(suit.to_string(), painted);
+        }
+        assert_eq!(SuitColors::named("red red red pink"), None);
+
+        let options = RenderOptions::new()
+            .style(RenderStyle::Compact)
+            .color(ColorMode::Always);
+        let green = options
+            .clone()
+            .theme(CardTheme::classic().suit_colors(four));
+        let green_prefix = Color::Green.prefix();
+        assert!(render_hand(&hand(), &green).contains(&green_prefix));
+        assert!(!render_hand(&hand(), &options).contains(&green_prefix));
+    }
+
+    #[test]
+    /// Tests cards are laid out in rows and numbered across them.
+    fn test_render_grid() {
+        let mut out = String::new();
+        let mut cards = hand();
+        cards.extend(hand());
+        write_grid(
+            &mut out,
+            &cards[..5],
+            3,
+            &RenderOptions::new().show_index(true),
+        )
+        .unwrap();
+        insta::assert_snapshot!(out);
+    }
+
+    #[test]
+    /// Tests stacks overlap their cards and outline empty columns.
+    fn test_render_stacks() {
+        let mut out = String::new();
+        let cards = hand();
+        let stacks: Vec<&[Card]> = vec![&cards, &[], &cards[2..]];
+        write_stacks(
+            &mut out,
+            &stacks,
+            &RenderOptions::new()
+                .show_index(true)
+                .color(ColorMode::Never),
+        )
+        .unwrap();
+        insta::assert_snapshot!(out);
+    }
+
+    #[test]
+    /// Tests rendering an empty hand.
+    fn test_render_empty_hand() {
+        assert_eq!(render_hand(&[], &RenderOptions::new()), "\n".repeat(7));
+    }
+
+    #[test]
+    /// Tests how many cards fit across a terminal in each style.
+    fn test_columns_for() {
+        let options = RenderOptions::new();
+        assert_eq!(columns_for(80, &options), 6);
+        assert_eq!(columns_for(36, &options), 3);
+        assert_eq!(columns_for(5, &options), 1);
+        assert_eq!(columns_for(80, &options.clone().size(CardSize::Large)), 5);
+        let casino = options.clone().theme(CardTheme::casino());
+        assert_eq!(columns_for(80, &casino), 6);
+        assert_eq!(columns_for(80, &options.style(RenderStyle::Compact)), 20);
+    }
+
+    #[test]
+    /// Tests positions inside a card box find it and the gaps find nothing.
+    fn test_card_at() {
+        let options = RenderOptions::new();
+        assert_eq!(card_at(3, 3, &options, 0, 0), Some(0));
+        assert_eq!(card_at(3, 3, &options, 10, 6), Some(0));
+        assert_eq!(card_at(3, 3, &options, 11, 3), None);
+        assert_eq!(card_at(3, 3, &options, 12, 3), Some(1));
+        assert_eq!(card_at(3, 3, &options, 30, 7), None);
+        assert_eq!(card_at(3, 3, &options, 40, 0), None);
+        assert_eq!(card_at(5, 3, &options, 13, 8), Some(4));
+        assert_eq!(card_at(5, 3, &options, 25, 8), None);
+        let indexed = options.clone().show_index(true);
+        assert_eq!(card_at(5, 3, &indexed, 0, 7), None);
+        assert_eq!(card_at(5, 3, &indexed, 0, 8), Some(3));
+        assert_eq!(
+            card_at(3, 3, &options.style(RenderStyle::Compact), 0, 0),
+            None
+        );
+    }
+
+    #[test]
+    /// Tests wide backs and borders still draw every line the same width.
+    fn test_render_wide_theme() {
+        let theme = CardTheme {
+            back: "🃏".to_string(),
+            horizontal: '━',
+            ..CardTheme::classic()
+        };
+        let mut cards = hand();
+        cards[1].state = CardState::Hidden;
+        for size in [CardSize::Small, CardSize::Large].iter() {
+            let options = RenderOptions::new()
+                .theme(theme.clone())
+                .size(*size)
+                .color(ColorMode::Never);
+            let out = render_hand(&cards, &options);
+            let widths: Vec<usize> = out.lines().map(width::width).collect();
+            assert!(widths.iter().all(|&line| line == widths[0]), "{}", out);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! This module provides animated dealing and turning over of hands.
+//!
+//! An animation is a list of frames, each a whole hand rendered to a string.
+//! Frames are drawn over one another in place by moving the cursor back up over
+//! the last one, so nothing else on the screen is disturbed. When stdout isn't
+//! a terminal only the last frame is printed.
+
+use super::{columns_for, display_hand_with, write_cards, write_grid, RenderOptions, RenderStyle};
+use crate::cards::Card;
+use crate::error::GameError;
+use console::Term;
+use std::fmt::Write;
+use std::slice;
+use std::time::Duration;
+use tokio::time;
+
+/// How far a dealt card is from its place in each frame as it slides in.
+const SLIDE: &[usize] = &[18, 9, 3, 0];
+
+/// How long each frame of a card sliding in is shown.
+pub const SLIDE_FRAME: Duration = Duration::from_millis(30);
+
+/// How long each card takes to turn over.
+pub const FLIP_FRAME: Duration = Duration::from_millis(150);
+
+/// The frames of `cards` being dealt one at a time in rows of `columns`, each
+/// card sliding in from the right. The compact and narrated styles have no
+/// room to slide, so their cards just appear.
+pub fn deal_frames(cards: &[Card], columns: usize, options: &RenderOptions) -> Vec<String> {
+    let slide = match options.style {
+        RenderStyle::Compact | RenderStyle::Narrated => &SLIDE[SLIDE.len() - 1..],
+        RenderStyle::Boxes => SLIDE,
+    };
+    let mut frames = Vec::new();
+    for dealt in 1..=cards.len() {
+        for &offset in slide {
+            frames.push(slide_frame(
+                &cards[..dealt],
+                columns.max(1),
+                options,
+                offset,
+            ));
+        }
+    }
+    frames
+}
+
+/// Draws `cards` with the last one `offset` columns right of its place.
+fn slide_frame(cards: &[Card], columns: usize, options: &RenderOptions, offset: usize) -> String {
+    let (last, dealt) = cards.split_last().expect("a frame has at least one card");
+    let row_start = dealt.len() / columns * columns;
+    let (above, beside) = dealt.split_at(row_start);
+
+    let mut out = String::new();
+    let mut left = String::new();
+    let mut right = String::new();
+    write_grid(&mut out, above, columns, options).expect("writing to a String cannot fail");
+    write_cards(&mut left, beside, options, row_start).expect("writing to a String cannot fail");
+    write_cards(&mut right, slice::from_ref(last), options, dealt.len())
+        .expect("writing to a String cannot fail");
+
+    let left: Vec<&str> = left.lines().collect();
+    for (idx, line) in right.lines().enumerate() {
+        let before = left.get(idx).copied().unwrap_or("");
+        writeln!(out, "{}{}{}", before, " ".repeat(offset), line)
+            .expect("writing to a String cannot fail");
+    }
+    out
+}
+
+/// Every card in `after` that's face up or down differently in `before`, turned
+/// over one at a time from the left. The last step is `after` itself.
+pub fn flip_steps(before: &[Card], after: &[Card]) -> Vec<Vec<Card>> {
+    let mut current = before.to_vec();
+    let mut steps = Vec::new();
+    for (idx, card) in after.iter().enumerate() {
+        if current.get(idx) != Some(card) {
+            if idx < current.len() {
+                current[idx] = *card;
+            } else {
+                current.push(*card);
+            }
+            steps.push(current.clone());
+        }
+    }
+    steps
+}
+
+/// The frames of the cards in `before` turning over to show `after`, drawn in
+/// rows of `columns`.
+pub fn flip_frames(
+    before: &[Card],
+    after: &[Card],
+    columns: usize,
+    options: &RenderOptions,
+) -> Vec<String> {
+    flip_steps(before, after)
+        .iter()
+        .map(|cards| {
+            let mut out = String::new();
+            write_grid(&mut out, cards, columns, options).expect("writing to a String cannot fail");
+            out
+        })
+        .collect()
+}
+
+/// Draws each frame in turn over the last, waiting `delay` after each one.
+pub async fn play(frames: &[String], delay: Duration) -> Result<(), GameError> {
+    let term = Term::stdout();
+    term.hide_cursor()?;
+    let mut drawn = 0;
+    for frame in frames {
+        if drawn > 0 {
+            term.clear_last_lines(drawn)?;
+        }
+        term.write_str(frame)?;
+        drawn = frame.lines().count();
+        time::sleep(delay).await;
+    }
+    term.show_cursor()?;
+    Ok(())
+}
+
+/// Prints `hand` dealt one card at a time with `frame` between frames, or all at
+/// once when stdout isn't a terminal. `SLIDE_FRAME` is the usual pace.
+pub async fn animate_deal(
+    hand: &[Card],
+    options: &RenderOptions,
+    frame: Duration,
+) -> Result<(), GameError> {
+    match terminal_columns(hand, options) {
+        Some(columns) => {
+            let options = options.resolve();
+            play(&deal_frames(hand, columns, &options), frame).await
+        }
+        None => display_hand_with(hand, options),
+    }
+}
+
+/// Prints `before` turning over into `after` one card at a time with `frame`
+/// between cards, or just `after` when stdout isn't a terminal. `FLIP_FRAME` is
+/// the usual pace.
+pub async fn animate_flip(
+    before: &[Card],
+    after: &[Card],
+    options: &RenderOptions,
+    frame: Duration,
+) -> Result<(), GameError> {
+    match terminal_columns(after, options) {
+        Some(columns) if before.len() == after.len() => {
+            let options = options.resolve();
+            play(&flip_frames(before, after, columns, &options), frame).await
+        }
+        _ => display_hand_with(after, options),
+    }
+}
+
+/// How many cards fit across the terminal, or None if stdout isn't one or the
+/// hand is narrated, since redrawing would repeat every sentence.
+fn terminal_columns(hand: &[Card], options: &RenderOptions) -> Option<usize> {
+    if options.style == RenderStyle::Narrated {
+        return None;
+    }
+    let (_, width) = Term::stdout().size_checked()?;
+    Some(columns_for(width as usize, options).min(hand.len()).max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{deal_frames, flip_frames, flip_steps, SLIDE};
+    use crate::cards::card_printer::{write_grid, RenderOptions, RenderStyle};
+    use crate::cards::{Card, CardState, Rank, Suit};
+
+    fn hand() -> Vec<Card> {
+        let mut cards = vec![
+            Card::new(Suit::Spades, Rank::King),
+            Card::new(Suit::Hearts, Rank::Four),
+            Card::new(Suit::Clubs, Rank::Eight),
+        ];
+        for card in &mut cards {
+            card.state = CardState::Visible;
+        }
+        cards
+    }
+
+    fn grid(cards: &[Card], columns: usize, options: &RenderOptions) -> String {
+        let mut out = String::new();
+        write_grid(&mut out, cards, columns, options).unwrap();
+        out
+    }
+
+    #[test]
+    /// Tests dealt cards slide into the same places they're normally drawn.
+    fn test_deal_frames() {
+        let options = RenderOptions::new().show_index(true);
+        let frames = deal_frames(&hand(), 2, &options);
+        assert_eq!(frames.len(), 3 * SLIDE.len());
+        assert_eq!(frames[SLIDE.len() - 1], grid(&hand()[..1], 2, &options));
+        assert_eq!(frames.last().unwrap(), &grid(&hand(), 2, &options));
+
+        let first = frames[0].lines().next().unwrap();
+        assert!(first.starts_with(&" ".repeat(SLIDE[0])));
+        let sliding = frames[SLIDE.len() * 2].lines().nth(9).unwrap();
+        assert!(sliding.starts_with(&" ".repeat(SLIDE[0])));
+
+        let compact = options.style(RenderStyle::Compact);
+        assert_eq!(deal_frames(&hand(), 3, &compact).len(), 3);
+    }
+
+    #[test]
+    /// Tests cards turn over one at a time from the left.
+    fn test_flip_frames() {
+        let mut hidden = hand();
+        for card in &mut hidden {
+            card.state = CardState::Hidden;
+        }
+        hidden[1].state = CardState::Visible;
+        let steps = flip_steps(&hidden, &hand());
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0][0].state, CardState::Visible);
+        assert_eq!(steps[0][2].state, CardState::Hidden);
+        assert_eq!(steps[1], hand());
+
+        let options = RenderOptions::new();
+        let frames = flip_frames(&hidden, &hand(), 3, &options);
+        assert_eq!(frames.last().unwrap(), &grid(&hand(), 3, &options));
+        assert!(flip_steps(&hand(), &hand()).is_empty());
+    }
+}
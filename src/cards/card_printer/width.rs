@@ -0,0 +1,109 @@
+//! This module provides the display width of terminal text, so card boxes line
+//! up whatever is drawn inside them.
+//!
+//! ANSI escape sequences take no columns. Everything else is measured by
+//! `unicode-width`: combining marks take none, East Asian wide characters and
+//! emoji take two, a narrow symbol followed by the emoji variation selector
+//! (e.g. `♠️`) is widened to two, and an emoji joined to the one before it with a
+//! zero width joiner is drawn as part of it.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Where text sits when padded out to a width.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// Columns a single character takes on its own.
+pub fn char_width(c: char) -> usize {
+    UnicodeWidthChar::width(c).unwrap_or(0)
+}
+
+/// Columns `text` takes in a terminal.
+pub fn width(text: &str) -> usize {
+    let mut visible = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1B}' {
+            skip_escape(&mut chars);
+        } else {
+            visible.push(c);
+        }
+    }
+    UnicodeWidthStr::width(visible.as_str())
+}
+
+/// Skips the rest of an escape sequence. CSI sequences, e.g. colours, run up to a
+/// final byte from `@` to `~`, other escapes are a single character.
+fn skip_escape(chars: &mut std::str::Chars) {
+    if chars.next() != Some('[') {
+        return;
+    }
+    for c in chars {
+        if ('@'..='~').contains(&c) {
+            return;
+        }
+    }
+}
+
+/// Pads `text` with spaces out to `columns`. Text already that wide or wider is
+/// returned as it is.
+pub fn pad(text: &str, columns: usize, align: Align) -> String {
+    let gap = columns.saturating_sub(width(text));
+    let (left, right) = match align {
+        Align::Left => (0, gap),
+        Align::Center => (gap / 2, gap - gap / 2),
+        Align::Right => (gap, 0),
+    };
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(right))
+}
+
+/// Repeats the characters of `pattern` across `columns`, finishing with spaces
+/// where the next character wouldn't fit.
+pub fn fill(pattern: &str, columns: usize) -> String {
+    let mut line = String::new();
+    let mut used = 0;
+    for c in pattern.chars().cycle() {
+        let c_width = char_width(c).max(1);
+        if used + c_width > columns {
+            break;
+        }
+        line.push(c);
+        used += c_width;
+    }
+    line.push_str(&" ".repeat(columns - used));
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fill, pad, width, Align};
+
+    #[test]
+    /// Tests escapes and marks take no room while wide characters take two.
+    fn test_width() {
+        assert_eq!(width("10"), 2);
+        assert_eq!(width("\u{1b}[31m♥\u{1b}[0m"), 1);
+        assert_eq!(width("e\u{301}"), 1);
+        assert_eq!(width("王"), 2);
+        assert_eq!(width("🃏"), 2);
+        assert_eq!(width("♠\u{FE0F}"), 2);
+        assert_eq!(width("👨\u{200D}👩"), 2);
+    }
+
+    #[test]
+    /// Tests padding and filling reach exactly the width asked for.
+    fn test_pad_and_fill() {
+        assert_eq!(pad("K", 5, Align::Left), "K    ");
+        assert_eq!(pad("王", 5, Align::Center), " 王  ");
+        assert_eq!(pad("10", 5, Align::Right), "   10");
+        assert_eq!(pad("toolong", 3, Align::Left), "toolong");
+        assert_eq!(fill(". ", 5), ". . .");
+        assert_eq!(fill("🂠", 5), "🂠🂠🂠🂠🂠");
+        assert_eq!(fill("🃏", 5), "🃏🃏 ");
+        assert_eq!(fill("", 3), "   ");
+    }
+}
@@ -0,0 +1,167 @@
+//! This module provides property tests for cards and decks.
+//!
+//! Each property is checked by `proptest` against randomly generated inputs, and
+//! a failing input is shrunk to the smallest one that still fails. Anything that
+//! shuffles is handed a seed, so a failure reruns the same way every time.
+
+use super::{Card, CardState, Deck, DeckBuilder, Hand, Rank, Suit};
+use crate::scoring::{BlackjackScoring, HighCardScoring, ScoringStrategy, SuitWeightedScoring};
+use proptest::prelude::*;
+use proptest::sample::select;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::cmp::Ordering;
+
+/// Cards in the biggest deck `any_deck` builds: three packs with jokers.
+const LARGEST_DECK: usize = 3 * 54;
+
+/// Any card from a standard deck, face down.
+fn standard_card() -> impl Strategy<Value = Card> {
+    (select(Suit::ALL.to_vec()), select(Rank::ALL.to_vec()))
+        .prop_map(|(suit, rank)| Card::new(suit, rank))
+}
+
+/// Any card, jokers included, face up or down.
+fn any_card() -> impl Strategy<Value = Card> {
+    let card = prop_oneof![
+        19 => standard_card(),
+        1 => Just(Card::new(Suit::Spades, Rank::Joker)),
+    ];
+    let states = select(vec![
+        CardState::Hidden,
+        CardState::Visible,
+        CardState::Selected,
+    ]);
+    (card, states).prop_map(|(mut card, state)| {
+        card.state = state;
+        card
+    })
+}
+
+/// A deck of one to three packs, sometimes with jokers, in a random order.
+fn any_deck() -> impl Strategy<Value = Deck> {
+    (1..=3usize, any::<bool>(), any::<u64>()).prop_map(|(decks, jokers, seed)| {
+        let mut builder = DeckBuilder::new().decks(decks);
+        if jokers {
+            builder = builder.with_jokers();
+        }
+        let mut deck = builder.build().unwrap();
+        deck.shuffle(&mut StdRng::seed_from_u64(seed));
+        deck
+    })
+}
+
+fn sorted<'a, I: IntoIterator<Item = &'a Card>>(cards: I) -> Vec<Card> {
+    let mut cards: Vec<Card> = cards.into_iter().copied().collect();
+    cards.sort();
+    cards
+}
+
+fn strategies() -> Vec<Box<dyn ScoringStrategy>> {
+    vec![
+        Box::new(SuitWeightedScoring),
+        Box::new(HighCardScoring),
+        Box::new(BlackjackScoring),
+    ]
+}
+
+proptest! {
+    #[test]
+    /// Tests shuffling a deck only reorders its cards.
+    fn test_shuffle_is_permutation(mut deck in any_deck(), seed in any::<u64>()) {
+        let before = sorted(deck.iter());
+        deck.shuffle(&mut StdRng::seed_from_u64(seed));
+        prop_assert_eq!(sorted(deck.iter()), before);
+    }
+
+    #[test]
+    /// Tests dealing cards and putting them back leaves the same cards in the deck,
+    /// whether they go under the deck or through the discard pile.
+    fn test_deal_and_return(mut deck in any_deck(), count in 0..=LARGEST_DECK + 5, seed in any::<u64>()) {
+        let before = sorted(deck.iter());
+
+        let mut hand = Hand::new();
+        deck.draw_into(&mut hand, count);
+        prop_assert_eq!(hand.len(), count.min(before.len()));
+        prop_assert_eq!(hand.len() + deck.remaining(), before.len());
+        for card in hand.drain() {
+            deck.place_bottom(card);
+        }
+        prop_assert_eq!(sorted(deck.iter()), before.clone());
+
+        for card in deck.draw(count) {
+            deck.discard(card);
+        }
+        deck.reshuffle_discards(&mut StdRng::seed_from_u64(seed));
+        prop_assert!(deck.discarded().is_empty());
+        prop_assert_eq!(sorted(deck.iter()), before);
+    }
+
+    #[test]
+    /// Tests cards are totally ordered: every pair compares exactly one way, the
+    /// order agrees with equality and it's transitive.
+    fn test_card_order_is_total(a in any_card(), b in any_card(), c in any_card()) {
+        prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+        prop_assert_eq!(a.cmp(&b) == Ordering::Equal, a == b);
+        prop_assert_eq!(a.partial_cmp(&b), Some(a.cmp(&b)));
+        if a <= b && b <= c {
+            prop_assert!(a <= c);
+        }
+        let mut cards = [a, b, c];
+        cards.sort();
+        prop_assert!(cards.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    /// Tests no scoring strategy ranks a card below one that's lower in both suit
+    /// and rank, and the high card of a hand has the best score.
+    fn test_order_agrees_with_scores(
+        a in standard_card(),
+        b in standard_card(),
+        cards in prop::collection::vec(any_card(), 1..=8),
+    ) {
+        let hand = Hand::from_cards(cards);
+        for scoring in strategies() {
+            if a.suit <= b.suit && a.rank <= b.rank {
+                prop_assert!(scoring.score(&a) <= scoring.score(&b), "{:?} {:?}", a, b);
+            }
+            let high = hand.high_card_with(scoring.as_ref()).unwrap();
+            let best = hand.iter().map(|card| scoring.score(card)).max().unwrap();
+            prop_assert_eq!(scoring.score(&hand.cards()[high]), best);
+            prop_assert!(hand.cards()[..high]
+                .iter()
+                .all(|card| scoring.score(card) < best));
+        }
+        prop_assert_eq!(SuitWeightedScoring.score(&a), a.value());
+    }
+
+    #[test]
+    /// Tests cards written out as short codes, long names or JSON read back as the
+    /// same card.
+    fn test_parse_round_trip(card in standard_card(), any in any_card()) {
+        let symbol = card.suit.symbol();
+        let letter = card.suit.letter();
+        for text in [
+            format!("{}{}", card.rank, symbol),
+            format!("{}{}", card.rank, letter),
+            format!("{} of {}", card.rank.name(), card.suit.name()),
+            card.to_string(),
+        ]
+        .iter()
+        {
+            prop_assert_eq!(text.parse::<Card>(), Ok(card), "{}", text);
+        }
+        prop_assert_eq!(card.rank.to_string().parse::<Rank>(), Ok(card.rank));
+
+        let json = serde_json::to_string(&any).unwrap();
+        prop_assert_eq!(serde_json::from_str::<Card>(&json).unwrap(), any);
+    }
+
+    #[test]
+    /// Tests every standard card numbers back to itself, and numbers order cards
+    /// the way a new deck does.
+    fn test_id_round_trip(card in standard_card(), other in standard_card()) {
+        prop_assert_eq!(Card::from_id(card.id()), Some(card));
+        prop_assert_eq!(card.id().cmp(&other.id()), card.cmp_by_rank(&other));
+    }
+}
@@ -0,0 +1,204 @@
+//! This module provides a set of cards kept as one bit per card.
+//!
+//! Each card is the bit numbered by `Card::id`, so asking whether a card is in
+//! the set, or how many cards are, takes the same time however many it holds.
+//! A set holds each card at most once and forgets whether cards were face up.
+
+use super::{Card, Rank, Suit};
+use serde::{Deserialize, Serialize};
+use std::iter::FromIterator;
+
+/// The bits of every card of the lowest suit, one every four bits.
+const SUIT_BITS: u64 = 0x1_1111_1111_1111;
+
+/// The bits of the 52 standard cards.
+const STANDARD_BITS: u64 = (1 << 52) - 1;
+
+/// A set of cards backed by a bitmask. Saved as the list of its cards.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "Vec<Card>", into = "Vec<Card>")]
+pub struct CardSet {
+    bits: u64,
+}
+
+impl CardSet {
+    /// Construct an empty set.
+    pub fn new() -> CardSet {
+        CardSet::default()
+    }
+
+    /// Every card of a standard deck, jokers left out.
+    pub fn standard() -> CardSet {
+        CardSet {
+            bits: STANDARD_BITS,
+        }
+    }
+
+    /// Every standard card of `suit`.
+    pub fn of_suit(suit: Suit) -> CardSet {
+        CardSet {
+            bits: SUIT_BITS << (suit as u8 - 1),
+        }
+    }
+
+    /// Every standard card of `rank`, or an empty set for jokers.
+    pub fn of_rank(rank: Rank) -> CardSet {
+        match rank {
+            Rank::Joker => CardSet::new(),
+            rank => CardSet {
+                bits: 0xF << (rank as u8 * 4),
+            },
+        }
+    }
+
+    fn bit(card: &Card) -> u64 {
+        1 << card.id()
+    }
+
+    /// Adds a card, returning false if it was already in the set.
+    pub fn insert(&mut self, card: Card) -> bool {
+        let added = !self.contains(&card);
+        self.bits |= CardSet::bit(&card);
+        added
+    }
+
+    /// Takes a card out, returning false if it wasn't in the set.
+    pub fn remove(&mut self, card: &Card) -> bool {
+        let removed = self.contains(card);
+        self.bits &= !CardSet::bit(card);
+        removed
+    }
+
+    /// Whether a card of the same suit and rank is in the set.
+    pub fn contains(&self, card: &Card) -> bool {
+        self.bits & CardSet::bit(card) != 0
+    }
+
+    /// How many cards are in the set.
+    pub fn len(&self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    /// Returns true if the set has no cards.
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// The cards in either set.
+    pub fn union(self, other: CardSet) -> CardSet {
+        CardSet {
+            bits: self.bits | other.bits,
+        }
+    }
+
+    /// The cards in both sets.
+    pub fn intersection(self, other: CardSet) -> CardSet {
+        CardSet {
+            bits: self.bits & other.bits,
+        }
+    }
+
+    /// The cards in this set but not in `other`.
+    pub fn difference(self, other: CardSet) -> CardSet {
+        CardSet {
+            bits: self.bits & !other.bits,
+        }
+    }
+
+    /// The cards in the set face down, in the order of their ids.
+    pub fn iter(&self) -> impl Iterator<Item = Card> {
+        let mut bits = self.bits;
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let id = bits.trailing_zeros() as u8;
+            bits &= bits - 1;
+            Card::from_id(id)
+        })
+    }
+}
+
+impl FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(iter: I) -> CardSet {
+        let mut set = CardSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<'a> FromIterator<&'a Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = &'a Card>>(iter: I) -> CardSet {
+        iter.into_iter().copied().collect()
+    }
+}
+
+impl Extend<Card> for CardSet {
+    fn extend<I: IntoIterator<Item = Card>>(&mut self, iter: I) {
+        for card in iter {
+            self.insert(card);
+        }
+    }
+}
+
+impl From<Vec<Card>> for CardSet {
+    fn from(cards: Vec<Card>) -> CardSet {
+        cards.into_iter().collect()
+    }
+}
+
+impl From<CardSet> for Vec<Card> {
+    fn from(set: CardSet) -> Vec<Card> {
+        set.iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CardSet;
+    use crate::cards::{Card, Deck, Rank, Suit};
+
+    #[test]
+    /// Tests cards go in and out of a set once each.
+    fn test_insert_remove() {
+        let ace = Card::new(Suit::Spades, Rank::Ace);
+        let mut set = CardSet::new();
+        assert!(set.insert(ace));
+        assert!(!set.insert(ace));
+        assert!(set.contains(&ace) && set.len() == 1);
+        assert!(set.remove(&ace));
+        assert!(!set.remove(&ace));
+        assert!(set.is_empty());
+
+        let joker = Card::new(Suit::Hearts, Rank::Joker);
+        set.insert(joker);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![joker]);
+        assert!(!CardSet::standard().contains(&joker));
+    }
+
+    #[test]
+    /// Tests sets combine and list their cards in deck order.
+    fn test_set_operations() {
+        let deck: CardSet = Deck::new().iter().collect();
+        assert_eq!(deck, CardSet::standard());
+        assert_eq!(
+            deck.iter().collect::<Vec<_>>(),
+            Deck::new().iter().copied().collect::<Vec<_>>()
+        );
+
+        let hearts = CardSet::of_suit(Suit::Hearts);
+        let kings = CardSet::of_rank(Rank::King);
+        assert_eq!(hearts.len(), 13);
+        assert!(hearts.iter().all(|card| card.suit == Suit::Hearts));
+        assert_eq!(
+            hearts.intersection(kings).iter().collect::<Vec<_>>(),
+            vec![Card::new(Suit::Hearts, Rank::King)]
+        );
+        assert_eq!(hearts.union(kings).len(), 16);
+        assert_eq!(deck.difference(hearts).len(), 39);
+
+        let json = serde_json::to_string(&kings).unwrap();
+        assert!(json.starts_with("[{\"suit\":\"Spades\",\"rank\":\"King\""));
+        assert_eq!(serde_json::from_str::<CardSet>(&json).unwrap(), kings);
+    }
+}
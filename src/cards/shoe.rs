@@ -0,0 +1,225 @@
+//! This module provides a dealer's shoe of several decks shuffled together.
+//!
+//! A cut card is placed part way into the shoe after each shuffle. Once the
+//! cards dealt reach it the shoe wants shuffling, which the dealer does before
+//! the next round rather than part way through one. How far in the cut card
+//! sits is the shoe's penetration. After a shuffle the top cards are burned,
+//! put aside unseen.
+//!
+//! The shoe keeps count of the cards that have been shown since it was
+//! shuffled, rank by rank and as a Hi-Lo running count, for practising card
+//! counting.
+
+use super::{Card, CardState, Deck, DeckBuilder, Rank};
+use crate::error::GameConfigError;
+use rand::Rng;
+
+/// The cards shown since the shoe was last shuffled.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct ShoeStats {
+    /// Times the shoe has been shuffled.
+    pub shuffles: usize,
+    /// Cards dealt since the last shuffle, shown or not.
+    pub dealt: usize,
+    /// Cards burned since the shoe was made.
+    pub burned: usize,
+    /// How many of each rank have been shown, Two first.
+    pub seen: [usize; 13],
+    /// The Hi-Lo count of the cards shown: +1 for Two to Six and -1 for Ten to
+    /// Ace.
+    pub running_count: i32,
+}
+
+impl ShoeStats {
+    /// How many cards of `rank` have been shown since the last shuffle.
+    pub fn seen(&self, rank: Rank) -> usize {
+        match rank {
+            Rank::Joker => 0,
+            rank => self.seen[rank as usize],
+        }
+    }
+
+    /// Counts `card` as shown.
+    fn see(&mut self, card: &Card) {
+        if card.rank == Rank::Joker {
+            return;
+        }
+        self.seen[card.rank as usize] += 1;
+        self.running_count += match card.rank.value() {
+            2..=6 => 1,
+            7..=9 => 0,
+            _ => -1,
+        };
+    }
+}
+
+/// Several decks shuffled together with a cut card.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shoe {
+    deck: Deck,
+    decks: usize,
+    penetration: f64,
+    burn: usize,
+    stats: ShoeStats,
+}
+
+impl Shoe {
+    /// Construct a shoe of `decks` standard decks, cut three quarters of the way
+    /// in and burning one card a shuffle. Fails without any decks. The shoe is in
+    /// order until it's shuffled.
+    pub fn new(decks: usize) -> Result<Shoe, GameConfigError> {
+        Ok(Shoe {
+            deck: DeckBuilder::new().decks(decks).build()?,
+            decks,
+            penetration: 0.75,
+            burn: 1,
+            stats: ShoeStats::default(),
+        })
+    }
+
+    /// Option to place the cut card `penetration` of the way into the shoe, from
+    /// 0 to 1.
+    pub fn penetration(mut self, penetration: f64) -> Shoe {
+        self.penetration = penetration.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Option to burn `burn` cards after each shuffle.
+    pub fn burn(mut self, burn: usize) -> Shoe {
+        self.burn = burn;
+        self
+    }
+
+    pub fn decks(&self) -> usize {
+        self.decks
+    }
+
+    pub fn stats(&self) -> &ShoeStats {
+        &self.stats
+    }
+
+    /// Number of cards left to draw.
+    pub fn remaining(&self) -> usize {
+        self.deck.remaining()
+    }
+
+    /// How many cards are dealt from a fresh shuffle before the cut card comes
+    /// out, burned cards included.
+    pub fn cut(&self) -> usize {
+        let total = self.decks * 52;
+        (total as f64 * self.penetration).round() as usize
+    }
+
+    /// Returns true once the cut card has come out.
+    pub fn needs_shuffle(&self) -> bool {
+        self.stats.dealt + self.burn >= self.cut()
+    }
+
+    /// Gathers every card back into the shoe, shuffles it and burns the top
+    /// cards. The count starts again.
+    pub fn shuffle<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.deck.reset();
+        self.deck.shuffle(rng);
+        let burned = self.deck.draw(self.burn).len();
+        self.stats = ShoeStats {
+            shuffles: self.stats.shuffles + 1,
+            burned: self.stats.burned + burned,
+            ..ShoeStats::default()
+        };
+    }
+
+    /// Deals the top card face up and counts it as shown. When the shoe runs dry
+    /// part way through a round the discards are shuffled back in first. None if
+    /// there's nothing left to deal at all.
+    pub fn deal_face_up<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<Card> {
+        let mut card = self.deal_face_down(rng)?;
+        card.state = CardState::Visible;
+        self.stats.see(&card);
+        Some(card)
+    }
+
+    /// Deals the top card face down, unseen until it's turned over.
+    pub fn deal_face_down<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Option<Card> {
+        if self.deck.is_empty() {
+            self.deck.reshuffle_discards(rng);
+        }
+        let mut card = self.deck.draw(1).pop()?;
+        card.state = CardState::Hidden;
+        self.stats.dealt += 1;
+        Some(card)
+    }
+
+    /// Counts a card dealt face down as shown once it's turned over.
+    pub fn turn_over(&mut self, card: &Card) {
+        self.stats.see(card);
+    }
+
+    /// Places a played card on the discard pile.
+    pub fn discard(&mut self, card: Card) {
+        self.deck.discard(card);
+    }
+
+    /// The running count divided by the decks left to deal, the figure counters
+    /// bet by. Never divides by less than half a deck.
+    pub fn true_count(&self) -> f64 {
+        let decks_left = (self.remaining() as f64 / 52.0).max(0.5);
+        self.stats.running_count as f64 / decks_left
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Shoe;
+    use crate::cards::{CardState, Rank};
+    use crate::error::GameConfigError;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    /// Tests the cut card and burned cards decide when the shoe wants shuffling.
+    fn test_cut_card() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut shoe = Shoe::new(6).unwrap().penetration(0.5).burn(2);
+        assert_eq!(shoe.cut(), 156);
+        shoe.shuffle(&mut rng);
+        assert_eq!(shoe.remaining(), 310);
+        assert_eq!((shoe.stats().shuffles, shoe.stats().burned), (1, 2));
+
+        for _ in 0..153 {
+            shoe.deal_face_up(&mut rng).unwrap();
+        }
+        assert!(!shoe.needs_shuffle());
+        shoe.deal_face_up(&mut rng).unwrap();
+        assert!(shoe.needs_shuffle());
+        shoe.shuffle(&mut rng);
+        assert!(!shoe.needs_shuffle());
+        assert_eq!((shoe.stats().dealt, shoe.stats().burned), (0, 4));
+        assert_eq!(Shoe::new(0), Err(GameConfigError::NoDecks));
+    }
+
+    #[test]
+    /// Tests only cards shown are counted, and the count adds up to zero over a
+    /// whole shoe.
+    fn test_count() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut shoe = Shoe::new(2).unwrap().burn(0).penetration(1.0);
+        shoe.shuffle(&mut rng);
+        let hidden = shoe.deal_face_down(&mut rng).unwrap();
+        assert_eq!(hidden.state, CardState::Hidden);
+        assert_eq!(shoe.stats().seen.iter().sum::<usize>(), 0);
+        shoe.turn_over(&hidden);
+        while let Some(card) = shoe.deal_face_up(&mut rng) {
+            shoe.discard(card);
+            if shoe.remaining() == 0 {
+                break;
+            }
+        }
+        assert_eq!(shoe.stats().running_count, 0);
+        assert_eq!(shoe.stats().seen(Rank::Ace), 8);
+        assert_eq!(shoe.stats().dealt, 104);
+
+        // An empty shoe deals from its shuffled discards.
+        assert!(shoe.deal_face_up(&mut rng).is_some());
+        assert_eq!(shoe.remaining(), 102);
+    }
+}
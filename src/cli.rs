@@ -0,0 +1,966 @@
+//! This module provides command line parsing for the game binary.
+//!
+//! Arguments are a subcommand followed by `--flag value` options, parsed with
+//! `clap` and turned into a `Command`. Running without any arguments opens the
+//! menu, and running with only options plays the high card game. Help is the
+//! hand-written `USAGE` rather than clap's.
+
+use crate::cards::card_printer::{CardTheme, ColorMode, SuitStyle};
+use crate::game::{Difficulty, RoundLimit, Speed};
+use crate::locale::Locale;
+#[cfg(feature = "net")]
+use crate::net::{NetGame, DEFAULT_PORT};
+use crate::opponent::Skill;
+use crate::rules::{AceRule, Ruleset, SuitTiebreak};
+use crate::simulator::SimGame;
+use crate::strategy;
+use crate::tournament::Format;
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::{Arg, ArgAction, ArgMatches};
+use std::fmt::{self, Display, Formatter};
+use std::iter;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Help text printed by `card_game help`.
+pub const USAGE: &str = "\
+Usage: card_game [COMMAND] [OPTIONS]
+
+Run without arguments to pick a game from the menu.
+
+Commands:
+  play                 Find the high card (the default with options)
+  replay FILE          Step through a session saved with --record
+  daily                Play today's daily challenge, once a day
+  tutorial             Learn the high card game with a guided round
+  rules                Explain how cards are ranked and scored
+  simulate             Estimate how often strategies win over many hands
+  tournament           Play the computer's strategies against each other
+  stats                Print lifetime stats
+  leaderboard          Rank every player profile
+  deck show            Print every card in a deck
+  blackjack            Play blackjack against the dealer
+  poker                Play Five Card Draw against the dealer
+  war                  Play War against the computer
+  memory               Find the matching pairs
+  hi-lo                Guess whether the next card is higher or lower
+  solitaire            Play Klondike solitaire
+  go-fish              Play Go Fish against the computer
+  holdem               Play Texas Hold'em against 1 to 3 opponents
+  host                 Host a match for another player to join
+  join HOST:PORT       Join a hosted match
+  help                 Print this help
+
+Play options:
+  --cards N            Cards dealt each round, 3 to 5
+  --seed N             Seed the shuffles to replay the same game
+  --players N          Players taking turns, 1 to 4
+  --difficulty NAME    easy, normal or hard
+  --speed NAME         slow, normal, fast or instant
+  --rounds N           Play N rounds, or endless until you quit
+  --theme NAME         classic, rounded, casino or minimal
+  --color MODE         auto, always, never or high-contrast
+  --suits STYLE        auto, symbols, letters or words
+  --opponent SKILL     Play against the computer: random, suit or counting
+  --aces RULE          high or low
+  --tiebreak ORDER     Suit order for same-rank cards: points, bridge,
+                       alternating or none
+  --chips N            Bet on each round starting with N chips
+  --payout N           Chips paid per chip on a winning bet
+  --timeout SECONDS    Pick a card in time or get a random one
+  --plain              Use the line-by-line prompt
+  --practice           Allow retrying a round with [u]
+  --hints              Show each card's chance of being high before guessing
+  --values             Show each card's score once the hand is turned over
+  --confirm            Ask before locking in a pick
+  --debug              Take debug commands such as `show deck` at the prompt
+  --autoplay           Let the computer play every pick while you watch
+  --strategy NAME      Who plays with --autoplay: first, random, suit or
+                       counting, the default
+  --no-animation       Deal and turn over cards without animating them
+  --screen-reader      Describe cards in sentences instead of drawing them
+  --locale NAME        en or es, taken from LANG by default
+  --player NAME        Track stats, achievements, chips and rating for NAME
+  --resume             Continue the saved game
+  --record FILE        Write the session to FILE when it ends
+  --log-file FILE      Log every step of the game to FILE as JSON lines
+  --deck-file FILE     Deal the cards in the order FILE lists them
+  --spectate PORT      Let browsers watch on PORT, with the events feature
+  --spectate-public    Let other machines watch too, not just this one
+  --mute               Turn off sound effects, with the audio feature
+
+Other options:
+  replay --watch       Advance rounds automatically
+  stats --player NAME  Print one player's stats, achievements and rating
+  deck show --decks N  Combine N decks
+  deck show --jokers   Add two jokers per deck
+  blackjack, poker, war, memory, hi-lo, solitaire,
+  go-fish, holdem --seed N
+  go-fish --hot-seat   Two players take turns at the keyboard
+  blackjack --decks N  Decks in the shoe, 1 to 8
+  blackjack --penetration PERCENT
+                       How far into the shoe the cut card goes, 75 by default
+  blackjack --count    Show the running and true count after each round
+  war --auto           Play every round automatically
+  war, hi-lo, rules --aces RULE, --tiebreak ORDER
+  holdem --opponents N Seats against you, 1 to 3
+  poker, holdem --hand-history FILE
+                       Add each hand to FILE in PokerStars' format
+  simulate --game NAME high-card or blackjack
+  simulate --strategy NAME
+                       Simulate only NAME, given once per strategy: first,
+                       random, suit or counting, or for blackjack stand,
+                       dealer or basic
+  simulate --hands N   Hands per strategy, a million by default
+  simulate --threads N Threads to share the hands, all cores by default
+  simulate --seed N    Seed the hands to repeat the results
+  tournament --format NAME
+                       round-robin or knockout
+  tournament --strategy NAME
+                       Enter NAME, given once per entrant: first, random,
+                       suit or counting
+  tournament --games N Games a match, 10 by default
+  tournament --seed N  Seed the deals to repeat the results
+  host --port N        Port to listen on, 7777 by default
+  host --game NAME     high-card or war
+  host --seed N        Seed the host's shuffles
+  host, join --player NAME
+                       Rate the match for NAME if the other player has a
+                       profile too";
+
+/// Options for the high card game.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayOptions {
+    pub cards: Option<u8>,
+    pub seed: Option<u64>,
+    pub players: Option<u8>,
+    pub difficulty: Option<Difficulty>,
+    pub speed: Option<Speed>,
+    pub rounds: Option<RoundLimit>,
+    pub theme: Option<String>,
+    pub color: Option<ColorMode>,
+    pub suits: Option<SuitStyle>,
+    pub opponent: Option<Skill>,
+    pub rules: Option<Ruleset>,
+    pub chips: Option<u64>,
+    pub payout: Option<u64>,
+    pub timeout: Option<u64>,
+    pub plain: bool,
+    pub practice: bool,
+    pub hints: bool,
+    pub values: bool,
+    pub confirm: bool,
+    pub debug: bool,
+    pub autoplay: bool,
+    /// The strategy playing with `autoplay`, counting if none is given.
+    pub strategy: Option<String>,
+    pub no_animation: bool,
+    pub screen_reader: bool,
+    pub locale: Option<Locale>,
+    pub player: Option<String>,
+    pub resume: bool,
+    pub record: Option<String>,
+    pub log_file: Option<String>,
+    pub deck_file: Option<String>,
+    pub spectate: Option<u16>,
+    /// Listen for spectators on every interface instead of only loopback.
+    pub spectate_public: bool,
+    pub mute: bool,
+}
+
+/// What the binary was asked to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Menu,
+    Play(PlayOptions),
+    Replay {
+        path: String,
+        watch: bool,
+    },
+    Daily,
+    Tutorial,
+    Rules(Ruleset),
+    Simulate {
+        game: SimGame,
+        strategies: Vec<String>,
+        hands: u64,
+        threads: Option<usize>,
+        seed: Option<u64>,
+    },
+    Tournament {
+        format: Format,
+        strategies: Vec<String>,
+        games: usize,
+        seed: Option<u64>,
+    },
+    Stats {
+        player: Option<String>,
+    },
+    Leaderboard,
+    DeckShow {
+        decks: usize,
+        jokers: bool,
+    },
+    Blackjack {
+        seed: Option<u64>,
+        decks: usize,
+        /// How far into the shoe the cut card goes, in percent.
+        penetration: u8,
+        count: bool,
+    },
+    Poker {
+        seed: Option<u64>,
+        hand_history: Option<String>,
+    },
+    War {
+        seed: Option<u64>,
+        auto: bool,
+        rules: Ruleset,
+    },
+    Memory {
+        seed: Option<u64>,
+    },
+    HiLo {
+        seed: Option<u64>,
+        rules: Ruleset,
+    },
+    Solitaire {
+        seed: Option<u64>,
+    },
+    GoFish {
+        seed: Option<u64>,
+        hot_seat: bool,
+    },
+    Holdem {
+        seed: Option<u64>,
+        opponents: usize,
+        hand_history: Option<String>,
+    },
+    #[cfg(feature = "net")]
+    Host {
+        port: u16,
+        game: NetGame,
+        seed: Option<u64>,
+        player: Option<String>,
+    },
+    #[cfg(feature = "net")]
+    Join {
+        addr: String,
+        player: Option<String>,
+    },
+    Help,
+}
+
+/// Why the arguments couldn't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    UnknownCommand(String),
+    UnknownOption(String),
+    MissingValue(String),
+    InvalidValue { option: String, value: String },
+}
+
+impl Display for CliError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            CliError::UnknownCommand(command) => write!(formatter, "unknown command {}", command),
+            CliError::UnknownOption(option) => write!(formatter, "unknown option {}", option),
+            CliError::MissingValue(option) => write!(formatter, "{} needs a value", option),
+            CliError::InvalidValue { option, value } => {
+                write!(formatter, "{} isn't a valid value for {}", value, option)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// The error clap found in the arguments to `command`.
+fn error(command: &str, err: clap::Error) -> CliError {
+    let context = |kind| match err.get(kind) {
+        Some(ContextValue::String(value)) => value.clone(),
+        _ => String::new(),
+    };
+    // clap names an option along with its value, e.g. `--cards <cards>`.
+    let option = context(ContextKind::InvalidArg)
+        .split(' ')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    match err.kind() {
+        ErrorKind::InvalidSubcommand => match context(ContextKind::InvalidSubcommand) {
+            name if name == command => CliError::UnknownCommand(name),
+            name => CliError::UnknownCommand(format!("{} {}", command, name)),
+        },
+        ErrorKind::InvalidValue | ErrorKind::ValueValidation => {
+            match context(ContextKind::InvalidValue) {
+                value if value.is_empty() => CliError::MissingValue(option),
+                value => CliError::InvalidValue { option, value },
+            }
+        }
+        ErrorKind::MissingRequiredArgument | ErrorKind::MissingSubcommand => {
+            CliError::MissingValue(command.to_string())
+        }
+        _ => CliError::UnknownOption(option),
+    }
+}
+
+/// A `--name value` option whose value is read by `parse`.
+fn named<T>(name: &'static str, parse: fn(&str) -> Option<T>) -> Arg
+where
+    T: Clone + Send + Sync + 'static,
+{
+    Arg::new(name)
+        .long(name)
+        .value_parser(move |value: &str| parse(value).ok_or("not a known name"))
+}
+
+/// A `--name N` option whose value is a number within `range`.
+fn number<T>(name: &'static str, range: RangeInclusive<T>) -> Arg
+where
+    T: FromStr + PartialOrd + Clone + Send + Sync + 'static,
+{
+    Arg::new(name)
+        .long(name)
+        .value_parser(move |value: &str| match value.parse() {
+            Ok(number) if range.contains(&number) => Ok(number),
+            _ => Err("out of range"),
+        })
+}
+
+/// A `--name value` option taking any text, such as a file or player name.
+fn text(name: &'static str) -> Arg {
+    Arg::new(name).long(name)
+}
+
+/// A `--name` option that's either given or not.
+fn switch(name: &'static str) -> Arg {
+    Arg::new(name).long(name).action(ArgAction::SetTrue)
+}
+
+/// A built-in strategy's name.
+fn strategy_name(name: &str) -> Option<String> {
+    strategy::NAMES.contains(&name).then(|| name.to_string())
+}
+
+fn seed() -> Arg {
+    number("seed", 0..=u64::MAX)
+}
+
+/// `--aces` and `--tiebreak`.
+fn rule_args() -> [Arg; 2] {
+    [
+        named("aces", AceRule::named),
+        named("tiebreak", SuitTiebreak::named),
+    ]
+}
+
+/// A subcommand whose options can be given more than once, the last one
+/// winning. Help is printed from `USAGE` rather than by clap.
+fn subcommand(name: &'static str) -> clap::Command {
+    clap::Command::new(name)
+        .disable_help_flag(true)
+        .args_override_self(true)
+}
+
+/// Every subcommand and the options each one takes.
+fn commands() -> clap::Command {
+    let play = subcommand("play").args([
+        number("cards", 3..=5u8),
+        seed(),
+        number("players", 1..=4u8),
+        named("difficulty", Difficulty::named),
+        named("speed", Speed::named),
+        named("rounds", RoundLimit::named),
+        named("theme", |name| {
+            CardTheme::named(name).map(|_| name.to_string())
+        }),
+        named("color", ColorMode::named),
+        named("suits", SuitStyle::named),
+        named("opponent", Skill::named),
+        number("chips", 1..=u64::MAX),
+        number("payout", 1..=1000u64),
+        number("timeout", 1..=3600u64),
+        switch("plain"),
+        switch("practice"),
+        switch("hints"),
+        switch("values"),
+        switch("confirm"),
+        switch("debug"),
+        switch("autoplay"),
+        named("strategy", strategy_name),
+        switch("no-animation"),
+        switch("screen-reader"),
+        named("locale", Locale::named),
+        text("player"),
+        switch("resume"),
+        text("record"),
+        text("log-file"),
+        text("deck-file"),
+    ]);
+    let play = play.args(rule_args());
+    #[cfg(feature = "events")]
+    let play = play.args([number("spectate", 1..=u16::MAX), switch("spectate-public")]);
+    #[cfg(feature = "audio")]
+    let play = play.arg(switch("mute"));
+
+    let commands =
+        clap::Command::new("card_game")
+            .disable_help_flag(true)
+            .disable_help_subcommand(true)
+            .subcommand_required(true)
+            .subcommand(play)
+            .subcommand(
+                subcommand("replay")
+                    .arg(Arg::new("path").value_name("FILE").required(true))
+                    .arg(switch("watch")),
+            )
+            .subcommand(subcommand("daily"))
+            .subcommand(subcommand("tutorial"))
+            .subcommand(subcommand("rules").args(rule_args()))
+            .subcommand(subcommand("simulate").args([
+                named("game", SimGame::named),
+                text("strategy").action(ArgAction::Append),
+                number("hands", 1..=u64::MAX),
+                number("threads", 1..=256usize),
+                seed(),
+            ]))
+            .subcommand(subcommand("tournament").args([
+                named("format", Format::named),
+                named("strategy", strategy_name).action(ArgAction::Append),
+                number("games", 1..=10_000usize),
+                seed(),
+            ]))
+            .subcommand(subcommand("stats").arg(text("player")))
+            .subcommand(subcommand("leaderboard"))
+            .subcommand(subcommand("deck").subcommand_required(true).subcommand(
+                subcommand("show").args([number("decks", 1..=8usize), switch("jokers")]),
+            ))
+            .subcommand(subcommand("blackjack").args([
+                seed(),
+                number("decks", 1..=8usize),
+                number("penetration", 10..=100u8),
+                switch("count"),
+            ]))
+            .subcommand(subcommand("poker").args([seed(), text("hand-history")]))
+            .subcommand(
+                subcommand("war")
+                    .args([seed(), switch("auto")])
+                    .args(rule_args()),
+            )
+            .subcommand(subcommand("memory").arg(seed()))
+            .subcommand(subcommand("hi-lo").arg(seed()).args(rule_args()))
+            .subcommand(subcommand("solitaire").arg(seed()))
+            .subcommand(subcommand("go-fish").args([seed(), switch("hot-seat")]))
+            .subcommand(subcommand("holdem").args([
+                seed(),
+                number("opponents", 1..=3usize),
+                text("hand-history"),
+            ]))
+            .subcommand(subcommand("help"));
+    #[cfg(feature = "net")]
+    let commands = commands
+        .subcommand(subcommand("host").args([
+            number("port", 1..=u16::MAX),
+            named("game", NetGame::named),
+            seed(),
+            text("player"),
+        ]))
+        .subcommand(
+            subcommand("join")
+                .arg(Arg::new("addr").value_name("HOST:PORT").required(true))
+                .arg(text("player")),
+        );
+    commands
+}
+
+/// Parses the arguments after the program name.
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, CliError> {
+    let mut args: Vec<String> = args.into_iter().collect();
+    if args.is_empty() {
+        return Ok(Command::Menu);
+    }
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        return Ok(Command::Help);
+    }
+    if args[0].starts_with("--") {
+        args.insert(0, "play".to_string());
+    }
+    let command = args[0].clone();
+    let matches = commands()
+        .try_get_matches_from(iter::once("card_game".to_string()).chain(args))
+        .map_err(|err| error(&command, err))?;
+    let (command, args) = matches.subcommand().expect("a subcommand is required");
+    let seed = || one(args, "seed");
+
+    Ok(match command {
+        "play" => Command::Play(play_options(args)),
+        "replay" => Command::Replay {
+            path: one(args, "path").expect("the path is required"),
+            watch: args.get_flag("watch"),
+        },
+        "daily" => Command::Daily,
+        "tutorial" => Command::Tutorial,
+        "rules" => Command::Rules(rules(args, Ruleset::high_card())),
+        "simulate" => {
+            let game = one(args, "game").unwrap_or_default();
+            let strategies = many(args, "strategy");
+            if let Some(value) = strategies
+                .iter()
+                .find(|name| !SimGame::strategies(&game).contains(&name.as_str()))
+            {
+                return Err(CliError::InvalidValue {
+                    option: "--strategy".to_string(),
+                    value: value.clone(),
+                });
+            }
+            Command::Simulate {
+                game,
+                strategies,
+                hands: one(args, "hands").unwrap_or(1_000_000),
+                threads: one(args, "threads"),
+                seed: seed(),
+            }
+        }
+        "tournament" => Command::Tournament {
+            format: one(args, "format").unwrap_or_default(),
+            strategies: many(args, "strategy"),
+            games: one(args, "games").unwrap_or(10),
+            seed: seed(),
+        },
+        "stats" => Command::Stats {
+            player: one(args, "player"),
+        },
+        "leaderboard" => Command::Leaderboard,
+        "deck" => {
+            let (_, args) = args.subcommand().expect("show is required");
+            Command::DeckShow {
+                decks: one(args, "decks").unwrap_or(1),
+                jokers: args.get_flag("jokers"),
+            }
+        }
+        "blackjack" => Command::Blackjack {
+            seed: seed(),
+            decks: one(args, "decks").unwrap_or(1),
+            penetration: one(args, "penetration").unwrap_or(75),
+            count: args.get_flag("count"),
+        },
+        "poker" => Command::Poker {
+            seed: seed(),
+            hand_history: one(args, "hand-history"),
+        },
+        "war" => Command::War {
+            seed: seed(),
+            auto: args.get_flag("auto"),
+            rules: rules(args, Ruleset::new()),
+        },
+        "memory" => Command::Memory { seed: seed() },
+        "hi-lo" => Command::HiLo {
+            seed: seed(),
+            rules: rules(args, Ruleset::new()),
+        },
+        "solitaire" => Command::Solitaire { seed: seed() },
+        "go-fish" => Command::GoFish {
+            seed: seed(),
+            hot_seat: args.get_flag("hot-seat"),
+        },
+        "holdem" => Command::Holdem {
+            seed: seed(),
+            opponents: one(args, "opponents").unwrap_or(2),
+            hand_history: one(args, "hand-history"),
+        },
+        #[cfg(feature = "net")]
+        "host" => Command::Host {
+            port: one(args, "port").unwrap_or(DEFAULT_PORT),
+            game: one(args, "game").unwrap_or_default(),
+            seed: seed(),
+            player: one(args, "player"),
+        },
+        #[cfg(feature = "net")]
+        "join" => Command::Join {
+            addr: one(args, "addr").expect("the address is required"),
+            player: one(args, "player"),
+        },
+        _ => Command::Help,
+    })
+}
+
+fn play_options(args: &ArgMatches) -> PlayOptions {
+    let given = |name| args.contains_id(name);
+    let options = PlayOptions {
+        cards: one(args, "cards"),
+        seed: one(args, "seed"),
+        players: one(args, "players"),
+        difficulty: one(args, "difficulty"),
+        speed: one(args, "speed"),
+        rounds: one(args, "rounds"),
+        theme: one(args, "theme"),
+        color: one(args, "color"),
+        suits: one(args, "suits"),
+        opponent: one(args, "opponent"),
+        rules: (given("aces") || given("tiebreak")).then(|| rules(args, Ruleset::high_card())),
+        chips: one(args, "chips"),
+        payout: one(args, "payout"),
+        timeout: one(args, "timeout"),
+        plain: args.get_flag("plain"),
+        practice: args.get_flag("practice"),
+        hints: args.get_flag("hints"),
+        values: args.get_flag("values"),
+        confirm: args.get_flag("confirm"),
+        debug: args.get_flag("debug"),
+        autoplay: args.get_flag("autoplay"),
+        strategy: one(args, "strategy"),
+        no_animation: args.get_flag("no-animation"),
+        screen_reader: args.get_flag("screen-reader"),
+        locale: one(args, "locale"),
+        player: one(args, "player"),
+        resume: args.get_flag("resume"),
+        record: one(args, "record"),
+        log_file: one(args, "log-file"),
+        deck_file: one(args, "deck-file"),
+        ..PlayOptions::default()
+    };
+    #[cfg(feature = "events")]
+    let options = PlayOptions {
+        spectate: one(args, "spectate"),
+        spectate_public: args.get_flag("spectate-public"),
+        ..options
+    };
+    #[cfg(feature = "audio")]
+    let options = PlayOptions {
+        mute: args.get_flag("mute"),
+        ..options
+    };
+    options
+}
+
+/// `rules` with the ace rule and suit tiebreak given by `--aces` and
+/// `--tiebreak`.
+fn rules(args: &ArgMatches, mut rules: Ruleset) -> Ruleset {
+    if let Some(aces) = one(args, "aces") {
+        rules.aces = aces;
+    }
+    if let Some(tiebreak) = one(args, "tiebreak") {
+        rules.tiebreak = tiebreak;
+    }
+    rules
+}
+
+/// The value given for the option `name`, if it was.
+fn one<T: Clone + Send + Sync + 'static>(args: &ArgMatches, name: &str) -> Option<T> {
+    args.get_one::<T>(name).cloned()
+}
+
+/// Every value given for the option `name`, in order.
+fn many(args: &ArgMatches, name: &str) -> Vec<String> {
+    args.get_many::<String>(name)
+        .map_or_else(Vec::new, |values| values.cloned().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, CliError, Command, PlayOptions};
+    use crate::cards::card_printer::SuitStyle;
+    use crate::game::{Difficulty, RoundLimit, Speed};
+    use crate::locale::Locale;
+    #[cfg(feature = "net")]
+    use crate::net::NetGame;
+    use crate::rules::{AceRule, Ruleset, SuitTiebreak};
+    use crate::simulator::SimGame;
+    use crate::tournament::Format;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    /// Tests subcommands and play options are parsed.
+    fn test_parse() {
+        assert_eq!(parse(args("")), Ok(Command::Menu));
+        assert_eq!(
+            parse(args("play")),
+            Ok(Command::Play(PlayOptions::default()))
+        );
+        #[cfg(feature = "events")]
+        assert_eq!(
+            parse(args("play --spectate 8080")),
+            Ok(Command::Play(PlayOptions {
+                spectate: Some(8080),
+                ..PlayOptions::default()
+            }))
+        );
+        #[cfg(feature = "events")]
+        assert_eq!(
+            parse(args("play --spectate 8080 --spectate-public")),
+            Ok(Command::Play(PlayOptions {
+                spectate: Some(8080),
+                spectate_public: true,
+                ..PlayOptions::default()
+            }))
+        );
+        #[cfg(feature = "audio")]
+        assert_eq!(
+            parse(args("play --mute")),
+            Ok(Command::Play(PlayOptions {
+                mute: true,
+                ..PlayOptions::default()
+            }))
+        );
+        assert_eq!(
+            parse(args(
+                "play --cards 5 --seed 42 --difficulty hard --theme casino --plain --practice \
+                 --hints --values --confirm --debug --no-animation --screen-reader --locale es \
+                 --speed fast"
+            )),
+            Ok(Command::Play(PlayOptions {
+                cards: Some(5),
+                seed: Some(42),
+                difficulty: Some(Difficulty::Hard),
+                speed: Some(Speed::Fast),
+                theme: Some("casino".to_string()),
+                plain: true,
+                practice: true,
+                hints: true,
+                values: true,
+                confirm: true,
+                debug: true,
+                no_animation: true,
+                screen_reader: true,
+                locale: Some(Locale::Spanish),
+                ..PlayOptions::default()
+            }))
+        );
+        assert_eq!(
+            parse(args("--autoplay --strategy suit --rounds endless")),
+            Ok(Command::Play(PlayOptions {
+                autoplay: true,
+                strategy: Some("suit".to_string()),
+                rounds: Some(RoundLimit::Endless),
+                ..PlayOptions::default()
+            }))
+        );
+        assert!(parse(args("--autoplay --strategy human")).is_err());
+        assert_eq!(
+            parse(args("--rounds endless")),
+            Ok(Command::Play(PlayOptions {
+                rounds: Some(RoundLimit::Endless),
+                ..PlayOptions::default()
+            }))
+        );
+        assert_eq!(
+            parse(args("--aces low --suits letters")),
+            Ok(Command::Play(PlayOptions {
+                rules: Some(Ruleset::high_card().aces(AceRule::Low)),
+                suits: Some(SuitStyle::Letters),
+                ..PlayOptions::default()
+            }))
+        );
+        assert_eq!(
+            parse(args("play --rounds 0")),
+            Err(CliError::InvalidValue {
+                option: "--rounds".to_string(),
+                value: "0".to_string()
+            })
+        );
+        assert_eq!(
+            parse(args("--seed 1")),
+            Ok(Command::Play(PlayOptions {
+                seed: Some(1),
+                ..PlayOptions::default()
+            }))
+        );
+        assert_eq!(parse(args("stats")), Ok(Command::Stats { player: None }));
+        assert_eq!(
+            parse(args("stats --player alice")),
+            Ok(Command::Stats {
+                player: Some("alice".to_string())
+            })
+        );
+        assert_eq!(parse(args("leaderboard")), Ok(Command::Leaderboard));
+        assert_eq!(
+            parse(args("--log-file game.jsonl --deck-file deck.txt")),
+            Ok(Command::Play(PlayOptions {
+                log_file: Some("game.jsonl".to_string()),
+                deck_file: Some("deck.txt".to_string()),
+                ..PlayOptions::default()
+            }))
+        );
+        assert_eq!(
+            parse(args("--player bob --chips 50")),
+            Ok(Command::Play(PlayOptions {
+                player: Some("bob".to_string()),
+                chips: Some(50),
+                ..PlayOptions::default()
+            }))
+        );
+        assert_eq!(parse(args("daily")), Ok(Command::Daily));
+        assert_eq!(parse(args("tutorial")), Ok(Command::Tutorial));
+        assert_eq!(
+            parse(args("rules --tiebreak bridge")),
+            Ok(Command::Rules(
+                Ruleset::high_card().tiebreak(SuitTiebreak::Bridge)
+            ))
+        );
+        assert_eq!(
+            parse(args(
+                "simulate --game blackjack --strategy basic --strategy stand --hands 500 \
+                 --threads 2 --seed 9"
+            )),
+            Ok(Command::Simulate {
+                game: SimGame::Blackjack,
+                strategies: vec!["basic".to_string(), "stand".to_string()],
+                hands: 500,
+                threads: Some(2),
+                seed: Some(9)
+            })
+        );
+        assert_eq!(
+            parse(args(
+                "tournament --format knockout --strategy suit --strategy first --games 3 \
+                 --seed 4"
+            )),
+            Ok(Command::Tournament {
+                format: Format::Knockout,
+                strategies: vec!["suit".to_string(), "first".to_string()],
+                games: 3,
+                seed: Some(4)
+            })
+        );
+        assert_eq!(
+            parse(args("blackjack --decks 6 --penetration 80 --count")),
+            Ok(Command::Blackjack {
+                seed: None,
+                decks: 6,
+                penetration: 80,
+                count: true
+            })
+        );
+        assert_eq!(parse(args("poker --help")), Ok(Command::Help));
+        assert_eq!(
+            parse(args("deck show --jokers")),
+            Ok(Command::DeckShow {
+                decks: 1,
+                jokers: true
+            })
+        );
+        assert_eq!(
+            parse(args("war --auto --seed 3 --tiebreak bridge")),
+            Ok(Command::War {
+                seed: Some(3),
+                auto: true,
+                rules: Ruleset::new().tiebreak(SuitTiebreak::Bridge)
+            })
+        );
+        assert_eq!(
+            parse(args("memory --seed 7")),
+            Ok(Command::Memory { seed: Some(7) })
+        );
+        assert_eq!(
+            parse(args("hi-lo --aces low")),
+            Ok(Command::HiLo {
+                seed: None,
+                rules: Ruleset::new().aces(AceRule::Low)
+            })
+        );
+        assert_eq!(
+            parse(args("holdem --opponents 3")),
+            Ok(Command::Holdem {
+                seed: None,
+                opponents: 3,
+                hand_history: None,
+            })
+        );
+        assert_eq!(
+            parse(args("poker --seed 4 --hand-history hands.txt")),
+            Ok(Command::Poker {
+                seed: Some(4),
+                hand_history: Some("hands.txt".to_string()),
+            })
+        );
+        assert!(parse(args("holdem --opponents 4")).is_err());
+        #[cfg(feature = "net")]
+        assert_eq!(
+            parse(args("host --port 9000 --game war")),
+            Ok(Command::Host {
+                port: 9000,
+                game: NetGame::War,
+                seed: None,
+                player: None
+            })
+        );
+        #[cfg(feature = "net")]
+        assert_eq!(
+            parse(args("join localhost:9000 --player bob")),
+            Ok(Command::Join {
+                addr: "localhost:9000".to_string(),
+                player: Some("bob".to_string())
+            })
+        );
+        assert_eq!(
+            parse(args("replay game.json --watch")),
+            Ok(Command::Replay {
+                path: "game.json".to_string(),
+                watch: true
+            })
+        );
+    }
+
+    #[test]
+    /// Tests bad arguments are reported instead of panicking.
+    fn test_parse_errors() {
+        assert_eq!(
+            parse(args("play --cards 9")),
+            Err(CliError::InvalidValue {
+                option: "--cards".to_string(),
+                value: "9".to_string()
+            })
+        );
+        assert_eq!(
+            parse(args("--seed")),
+            Err(CliError::MissingValue("--seed".to_string()))
+        );
+        assert_eq!(
+            parse(args("hi-lo --tiebreak color")),
+            Err(CliError::InvalidValue {
+                option: "--tiebreak".to_string(),
+                value: "color".to_string()
+            })
+        );
+        assert_eq!(
+            parse(args("tournament --strategy human")),
+            Err(CliError::InvalidValue {
+                option: "--strategy".to_string(),
+                value: "human".to_string()
+            })
+        );
+        assert_eq!(
+            parse(args("simulate --strategy basic")),
+            Err(CliError::InvalidValue {
+                option: "--strategy".to_string(),
+                value: "basic".to_string()
+            })
+        );
+        assert_eq!(
+            parse(args("fly")),
+            Err(CliError::UnknownCommand("fly".to_string()))
+        );
+        assert_eq!(
+            parse(args("replay")),
+            Err(CliError::MissingValue("replay".to_string()))
+        );
+        assert_eq!(
+            parse(args("deck shuffle")),
+            Err(CliError::UnknownCommand("deck shuffle".to_string()))
+        );
+        assert_eq!(
+            parse(args("stats --verbose")),
+            Err(CliError::UnknownOption("--verbose".to_string()))
+        );
+        assert_eq!(
+            parse(args("play --difficulty brutal"))
+                .unwrap_err()
+                .to_string(),
+            "brutal isn't a valid value for --difficulty"
+        );
+    }
+}
@@ -0,0 +1,442 @@
+//! This module provides defaults loaded from a configuration file.
+//!
+//! The file is a small subset of TOML: one `key = value` per line, where values
+//! are quoted strings, whole numbers or `true`/`false`, and `#` starts a comment.
+//!
+//! ```toml
+//! cards = 4
+//! theme = "rounded"
+//! color = "high-contrast"
+//! sleep_ms = 500
+//! speed = "fast"
+//! difficulty = "hard"
+//! animation = false
+//! suit_colors = "four-color"
+//! suits = "letters"
+//! screen_reader = true
+//! locale = "es"
+//! mute = true
+//! hints = true
+//! values = true
+//! confirm = true
+//! k_factor = 24
+//! ```
+
+use crate::cards::card_printer::{
+    CardTheme, ColorMode, RenderOptions, RenderStyle, SuitColors, SuitStyle,
+};
+use crate::game::{Difficulty, Speed};
+use crate::locale::Locale;
+use crate::rating::DEFAULT_K_FACTOR;
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Why a configuration file couldn't be used.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file exists but couldn't be read.
+    Io(io::Error),
+    /// A line isn't a `key = value` pair, or the value isn't a string, number or
+    /// boolean.
+    Syntax { line: usize, message: String },
+    /// A known key has a value out of range, or the key isn't known.
+    Invalid {
+        line: usize,
+        key: String,
+        message: String,
+    },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(formatter, "{}", err),
+            ConfigError::Syntax { line, message } => {
+                write!(formatter, "line {}: {}", line, message)
+            }
+            ConfigError::Invalid { line, key, message } => {
+                write!(formatter, "line {}: {} {}", line, key, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<io::Error> for ConfigError {
+    fn from(err: io::Error) -> ConfigError {
+        ConfigError::Io(err)
+    }
+}
+
+/// A value on the right of `=`.
+enum Value {
+    String(String),
+    Integer(u64),
+    Boolean(bool),
+}
+
+/// Defaults for the high card game. Anything left unset uses the built-in default.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub cards: Option<u8>,
+    pub theme: Option<CardTheme>,
+    pub color: Option<ColorMode>,
+    pub sleep: Option<Duration>,
+    /// Scales every pause and animation.
+    pub speed: Option<Speed>,
+    pub difficulty: Option<Difficulty>,
+    pub animation: Option<bool>,
+    /// Suit colours used in place of the theme's own.
+    pub suit_colors: Option<SuitColors>,
+    pub suits: Option<SuitStyle>,
+    /// Narrate hands in sentences instead of drawing them.
+    pub screen_reader: Option<bool>,
+    /// The language of the game's text, taken from the environment if unset.
+    pub locale: Option<Locale>,
+    /// Silence the sound effects of an `audio` build.
+    pub mute: Option<bool>,
+    /// Show the odds of each card being high before a guess.
+    pub hints: Option<bool>,
+    /// Show what each card scored once a hand is turned over.
+    pub values: Option<bool>,
+    /// Ask before locking in a typed pick.
+    pub confirm: Option<bool>,
+    /// The most a profile's rating moves in one match.
+    pub k_factor: Option<u32>,
+}
+
+impl Config {
+    /// Construct an empty config.
+    pub fn new() -> Config {
+        Config::default()
+    }
+
+    /// Where the config lives: `$XDG_CONFIG_HOME/card_game/config.toml`, falling
+    /// back to `~/.config/card_game/config.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        let config_home = match env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        Some(config_home.join("card_game").join("config.toml"))
+    }
+
+    /// Loads the config at `path`. A missing file means nothing is configured.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Config, ConfigError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Config::parse(&text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Config::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Parses the text of a config file.
+    pub fn parse(text: &str) -> Result<Config, ConfigError> {
+        let mut config = Config::new();
+        for (idx, line) in text.lines().enumerate() {
+            let number = idx + 1;
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let syntax = |message: &str| ConfigError::Syntax {
+                line: number,
+                message: message.to_string(),
+            };
+            let (key, value) = match line.find('=') {
+                Some(idx) => (line[..idx].trim(), line[idx + 1..].trim()),
+                None => return Err(syntax("expected key = value")),
+            };
+            if key.is_empty() {
+                return Err(syntax("missing key"));
+            }
+            let value = parse_value(value)
+                .ok_or_else(|| syntax("expected a quoted string, a number, true or false"))?;
+            config.set(number, key, value)?;
+        }
+        Ok(config)
+    }
+
+    fn set(&mut self, line: usize, key: &str, value: Value) -> Result<(), ConfigError> {
+        let invalid = |message: &str| ConfigError::Invalid {
+            line,
+            key: key.to_string(),
+            message: message.to_string(),
+        };
+        match (key, value) {
+            ("cards", Value::Integer(cards @ 3..=5)) => self.cards = Some(cards as u8),
+            ("cards", _) => return Err(invalid("must be 3, 4 or 5")),
+            ("theme", Value::String(name)) => match CardTheme::named(&name) {
+                Some(theme) => self.theme = Some(theme),
+                None => return Err(invalid("must be classic, rounded, casino or minimal")),
+            },
+            ("color", Value::String(name)) => match ColorMode::named(&name) {
+                Some(color) => self.color = Some(color),
+                None => return Err(invalid("must be auto, always, never or high-contrast")),
+            },
+            ("sleep_ms", Value::Integer(millis @ 0..=10_000)) => {
+                self.sleep = Some(Duration::from_millis(millis))
+            }
+            ("sleep_ms", _) => return Err(invalid("must be a number of milliseconds up to 10000")),
+            ("k_factor", Value::Integer(k @ 1..=100)) => self.k_factor = Some(k as u32),
+            ("k_factor", _) => return Err(invalid("must be a number from 1 to 100")),
+            ("speed", Value::String(name)) => match Speed::named(&name) {
+                Some(speed) => self.speed = Some(speed),
+                None => return Err(invalid("must be slow, normal, fast or instant")),
+            },
+            ("difficulty", Value::String(name)) => match Difficulty::named(&name) {
+                Some(difficulty) => self.difficulty = Some(difficulty),
+                None => return Err(invalid("must be easy, normal or hard")),
+            },
+            ("animation", Value::Boolean(on)) => self.animation = Some(on),
+            ("screen_reader", Value::Boolean(on)) => self.screen_reader = Some(on),
+            ("hints", Value::Boolean(on)) => self.hints = Some(on),
+            ("values", Value::Boolean(on)) => self.values = Some(on),
+            ("confirm", Value::Boolean(on)) => self.confirm = Some(on),
+            ("mute", Value::Boolean(on)) => self.mute = Some(on),
+            ("locale", Value::String(name)) => match Locale::named(&name) {
+                Some(locale) => self.locale = Some(locale),
+                None => return Err(invalid("must be en or es")),
+            },
+            ("suit_colors", Value::String(name)) => match SuitColors::named(&name) {
+                Some(colors) => self.suit_colors = Some(colors),
+                None => {
+                    return Err(invalid(
+                        "must be two-color, four-color or four colour names",
+                    ))
+                }
+            },
+            ("suits", Value::String(name)) => match SuitStyle::named(&name) {
+                Some(suits) => self.suits = Some(suits),
+                None => return Err(invalid("must be auto, symbols, letters or words")),
+            },
+            ("animation", _)
+            | ("screen_reader", _)
+            | ("mute", _)
+            | ("hints", _)
+            | ("values", _)
+            | ("confirm", _) => return Err(invalid("must be true or false")),
+            ("theme", _)
+            | ("color", _)
+            | ("speed", _)
+            | ("difficulty", _)
+            | ("locale", _)
+            | ("suit_colors", _)
+            | ("suits", _) => return Err(invalid("must be a quoted string")),
+            _ => return Err(invalid("isn't a known setting")),
+        }
+        Ok(())
+    }
+
+    /// Render options using the configured theme, suit colours, colour mode and
+    /// suit style. Hands are narrated for a screen reader.
+    pub fn render_options(&self) -> RenderOptions {
+        let mut theme = self.theme.clone().unwrap_or_default();
+        if let Some(colors) = self.suit_colors {
+            theme = theme.suit_colors(colors);
+        }
+        let options = RenderOptions::new()
+            .theme(theme)
+            .color(self.color.unwrap_or_default())
+            .suits(self.suits.unwrap_or_default());
+        if self.screen_reader() {
+            options.style(RenderStyle::Narrated).color(ColorMode::Never)
+        } else {
+            options
+        }
+    }
+
+    /// How long to pause between steps of a round at normal speed, one second by
+    /// default.
+    pub fn sleep(&self) -> Duration {
+        self.sleep.unwrap_or_else(|| Duration::from_secs(1))
+    }
+
+    /// How quickly games pause and animate, normal by default.
+    pub fn speed(&self) -> Speed {
+        self.speed.unwrap_or_default()
+    }
+
+    /// How long to pause between steps of a round at the configured speed.
+    pub fn pause(&self) -> Duration {
+        self.speed().scale(self.sleep())
+    }
+
+    /// Whether hands are dealt and turned over with animations, on by default
+    /// and always off for a screen reader or at instant speed.
+    pub fn animation(&self) -> bool {
+        self.animation.unwrap_or(true) && !self.screen_reader() && self.speed().animates()
+    }
+
+    /// The language of the game's text: the configured one, or the one in the
+    /// environment.
+    pub fn locale(&self) -> Locale {
+        self.locale.unwrap_or_else(Locale::from_env)
+    }
+
+    /// Whether the game is narrated in plain sentences for a screen reader, off
+    /// by default.
+    pub fn screen_reader(&self) -> bool {
+        self.screen_reader.unwrap_or(false)
+    }
+
+    /// Whether the odds of each card being high are shown before a guess, off by
+    /// default.
+    pub fn hints(&self) -> bool {
+        self.hints.unwrap_or(false)
+    }
+
+    /// Whether each card's score is shown when a hand is turned over, off by
+    /// default.
+    pub fn values(&self) -> bool {
+        self.values.unwrap_or(false)
+    }
+
+    /// Whether a typed pick is shown and has to be confirmed before the hand is
+    /// turned over, off by default.
+    pub fn confirm(&self) -> bool {
+        self.confirm.unwrap_or(false)
+    }
+
+    /// Whether sound effects are silenced, off by default.
+    pub fn muted(&self) -> bool {
+        self.mute.unwrap_or(false)
+    }
+
+    /// The most a profile's rating moves in one match, 32 by default.
+    pub fn k_factor(&self) -> u32 {
+        self.k_factor.unwrap_or(DEFAULT_K_FACTOR)
+    }
+}
+
+/// Drops a trailing `#` comment that isn't inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (idx, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..idx],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(value: &str) -> Option<Value> {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        return Some(Value::String(value[1..value.len() - 1].to_string()));
+    }
+    match value {
+        "true" => return Some(Value::Boolean(true)),
+        "false" => return Some(Value::Boolean(false)),
+        _ => {}
+    }
+    value.replace('_', "").parse().ok().map(Value::Integer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, ConfigError};
+    use crate::cards::card_printer::{CardTheme, ColorMode, RenderStyle, SuitColors, SuitStyle};
+    use crate::game::{Difficulty, Speed};
+    use crate::locale::Locale;
+    use std::time::Duration;
+
+    #[test]
+    /// Tests every setting is read and comments are skipped.
+    fn test_parse() {
+        let config = Config::parse(
+            "# defaults\n\
+             cards = 4\n\
+             theme = \"rounded\"  # softer corners\n\
+             color = \"never\"\n\
+             \n\
+             sleep_ms = 1_500\n\
+             speed = \"slow\"\n\
+             difficulty = \"hard\"\n\
+             animation = false\n\
+             suit_colors = \"four-color\"\n\
+             suits = \"words\"\n\
+             screen_reader = false\n\
+             locale = \"es\"\n\
+             mute = true\n\
+             hints = true\n\
+             values = true\n\
+             confirm = true\n\
+             k_factor = 16\n",
+        )
+        .unwrap();
+
+        assert_eq!(config.cards, Some(4));
+        assert_eq!(config.theme, Some(CardTheme::rounded()));
+        assert_eq!(config.color, Some(ColorMode::Never));
+        assert_eq!(config.sleep(), Duration::from_millis(1500));
+        assert_eq!(config.speed(), Speed::Slow);
+        assert_eq!(config.pause(), Duration::from_secs(3));
+        assert_eq!(config.difficulty, Some(Difficulty::Hard));
+        assert!(!config.animation());
+        assert_eq!(config.suit_colors, Some(SuitColors::four_color()));
+        assert_eq!(config.render_options().suits, SuitStyle::Words);
+        assert_eq!(config.locale(), Locale::Spanish);
+        assert!(config.muted() && !Config::new().muted());
+        assert!(config.hints() && !Config::new().hints());
+        assert!(config.values() && !Config::new().values());
+        assert!(config.confirm() && !Config::new().confirm());
+        assert_eq!(config.k_factor(), 16);
+        assert_eq!(Config::new().k_factor(), 32);
+        assert_eq!(
+            config.render_options().theme,
+            CardTheme::rounded().suit_colors(SuitColors::four_color())
+        );
+        assert_eq!(Config::parse("").unwrap(), Config::new());
+        assert!(Config::new().animation());
+
+        let narrated = Config::parse("screen_reader = true").unwrap();
+        assert!(narrated.screen_reader() && !narrated.animation());
+        assert!(!Config::parse("speed = \"instant\"").unwrap().animation());
+        assert_eq!(narrated.render_options().style, RenderStyle::Narrated);
+    }
+
+    #[test]
+    /// Tests mistakes are reported with their line number.
+    fn test_parse_errors() {
+        let error = |text: &str| Config::parse(text).unwrap_err().to_string();
+
+        assert_eq!(error("cards = 4\ncards 5"), "line 2: expected key = value");
+        assert_eq!(
+            error("theme = rounded"),
+            "line 1: expected a quoted string, a number, true or false"
+        );
+        assert_eq!(error("cards = 9"), "line 1: cards must be 3, 4 or 5");
+        assert_eq!(
+            error("theme = \"neon\""),
+            "line 1: theme must be classic, rounded, casino or minimal"
+        );
+        assert_eq!(error("volume = 3"), "line 1: volume isn't a known setting");
+        assert_eq!(
+            error("k_factor = 0"),
+            "line 1: k_factor must be a number from 1 to 100"
+        );
+        assert_eq!(
+            error("animation = \"off\""),
+            "line 1: animation must be true or false"
+        );
+        assert_eq!(
+            error("theme = true"),
+            "line 1: theme must be a quoted string"
+        );
+        assert_eq!(
+            error("suit_colors = \"red blue\""),
+            "line 1: suit_colors must be two-color, four-color or four colour names"
+        );
+        assert!(matches!(
+            Config::parse("difficulty = 2"),
+            Err(ConfigError::Invalid { line: 1, .. })
+        ));
+    }
+}
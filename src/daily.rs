@@ -0,0 +1,194 @@
+//! This module provides the daily challenge: one high card game a day, dealt
+//! from a seed worked out from the date so everyone plays the same cards.
+//!
+//! Days are counted in UTC, so the challenge changes at the same moment
+//! everywhere. Each day's result is kept in a log next to the lifetime stats,
+//! and a day already in the log can't be played again.
+
+use crate::game::Game;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seconds in a day.
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// A day, counted from 1970-01-01 in UTC.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Day(pub u64);
+
+impl Day {
+    /// The day it is now in UTC.
+    pub fn today() -> Day {
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Day(since_epoch.as_secs() / DAY_SECS)
+    }
+
+    /// The seed the day's cards are shuffled with. Neighbouring days get
+    /// unrelated seeds.
+    pub fn seed(self) -> u64 {
+        let mut seed = self.0 ^ 0xDA11_C4A1_1E46_E000;
+        seed = (seed ^ (seed >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        seed = (seed ^ (seed >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        seed ^ (seed >> 31)
+    }
+
+    /// The year, month and day of the month.
+    pub fn date(self) -> (i64, u32, u32) {
+        // Howard Hinnant's days-to-civil algorithm, counting from 0000-03-01 so
+        // leap days fall at the end of the year.
+        let days = self.0 as i64 + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        } as u32;
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+        (year, month, day)
+    }
+}
+
+/// Displays the day as `YYYY-MM-DD`.
+impl Display for Day {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let (year, month, day) = self.date();
+        write!(formatter, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+/// How a daily challenge went.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub points: u64,
+    pub wins: usize,
+    pub rounds: usize,
+}
+
+impl DailyResult {
+    /// The result of `game` so far.
+    pub fn from_game(game: &Game) -> DailyResult {
+        DailyResult {
+            points: game.points(),
+            wins: game.wins(),
+            rounds: game.games_played(),
+        }
+    }
+}
+
+impl Display for DailyResult {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} points, {} of {} rounds won",
+            self.points, self.wins, self.rounds
+        )
+    }
+}
+
+/// Every daily challenge played, by date.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyLog {
+    results: BTreeMap<String, DailyResult>,
+}
+
+impl DailyLog {
+    /// Construct an empty log.
+    pub fn new() -> DailyLog {
+        DailyLog::default()
+    }
+
+    /// Where the log is kept: `daily.json` next to the lifetime stats.
+    pub fn default_path() -> Option<PathBuf> {
+        let data_home = match env::var_os("XDG_DATA_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".local/share"),
+        };
+        Some(data_home.join("card_game").join("daily.json"))
+    }
+
+    /// Loads the log from `path`. A missing file means no daily has been played.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<DailyLog> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(DailyLog::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Saves the log to `path`, creating its directory if needed.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, json)
+    }
+
+    /// The result recorded for `day`, if it's been played.
+    pub fn result(&self, day: Day) -> Option<&DailyResult> {
+        self.results.get(&day.to_string())
+    }
+
+    /// Records the result for `day`, replacing one recorded earlier.
+    pub fn record(&mut self, day: Day, result: DailyResult) {
+        self.results.insert(day.to_string(), result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DailyLog, DailyResult, Day};
+    use std::env;
+    use std::fs;
+
+    #[test]
+    /// Tests days are dated in UTC and seed different shuffles.
+    fn test_day() {
+        assert_eq!(Day(0).to_string(), "1970-01-01");
+        assert_eq!(Day(19_723).to_string(), "2024-01-01");
+        assert_eq!(Day(19_782).to_string(), "2024-02-29");
+        assert_eq!(Day(20_740).to_string(), "2026-10-14");
+        assert_eq!(Day(20_740).seed(), Day(20_740).seed());
+        assert_ne!(Day(20_740).seed(), Day(20_741).seed());
+        assert!(Day::today() > Day(20_000));
+    }
+
+    #[test]
+    /// Tests the log remembers each day played across saves.
+    fn test_log() {
+        let path = env::temp_dir().join(format!("card_game_daily_{}.json", std::process::id()));
+        let mut log = DailyLog::load(&path).unwrap();
+        assert_eq!(log.result(Day(5)), None);
+
+        let result = DailyResult {
+            points: 40,
+            wins: 3,
+            rounds: 17,
+        };
+        log.record(Day(5), result);
+        log.save(&path).unwrap();
+        let loaded = DailyLog::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.result(Day(5)), Some(&result));
+        assert_eq!(loaded.result(Day(6)), None);
+        assert_eq!(result.to_string(), "40 points, 3 of 17 rounds won");
+    }
+}
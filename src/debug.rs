@@ -0,0 +1,214 @@
+//! This module provides the debug console for setting up high card rounds.
+//!
+//! With `--debug` the plain prompt also takes commands that look inside the
+//! game or arrange what happens next: `show deck` lists the cards left in
+//! order, `stack next AS KH 2C` deals those cards first next round and
+//! `force win` makes the next pick win whichever card is chosen.
+
+use crate::cards::{Card, ParseCardError};
+use crate::game::Game;
+use std::fmt::{self, Display, Formatter};
+
+/// The commands the console understands.
+pub const HELP: &str = "show deck|hand|discards, stack next <cards>, force win|lose, help";
+
+/// A debug console command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Lists the cards left in the deck, top card first.
+    ShowDeck,
+    /// Lists the hand with every card face up.
+    ShowHand,
+    /// Lists the played cards waiting to be shuffled back in.
+    ShowDiscards,
+    /// Deals these cards first next round, in order.
+    Stack(Vec<Card>),
+    /// Makes the next pick win, or lose.
+    Force(bool),
+    Help,
+}
+
+/// Why a debug command couldn't be run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DebugError {
+    /// The line wasn't a command.
+    Unknown(String),
+    /// One of the cards to stack wasn't a card.
+    Card(ParseCardError),
+    /// One of the cards to stack isn't left in the deck.
+    NotInDeck(Card),
+    /// Results can only be forced with a single player.
+    Multiplayer,
+}
+
+impl Display for DebugError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            DebugError::Unknown(input) => {
+                write!(formatter, "unknown command {:?}, try {}", input, HELP)
+            }
+            DebugError::Card(err) => write!(formatter, "{}", err),
+            DebugError::NotInDeck(card) => write!(formatter, "{} isn't left in the deck", card),
+            DebugError::Multiplayer => {
+                write!(formatter, "results can only be forced with one player")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DebugError {}
+
+impl From<ParseCardError> for DebugError {
+    fn from(err: ParseCardError) -> DebugError {
+        DebugError::Card(err)
+    }
+}
+
+impl Command {
+    /// Parses a console line such as `stack next AS KH 2C`.
+    pub fn parse(input: &str) -> Result<Command, DebugError> {
+        let words: Vec<&str> = input.split_whitespace().collect();
+        match words.as_slice() {
+            ["show", "deck"] => Ok(Command::ShowDeck),
+            ["show", "hand"] => Ok(Command::ShowHand),
+            ["show", "discards"] => Ok(Command::ShowDiscards),
+            ["stack", "next", cards @ ..] if !cards.is_empty() => {
+                let cards = cards
+                    .iter()
+                    .map(|card| card.parse())
+                    .collect::<Result<_, _>>()?;
+                Ok(Command::Stack(cards))
+            }
+            ["force", "win"] => Ok(Command::Force(true)),
+            ["force", "lose"] => Ok(Command::Force(false)),
+            ["help"] => Ok(Command::Help),
+            _ => Err(DebugError::Unknown(input.trim().to_string())),
+        }
+    }
+
+    /// Runs the command on `game`, returning what to show.
+    pub fn run(self, game: &mut Game) -> Result<String, DebugError> {
+        match self {
+            Command::ShowDeck => Ok(format!(
+                "{} cards left: {}",
+                game.deck().remaining(),
+                list(game.deck().iter())
+            )),
+            Command::ShowHand => Ok(list(game.hand().cards().iter())),
+            Command::ShowDiscards => Ok(format!(
+                "{} discarded: {}",
+                game.deck().discarded().len(),
+                list(game.deck().discarded().iter())
+            )),
+            Command::Stack(cards) => {
+                let next = list(cards.iter());
+                game.stack_next(cards).map_err(DebugError::NotInDeck)?;
+                Ok(format!("Next deal starts {}", next))
+            }
+            Command::Force(_) if game.is_multiplayer() => Err(DebugError::Multiplayer),
+            Command::Force(win) => {
+                game.force_result(win);
+                Ok(match win {
+                    true => "The next pick wins.".to_string(),
+                    false => "The next pick loses.".to_string(),
+                })
+            }
+            Command::Help => Ok(HELP.to_string()),
+        }
+    }
+}
+
+/// The cards face up, separated by commas.
+fn list<'a, I: Iterator<Item = &'a Card>>(cards: I) -> String {
+    cards
+        .map(|card| card.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Command, DebugError};
+    use crate::cards::{Card, Rank, Suit};
+    use crate::game::state::{Event, Input};
+    use crate::game::GameBuilder;
+
+    #[test]
+    /// Tests console lines are parsed into commands.
+    fn test_parse() {
+        assert_eq!(Command::parse(" show  deck "), Ok(Command::ShowDeck));
+        assert_eq!(
+            Command::parse("stack next AS 10♥"),
+            Ok(Command::Stack(vec![
+                Card::new(Suit::Spades, Rank::Ace),
+                Card::new(Suit::Hearts, Rank::Ten)
+            ]))
+        );
+        assert_eq!(Command::parse("force lose"), Ok(Command::Force(false)));
+        assert!(matches!(
+            Command::parse("stack next AS XX"),
+            Err(DebugError::Card(_))
+        ));
+        assert_eq!(
+            Command::parse("stack next"),
+            Err(DebugError::Unknown("stack next".to_string()))
+        );
+        assert!(Command::parse("2").is_err());
+    }
+
+    #[test]
+    /// Tests stacked cards are dealt first after the shuffle.
+    fn test_stack() {
+        let mut game = GameBuilder::new().with_seed(5).spawn().unwrap();
+        let next = vec![
+            Card::new(Suit::Spades, Rank::Ace),
+            Card::new(Suit::Hearts, Rank::King),
+            Card::new(Suit::Clubs, Rank::Two),
+        ];
+        let shown = Command::Stack(next.clone()).run(&mut game).unwrap();
+        assert_eq!(shown, "Next deal starts A♠, K♥, 2♣");
+        game.advance(Input::Deal);
+        let dealt: Vec<_> = game
+            .hand()
+            .cards()
+            .iter()
+            .map(|card| (card.suit, card.rank))
+            .collect();
+        let next: Vec<_> = next.iter().map(|card| (card.suit, card.rank)).collect();
+        assert_eq!(dealt, next);
+
+        // The ace has been dealt, so it can't be stacked again.
+        let ace = Card::new(Suit::Spades, Rank::Ace);
+        assert_eq!(
+            Command::Stack(vec![ace]).run(&mut game),
+            Err(DebugError::NotInDeck(ace))
+        );
+        assert!(Command::ShowDeck
+            .run(&mut game)
+            .unwrap()
+            .starts_with("49 cards left: "));
+    }
+
+    #[test]
+    /// Tests forced results hold whichever card is picked.
+    fn test_force() {
+        for &win in &[true, false] {
+            for choice in 0..3 {
+                let mut game = GameBuilder::new().with_seed(9).spawn().unwrap();
+                game.advance(Input::Deal);
+                Command::Force(win).run(&mut game).unwrap();
+                game.advance(Input::Choose(choice));
+                match game.advance(Input::Reveal) {
+                    Event::Revealed(result) => assert_eq!(result.won, win),
+                    event => panic!("expected a result, got {:?}", event),
+                }
+            }
+        }
+
+        let mut game = GameBuilder::new().players(2).spawn().unwrap();
+        assert_eq!(
+            Command::Force(true).run(&mut game),
+            Err(DebugError::Multiplayer)
+        );
+    }
+}
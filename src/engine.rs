@@ -0,0 +1,168 @@
+//! This module provides a common runner for turn based game modes.
+//!
+//! A mode only says how to play one round and when it's finished. The runner
+//! owns the loop, the pause between rounds and printing the summary at the end,
+//! so every mode plays the same way in the terminal.
+//!
+//! The loop is async and runs on the binary's tokio runtime. Rounds wait on the
+//! player and on delays without blocking it, so anything else on the runtime
+//! carries on in the meantime.
+
+pub mod high_card;
+pub mod tutorial;
+
+use crate::error::GameError;
+use crate::input::{read_event_timeout, read_key_timeout, read_line};
+use console::Term;
+use std::future::Future;
+use std::io::{self, IsTerminal};
+use std::time::{Duration, Instant};
+use tokio::time;
+
+/// What happened in a round.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Flow {
+    /// Carry on with the next round.
+    Continue,
+    /// The player asked to save and quit.
+    Quit,
+}
+
+/// A game played one round at a time.
+pub trait GameMode {
+    /// Prints anything the player should see before the first round.
+    fn setup(&mut self) -> Result<(), GameError>;
+
+    /// Plays a single round, reading input and printing the table as it goes.
+    fn play_round(&mut self) -> impl Future<Output = Result<Flow, GameError>>;
+
+    /// Returns true once no more rounds can be played.
+    fn is_over(&self) -> bool;
+
+    /// Text printed once the game is over.
+    fn summary(&self) -> String;
+
+    /// Waits between rounds. Modes playing through an `Io` wait on it instead.
+    fn pause(&mut self, duration: Duration) -> impl Future<Output = io::Result<()>> {
+        pause(duration)
+    }
+
+    /// Prints the summary once the game is over.
+    fn finish(&mut self) -> io::Result<()> {
+        println!("{}", self.summary());
+        Ok(())
+    }
+}
+
+/// Plays `mode` until it's over, pausing for `pause` after each round.
+/// Returns true if the player quit early.
+pub async fn run(mode: &mut impl GameMode, pause: Duration) -> Result<bool, GameError> {
+    mode.setup()?;
+    while !mode.is_over() {
+        if mode.play_round().await? == Flow::Quit {
+            return Ok(true);
+        }
+        mode.pause(pause).await?;
+    }
+    mode.finish()?;
+    Ok(false)
+}
+
+/// Waits for `duration`, or until a key is pressed so an impatient player can
+/// skip ahead. Piped input is left alone for the game to read, and a terminal
+/// that can't be waited on with a timeout is slept through instead, so the wait
+/// always ends on its own.
+pub async fn pause(duration: Duration) -> io::Result<()> {
+    let started = Instant::now();
+    if io::stdin().is_terminal() && read_key_timeout(duration).await.is_ok() {
+        return Ok(());
+    }
+    time::sleep(duration.saturating_sub(started.elapsed())).await;
+    Ok(())
+}
+
+/// Clears the screen and waits for a key press before `name` takes their turn,
+/// so players sharing the terminal don't see each other's cards.
+pub async fn pass_to(name: &str) -> io::Result<()> {
+    let term = Term::stdout();
+    if !term.features().is_attended() {
+        println!("Pass the keyboard to {} and press [Enter].", name);
+        read_line().await?;
+        return Ok(());
+    }
+    term.clear_screen()?;
+    println!(
+        "Pass the keyboard to {} and press any key when they're ready.",
+        name
+    );
+    read_event_timeout(None).await?;
+    term.clear_screen()
+}
+
+/// Returns true if the player typed [q] to save and quit.
+pub fn wants_to_quit(input: &str) -> bool {
+    input.trim() == "q"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pause, run, Flow, GameMode};
+    use crate::error::GameError;
+    use std::time::{Duration, Instant};
+
+    /// Counts down a fixed number of rounds, quitting on `quit_at` if set.
+    struct Countdown {
+        rounds: usize,
+        quit_at: Option<usize>,
+    }
+
+    impl GameMode for Countdown {
+        fn setup(&mut self) -> Result<(), GameError> {
+            Ok(())
+        }
+
+        async fn play_round(&mut self) -> Result<Flow, GameError> {
+            if Some(self.rounds) == self.quit_at {
+                return Ok(Flow::Quit);
+            }
+            self.rounds -= 1;
+            Ok(Flow::Continue)
+        }
+
+        fn is_over(&self) -> bool {
+            self.rounds == 0
+        }
+
+        fn summary(&self) -> String {
+            "Done.".to_string()
+        }
+    }
+
+    #[tokio::test]
+    /// Tests the runner plays every round unless the mode quits.
+    async fn test_run() {
+        let mut mode = Countdown {
+            rounds: 3,
+            quit_at: None,
+        };
+        assert!(!run(&mut mode, Duration::from_millis(0)).await.unwrap());
+        assert_eq!(mode.rounds, 0);
+
+        let mut mode = Countdown {
+            rounds: 3,
+            quit_at: Some(1),
+        };
+        assert!(run(&mut mode, Duration::from_millis(0)).await.unwrap());
+        assert_eq!(mode.rounds, 1);
+    }
+
+    #[tokio::test]
+    /// Tests a pause runs its course and then ends without a key press.
+    async fn test_pause() {
+        let started = Instant::now();
+        pause(Duration::from_millis(50)).await.unwrap();
+        let took = started.elapsed();
+        assert!(took >= Duration::from_millis(50));
+        assert!(took < Duration::from_secs(2));
+    }
+}
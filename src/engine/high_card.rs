@@ -0,0 +1,1046 @@
+//! This module provides the high card game played at a line-by-line prompt.
+
+use super::{wants_to_quit, Flow, GameMode};
+use crate::analysis::suit_counts;
+use crate::cards::card_printer::animation::{animate_deal, animate_flip, FLIP_FRAME, SLIDE_FRAME};
+use crate::cards::card_printer::{write_labels, ColorMode, RenderOptions};
+use crate::cards::Card;
+use crate::cards::Hand;
+use crate::config::Config;
+use crate::debug::Command;
+use crate::error::GameError;
+use crate::game::state::{Event, Input, Phase, RoundResult};
+use crate::game::{Game, Speed};
+use crate::io::{Io, Terminal};
+use crate::locale::{Locale, Message};
+use crate::stats::Stats;
+use crate::strategy::{Choice, GameView, Strategy};
+use crossterm::event::KeyCode;
+use std::cmp::Ordering;
+use std::future::Future;
+use std::io::{self};
+use std::time::{Duration, Instant};
+
+/// One or more players finding the high card. A single player guesses which
+/// card is high, several players are each dealt a card and the high card wins.
+pub struct HighCard<'a> {
+    game: &'a mut Game,
+    stats: &'a mut Stats,
+    render: RenderOptions,
+    sleep: Duration,
+    speed: Speed,
+    timeout: Option<Duration>,
+    practice: bool,
+    animation: bool,
+    locale: Locale,
+    hints: bool,
+    values: bool,
+    confirm: bool,
+    debug: bool,
+    /// The name and strategy picking cards in place of the player.
+    autoplay: Option<(String, Box<dyn Strategy + 'a>)>,
+    io: Box<dyn Io + 'a>,
+}
+
+impl<'a> HighCard<'a> {
+    /// Plays `game` with the configured theme at the game's speed, recording
+    /// single player results in `stats`. With a `timeout` the random choice is
+    /// made once it passes.
+    pub fn new(
+        game: &'a mut Game,
+        stats: &'a mut Stats,
+        config: &Config,
+        timeout: Option<Duration>,
+    ) -> HighCard<'a> {
+        let speed = game.speed();
+        HighCard {
+            game,
+            stats,
+            render: config.render_options().show_index(true),
+            sleep: speed.scale(config.sleep()),
+            speed,
+            timeout,
+            practice: false,
+            animation: config.animation() && speed.animates(),
+            locale: config.locale(),
+            hints: config.hints(),
+            values: config.values(),
+            confirm: config.confirm(),
+            debug: false,
+            autoplay: None,
+            io: Box::new(Terminal::new()),
+        }
+    }
+
+    /// Option to offer retrying each round once its result is in.
+    pub fn practice(mut self, practice: bool) -> HighCard<'a> {
+        self.practice = practice;
+        self
+    }
+
+    /// Option to take debug console commands at the pick prompt.
+    pub fn debug(mut self, debug: bool) -> HighCard<'a> {
+        self.debug = debug;
+        self
+    }
+
+    /// Option to have `strategy`, called `name` in the commentary, play every
+    /// pick and wager while the game is shown as usual.
+    pub fn autoplay<S: Strategy + 'a>(mut self, name: &str, strategy: S) -> HighCard<'a> {
+        self.autoplay = Some((name.to_string(), Box::new(strategy)));
+        self
+    }
+
+    /// Option to play through `io` instead of the terminal.
+    pub fn io<I: Io + 'a>(mut self, io: I) -> HighCard<'a> {
+        self.io = Box::new(io);
+        self
+    }
+
+    /// Each player is dealt a card and the high card takes the round.
+    async fn play_shared(&mut self) -> Result<Flow, GameError> {
+        let (render, locale, speed) = (&self.render, self.locale, self.speed);
+        let animation = self.animation;
+        let game = &mut *self.game;
+        let io = self.io.as_mut();
+        game.advance(Input::Deal);
+        show_deal(io, game.hand().cards(), render, animation, speed).await?;
+
+        if self.autoplay.is_some() {
+            io.pause(self.sleep).await?;
+        } else {
+            writeln!(io.out(), "{}", locale.text(Message::RevealOrQuit))?;
+            if wants_to_quit(&io.read_line().await?) {
+                return Ok(Flow::Quit);
+            }
+        }
+
+        let before = game.hand().clone();
+        if let Event::Revealed(result) = game.advance(Input::Reveal) {
+            show_reveal(io, &before, game.hand().cards(), render, animation, speed).await?;
+            writeln!(io.out(), "{}", explain(game, result.winner, locale))?;
+            let winner = game.players()[result.winner].name();
+            writeln!(
+                io.out(),
+                "{}",
+                locale.format(Message::RoundWinner, &[&winner])
+            )?;
+        }
+        writeln!(io.out(), "{}\n\n", game)?;
+        game.advance(Input::Continue);
+        Ok(Flow::Continue)
+    }
+
+    /// A single player guesses which card is the high card.
+    async fn play_single(&mut self) -> Result<Flow, GameError> {
+        let (render, locale, speed) = (&self.render, self.locale, self.speed);
+        let animation = self.animation;
+        let game = &mut *self.game;
+        let io = self.io.as_mut();
+        if game.phase() == Phase::AwaitingChoice {
+            io.show_hand(game.hand().cards(), render)?;
+        } else {
+            game.advance(Input::Deal);
+            show_deal(io, game.hand().cards(), render, animation, speed).await?;
+        }
+
+        if self.autoplay.is_some() {
+            auto_wager(io, game, locale)?;
+        } else if ask_wager(io, game, locale).await? {
+            return Ok(Flow::Quit);
+        }
+        writeln!(io.out(), "{}", locale.text(Message::FindHighCard))?;
+        if self.hints {
+            writeln!(io.out(), "{}", hint(game, locale))?;
+        }
+
+        let pick = if let Some((name, strategy)) = &mut self.autoplay {
+            // Long enough for anyone watching to take in the hand.
+            io.pause(self.sleep).await?;
+            match strategy.choose(&GameView::new(game.hand(), game.scoring())) {
+                Choice::Card(choice) => {
+                    let line = locale.format(Message::AutoplayPick, &[name, &choice]);
+                    writeln!(io.out(), "{}", line)?;
+                    Input::Choose(choice)
+                }
+                Choice::Quit => return Ok(Flow::Quit),
+            }
+        } else if let Some(timeout) = self.timeout {
+            let started = Instant::now();
+            match timed_choice(io, game, timeout, locale).await? {
+                Some(Some(i)) => Input::ChooseIn(i, started.elapsed()),
+                Some(None) => return Ok(Flow::Quit),
+                None => Input::Random,
+            }
+        } else {
+            writeln!(io.out(), "{}", locale.text(Message::RandomOrQuit))?;
+
+            let started = Instant::now();
+            loop {
+                let input = io.read_line().await?;
+                if self.debug && is_command(&input) {
+                    match Command::parse(&input).and_then(|command| command.run(game)) {
+                        Ok(shown) => writeln!(io.out(), "{}", shown)?,
+                        Err(err) => writeln!(io.out(), "{}", err)?,
+                    }
+                    continue;
+                }
+                if wants_to_quit(&input) {
+                    return Ok(Flow::Quit);
+                }
+                match parse_pick(&input, game.hand().len()) {
+                    Some(Some(i))
+                        if self.confirm && !confirm_pick(io, game, i, render, locale).await? =>
+                    {
+                        writeln!(io.out(), "{}", locale.text(Message::RandomOrQuit))?;
+                    }
+                    Some(Some(i)) => break Input::ChooseIn(i, started.elapsed()),
+                    Some(None) => break Input::Random,
+                    None => writeln!(io.out(), "{}", not_a_card(game, locale))?,
+                }
+            }
+        };
+
+        let dealt = game.hand().clone();
+        if let Event::Chose { swaps, .. } = game.advance(pick) {
+            shuffle_hand(io, dealt, &swaps, render, locale, speed).await?;
+        }
+        io.show_hand(game.hand().cards(), render)?;
+
+        writeln!(io.out(), "{}", locale.text(Message::Results))?;
+
+        io.pause(self.sleep).await?;
+
+        let stats = self.stats.clone();
+        let before = game.hand().clone();
+        if let Event::Revealed(result) = game.advance(Input::Reveal) {
+            show_reveal(io, &before, game.hand().cards(), render, animation, speed).await?;
+            if self.values {
+                write!(io.out(), "{}", values(game, render))?;
+            }
+            writeln!(io.out(), "{}", explain(game, result.winner, locale))?;
+            if result.won {
+                let (points, multiplier) = (result.points, game.multiplier());
+                writeln!(
+                    io.out(),
+                    "{}",
+                    locale.format(Message::Win, &[&points, &multiplier])
+                )?;
+            } else {
+                writeln!(io.out(), "{}", locale.text(Message::Lose))?;
+            }
+            self.stats.record(result.won);
+            if let Some(took) = result.decision_time {
+                self.stats.record_pick(took);
+            }
+            if let Some(change) = result.chips {
+                let change = format!("{:+}", change);
+                writeln!(io.out(), "{}", locale.format(Message::Chips, &[&change]))?;
+            }
+            if let Some(pick) = result.opponent {
+                writeln!(io.out(), "{}", opponent_result(pick, result.winner, locale))?;
+            }
+            if let Some((name, strategy)) = &mut self.autoplay {
+                strategy.observe(game.hand(), result.won);
+                writeln!(io.out(), "{}", commentary(game, name, &result, locale))?;
+            }
+        }
+
+        writeln!(io.out(), "{}\n\n", game)?;
+        if self.practice && self.autoplay.is_none() {
+            writeln!(io.out(), "{}", locale.text(Message::NextOrRetry))?;
+            if io.read_line().await?.trim() == "u" {
+                game.advance(Input::Undo);
+                *self.stats = stats;
+                return Ok(Flow::Continue);
+            }
+        }
+        game.advance(Input::Continue);
+        Ok(Flow::Continue)
+    }
+}
+
+impl GameMode for HighCard<'_> {
+    fn setup(&mut self) -> Result<(), GameError> {
+        let (locale, seed) = (self.locale, self.game.seed());
+        if let Some((name, _)) = &self.autoplay {
+            let line = locale.format(Message::AutoplayStart, &[name]);
+            writeln!(self.io.out(), "{}", line)?;
+        }
+        writeln!(self.io.out(), "{}", locale.format(Message::Seed, &[&seed]))?;
+        if !self.game.is_multiplayer() {
+            let difficulty = self.game.difficulty();
+            writeln!(
+                self.io.out(),
+                "{}",
+                locale.format(Message::Difficulty, &[&difficulty])
+            )?;
+        }
+        Ok(())
+    }
+
+    async fn play_round(&mut self) -> Result<Flow, GameError> {
+        if self.game.is_multiplayer() {
+            self.play_shared().await
+        } else {
+            self.play_single().await
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        self.game.is_over()
+    }
+
+    fn summary(&self) -> String {
+        summary(self.game, self.locale)
+    }
+
+    fn pause(&mut self, duration: Duration) -> impl Future<Output = io::Result<()>> {
+        self.io.pause(duration)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        writeln!(self.io.out(), "{}", summary(self.game, self.locale))
+    }
+}
+
+/// Why the game ended, followed by every round played.
+pub fn summary(game: &Game, locale: Locale) -> String {
+    let mut summary = if game.is_bankrupt() {
+        locale.text(Message::OutOfChips).to_string()
+    } else if game.played_all_rounds() {
+        locale.format(Message::AllRounds, &[&game.games_played()])
+    } else if game.out_of_cards() {
+        game_over(game, locale)
+    } else {
+        locale.text(Message::Thanks).to_string()
+    };
+    if let (Some(opponent), Some(result)) = (game.opponent(), game.match_result()) {
+        let (wins, theirs) = (game.wins(), opponent.wins());
+        let verdict = match result {
+            Ordering::Greater => locale.format(Message::MatchWon, &[&wins, &theirs]),
+            Ordering::Less => locale.format(Message::MatchLost, &[&theirs, &wins]),
+            Ordering::Equal => locale.format(Message::MatchDrawn, &[&wins]),
+        };
+        summary = format!("{}\n{}", summary, verdict);
+    }
+    if !game.history().is_empty() {
+        summary = format!("{}\n\n{}", summary, game.history());
+    }
+    summary
+}
+
+/// The results shown once the deck runs out: how often the player found the
+/// high card, their points and their best streak.
+pub fn game_over(game: &Game, locale: Locale) -> String {
+    let mut lines = vec![locale.text(Message::OutOfCards).to_string()];
+    if !game.is_multiplayer() {
+        let (wins, played) = (game.wins(), game.games_played());
+        let accuracy = match played {
+            0 => 0.0,
+            played => wins as f64 * 100.0 / played as f64,
+        };
+        let accuracy = format!("{:.1}", accuracy);
+        lines.push(locale.format(Message::GameOverAccuracy, &[&wins, &played, &accuracy]));
+        let (points, best) = (game.points(), game.best_streak());
+        lines.push(locale.format(Message::GameOverStreaks, &[&points, &best]));
+    }
+    lines.join("\n")
+}
+
+/// What the player wants once a game has run out of cards.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AfterGame {
+    /// Another game with a freshly shuffled deck.
+    PlayAgain,
+    /// Back to the main menu.
+    Menu,
+    Quit,
+}
+
+/// Asks whether to play again, go back to the menu or quit, asking again until
+/// one of them is picked.
+pub async fn play_again(io: &mut dyn Io, locale: Locale) -> io::Result<AfterGame> {
+    loop {
+        writeln!(io.out(), "{}", locale.text(Message::PlayAgain))?;
+        match io.read_line().await?.trim() {
+            "p" => return Ok(AfterGame::PlayAgain),
+            "m" => return Ok(AfterGame::Menu),
+            "" | "q" => return Ok(AfterGame::Quit),
+            _ => {}
+        }
+    }
+}
+
+/// The chance of each card being the high card, and how many cards of each suit
+/// are still unseen.
+pub fn hint(game: &Game, locale: Locale) -> String {
+    let mut lines: Vec<String> = game
+        .hand()
+        .iter()
+        .zip(game.odds())
+        .enumerate()
+        .map(|(idx, (card, odds))| {
+            let percent = (odds * 100.0).round() as u32;
+            if card.state.is_face_up() {
+                locale.format(Message::HintCard, &[&idx, card, &percent])
+            } else {
+                locale.format(Message::HintHidden, &[&idx, &percent])
+            }
+        })
+        .collect();
+    let suits: Vec<String> = suit_counts(&game.unseen_cards())
+        .iter()
+        .map(|(suit, count)| format!("{}{}", count, suit.symbol()))
+        .collect();
+    lines.push(locale.format(Message::HintUnseen, &[&suits.join(" ")]));
+    lines.join("\n")
+}
+
+/// What the opponent picked, given the high card was `winner`.
+pub fn opponent_result(pick: usize, winner: usize, locale: Locale) -> String {
+    if pick == winner {
+        locale.format(Message::OpponentWon, &[&pick])
+    } else {
+        locale.format(Message::OpponentLost, &[&pick])
+    }
+}
+
+/// Waits up to `timeout` for a single key press picking a card, counting down
+/// the seconds left on the same line. Returns None for a random choice and
+/// Some(None) to quit.
+async fn timed_choice(
+    io: &mut dyn Io,
+    game: &Game,
+    timeout: Duration,
+    locale: Locale,
+) -> io::Result<Option<Option<usize>>> {
+    let seconds = timeout.as_secs();
+    writeln!(
+        io.out(),
+        "{}",
+        locale.format(Message::TimedPrompt, &[&seconds])
+    )?;
+    writeln!(io.out(), "{}", locale.text(Message::FasterPicks))?;
+    let deadline = Instant::now() + timeout;
+    let cards = game.hand().len();
+    let choice = loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        let seconds = left.as_secs_f64().ceil();
+        write!(
+            io.out(),
+            "\r{}",
+            locale.format(Message::SecondsLeft, &[&seconds])
+        )?;
+        io.out().flush()?;
+        let second = Duration::from_secs(1);
+        let key = match io.read_key(left.min(second)).await? {
+            None if left > second => continue,
+            None => {
+                writeln!(io.out())?;
+                writeln!(io.out(), "{}", locale.text(Message::TimesUp))?;
+                break None;
+            }
+            Some(key) => key,
+        };
+        // Only [Enter] picks at random. Arrows and the like are ignored, and a
+        // key that isn't a card's number is pointed out.
+        let choice = match key {
+            KeyCode::Char('q') => Some(None),
+            KeyCode::Enter => None,
+            KeyCode::Char(c) => match c.to_digit(10).map(|i| i as usize) {
+                Some(i) if i < cards => Some(Some(i)),
+                _ => {
+                    writeln!(io.out())?;
+                    writeln!(io.out(), "{}", not_a_card(game, locale))?;
+                    continue;
+                }
+            },
+            _ => continue,
+        };
+        writeln!(io.out())?;
+        break choice;
+    };
+    Ok(choice)
+}
+
+/// Each card's score under the game's rules, written under the hand.
+pub fn values(game: &Game, render: &RenderOptions) -> String {
+    let labels: Vec<String> = game
+        .hand()
+        .iter()
+        .map(|card| format!("= {}", game.scoring().score(card)))
+        .collect();
+    let mut out = String::new();
+    write_labels(&mut out, &labels, render).expect("writing to a String cannot fail");
+    out
+}
+
+/// Why the card at `winner` is the high card of a turned over hand: each card's
+/// score as the game's scoring strategy explains it, then how a tie was broken
+/// if the winner had to beat a card of the same rank.
+pub fn explain(game: &Game, winner: usize, locale: Locale) -> String {
+    let (scoring, cards) = (game.scoring(), game.hand().cards());
+    let high = cards[winner];
+    let mut lines = vec![locale.format(Message::WhyWon, &[&winner])];
+    lines.extend(
+        cards
+            .iter()
+            .enumerate()
+            .map(|(idx, card)| format!("  [{}] {}", idx, scoring.explain_score(card, locale))),
+    );
+    let score = scoring.score(&high);
+    let rival = |tied: &dyn Fn(&Card) -> bool| {
+        cards
+            .iter()
+            .enumerate()
+            .find(|&(idx, card)| idx != winner && tied(card))
+            .map(|(_, card)| *card)
+    };
+    if let Some(tied) = rival(&|card: &Card| scoring.score(card) == score) {
+        lines.push(locale.format(Message::FirstOfTie, &[&high, &tied, &score]));
+    } else if let Some(beaten) = rival(&|card: &Card| card.rank == high.rank) {
+        lines.push(locale.format(Message::SuitDecided, &[&high, &beaten]));
+    }
+    lines.join("\n")
+}
+
+/// A line on how an autoplayed round went for the strategy called `name`.
+fn commentary(game: &Game, name: &str, result: &RoundResult, locale: Locale) -> String {
+    match (result.won, game.streak()) {
+        (true, streak) if streak > 1 => locale.format(Message::AutoplayStreak, &[&name, &streak]),
+        (true, _) => locale.format(Message::AutoplayFound, &[&name]),
+        (false, _) => {
+            let high = game.hand().cards()[result.winner];
+            locale.format(Message::AutoplayMissed, &[&name, &high])
+        }
+    }
+}
+
+/// Bets the usual wager on an autoplayed round when the game is betting.
+fn auto_wager(io: &mut dyn Io, game: &mut Game, locale: Locale) -> io::Result<()> {
+    let wager = match game.bank() {
+        Some(bank) => bank.chips().min(10),
+        None => return Ok(()),
+    };
+    if let Event::Wagered(chips) = game.advance(Input::Wager(wager)) {
+        writeln!(io.out(), "{}", locale.format(Message::Wager, &[&chips]))?;
+    }
+    Ok(())
+}
+
+/// Reads a line typed at the pick prompt: None unless it's the number of one
+/// of `cards` cards, or empty for a random choice, which is Some(None).
+fn parse_pick(input: &str, cards: usize) -> Option<Option<usize>> {
+    match input.trim() {
+        "" => Some(None),
+        input => input
+            .parse::<usize>()
+            .ok()
+            .filter(|&pick| pick < cards)
+            .map(Some),
+    }
+}
+
+/// Shows the hand with a marker under the card at `pick` and asks to lock it
+/// in, taking the locale's yes or no. Returns false if the player would rather
+/// pick again.
+async fn confirm_pick(
+    io: &mut dyn Io,
+    game: &Game,
+    pick: usize,
+    render: &RenderOptions,
+    locale: Locale,
+) -> io::Result<bool> {
+    io.show_hand(game.hand().cards(), render)?;
+    let labels: Vec<String> = (0..game.hand().len())
+        .map(|idx| if idx == pick { "^^^^^^^" } else { "" }.to_string())
+        .collect();
+    let mut marker = String::new();
+    write_labels(&mut marker, &labels, render).expect("writing to a String cannot fail");
+    let colored = io.is_terminal() && render.color.resolve() == ColorMode::Always;
+    if colored {
+        marker = render.theme.highlight.bold().paint(marker);
+    }
+    write!(io.out(), "{}", marker)?;
+    loop {
+        writeln!(
+            io.out(),
+            "{}",
+            locale.format(Message::ConfirmPick, &[&pick])
+        )?;
+        match io.read_line().await?.trim().to_lowercase().as_str() {
+            answer if locale.is_yes(answer) => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => {}
+        }
+    }
+}
+
+/// The error shown when a pick isn't one of the cards, listing their numbers.
+fn not_a_card(game: &Game, locale: Locale) -> String {
+    let numbers: Vec<String> = (0..game.hand().len()).map(|idx| idx.to_string()).collect();
+    locale.format(Message::NotACard, &[&locale.or_list(&numbers)])
+}
+
+/// Whether a line at the pick prompt is something other than a pick, a random
+/// choice or quitting, and so a debug command.
+fn is_command(input: &str) -> bool {
+    let input = input.trim();
+    !input.is_empty() && !wants_to_quit(input) && input.parse::<usize>().is_err()
+}
+
+/// Asks how many chips to bet on the round when the player is betting.
+/// Returns true if the player typed [q] to save and quit.
+async fn ask_wager(io: &mut dyn Io, game: &mut Game, locale: Locale) -> io::Result<bool> {
+    let chips = match game.bank() {
+        Some(bank) => bank.chips(),
+        None => return Ok(false),
+    };
+    let default = chips.min(10);
+    loop {
+        writeln!(
+            io.out(),
+            "{}",
+            locale.format(Message::WagerPrompt, &[&chips, &default])
+        )?;
+        let input = io.read_line().await?;
+        if wants_to_quit(&input) {
+            return Ok(true);
+        }
+        let amount = match input.trim() {
+            "" => default,
+            amount => match amount.parse() {
+                Ok(amount) => amount,
+                Err(_) => {
+                    writeln!(io.out(), "{}", locale.text(Message::WagerNotNumber))?;
+                    continue;
+                }
+            },
+        };
+        match game.advance(Input::Wager(amount)) {
+            Event::WagerRejected(err) => writeln!(
+                io.out(),
+                "{}",
+                locale.format(Message::WagerRejected, &[&err])
+            )?,
+            _ => return Ok(false),
+        }
+    }
+}
+
+/// Shows a hand that was just dealt, sliding the cards in one at a time at
+/// `speed` when `animation` is on and `io` is a terminal.
+pub(super) async fn show_deal(
+    io: &mut dyn Io,
+    hand: &[Card],
+    render: &RenderOptions,
+    animation: bool,
+    speed: Speed,
+) -> Result<(), GameError> {
+    if animation && io.is_terminal() {
+        animate_deal(hand, render, speed.scale(SLIDE_FRAME)).await
+    } else {
+        Ok(io.show_hand(hand, render)?)
+    }
+}
+
+/// Shows a hand that was just turned over from how it looked in `before`,
+/// flipping the cards one at a time at `speed` when `animation` is on and `io`
+/// is a terminal.
+pub(super) async fn show_reveal(
+    io: &mut dyn Io,
+    before: &Hand,
+    after: &[Card],
+    render: &RenderOptions,
+    animation: bool,
+    speed: Speed,
+) -> Result<(), GameError> {
+    if animation && io.is_terminal() {
+        animate_flip(before.cards(), after, render, speed.scale(FLIP_FRAME)).await
+    } else {
+        Ok(io.show_hand(after, render)?)
+    }
+}
+
+/// Shows the `dealt` hand being shuffled by `swaps` after a guess on hard.
+async fn shuffle_hand(
+    io: &mut dyn Io,
+    mut dealt: Hand,
+    swaps: &[(usize, usize)],
+    render: &RenderOptions,
+    locale: Locale,
+    speed: Speed,
+) -> io::Result<()> {
+    if swaps.is_empty() {
+        return Ok(());
+    }
+    writeln!(io.out(), "{}", locale.text(Message::Shuffling))?;
+    for &(first, second) in swaps {
+        dealt.swap(first, second);
+        io.show_hand(dealt.cards(), render)?;
+        io.pause(speed.scale(Duration::from_millis(300))).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{explain, game_over, parse_pick, play_again, summary, AfterGame, HighCard};
+    use crate::bank::Bank;
+    use crate::cards::{Card, Hand, Rank, Suit};
+    use crate::config::Config;
+    use crate::engine::run;
+    use crate::game::state::Input;
+    use crate::game::{Difficulty, GameBuilder, RoundLimit};
+    use crate::io::Script;
+    use crate::locale::Locale;
+    use crate::stats::Stats;
+    use crate::strategy::FirstCard;
+    use std::time::Duration;
+
+    /// The default config in English, whatever the environment's locale.
+    fn config() -> Config {
+        Config {
+            locale: Some(Locale::English),
+            ..Config::new()
+        }
+    }
+
+    #[tokio::test]
+    /// Tests a scripted game plays through and shows every round.
+    async fn test_scripted_game() {
+        let mut game = GameBuilder::new()
+            .with_seed(11)
+            .rounds(RoundLimit::Rounds(2))
+            .spawn()
+            .unwrap();
+        let mut stats = Stats::new();
+        let mut script = Script::new(vec!["2", ""]);
+        let quit = {
+            let mut mode = HighCard::new(&mut game, &mut stats, &config(), None).io(&mut script);
+            run(&mut mode, Duration::from_secs(5)).await.unwrap()
+        };
+
+        assert!(!quit);
+        assert_eq!(script.remaining(), 0);
+        assert_eq!(stats.games, 2);
+        assert_eq!(game.history().rounds()[0].choice, 2);
+        let output = script.output();
+        assert!(output.starts_with("Seed 11\nDifficulty Normal\n"));
+        assert_eq!(output.matches("Find the High card.").count(), 2);
+        assert_eq!(output.matches("You win!!!").count(), game.wins());
+        assert!(output.trim_end().ends_with(&game.history().to_string()));
+        assert!(output.contains("That's all 2 rounds."));
+    }
+
+    #[tokio::test]
+    /// Tests the results screen is shown when the deck runs out, and the player
+    /// is asked again until they pick what to do next.
+    async fn test_game_over() {
+        let mut game = GameBuilder::new().with_seed(11).spawn().unwrap();
+        while !game.out_of_cards() {
+            game.advance(Input::Deal);
+            game.advance(Input::Choose(0));
+            game.advance(Input::Reveal);
+            game.advance(Input::Continue);
+        }
+        let screen = game_over(&game, Locale::English);
+        assert!(screen.starts_with("Game over, the deck is empty.\n"));
+        assert!(screen.contains(&format!(
+            "You found the high card {} times in {} rounds",
+            game.wins(),
+            game.games_played()
+        )));
+        assert!(screen.contains(&format!("Best streak {}", game.best_streak())));
+        assert!(summary(&game, Locale::English).starts_with(&screen));
+
+        let mut script = Script::new(vec!["x", "p"]);
+        assert_eq!(
+            play_again(&mut script, Locale::English).await.unwrap(),
+            AfterGame::PlayAgain
+        );
+        assert_eq!(script.output().matches("[p] to play again").count(), 2);
+        let mut script = Script::new(vec!["m"]);
+        assert_eq!(
+            play_again(&mut script, Locale::English).await.unwrap(),
+            AfterGame::Menu
+        );
+        let mut script = Script::new(vec![""]);
+        assert_eq!(
+            play_again(&mut script, Locale::English).await.unwrap(),
+            AfterGame::Quit
+        );
+    }
+
+    #[tokio::test]
+    /// Tests a pick that isn't a card's number is refused and asked for again,
+    /// and only an empty line picks at random.
+    async fn test_scripted_invalid_pick() {
+        let mut game = GameBuilder::new()
+            .with_seed(11)
+            .rounds(RoundLimit::Rounds(1))
+            .spawn()
+            .unwrap();
+        let mut stats = Stats::new();
+        let mut script = Script::new(vec!["7", "two", "1"]);
+        {
+            let mut mode = HighCard::new(&mut game, &mut stats, &config(), None).io(&mut script);
+            run(&mut mode, Duration::from_secs(0)).await.unwrap();
+        }
+        let output = script.output();
+        let error = "Enter 0, 1, or 2, or press Enter for random.";
+        assert_eq!(output.matches(error).count(), 2);
+        assert_eq!(game.history().rounds()[0].choice, 1);
+
+        assert_eq!(parse_pick(" ", 3), Some(None));
+        assert_eq!(parse_pick("2\n", 3), Some(Some(2)));
+        assert_eq!(parse_pick("3", 3), None);
+        assert_eq!(parse_pick("-1", 3), None);
+    }
+
+    #[tokio::test]
+    /// Tests a typed pick is marked and asked about before it's locked in, and
+    /// refusing it goes back to picking. Only English answers count as yes.
+    async fn test_scripted_confirm() {
+        let mut game = GameBuilder::new()
+            .with_seed(11)
+            .rounds(RoundLimit::Rounds(1))
+            .spawn()
+            .unwrap();
+        let mut stats = Stats::new();
+        let config = Config {
+            confirm: Some(true),
+            animation: Some(false),
+            ..config()
+        };
+        let mut script = Script::new(vec!["0", "n", "2", "maybe", "s", "y"]);
+        {
+            let mut mode = HighCard::new(&mut game, &mut stats, &config, None).io(&mut script);
+            run(&mut mode, Duration::from_secs(0)).await.unwrap();
+        }
+        let output = script.output();
+        assert!(output.contains("Lock in card 0? [Y/n]"));
+        assert_eq!(output.matches("Lock in card 2? [Y/n]").count(), 3);
+        assert!(output.contains("^^^^^^^"));
+        assert_eq!(script.remaining(), 0);
+        assert_eq!(game.history().rounds()[0].choice, 2);
+    }
+
+    #[tokio::test]
+    /// Tests the reveal explains every card's score and how a tie between cards
+    /// of the same rank was broken.
+    async fn test_explain() {
+        let mut game = GameBuilder::new()
+            .with_seed(4)
+            .rounds(RoundLimit::Rounds(1))
+            .spawn()
+            .unwrap();
+        game.advance(Input::Deal);
+        let same_rank = vec![
+            Card::new(Suit::Clubs, Rank::Nine),
+            Card::new(Suit::Spades, Rank::Four),
+            Card::new(Suit::Diamonds, Rank::Nine),
+        ];
+        *game.hand_mut() = Hand::from_cards(same_rank);
+        let explained = explain(&game, 0, Locale::English);
+        assert_eq!(
+            explained,
+            "Card 0 is the high card:\n  \
+             [0] 9♣ scores 9 x 4 + 3 = 39\n  \
+             [1] 4♠ scores 4 x 4 + 0 = 16\n  \
+             [2] 9♦ scores 9 x 4 + 1 = 37\n\
+             9♣ and 9♦ are the same rank, so the suit decided."
+        );
+
+        let mut stats = Stats::new();
+        let mut script = Script::new(vec!["1"]);
+        {
+            let mut mode = HighCard::new(&mut game, &mut stats, &config(), None).io(&mut script);
+            run(&mut mode, Duration::from_secs(0)).await.unwrap();
+        }
+        let winner = game.history().rounds()[0].winner;
+        let heading = format!("Card {} is the high card:", winner);
+        assert!(script.output().contains(&heading));
+    }
+
+    #[tokio::test]
+    /// Tests quitting at the prompt stops the game before the round is played.
+    async fn test_scripted_quit() {
+        let mut game = GameBuilder::new().with_seed(11).spawn().unwrap();
+        let mut stats = Stats::new();
+        let mut script = Script::new(vec!["q"]);
+        let mut mode = HighCard::new(&mut game, &mut stats, &config(), None).io(&mut script);
+        assert!(run(&mut mode, Duration::from_secs(5)).await.unwrap());
+        drop(mode);
+        assert_eq!(game.games_played(), 0);
+    }
+
+    #[tokio::test]
+    /// Tests a screen reader hears each card described instead of drawn.
+    async fn test_scripted_screen_reader() {
+        let mut game = GameBuilder::new()
+            .with_seed(11)
+            .rounds(RoundLimit::Rounds(1))
+            .spawn()
+            .unwrap();
+        let mut stats = Stats::new();
+        let config = Config {
+            screen_reader: Some(true),
+            ..config()
+        };
+        let mut script = Script::new(vec!["1"]);
+        {
+            let mut mode = HighCard::new(&mut game, &mut stats, &config, None).io(&mut script);
+            run(&mut mode, Duration::from_secs(5)).await.unwrap();
+        }
+
+        let output = script.output();
+        assert!(output.contains("Card 0 is hidden. Card 1 is hidden. Card 2 is hidden.\n"));
+        assert!(output.contains("Card 1, your pick, is the "));
+        assert!(!output.contains("*---"));
+    }
+
+    #[tokio::test]
+    /// Tests the game's text follows the configured locale.
+    async fn test_scripted_locale() {
+        let mut game = GameBuilder::new().with_seed(11).spawn().unwrap();
+        let mut stats = Stats::new();
+        let config = Config {
+            locale: Some(Locale::Spanish),
+            ..config()
+        };
+        let mut script = Script::new(vec!["q"]);
+        {
+            let mut mode = HighCard::new(&mut game, &mut stats, &config, None).io(&mut script);
+            run(&mut mode, Duration::from_secs(5)).await.unwrap();
+        }
+        let output = script.output();
+        assert!(output.starts_with("Semilla 11\nDificultad Normal\n"));
+        assert!(output.contains("Encuentra la carta más alta."));
+    }
+
+    #[tokio::test]
+    /// Tests hints give the odds of every card before the guess, adding up to
+    /// a whole.
+    async fn test_scripted_hints() {
+        let mut game = GameBuilder::new()
+            .with_seed(11)
+            .difficulty(Difficulty::Easy)
+            .spawn()
+            .unwrap();
+        let mut stats = Stats::new();
+        let config = Config {
+            hints: Some(true),
+            ..config()
+        };
+        let mut script = Script::new(vec!["q"]);
+        {
+            let mut mode = HighCard::new(&mut game, &mut stats, &config, None).io(&mut script);
+            run(&mut mode, Duration::from_secs(5)).await.unwrap();
+        }
+        let output = script.output();
+        let face_up = game.hand().iter().position(|card| card.state.is_face_up());
+        let face_up = face_up.unwrap();
+        assert!(output.contains(&format!(
+            "Card {}, {}, has a",
+            face_up,
+            game.hand().cards()[face_up]
+        )));
+        assert!(output.contains("Still unseen: "));
+        assert!((game.odds().iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    /// Tests debug commands are run at the pick prompt before the pick.
+    async fn test_scripted_debug() {
+        let mut game = GameBuilder::new()
+            .with_seed(4)
+            .rounds(RoundLimit::Rounds(1))
+            .spawn()
+            .unwrap();
+        let mut stats = Stats::new();
+        let mut script = Script::new(vec!["show deck", "force win", "shuffle", "0", ""]);
+        {
+            let mut mode = HighCard::new(&mut game, &mut stats, &config(), None)
+                .debug(true)
+                .io(&mut script);
+            run(&mut mode, Duration::from_secs(0)).await.unwrap();
+        }
+        let output = script.output();
+        assert!(output.contains("49 cards left: "));
+        assert!(output.contains("The next pick wins."));
+        assert!(output.contains("unknown command \"shuffle\""));
+        assert_eq!(stats.wins, 1);
+    }
+
+    #[tokio::test]
+    /// Tests each card's score is shown under the revealed hand.
+    async fn test_scripted_values() {
+        let mut game = GameBuilder::new()
+            .with_seed(4)
+            .rounds(RoundLimit::Rounds(1))
+            .spawn()
+            .unwrap();
+        let mut stats = Stats::new();
+        let config = Config {
+            values: Some(true),
+            animation: Some(false),
+            ..config()
+        };
+        let mut script = Script::new(vec!["0", ""]);
+        {
+            let mut mode = HighCard::new(&mut game, &mut stats, &config, None).io(&mut script);
+            run(&mut mode, Duration::from_secs(0)).await.unwrap();
+        }
+        let scores: Vec<String> = game
+            .hand()
+            .iter()
+            .map(|card| format!("= {}", game.scoring().score(card)))
+            .collect();
+        let line = script
+            .output()
+            .lines()
+            .find(|line| line.contains("= "))
+            .unwrap()
+            .to_string();
+        assert_eq!(line.split_whitespace().count(), 6);
+        assert!(scores.iter().all(|score| line.contains(score.as_str())));
+    }
+
+    #[tokio::test]
+    /// Tests autoplay picks and bets without reading any input, commentating as
+    /// it goes, and keeps the player's practice prompt out of the way.
+    async fn test_scripted_autoplay() {
+        let mut game = GameBuilder::new()
+            .with_seed(11)
+            .rounds(RoundLimit::Rounds(3))
+            .bank(Bank::new(50))
+            .spawn()
+            .unwrap();
+        let mut stats = Stats::new();
+        let mut script = Script::new(Vec::<String>::new());
+        {
+            let mut mode = HighCard::new(&mut game, &mut stats, &config(), None)
+                .autoplay("first", FirstCard)
+                .practice(true)
+                .io(&mut script);
+            assert!(!run(&mut mode, Duration::from_secs(0)).await.unwrap());
+        }
+
+        assert!(game
+            .history()
+            .rounds()
+            .iter()
+            .all(|round| round.choice == 0));
+        let output = script.output();
+        assert!(output.starts_with("Autoplay: first is playing."));
+        assert_eq!(output.matches("first picks card 0.").count(), 3);
+        assert_eq!(output.matches("Wager 10 chips.").count(), 3);
+        let commented = output.matches("first found the high card").count()
+            + output.matches("in a row").count()
+            + output.matches("first missed").count();
+        assert_eq!(commented, 3);
+        assert!(!output.contains("[u]"));
+    }
+}
@@ -0,0 +1,240 @@
+//! This module provides a guided first round of the high card game.
+//!
+//! The deck is stacked so every new player is dealt the same hand: two kings
+//! and a four, so the suit tiebreak comes up straight away. Each step of the
+//! round is explained as it happens, from reading the cards to typing a pick,
+//! and once the cards are turned over the value of each is shown.
+
+use super::high_card::{show_deal, show_reveal};
+use super::{wants_to_quit, Flow, GameMode};
+use crate::cards::card_printer::RenderOptions;
+use crate::cards::{Card, Deck, Rank, Suit};
+use crate::config::Config;
+use crate::error::GameError;
+use crate::game::state::{Event, Input};
+use crate::game::{Game, GameBuilder};
+use crate::io::{Io, Terminal};
+use crate::locale::{Locale, Message};
+use std::future::Future;
+use std::io::{self};
+use std::time::Duration;
+
+/// The hand the tutorial deals, left to right. The king of clubs is high.
+pub const DEAL: [(Suit, Rank); 3] = [
+    (Suit::Hearts, Rank::King),
+    (Suit::Spades, Rank::Four),
+    (Suit::Clubs, Rank::King),
+];
+
+/// A single round of the high card game with every step explained.
+pub struct Tutorial<'a> {
+    game: Game,
+    render: RenderOptions,
+    animation: bool,
+    locale: Locale,
+    done: bool,
+    io: Box<dyn Io + 'a>,
+}
+
+impl<'a> Tutorial<'a> {
+    /// Sets up the tutorial with the configured theme and language.
+    pub fn new(config: &Config) -> Tutorial<'a> {
+        let mut deck = Deck::new();
+        for &(suit, rank) in DEAL.iter().rev() {
+            if let Some(card) = deck.take(&Card::new(suit, rank)) {
+                deck.place_top(card);
+            }
+        }
+        Tutorial {
+            game: GameBuilder::new()
+                .deck(deck)
+                .speed(config.speed())
+                .spawn()
+                .expect("the tutorial's options are always in range"),
+            render: config.render_options().show_index(true),
+            animation: config.animation(),
+            locale: config.locale(),
+            done: false,
+            io: Box::new(Terminal::new()),
+        }
+    }
+
+    /// Option to play through `io` instead of the terminal.
+    pub fn io<I: Io + 'a>(mut self, io: I) -> Tutorial<'a> {
+        self.io = Box::new(io);
+        self
+    }
+
+    /// Asks for a card number until one is given, or None if the player quits
+    /// or the input runs out.
+    async fn read_choice(&mut self) -> io::Result<Option<usize>> {
+        let (locale, last) = (self.locale, self.game.hand().len() - 1);
+        loop {
+            let input = self.io.read_line().await?;
+            if input.is_empty() || wants_to_quit(&input) {
+                return Ok(None);
+            }
+            match input.trim().parse::<usize>() {
+                Ok(choice) if choice <= last => return Ok(Some(choice)),
+                _ => writeln!(
+                    self.io.out(),
+                    "{}",
+                    locale.format(Message::TutorialNotCard, &[&last])
+                )?,
+            }
+        }
+    }
+}
+
+impl<'a> GameMode for Tutorial<'a> {
+    fn setup(&mut self) -> Result<(), GameError> {
+        writeln!(
+            self.io.out(),
+            "{}",
+            self.locale.text(Message::TutorialWelcome)
+        )?;
+        Ok(())
+    }
+
+    async fn play_round(&mut self) -> Result<Flow, GameError> {
+        let locale = self.locale;
+        self.game.advance(Input::Deal);
+        writeln!(self.io.out(), "{}", locale.text(Message::TutorialDeal))?;
+        show_deal(
+            self.io.as_mut(),
+            self.game.hand().cards(),
+            &self.render,
+            self.animation,
+            self.game.speed(),
+        )
+        .await?;
+        writeln!(self.io.out(), "{}", locale.text(Message::TutorialRanks))?;
+        writeln!(self.io.out(), "{}", locale.text(Message::TutorialSuits))?;
+        let last = self.game.hand().len() - 1;
+        writeln!(
+            self.io.out(),
+            "{}",
+            locale.format(Message::TutorialChoose, &[&last])
+        )?;
+
+        let choice = match self.read_choice().await? {
+            Some(choice) => choice,
+            None => return Ok(Flow::Quit),
+        };
+        self.game.advance(Input::Choose(choice));
+        let before = self.game.hand().clone();
+        if let Event::Revealed(result) = self.game.advance(Input::Reveal) {
+            show_reveal(
+                self.io.as_mut(),
+                &before,
+                self.game.hand().cards(),
+                &self.render,
+                self.animation,
+                self.game.speed(),
+            )
+            .await?;
+            writeln!(self.io.out(), "{}", locale.text(Message::TutorialValues))?;
+            for (idx, card) in self.game.hand().iter().enumerate() {
+                let value = self.game.scoring().score(card);
+                writeln!(
+                    self.io.out(),
+                    "{}",
+                    locale.format(Message::TutorialValue, &[&idx, card, &value])
+                )?;
+            }
+            let high = result.winner;
+            let message = match result.won {
+                true => Message::TutorialWon,
+                false => Message::TutorialLost,
+            };
+            let card = self.game.hand().cards()[high];
+            writeln!(self.io.out(), "{}", locale.format(message, &[&high, &card]))?;
+        }
+        self.game.advance(Input::Continue);
+        self.done = true;
+        Ok(Flow::Continue)
+    }
+
+    fn is_over(&self) -> bool {
+        self.done
+    }
+
+    fn summary(&self) -> String {
+        self.locale.text(Message::TutorialDone).to_string()
+    }
+
+    fn pause(&mut self, duration: Duration) -> impl Future<Output = io::Result<()>> {
+        self.io.pause(duration)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        let summary = self.summary();
+        writeln!(self.io.out(), "{}", summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tutorial;
+    use crate::config::Config;
+    use crate::engine::run;
+    use crate::io::Script;
+    use crate::locale::Locale;
+    use std::time::Duration;
+
+    /// The default config in English, whatever the environment's locale.
+    fn config() -> Config {
+        Config {
+            locale: Some(Locale::English),
+            animation: Some(false),
+            ..Config::new()
+        }
+    }
+
+    #[tokio::test]
+    /// Tests the tutorial re-asks for a pick until it's a card and explains
+    /// the result.
+    async fn test_tutorial() {
+        let mut script = Script::new(vec!["king", "7", "2"]);
+        let quit = run(
+            &mut Tutorial::new(&config()).io(&mut script),
+            Duration::from_secs(0),
+        )
+        .await
+        .unwrap();
+        assert!(!quit);
+        let output = script.output();
+        assert_eq!(output.matches("isn't one of the card numbers").count(), 2);
+        assert!(output.contains("Card 2, K♣, is worth 55."));
+        assert!(output.contains("Card 0, K♥, is worth 54."));
+        assert!(output.contains("You found it: card 2, K♣, is the high card."));
+        assert!(output.contains("That's the tutorial done."));
+    }
+
+    #[tokio::test]
+    /// Tests quitting at the pick ends the tutorial early.
+    async fn test_tutorial_quit() {
+        let mut script = Script::new(vec!["q"]);
+        let quit = run(
+            &mut Tutorial::new(&config()).io(&mut script),
+            Duration::from_secs(0),
+        )
+        .await
+        .unwrap();
+        assert!(quit);
+    }
+
+    #[tokio::test]
+    /// Tests the tutorial ends when the input runs out instead of asking again.
+    async fn test_tutorial_eof() {
+        let mut script = Script::new(Vec::<&str>::new());
+        let quit = run(
+            &mut Tutorial::new(&config()).io(&mut script),
+            Duration::from_secs(0),
+        )
+        .await
+        .unwrap();
+        assert!(quit);
+        assert!(!script.output().contains("isn't one of the card numbers"));
+    }
+}
@@ -0,0 +1,118 @@
+//! This module provides the error types shared across the crate.
+
+use crate::cards::ParseCardError;
+#[cfg(feature = "net")]
+use crate::net::NetError;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self};
+
+/// Why a builder couldn't build what was asked for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GameConfigError {
+    /// Fewer than three cards a round.
+    TooFewCards(u8),
+    /// More than five cards a round.
+    TooManyCards(u8),
+    /// A game needs at least one player.
+    NoPlayers,
+    /// More than four players.
+    TooManyPlayers(u8),
+    /// A shoe needs at least one deck.
+    NoDecks,
+    /// Every rank or every suit was stripped from the deck.
+    EmptyDeck,
+}
+
+impl Display for GameConfigError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            GameConfigError::TooFewCards(count) => {
+                write!(formatter, "{} cards is too few, deal at least 3", count)
+            }
+            GameConfigError::TooManyCards(count) => {
+                write!(formatter, "{} cards is too many, deal at most 5", count)
+            }
+            GameConfigError::NoPlayers => write!(formatter, "a game needs at least one player"),
+            GameConfigError::TooManyPlayers(count) => {
+                write!(formatter, "{} players is too many, seat at most 4", count)
+            }
+            GameConfigError::NoDecks => write!(formatter, "a shoe needs at least one deck"),
+            GameConfigError::EmptyDeck => write!(formatter, "the deck has no cards left in it"),
+        }
+    }
+}
+
+impl std::error::Error for GameConfigError {}
+
+/// Anything that can stop a game part way through.
+#[derive(Debug)]
+pub enum GameError {
+    /// Reading input or writing output failed.
+    Io(io::Error),
+    /// Text that should have named a card didn't.
+    Parse(ParseCardError),
+    /// The game couldn't be set up as asked.
+    Config(GameConfigError),
+    /// A hand couldn't be drawn.
+    Render(fmt::Error),
+    /// A network match broke off.
+    #[cfg(feature = "net")]
+    Net(NetError),
+}
+
+impl Display for GameError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            GameError::Io(err) => write!(formatter, "{}", err),
+            GameError::Parse(err) => write!(formatter, "{}", err),
+            GameError::Config(err) => write!(formatter, "couldn't start the game: {}", err),
+            GameError::Render(_) => write!(formatter, "couldn't draw the cards"),
+            #[cfg(feature = "net")]
+            GameError::Net(err) => write!(formatter, "network match ended: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for GameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GameError::Io(err) => Some(err),
+            GameError::Parse(err) => Some(err),
+            GameError::Config(err) => Some(err),
+            GameError::Render(err) => Some(err),
+            #[cfg(feature = "net")]
+            GameError::Net(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for GameError {
+    fn from(err: io::Error) -> GameError {
+        GameError::Io(err)
+    }
+}
+
+impl From<ParseCardError> for GameError {
+    fn from(err: ParseCardError) -> GameError {
+        GameError::Parse(err)
+    }
+}
+
+impl From<GameConfigError> for GameError {
+    fn from(err: GameConfigError) -> GameError {
+        GameError::Config(err)
+    }
+}
+
+impl From<fmt::Error> for GameError {
+    fn from(err: fmt::Error) -> GameError {
+        GameError::Render(err)
+    }
+}
+
+#[cfg(feature = "net")]
+impl From<NetError> for GameError {
+    fn from(err: NetError) -> GameError {
+        GameError::Net(err)
+    }
+}
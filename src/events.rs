@@ -0,0 +1,299 @@
+//! This module provides live spectating of the high card game over WebSockets.
+//!
+//! Built with the `events` feature. A game given `Spectators` sends each deal,
+//! choice, reveal and result as a JSON text message to every connected
+//! WebSocket client. Opening the port in a browser without upgrading serves a
+//! small page that connects back and lists the rounds as they're played.
+//!
+//! The server runs on a tokio runtime of its own thread, so a game sends to it
+//! without waiting whatever drives the game. The WebSocket side is
+//! `tokio-tungstenite`; only telling a browser's page request apart from an
+//! upgrade is done here. Anything the client sends is ignored.
+
+use crate::cards::Card;
+use crate::game::state::Event;
+use crate::game::Game;
+use futures_util::SinkExt;
+use serde::Serialize;
+use std::io;
+use std::net::{IpAddr, TcpListener};
+use std::thread;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::broadcast::{self, Sender};
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+/// Broadcasts held for a spectator that hasn't been sent them yet. One that
+/// falls further behind is dropped.
+const BACKLOG: usize = 64;
+
+/// How long a slow spectator can hold up a round before being dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// How long a client gets to send its request before it's hung up on.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The page served to a browser that isn't asking for a WebSocket.
+const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><title>Super Card Game</title></head>
+<body style="font-family: monospace">
+<h1>Super Card Game</h1>
+<ol id="log"></ol>
+<script>
+const log = document.getElementById("log");
+const socket = new WebSocket("ws://" + location.host + "/");
+socket.onmessage = (message) => {
+  const item = document.createElement("li");
+  item.textContent = message.data;
+  log.appendChild(item);
+};
+socket.onclose = () => { log.insertAdjacentHTML("beforeend", "<li>Game over.</li>"); };
+</script>
+</body>
+</html>
+"#;
+
+/// A round event as spectators see it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Broadcast {
+    /// A hand was dealt, with the cards still face down.
+    Deal { cards: Vec<Card> },
+    /// The player picked the card at `choice`.
+    Choice { choice: usize },
+    /// The hand was turned face up.
+    Reveal { cards: Vec<Card> },
+    /// The round was scored.
+    Result {
+        winner: usize,
+        won: bool,
+        points: u64,
+    },
+    /// The deck or the chips ran out.
+    GameOver,
+}
+
+impl Broadcast {
+    /// What spectators are told about `event`, which `game` just returned.
+    pub fn from_event(event: &Event, game: &Game) -> Vec<Broadcast> {
+        match event {
+            Event::Dealt => vec![Broadcast::Deal {
+                cards: game.hand().cards().to_vec(),
+            }],
+            Event::Chose { choice, .. } => vec![Broadcast::Choice { choice: *choice }],
+            Event::Revealed(result) => vec![
+                Broadcast::Reveal {
+                    cards: game.hand().cards().to_vec(),
+                },
+                Broadcast::Result {
+                    winner: result.winner,
+                    won: result.won,
+                    points: result.points,
+                },
+            ],
+            Event::GameOver => vec![Broadcast::GameOver],
+            _ => vec![],
+        }
+    }
+}
+
+/// Everyone watching a game. Clients connect in the background for as long as
+/// the program runs.
+#[derive(Clone)]
+pub struct Spectators {
+    broadcasts: Sender<String>,
+    port: u16,
+}
+
+impl Spectators {
+    /// Listens on `addr` and `port` for spectators, or on any free port given
+    /// 0. Only this machine can watch unless `addr` is wider than loopback.
+    pub fn bind(addr: IpAddr, port: u16) -> io::Result<Spectators> {
+        let listener = TcpListener::bind((addr, port))?;
+        listener.set_nonblocking(true)?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let (broadcasts, _) = broadcast::channel(BACKLOG);
+        let spectators = Spectators {
+            broadcasts: broadcasts.clone(),
+            port: listener.local_addr()?.port(),
+        };
+        thread::spawn(move || {
+            runtime.block_on(async move {
+                let listener = match tokio::net::TcpListener::from_std(listener) {
+                    Ok(listener) => listener,
+                    Err(_) => return,
+                };
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(serve(stream, broadcasts.clone()));
+                }
+            })
+        });
+        Ok(spectators)
+    }
+
+    /// The port spectators connect to.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// How many spectators are connected.
+    pub fn len(&self) -> usize {
+        self.broadcasts.receiver_count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sends `broadcast` to every spectator, dropping any that can't keep up.
+    pub fn send(&self, broadcast: &Broadcast) {
+        let json = serde_json::to_string(broadcast).expect("broadcasts always serialize");
+        // Nobody watching isn't a failure.
+        let _ = self.broadcasts.send(json);
+    }
+}
+
+/// Upgrades a client to a WebSocket and sends it every broadcast, or serves the
+/// spectator page to a browser that didn't ask for one. Each client is served
+/// by a task of its own, so one that never finishes its request only ties up
+/// itself.
+async fn serve(stream: TcpStream, broadcasts: Sender<String>) {
+    let mut socket = match timeout(READ_TIMEOUT, handshake(stream)).await {
+        Ok(Ok(Some(socket))) => socket,
+        _ => return,
+    };
+    let mut receiver = broadcasts.subscribe();
+    // A closed channel or a spectator that fell behind ends the connection.
+    while let Ok(json) = receiver.recv().await {
+        match timeout(WRITE_TIMEOUT, socket.send(Message::text(json))).await {
+            Ok(Ok(())) => {}
+            _ => return,
+        }
+    }
+}
+
+/// Reads an HTTP request from `stream`. Answers the WebSocket upgrade and returns
+/// the socket, or serves the spectator page and returns None.
+async fn handshake(stream: TcpStream) -> io::Result<Option<WebSocketStream<TcpStream>>> {
+    let mut reader = BufReader::new(stream);
+    let mut key = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("sec-websocket-key") {
+                key = Some(value.trim().to_string());
+            }
+        }
+    }
+    let read_ahead = reader.buffer().to_vec();
+    let mut stream = reader.into_inner();
+
+    match key {
+        Some(key) => {
+            let response = format!(
+                "HTTP/1.1 101 Switching Protocols\r\n\
+                 Upgrade: websocket\r\n\
+                 Connection: Upgrade\r\n\
+                 Sec-WebSocket-Accept: {}\r\n\r\n",
+                derive_accept_key(key.as_bytes())
+            );
+            stream.write_all(response.as_bytes()).await?;
+            let socket =
+                WebSocketStream::from_partially_read(stream, read_ahead, Role::Server, None).await;
+            Ok(Some(socket))
+        }
+        None => {
+            let response = format!(
+                "HTTP/1.1 200 OK\r\n\
+                 Content-Type: text/html; charset=utf-8\r\n\
+                 Content-Length: {}\r\n\
+                 Connection: close\r\n\r\n{}",
+                PAGE.len(),
+                PAGE
+            );
+            stream.write_all(response.as_bytes()).await?;
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Spectators;
+    use crate::game::state::Input;
+    use crate::game::GameBuilder;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{Ipv4Addr, TcpStream};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    /// Tests a browser that doesn't ask for a WebSocket is served the page.
+    fn test_page() {
+        let spectators = Spectators::bind(Ipv4Addr::LOCALHOST.into(), 0).unwrap();
+        let mut client = TcpStream::connect(("127.0.0.1", spectators.port())).unwrap();
+        client
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("</html>\n"));
+        assert!(spectators.is_empty());
+    }
+
+    #[test]
+    /// Tests a connected spectator receives each round event as a text frame.
+    fn test_spectate() {
+        let spectators = Spectators::bind(Ipv4Addr::LOCALHOST.into(), 0).unwrap();
+        // A client that never finishes its request mustn't hold up the others.
+        let _stalled = TcpStream::connect(("127.0.0.1", spectators.port())).unwrap();
+        let mut client = TcpStream::connect(("127.0.0.1", spectators.port())).unwrap();
+        client
+            .write_all(b"GET / HTTP/1.1\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n")
+            .unwrap();
+        let mut reader = BufReader::new(client);
+        let mut response = String::new();
+        while !response.ends_with("\r\n\r\n") {
+            assert_ne!(reader.read_line(&mut response).unwrap(), 0);
+        }
+        // The answer RFC 6455 gives for its sample key.
+        assert!(response.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n"));
+        while spectators.is_empty() {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let mut game = GameBuilder::new().with_seed(3).spawn().unwrap();
+        game.spectate(spectators);
+        game.advance(Input::Deal);
+        game.advance(Input::Choose(1));
+
+        let mut read_frame = || {
+            let mut header = [0u8; 2];
+            reader.read_exact(&mut header).unwrap();
+            assert_eq!(header[0], 0x81);
+            let mut len = header[1] as usize;
+            if len == 126 {
+                let mut extended = [0u8; 2];
+                reader.read_exact(&mut extended).unwrap();
+                len = u16::from_be_bytes(extended) as usize;
+            }
+            let mut payload = vec![0u8; len];
+            reader.read_exact(&mut payload).unwrap();
+            String::from_utf8(payload).unwrap()
+        };
+        assert!(read_frame().starts_with(r#"{"type":"deal","cards":["#));
+        assert_eq!(read_frame(), r#"{"type":"choice","choice":1}"#);
+    }
+}
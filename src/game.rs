@@ -0,0 +1,1303 @@
+//! This module provides the high card game engine.
+
+pub mod log;
+pub mod simulate;
+pub mod state;
+
+use crate::analysis;
+#[cfg(feature = "audio")]
+use crate::audio::Sounds;
+use crate::bank::Bank;
+use crate::cards::set::CardSet;
+use crate::cards::{Card, Deck, Hand};
+use crate::error::GameConfigError;
+#[cfg(feature = "events")]
+use crate::events::Spectators;
+use crate::history::History;
+use crate::opponent::{Opponent, Skill};
+use crate::rules::Ruleset;
+use crate::scoring::ScoringStrategy;
+use log::EventLog;
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use state::{Phase, UndoStack};
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self};
+use std::path::Path;
+use std::time::Duration;
+
+/// How hard the single player game is.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// One of the losing cards is turned face up before you guess.
+    Easy,
+    /// Every card stays face down until you guess.
+    #[default]
+    Normal,
+    /// The cards are swapped around after you guess, three-card-monte style,
+    /// and you get whichever card ends up where you pointed.
+    Hard,
+}
+
+impl Difficulty {
+    /// Looks up a difficulty by name.
+    pub fn named(name: &str) -> Option<Difficulty> {
+        match name {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+
+/// How long a game lasts.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoundLimit {
+    /// Until the deck is too low to deal another round.
+    #[default]
+    DeckEmpty,
+    /// A fixed number of rounds, reshuffling the discards as needed.
+    Rounds(usize),
+    /// Reshuffling forever until the player quits.
+    Endless,
+}
+
+impl RoundLimit {
+    /// Looks up `endless` or `deck`, or reads a number of rounds above zero.
+    pub fn named(name: &str) -> Option<RoundLimit> {
+        match name {
+            "endless" => Some(RoundLimit::Endless),
+            "deck" => Some(RoundLimit::DeckEmpty),
+            _ => match name.parse() {
+                Ok(0) | Err(_) => None,
+                Ok(rounds) => Some(RoundLimit::Rounds(rounds)),
+            },
+        }
+    }
+}
+
+impl Display for RoundLimit {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            RoundLimit::DeckEmpty => write!(formatter, "Until the deck runs out"),
+            RoundLimit::Rounds(rounds) => write!(formatter, "{} rounds", rounds),
+            RoundLimit::Endless => write!(formatter, "Endless"),
+        }
+    }
+}
+
+/// How quickly a frontend plays: the pauses between steps and the animation
+/// frames are written for normal speed and scaled to suit.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Speed {
+    /// Twice as long as normal.
+    Slow,
+    #[default]
+    Normal,
+    /// A quarter as long as normal.
+    Fast,
+    /// No pauses or animation at all.
+    Instant,
+}
+
+impl Speed {
+    /// Looks up a speed by name.
+    pub fn named(name: &str) -> Option<Speed> {
+        match name {
+            "slow" => Some(Speed::Slow),
+            "normal" => Some(Speed::Normal),
+            "fast" => Some(Speed::Fast),
+            "instant" => Some(Speed::Instant),
+            _ => None,
+        }
+    }
+
+    /// How long `delay`, written for normal speed, lasts at this speed.
+    pub fn scale(&self, delay: Duration) -> Duration {
+        match self {
+            Speed::Slow => delay * 2,
+            Speed::Normal => delay,
+            Speed::Fast => delay / 4,
+            Speed::Instant => Duration::ZERO,
+        }
+    }
+
+    /// Returns false when cards should be shown without any animation.
+    pub fn animates(&self) -> bool {
+        *self != Speed::Instant
+    }
+}
+
+impl Display for Speed {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Speed::Slow => "Slow",
+            Speed::Normal => "Normal",
+            Speed::Fast => "Fast",
+            Speed::Instant => "Instant",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+
+/// Points for a correct guess before the streak multiplier.
+pub const POINTS_PER_WIN: u64 = 10;
+
+/// The streak multiplier stops growing here.
+pub const MAX_MULTIPLIER: u64 = 5;
+
+/// GameBuilder struct representing game options.
+pub struct GameBuilder {
+    card_count: u8,
+    players: u8,
+    seed: Option<u64>,
+    scoring: Box<dyn ScoringStrategy>,
+    difficulty: Difficulty,
+    bank: Option<Bank>,
+    opponent: Option<Skill>,
+    reshuffle: bool,
+    rounds: RoundLimit,
+    challenge: Option<Duration>,
+    deck: Option<Deck>,
+    speed: Speed,
+}
+
+/// Builds the game object using the builder pattern.
+impl GameBuilder {
+    /// GameBuilder Contsturctor.
+    pub fn new() -> GameBuilder {
+        GameBuilder {
+            card_count: 3,
+            players: 1,
+            seed: None,
+            scoring: Box::new(Ruleset::high_card()),
+            difficulty: Difficulty::Normal,
+            bank: None,
+            opponent: None,
+            reshuffle: false,
+            rounds: RoundLimit::DeckEmpty,
+            challenge: None,
+            deck: None,
+            speed: Speed::Normal,
+        }
+    }
+
+    /// Option to change the number of cards dealt each round, from 3 to 5.
+    pub fn max_cards(mut self, count: u8) -> GameBuilder {
+        self.card_count = count;
+        self
+    }
+
+    /// Option to change the number of players, from 1 to 4. With two or more players
+    /// each player is dealt a card per round and the highest card wins the round.
+    pub fn players(mut self, count: u8) -> GameBuilder {
+        self.players = count;
+        self
+    }
+
+    /// Option to seed the random number generator so shuffles and random choices are
+    /// reproducible. A random seed is picked when this isn't set.
+    pub fn with_seed(mut self, seed: u64) -> GameBuilder {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Option to change how cards are scored when finding the high card.
+    pub fn scoring<S: ScoringStrategy + 'static>(mut self, scoring: S) -> GameBuilder {
+        self.scoring = Box::new(scoring);
+        self
+    }
+
+    /// Option to compare cards under `rules` when finding the high card. This
+    /// replaces any scoring set with `scoring`.
+    pub fn rules(mut self, rules: Ruleset) -> GameBuilder {
+        self.scoring = Box::new(rules);
+        self
+    }
+
+    /// Option to change the difficulty of the single player game.
+    pub fn difficulty(mut self, difficulty: Difficulty) -> GameBuilder {
+        self.difficulty = difficulty;
+        self
+    }
+
+    /// Option to bet chips from `bank` on each round. The session ends when the
+    /// bank runs out of chips.
+    pub fn bank(mut self, bank: Bank) -> GameBuilder {
+        self.bank = Some(bank);
+        self
+    }
+
+    /// Option to have a computer opponent with `skill` guess each hand of the single
+    /// player game too. Ignored with more than one player.
+    pub fn opponent(mut self, skill: Skill) -> GameBuilder {
+        self.opponent = Some(skill);
+        self
+    }
+
+    /// Option to shuffle the discard pile back into the deck whenever it runs
+    /// too low to deal, so the game never runs out of cards.
+    pub fn reshuffle(mut self, reshuffle: bool) -> GameBuilder {
+        self.reshuffle = reshuffle;
+        self
+    }
+
+    /// Option to change how long the game lasts. Endless games and games of a
+    /// fixed number of rounds always reshuffle.
+    pub fn rounds(mut self, rounds: RoundLimit) -> GameBuilder {
+        self.rounds = rounds;
+        self
+    }
+
+    /// Option to make each guess a timed challenge: a right pick made with
+    /// `Input::ChooseIn` scores a bonus for however much of `limit` was left.
+    pub fn challenge(mut self, limit: Duration) -> GameBuilder {
+        self.challenge = Some(limit);
+        self
+    }
+
+    /// Option to change how quickly frontends pause and animate. Doesn't change
+    /// the cards dealt.
+    pub fn speed(mut self, speed: Speed) -> GameBuilder {
+        self.speed = speed;
+        self
+    }
+
+    /// Option to deal from `deck` in its exact order instead of shuffling, as
+    /// built by `DeckBuilder::from_spec`. Discards shuffled back in when the
+    /// deck runs low are still shuffled.
+    pub fn deck(mut self, deck: Deck) -> GameBuilder {
+        self.deck = Some(deck);
+        self
+    }
+
+    /// Creates a new Game opject, or explains which option is out of range.
+    pub fn spawn(self) -> Result<Game, GameConfigError> {
+        match self.card_count {
+            0..=2 => return Err(GameConfigError::TooFewCards(self.card_count)),
+            3..=5 => {}
+            _ => return Err(GameConfigError::TooManyCards(self.card_count)),
+        }
+        match self.players {
+            0 => return Err(GameConfigError::NoPlayers),
+            1..=4 => {}
+            _ => return Err(GameConfigError::TooManyPlayers(self.players)),
+        }
+
+        let seed = self.seed.unwrap_or_else(|| thread_rng().gen());
+        let opponent = self.opponent.filter(|_| self.players == 1);
+        Ok(Game {
+            card_count: self.card_count as usize,
+            players: (1..=self.players)
+                .map(|number| Player::new(format!("Player {}", number)))
+                .collect(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            choice_rng: StdRng::seed_from_u64(!seed),
+            scoring: self.scoring,
+            difficulty: self.difficulty,
+            bank: self.bank,
+            opponent: opponent.map(|skill| Opponent::new(skill, seed.rotate_left(32))),
+            reshuffle: self.reshuffle || self.rounds != RoundLimit::DeckEmpty,
+            rounds: self.rounds,
+            challenge: self.challenge,
+            answer_time: None,
+            fixed_order: self.deck.is_some(),
+            given_deck: self
+                .deck
+                .as_ref()
+                .map(|deck| deck.iter().copied().collect()),
+            deck: self.deck.unwrap_or_default(),
+            speed: self.speed,
+            hand: Hand::new(),
+            games_played: 0,
+            wins: 0,
+            streak: 0,
+            best_streak: 0,
+            points: 0,
+            history: History::new(),
+            phase: Phase::Dealing,
+            undo: UndoStack::default(),
+            log: None,
+            #[cfg(feature = "events")]
+            spectators: None,
+            #[cfg(feature = "audio")]
+            sounds: None,
+            stacked: Vec::new(),
+            forced: None,
+        })
+    }
+}
+
+impl Default for GameBuilder {
+    fn default() -> GameBuilder {
+        GameBuilder::new()
+    }
+}
+
+/// A seat at the table with its own score.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Player {
+    name: String,
+    wins: usize,
+}
+
+impl Player {
+    /// Construct a new player with no wins.
+    pub fn new(name: String) -> Player {
+        Player { name, wins: 0 }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn wins(&self) -> usize {
+        self.wins
+    }
+}
+
+/// Holds game state.
+///
+/// Game state can be serialized, e.g. to JSON. The random number generator isn't
+/// saved, so a loaded game gets a fresh one. The scoring is saved when it's a
+/// `Ruleset`; any other strategy loads as the default rules.
+///
+#[derive(Serialize, Deserialize)]
+pub struct Game {
+    card_count: usize,
+    players: Vec<Player>,
+    seed: u64,
+    #[serde(skip, default = "StdRng::from_entropy")]
+    rng: StdRng,
+    /// Random choices come from their own generator so the cards dealt only
+    /// depend on the seed and the rounds played, not on how often a frontend
+    /// asked for a random choice.
+    #[serde(skip, default = "StdRng::from_entropy")]
+    choice_rng: StdRng,
+    #[serde(
+        rename = "rules",
+        default = "default_scoring",
+        serialize_with = "save_rules",
+        deserialize_with = "load_rules"
+    )]
+    scoring: Box<dyn ScoringStrategy>,
+    #[serde(default)]
+    difficulty: Difficulty,
+    #[serde(default)]
+    bank: Option<Bank>,
+    #[serde(default)]
+    opponent: Option<Opponent>,
+    /// Whether the discard pile goes back into the deck when it runs low.
+    #[serde(default)]
+    reshuffle: bool,
+    #[serde(default)]
+    rounds: RoundLimit,
+    /// The time allowed for each guess in a timed challenge.
+    #[serde(default)]
+    challenge: Option<Duration>,
+    /// How long the pick being revealed took, if it was timed.
+    #[serde(skip)]
+    answer_time: Option<Duration>,
+    /// Whether the deck is dealt in the order it was given, unshuffled.
+    #[serde(default)]
+    fixed_order: bool,
+    /// The deck as it was given, top card first, so the session can be
+    /// replayed.
+    #[serde(skip)]
+    given_deck: Option<Vec<Card>>,
+    #[serde(default)]
+    speed: Speed,
+    deck: Deck,
+    hand: Hand,
+    games_played: usize,
+    wins: usize,
+    #[serde(default)]
+    streak: usize,
+    #[serde(default)]
+    best_streak: usize,
+    #[serde(default)]
+    points: u64,
+    #[serde(default)]
+    history: History,
+    /// A loaded game starts a fresh round.
+    #[serde(skip)]
+    phase: Phase,
+    #[serde(skip)]
+    undo: UndoStack,
+    /// Where every step of the game is logged.
+    #[serde(skip)]
+    log: Option<EventLog>,
+    /// Who round events are broadcast to.
+    #[cfg(feature = "events")]
+    #[serde(skip)]
+    spectators: Option<Spectators>,
+    /// What plays the sound effects.
+    #[cfg(feature = "audio")]
+    #[serde(skip)]
+    sounds: Option<Sounds>,
+    /// Cards to put on top of the deck at the next deal, top card first.
+    #[serde(skip)]
+    stacked: Vec<Card>,
+    /// Whether the next pick is made to win or lose, whatever's picked.
+    #[serde(skip)]
+    forced: Option<bool>,
+}
+
+fn default_scoring() -> Box<dyn ScoringStrategy> {
+    Box::new(Ruleset::high_card())
+}
+
+/// Saves the scoring as its ruleset, or nothing if it isn't one.
+fn save_rules<T, S>(scoring: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: AsRef<dyn ScoringStrategy>,
+    S: Serializer,
+{
+    scoring.as_ref().rules().serialize(serializer)
+}
+
+/// Loads the saved ruleset, falling back to the default rules.
+fn load_rules<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Box<dyn ScoringStrategy>, D::Error> {
+    Ok(match Option::<Ruleset>::deserialize(deserializer)? {
+        Some(rules) => Box::new(rules),
+        None => default_scoring(),
+    })
+}
+
+impl Game {
+    /// Randomly shuffles cards
+    pub fn shuffle_deck(&mut self) {
+        self.deck.shuffle(&mut self.rng);
+    }
+
+    /// Picks a random card index in the current hand.
+    pub fn random_choice(&mut self) -> usize {
+        self.choice_rng.gen_range(0..self.hand.len())
+    }
+
+    /// Discards the previous hand and deals a new one, first shuffling the
+    /// discard pile back in if the game reshuffles and the deck has run low. On
+    /// easy one of the losing cards is dealt face up.
+    pub fn deal_cards(&mut self) {
+        for card in self.hand.drain() {
+            self.deck.discard(card);
+        }
+        let count = self.cards_per_round();
+        if self.reshuffle && self.deck.remaining() < count {
+            self.deck.reshuffle_discards(&mut self.rng);
+        }
+        if !self.fixed_order {
+            self.shuffle_deck();
+        }
+        for card in self.stacked.drain(..).rev() {
+            if let Some(card) = self.deck.take(&card) {
+                self.deck.place_top(card);
+            }
+        }
+        self.deck.draw_into(&mut self.hand, count);
+
+        if self.difficulty == Difficulty::Easy && !self.is_multiplayer() && self.hand.len() > 1 {
+            let winner = self.find_high_card();
+            let mut hint = self.rng.gen_range(0..self.hand.len() - 1);
+            if hint >= winner {
+                hint += 1;
+            }
+            self.hand.reveal(hint);
+        }
+    }
+
+    /// Random swaps to make to the hand after the player guesses on hard, three
+    /// per card. Empty on the other difficulties.
+    pub fn monte_swaps(&mut self) -> Vec<(usize, usize)> {
+        if self.difficulty != Difficulty::Hard || self.hand.len() < 2 {
+            return Vec::new();
+        }
+        let len = self.hand.len();
+        (0..len * 3)
+            .map(|_| {
+                let first = self.rng.gen_range(0..len);
+                let second = (first + self.rng.gen_range(1..len)) % len;
+                (first, second)
+            })
+            .collect()
+    }
+
+    /// Deals `cards` first at the next deal, in order, after the deck is
+    /// shuffled. Every card has to still be in the deck.
+    pub fn stack_next(&mut self, cards: Vec<Card>) -> Result<(), Card> {
+        for card in &cards {
+            let in_deck = self
+                .deck
+                .iter()
+                .filter(|other| other.suit == card.suit && other.rank == card.rank)
+                .count();
+            let stacked = cards
+                .iter()
+                .filter(|other| other.suit == card.suit && other.rank == card.rank)
+                .count();
+            if stacked > in_deck {
+                return Err(*card);
+            }
+        }
+        self.stacked = cards;
+        Ok(())
+    }
+
+    /// Makes the next single player pick win, or lose, by swapping the high
+    /// card into or out of the picked place once the pick is made.
+    pub fn force_result(&mut self, win: bool) {
+        self.forced = Some(win);
+    }
+
+    /// Swaps cards to give the pick at `choice` the forced result, if any.
+    fn apply_forced(&mut self, choice: usize) {
+        let win = match self.forced.take() {
+            Some(win) => win,
+            None => return,
+        };
+        let winner = self.find_high_card();
+        if win && winner != choice {
+            self.hand.swap(choice, winner);
+        } else if !win && winner == choice && self.hand.len() > 1 {
+            self.hand.swap(choice, (choice + 1) % self.hand.len());
+            // The card swapped in may still be high when it ties.
+            let winner = self.find_high_card();
+            if winner == choice {
+                self.hand.swap(choice, (choice + 2) % self.hand.len());
+            }
+        }
+    }
+
+    /// Turns the chosen card face up and highlights it as the player's pick.
+    pub fn reveal_choice(&mut self, choice: usize) {
+        self.hand.select(choice);
+    }
+
+    /// Ends a multiplayer round, awarding it to the player holding the high card.
+    /// In a multiplayer round card `n` of the hand belongs to player `n`.
+    pub fn finish_round(&mut self) -> usize {
+        let winner = self.find_high_card();
+        self.players[winner].wins += 1;
+        self.games_played += 1;
+        winner
+    }
+
+    /// Index of the winning card in the current hand.
+    pub fn find_high_card(&self) -> usize {
+        self.hand.high_card_with(self.scoring.as_ref()).unwrap_or(0)
+    }
+
+    pub fn inc_gamesplayed(&mut self) {
+        self.games_played += 1;
+    }
+
+    pub fn inc_wins(&mut self) {
+        self.wins += 1;
+    }
+
+    /// Records a single player guess of the card at `choice` in the history,
+    /// counting the game and updating the win streak. A correct guess scores
+    /// `POINTS_PER_WIN` plus any speed bonus, times the streak multiplier, and the
+    /// points scored are returned.
+    pub fn record_guess(&mut self, choice: usize) -> u64 {
+        let winner = self.find_high_card();
+        let won = choice == winner;
+        self.history
+            .record(self.hand.cards(), choice, winner, self.answer_time);
+        self.inc_gamesplayed();
+        if !won {
+            self.streak = 0;
+            return 0;
+        }
+        self.inc_wins();
+        self.streak += 1;
+        self.best_streak = self.best_streak.max(self.streak);
+        let points = (POINTS_PER_WIN + self.speed_bonus()) * self.multiplier();
+        self.points += points;
+        points
+    }
+
+    /// Up to `POINTS_PER_WIN` more for a timed challenge pick, in proportion to
+    /// the time left when it was made. Zero for untimed picks.
+    pub fn speed_bonus(&self) -> u64 {
+        match (self.challenge, self.answer_time) {
+            (Some(limit), Some(elapsed)) if !limit.is_zero() => {
+                let left = limit.saturating_sub(elapsed);
+                (POINTS_PER_WIN as u128 * left.as_millis() / limit.as_millis().max(1)) as u64
+            }
+            _ => 0,
+        }
+    }
+
+    /// The time allowed for each guess in a timed challenge.
+    pub fn challenge(&self) -> Option<Duration> {
+        self.challenge
+    }
+
+    /// The bonus multiplier for the current streak: one for the first correct
+    /// guess, going up by one for each guess in a row up to `MAX_MULTIPLIER`.
+    pub fn multiplier(&self) -> u64 {
+        (self.streak as u64).clamp(1, MAX_MULTIPLIER)
+    }
+
+    /// Returns true when there aren't enough cards left to deal another round,
+    /// counting the discard pile and the hand in play if the game reshuffles.
+    pub fn out_of_cards(&self) -> bool {
+        let mut left = self.deck.remaining();
+        if self.reshuffle {
+            left += self.deck.discarded().len() + self.hand.len();
+        }
+        left < self.cards_per_round()
+    }
+
+    /// Returns true when the deck has run out, the last round has been played
+    /// or the player is out of chips.
+    pub fn is_over(&self) -> bool {
+        self.out_of_cards() || self.played_all_rounds() || self.is_bankrupt()
+    }
+
+    pub fn round_limit(&self) -> RoundLimit {
+        self.rounds
+    }
+
+    /// Returns true once a game of a fixed number of rounds has played them all.
+    pub fn played_all_rounds(&self) -> bool {
+        match self.rounds {
+            RoundLimit::Rounds(rounds) => self.games_played >= rounds,
+            RoundLimit::DeckEmpty | RoundLimit::Endless => false,
+        }
+    }
+
+    /// Returns true if the player is betting and has no chips left.
+    pub fn is_bankrupt(&self) -> bool {
+        self.bank.as_ref().is_some_and(Bank::is_bankrupt)
+    }
+
+    /// Settles the wager on the round just played, returning the change in chips.
+    /// Does nothing when the player isn't betting.
+    pub fn settle_wager(&mut self, won: bool) -> Option<i64> {
+        self.bank.as_mut().map(|bank| bank.settle(won))
+    }
+
+    /// Returns true if two or more players each get a card per round.
+    pub fn is_multiplayer(&self) -> bool {
+        self.players.len() > 1
+    }
+
+    /// Number of cards dealt each round: one per player in a multiplayer game,
+    /// otherwise the configured card count.
+    pub fn cards_per_round(&self) -> usize {
+        if self.is_multiplayer() {
+            self.players.len()
+        } else {
+            self.card_count
+        }
+    }
+
+    /// Saves the game state to a JSON file so the session can be resumed later.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, json)
+    }
+
+    /// Loads a game saved with `save`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Game> {
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Writes every step of the game from now on to `log`.
+    pub fn log_events(&mut self, log: EventLog) {
+        self.log = Some(log);
+    }
+
+    /// Broadcasts every round from now on to `spectators`.
+    #[cfg(feature = "events")]
+    pub fn spectate(&mut self, spectators: Spectators) {
+        self.spectators = Some(spectators);
+    }
+
+    /// Plays a sound effect for every deal, reveal and result from now on.
+    #[cfg(feature = "audio")]
+    pub fn play_sounds(&mut self, sounds: Sounds) {
+        self.sounds = Some(sounds);
+    }
+
+    /// Seed used for the random number generator.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Rules used to score cards.
+    pub fn scoring(&self) -> &dyn ScoringStrategy {
+        self.scoring.as_ref()
+    }
+
+    /// How hard the single player game is.
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// How quickly frontends should pause and animate.
+    pub fn speed(&self) -> Speed {
+        self.speed
+    }
+
+    /// Changes how quickly frontends should pause and animate, such as for a
+    /// resumed game.
+    pub fn set_speed(&mut self, speed: Speed) {
+        self.speed = speed;
+    }
+
+    /// Number of cards dealt each round.
+    pub fn card_count(&self) -> usize {
+        self.card_count
+    }
+
+    /// The player's chips, if they're betting.
+    pub fn bank(&self) -> Option<&Bank> {
+        self.bank.as_ref()
+    }
+
+    /// The computer opponent, if there is one.
+    pub fn opponent(&self) -> Option<&Opponent> {
+        self.opponent.as_ref()
+    }
+
+    /// How the player's wins compare to the opponent's, e.g. `Greater` when the
+    /// player is ahead. None without an opponent.
+    pub fn match_result(&self) -> Option<Ordering> {
+        self.opponent
+            .as_ref()
+            .map(|opponent| self.wins.cmp(&opponent.wins()))
+    }
+
+    /// Mutable access to the player's chips, e.g. to place a wager.
+    pub fn bank_mut(&mut self) -> Option<&mut Bank> {
+        self.bank.as_mut()
+    }
+
+    /// The deck cards are dealt from.
+    pub fn deck(&self) -> &Deck {
+        &self.deck
+    }
+
+    /// The cards of the deck given with `GameBuilder::deck` as they were before
+    /// the first deal, top card first. None for a shuffled deck or a loaded game.
+    pub fn given_deck(&self) -> Option<&[Card]> {
+        self.given_deck.as_deref()
+    }
+
+    /// The cards the player hasn't seen: the rest of the deck and the face down
+    /// cards in the hand.
+    pub fn unseen_cards(&self) -> CardSet {
+        let hidden = self.hand.iter().filter(|card| !card.state.is_face_up());
+        self.deck.iter().chain(hidden).collect()
+    }
+
+    /// The chance of each card in the hand being the high card, judging only by
+    /// what the player can see. See `analysis::high_card_odds`.
+    pub fn odds(&self) -> Vec<f64> {
+        let unseen = self.unseen_cards();
+        analysis::high_card_odds(self.hand.cards(), &unseen, self.scoring.as_ref())
+    }
+
+    /// The current hand.
+    pub fn hand(&self) -> &Hand {
+        &self.hand
+    }
+
+    /// Mutable access to the current hand, e.g. to flip cards.
+    pub fn hand_mut(&mut self) -> &mut Hand {
+        &mut self.hand
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+
+    pub fn games_played(&self) -> usize {
+        self.games_played
+    }
+
+    pub fn wins(&self) -> usize {
+        self.wins
+    }
+
+    /// Every guess made so far.
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Correct guesses in a row.
+    pub fn streak(&self) -> usize {
+        self.streak
+    }
+
+    pub fn best_streak(&self) -> usize {
+        self.best_streak
+    }
+
+    /// Points scored, including streak bonuses.
+    pub fn points(&self) -> u64 {
+        self.points
+    }
+}
+
+impl Display for Game {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        if self.is_multiplayer() {
+            for player in self.players.iter() {
+                writeln!(formatter, "{}: {} wins", player.name, player.wins)?;
+            }
+            return write!(
+                formatter,
+                "Rounds played {}\nCards Left {}",
+                self.games_played,
+                self.deck.remaining(),
+            );
+        }
+
+        write!(
+            formatter,
+            "Won {} out of {} games.\nPoints {}  Streak {} (best {})\nCards Left {}",
+            self.wins,
+            self.games_played,
+            self.points,
+            self.streak,
+            self.best_streak,
+            self.deck.remaining(),
+        )?;
+        if let Some(bank) = &self.bank {
+            write!(formatter, "\nChips {}", bank.chips())?;
+        }
+        if let Some(opponent) = &self.opponent {
+            write!(
+                formatter,
+                "\nOpponent ({}) won {} games.",
+                opponent.skill(),
+                opponent.wins()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::state::{Event, Input};
+    use super::{Difficulty, Game, GameBuilder, RoundLimit, Speed};
+    use crate::bank::Bank;
+    use crate::cards::{CardState, Deck};
+    use crate::error::GameConfigError;
+    use crate::opponent::Skill;
+    use crate::rules::{AceRule, Ruleset, SuitTiebreak};
+    use crate::scoring::{HighCardScoring, ScoringStrategy};
+    use std::time::Duration;
+
+    #[test]
+    /// Tests out of range options are reported instead of panicking.
+    fn test_builder_errors() {
+        let error = |builder: GameBuilder| builder.spawn().err();
+        assert_eq!(
+            error(GameBuilder::new().max_cards(2)),
+            Some(GameConfigError::TooFewCards(2))
+        );
+        assert_eq!(
+            error(GameBuilder::new().max_cards(6)),
+            Some(GameConfigError::TooManyCards(6))
+        );
+        assert_eq!(
+            error(GameBuilder::new().players(0)),
+            Some(GameConfigError::NoPlayers)
+        );
+        assert_eq!(
+            error(GameBuilder::new().players(5)),
+            Some(GameConfigError::TooManyPlayers(5))
+        );
+        assert_eq!(error(GameBuilder::new().max_cards(5).players(4)), None);
+    }
+
+    #[test]
+    /// Tests the configured card count is dealt until the deck runs out.
+    fn test_deals_card_count() {
+        let mut game = GameBuilder::new().max_cards(5).spawn().unwrap();
+        let mut rounds = 0;
+
+        while !game.out_of_cards() {
+            game.deal_cards();
+            assert_eq!(game.hand().len(), 5);
+            rounds += 1;
+        }
+
+        assert_eq!(rounds, 10);
+        assert_eq!(game.deck().remaining(), 2);
+    }
+
+    #[test]
+    /// Tests a given deck is dealt in its own order, saved games included.
+    fn test_deals_fixed_deck() {
+        let cards: Vec<_> = Deck::new().iter().rev().copied().collect();
+        let mut game = GameBuilder::new()
+            .deck(Deck::from_cards(cards.clone()))
+            .spawn()
+            .unwrap();
+        game.deal_cards();
+        assert_eq!(game.hand().cards(), &cards[..3]);
+
+        let mut loaded: Game =
+            serde_json::from_str(&serde_json::to_string(&game).unwrap()).unwrap();
+        loaded.deal_cards();
+        assert_eq!(loaded.hand().cards(), &cards[3..6]);
+    }
+
+    #[test]
+    /// Tests each player gets a card and the round goes to the high card.
+    fn test_multiplayer_rounds() {
+        let mut game = GameBuilder::new().players(4).with_seed(1).spawn().unwrap();
+        game.deal_cards();
+        assert_eq!(game.hand().len(), 4);
+
+        let high = game.find_high_card();
+        assert_eq!(game.finish_round(), high);
+        assert_eq!(game.players()[high].wins(), 1);
+        assert_eq!(game.games_played(), 1);
+
+        let scoreboard = format!("{}", game);
+        assert!(scoreboard.contains(&format!("Player {}: 1 wins", high + 1)));
+        assert!(scoreboard.contains("Rounds played 1"));
+    }
+
+    #[test]
+    /// Tests games with the same seed deal the same cards and random choices.
+    fn test_seeded_games_repeat() {
+        let mut first = GameBuilder::new().with_seed(42).spawn().unwrap();
+        let mut second = GameBuilder::new().with_seed(42).spawn().unwrap();
+
+        while !first.out_of_cards() {
+            first.deal_cards();
+            second.deal_cards();
+            assert_eq!(first.hand(), second.hand());
+            assert_eq!(first.random_choice(), second.random_choice());
+        }
+        assert_eq!(first.seed(), 42);
+    }
+
+    #[test]
+    /// Tests game state round-trips through JSON.
+    fn test_serde_round_trip() {
+        let mut game = GameBuilder::new().players(2).with_seed(4).spawn().unwrap();
+        game.deal_cards();
+        game.finish_round();
+        game.deal_cards();
+
+        let json = serde_json::to_string(&game).unwrap();
+        let loaded: Game = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.deck(), game.deck());
+        assert_eq!(loaded.hand(), game.hand());
+        assert_eq!(loaded.players(), game.players());
+        assert_eq!(loaded.games_played(), 1);
+        assert_eq!(loaded.seed(), 4);
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+    }
+
+    #[test]
+    /// Tests saving and loading a game file.
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join("card_game_test_save_and_load.json");
+        let mut game = GameBuilder::new().with_seed(12).spawn().unwrap();
+        game.deal_cards();
+        game.record_guess(game.find_high_card());
+
+        game.save(&path).unwrap();
+        let loaded = Game::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.deck(), game.deck());
+        assert_eq!(loaded.wins(), 1);
+        assert_eq!(loaded.games_played(), 1);
+        assert!(Game::load(&path).is_err());
+    }
+
+    #[test]
+    /// Tests a saved game keeps its rules, and other scoring loads as the
+    /// default rules.
+    fn test_save_keeps_rules() {
+        let path = std::env::temp_dir().join("card_game_test_save_keeps_rules.json");
+        let rules = Ruleset::new()
+            .aces(AceRule::Low)
+            .tiebreak(SuitTiebreak::Bridge);
+        let game = GameBuilder::new()
+            .with_seed(5)
+            .rules(rules)
+            .spawn()
+            .unwrap();
+        game.save(&path).unwrap();
+        let loaded = Game::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.scoring().rules(), Some(rules));
+        for card in Deck::new().iter() {
+            assert_eq!(loaded.scoring().score(card), rules.score(card));
+        }
+
+        let game = GameBuilder::new().scoring(HighCardScoring).spawn().unwrap();
+        let loaded: Game = serde_json::from_str(&serde_json::to_string(&game).unwrap()).unwrap();
+        assert_eq!(loaded.scoring().rules(), Some(Ruleset::high_card()));
+    }
+
+    #[test]
+    /// Tests easy deals a losing card face up and hard swaps cards around.
+    fn test_difficulty() {
+        let mut easy = GameBuilder::new()
+            .difficulty(Difficulty::Easy)
+            .with_seed(3)
+            .spawn()
+            .unwrap();
+        while !easy.out_of_cards() {
+            easy.deal_cards();
+            let shown: Vec<usize> = (0..easy.hand().len())
+                .filter(|&idx| easy.hand().cards()[idx].state == CardState::Visible)
+                .collect();
+            assert_eq!(shown.len(), 1);
+            assert_ne!(shown[0], easy.find_high_card());
+            assert!(easy.monte_swaps().is_empty());
+        }
+
+        let mut hard = GameBuilder::new()
+            .difficulty(Difficulty::Hard)
+            .with_seed(3)
+            .spawn()
+            .unwrap();
+        hard.deal_cards();
+        let swaps = hard.monte_swaps();
+        assert_eq!(swaps.len(), 9);
+        assert!(swaps
+            .iter()
+            .all(|&(first, second)| first != second && second < 3));
+        assert_eq!(Difficulty::named("hard"), Some(Difficulty::Hard));
+    }
+
+    #[test]
+    /// Tests speeds stretch and shrink pauses, and instant skips them.
+    fn test_speed() {
+        let second = Duration::from_secs(1);
+        assert_eq!(Speed::Slow.scale(second), Duration::from_secs(2));
+        assert_eq!(Speed::Normal.scale(second), second);
+        assert_eq!(Speed::Fast.scale(second), Duration::from_millis(250));
+        assert_eq!(Speed::Instant.scale(second), Duration::ZERO);
+        assert!(Speed::Fast.animates() && !Speed::Instant.animates());
+        assert_eq!(Speed::named("instant"), Some(Speed::Instant));
+        assert_eq!(Speed::named("ludicrous"), None);
+
+        let mut game = GameBuilder::new().speed(Speed::Fast).spawn().unwrap();
+        assert_eq!(game.speed(), Speed::Fast);
+        game.set_speed(Speed::Slow);
+        assert_eq!(game.speed(), Speed::Slow);
+        assert_eq!(GameBuilder::new().spawn().unwrap().speed(), Speed::Normal);
+    }
+
+    #[test]
+    /// Tests streaks raise the multiplier and a wrong guess resets it.
+    fn test_streak_bonus() {
+        let mut game = GameBuilder::new().with_seed(6).spawn().unwrap();
+        let guess = |game: &mut Game, won: bool| {
+            game.deal_cards();
+            let winner = game.find_high_card();
+            let choice = if won { winner } else { (winner + 1) % 3 };
+            game.record_guess(choice)
+        };
+        let points: Vec<u64> = [true, true, true, false, true]
+            .iter()
+            .map(|&won| guess(&mut game, won))
+            .collect();
+
+        assert_eq!(points, [10, 20, 30, 0, 10]);
+        assert_eq!(game.points(), 70);
+        assert_eq!(game.streak(), 1);
+        assert_eq!(game.best_streak(), 3);
+        assert_eq!(game.wins(), 4);
+        assert_eq!(game.games_played(), 5);
+
+        assert_eq!(game.history().len(), 5);
+        assert_eq!(game.history().accuracy(), 80.0);
+
+        for _ in 0..10 {
+            guess(&mut game, true);
+        }
+        assert_eq!(game.multiplier(), 5);
+    }
+
+    #[test]
+    /// Tests betting settles wagers and ends the session when bankrupt.
+    fn test_betting() {
+        let mut game = GameBuilder::new()
+            .bank(Bank::new(10))
+            .with_seed(2)
+            .spawn()
+            .unwrap();
+        assert_eq!(GameBuilder::new().spawn().unwrap().settle_wager(true), None);
+
+        game.deal_cards();
+        game.bank_mut().unwrap().wager(5).unwrap();
+        assert_eq!(game.settle_wager(true), Some(10));
+        assert!(format!("{}", game).ends_with("Chips 20"));
+
+        game.bank_mut().unwrap().wager(20).unwrap();
+        assert_eq!(game.settle_wager(false), Some(-20));
+        assert!(game.is_bankrupt());
+        assert!(game.is_over());
+        assert!(!game.out_of_cards());
+    }
+
+    #[test]
+    /// Tests a reshuffling game keeps dealing from the same 52 cards.
+    fn test_reshuffle() {
+        let mut game = GameBuilder::new()
+            .max_cards(5)
+            .reshuffle(true)
+            .with_seed(3)
+            .spawn()
+            .unwrap();
+        for _ in 0..100 {
+            assert!(!game.out_of_cards());
+            game.deal_cards();
+            assert_eq!(game.hand().len(), 5);
+            let deck = game.deck();
+            assert_eq!(deck.remaining() + deck.discarded().len() + 5, 52);
+        }
+
+        let mut once = GameBuilder::new().max_cards(5).spawn().unwrap();
+        for _ in 0..10 {
+            once.deal_cards();
+        }
+        assert!(once.out_of_cards());
+    }
+
+    #[test]
+    /// Tests quick picks in a timed challenge score a bonus.
+    fn test_speed_bonus() {
+        let mut game = GameBuilder::new()
+            .challenge(Duration::from_secs(10))
+            .with_seed(6)
+            .spawn()
+            .unwrap();
+        let mut points = Vec::new();
+        for elapsed in [2, 12, 0] {
+            game.advance(Input::Deal);
+            let winner = game.find_high_card();
+            let pick = if elapsed == 0 {
+                Input::Choose(winner)
+            } else {
+                Input::ChooseIn(winner, Duration::from_secs(elapsed))
+            };
+            game.advance(pick);
+            match game.advance(Input::Reveal) {
+                Event::Revealed(result) => points.push(result.points),
+                event => panic!("unexpected {:?}", event),
+            }
+            game.advance(Input::Continue);
+        }
+        // The streak multiplier goes up for each win in a row.
+        assert_eq!(points, [18, 20, 30]);
+        assert_eq!(game.challenge(), Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    /// Tests fixed length games stop after their rounds and endless ones don't.
+    fn test_round_limits() {
+        let play = |limit: RoundLimit| {
+            let mut game = GameBuilder::new()
+                .rounds(limit)
+                .with_seed(2)
+                .spawn()
+                .unwrap();
+            let mut rounds = 0;
+            while game.advance(Input::Deal) == Event::Dealt && rounds < 200 {
+                game.advance(Input::Random);
+                game.advance(Input::Reveal);
+                rounds += 1;
+                game.advance(Input::Continue);
+            }
+            (rounds, game.is_over())
+        };
+        assert_eq!(play(RoundLimit::DeckEmpty), (17, true));
+        assert_eq!(play(RoundLimit::Rounds(40)), (40, true));
+        assert_eq!(play(RoundLimit::Endless), (200, false));
+
+        assert_eq!(RoundLimit::named("endless"), Some(RoundLimit::Endless));
+        assert_eq!(RoundLimit::named("25"), Some(RoundLimit::Rounds(25)));
+        assert_eq!(RoundLimit::named("0"), None);
+        assert_eq!(RoundLimit::named("lots"), None);
+    }
+
+    #[test]
+    /// Tests the injected scoring strategy picks the high card.
+    fn test_scoring_strategy() {
+        let mut game = GameBuilder::new()
+            .scoring(HighCardScoring)
+            .with_seed(8)
+            .spawn()
+            .unwrap();
+
+        while !game.out_of_cards() {
+            game.deal_cards();
+            let high = game.hand().cards()[game.find_high_card()];
+            assert!(game
+                .hand()
+                .cards()
+                .iter()
+                .all(|card| HighCardScoring.score(card) <= HighCardScoring.score(&high)));
+        }
+    }
+
+    #[test]
+    /// Tests the opponent guesses every round and the match is decided on wins.
+    fn test_opponent() {
+        let mut game = GameBuilder::new()
+            .opponent(Skill::CardCounting)
+            .with_seed(8)
+            .spawn()
+            .unwrap();
+        let mut opponent_wins = 0;
+        while game.advance(Input::Deal) == Event::Dealt {
+            game.advance(Input::Random);
+            if let Event::Revealed(result) = game.advance(Input::Reveal) {
+                if result.opponent == Some(result.winner) {
+                    opponent_wins += 1;
+                }
+            }
+            game.advance(Input::Continue);
+        }
+
+        let opponent = game.opponent().unwrap();
+        assert_eq!(opponent.wins(), opponent_wins);
+        assert_eq!(game.match_result(), Some(game.wins().cmp(&opponent_wins)));
+        assert!(GameBuilder::new()
+            .players(2)
+            .opponent(Skill::Random)
+            .spawn()
+            .unwrap()
+            .opponent()
+            .is_none());
+    }
+}
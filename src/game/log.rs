@@ -0,0 +1,231 @@
+//! This module provides a structured log of everything a high card game does.
+//!
+//! A game given an `EventLog` emits a `tracing` event for every step of every
+//! round: the deal, the wager, the choice, the reveal and the result, as well as
+//! undos and any input the game ignored. Each log has its own JSON subscriber, so
+//! it writes one object per line carrying the time since the log was opened, a
+//! level and the round number, and a session can be audited or a bug in the
+//! rules traced back to the input that caused it:
+//!
+//! ```text
+//! {"timestamp":"0ms","level":"INFO","event":"deal","round":1,"cards":"7♣ K♦ 2♠","cards_left":49}
+//! {"timestamp":"1840ms","level":"INFO","event":"choice","round":1,"choice":2,"swaps":"","answer_ms":1838}
+//! ```
+
+use super::state::{Event, Input, Phase};
+use super::Game;
+use crate::cards::Card;
+use std::fmt::{self, Display};
+use std::fs::{File, OpenOptions};
+use std::io::{self, LineWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::{dispatcher, info, warn, Dispatch};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::time::FormatTime;
+
+/// Where a game's events are written, one JSON line each.
+pub struct EventLog {
+    dispatch: Dispatch,
+    failed: Arc<AtomicBool>,
+    round: usize,
+}
+
+/// Stamps each line with the milliseconds since the log was opened.
+struct Elapsed(Instant);
+
+impl FormatTime for Elapsed {
+    fn format_time(&self, writer: &mut Writer<'_>) -> fmt::Result {
+        write!(writer, "{}ms", self.0.elapsed().as_millis())
+    }
+}
+
+/// Passes the subscriber's lines on to the log's writer, noting if one couldn't
+/// be written since the subscriber itself drops write errors.
+struct Output<W> {
+    out: W,
+    failed: Arc<AtomicBool>,
+}
+
+impl<W: Write> Write for Output<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.out
+            .write(buf)
+            .inspect_err(|_| self.failed.store(true, Ordering::Relaxed))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out
+            .flush()
+            .inspect_err(|_| self.failed.store(true, Ordering::Relaxed))
+    }
+}
+
+impl EventLog {
+    /// Construct a log writing to `out`.
+    pub fn new<W: Write + Send + 'static>(out: W) -> EventLog {
+        let failed = Arc::new(AtomicBool::new(false));
+        let output = Output {
+            out,
+            failed: Arc::clone(&failed),
+        };
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .flatten_event(true)
+            .with_target(false)
+            .with_timer(Elapsed(Instant::now()))
+            .with_writer(Mutex::new(output))
+            .finish();
+        EventLog {
+            dispatch: Dispatch::new(subscriber),
+            failed,
+            round: 0,
+        }
+    }
+
+    /// Opens the file at `path` for logging, adding to the end of it if it's
+    /// already there. Each line is written out as soon as it's logged.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<EventLog> {
+        let file: File = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(EventLog::new(LineWriter::new(file)))
+    }
+
+    /// Writes the lines for `event`, which `game` just returned for `input`.
+    pub fn record(&mut self, input: Input, event: &Event, game: &Game) -> io::Result<()> {
+        match event {
+            Event::Dealt => self.round += 1,
+            Event::Undone(Phase::Dealing) => self.round = self.round.saturating_sub(1),
+            _ => {}
+        }
+        dispatcher::with_default(&self.dispatch, || emit(self.round, input, event, game));
+        if self.failed.load(Ordering::Relaxed) {
+            return Err(io::Error::other("the event log couldn't be written"));
+        }
+        Ok(())
+    }
+}
+
+/// Emits the events logged for `event` to the current subscriber.
+fn emit(round: usize, input: Input, event: &Event, game: &Game) {
+    match event {
+        Event::Dealt => info!(
+            event = "deal",
+            round,
+            cards = %cards(game.hand.cards()),
+            cards_left = game.deck.remaining(),
+        ),
+        Event::Wagered(amount) => info!(event = "wager", round, amount),
+        Event::WagerRejected(err) => info!(event = "wager_rejected", round, reason = %err),
+        Event::Chose { choice, swaps } => info!(
+            event = "choice",
+            round,
+            choice,
+            swaps = %list(swaps.iter().map(|(a, b)| format!("{}-{}", a, b))),
+            answer_ms = game.answer_time.map(|time| time.as_millis() as u64),
+        ),
+        Event::Revealed(result) => {
+            info!(event = "reveal", round, cards = %cards(game.hand.cards()));
+            info!(
+                event = "result",
+                round,
+                choice = result.choice,
+                opponent = result.opponent,
+                winner = result.winner,
+                won = result.won,
+                points = result.points,
+                chips = result.chips,
+            );
+        }
+        Event::NextRound => info!(event = "next_round", round),
+        Event::GameOver => info!(event = "game_over", round),
+        Event::Undone(phase) => info!(event = "undo", round, phase = ?phase),
+        Event::Ignored(phase) => warn!(event = "ignored", round, input = ?input, phase = ?phase),
+    }
+}
+
+/// Cards written the way they're typed, e.g. `7♣ K♦ 2♠`.
+fn cards(cards: &[Card]) -> String {
+    list(cards)
+}
+
+fn list<T: Display, I: IntoIterator<Item = T>>(items: I) -> String {
+    items
+        .into_iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EventLog;
+    use crate::game::state::{Event, Input};
+    use crate::game::GameBuilder;
+    use std::fs;
+    use std::io::{self, Write};
+
+    #[test]
+    /// Tests every step of a round is written as a JSON line.
+    fn test_event_log() {
+        let path = std::env::temp_dir().join(format!("card_game_log_{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut game = GameBuilder::new().with_seed(3).spawn().unwrap();
+        game.log_events(EventLog::create(&path).unwrap());
+        for input in [
+            Input::Deal,
+            Input::Continue,
+            Input::Choose(1),
+            Input::Reveal,
+        ]
+        .iter()
+        {
+            game.advance(*input);
+        }
+        let lines: Vec<serde_json::Value> = fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        fs::remove_file(&path).unwrap();
+
+        let events: Vec<&str> = lines
+            .iter()
+            .map(|line| line["event"].as_str().unwrap())
+            .collect();
+        assert_eq!(events, ["deal", "ignored", "choice", "reveal", "result"]);
+        assert_eq!(lines[0]["cards"].as_str().unwrap().split(' ').count(), 3);
+        assert_eq!(lines[0]["cards_left"], 49);
+        assert_eq!(lines[1]["level"], "WARN");
+        assert_eq!(lines[1]["input"], "Continue");
+        assert_eq!(lines[2]["choice"], 1);
+        assert!(lines.iter().all(|line| line["round"] == 1));
+        assert_eq!(lines[4]["won"], game.wins() == 1);
+    }
+
+    #[test]
+    /// Tests a log that can't be written to is dropped and the game carries on.
+    fn test_event_log_failed() {
+        struct Broken;
+        impl Write for Broken {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::other("disk full"))
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut game = GameBuilder::new().with_seed(3).spawn().unwrap();
+        let mut log = EventLog::new(Broken);
+        let event = game.advance(Input::Deal);
+        assert!(log.record(Input::Deal, &event, &game).is_err());
+        game.log_events(log);
+        assert!(matches!(
+            game.advance(Input::Choose(1)),
+            Event::Chose { .. }
+        ));
+        assert!(game.log.is_none());
+    }
+}
@@ -0,0 +1,138 @@
+//! This module provides headless play of the high card game for gathering
+//! statistics.
+//!
+//! `Game::simulate` plays rounds straight through the state machine with a
+//! `Strategy` making the picks, so nothing is printed, read or slept on.
+//! When the deck runs out a fresh one is brought in, so any number of rounds can
+//! be played from one game.
+
+use super::{Game, Speed};
+use crate::cards::Deck;
+use crate::game::state::{Event, Input, Phase};
+use crate::strategy::{Choice, GameView, Strategy};
+use std::fmt::{self, Display, Formatter};
+
+/// What a simulation added up to.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Simulation {
+    pub rounds: usize,
+    pub wins: usize,
+    /// Points scored, including streak bonuses.
+    pub points: u64,
+    pub best_streak: usize,
+    /// Chips won or lost when betting.
+    pub chips: i64,
+}
+
+impl Simulation {
+    /// The share of rounds won, from 0 to 1.
+    pub fn win_rate(&self) -> f64 {
+        if self.rounds == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.rounds as f64
+    }
+}
+
+impl Display for Simulation {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{} wins in {} rounds ({:.1}%), {} points, best streak {}",
+            self.wins,
+            self.rounds,
+            self.win_rate() * 100.0,
+            self.points,
+            self.best_streak
+        )
+    }
+}
+
+impl Game {
+    /// Plays up to `rounds` single player rounds with `strategy` picking the cards.
+    /// A game that's betting stops early once it's out of chips, and a game of a
+    /// fixed number of rounds once they've all been played, and any game once the
+    /// strategy quits. Each round is recorded in the game's history like any
+    /// other.
+    pub fn simulate(&mut self, rounds: usize, strategy: &mut dyn Strategy) -> Simulation {
+        // Nothing is shown, so nothing should wait to be seen.
+        self.speed = Speed::Instant;
+        let mut simulation = Simulation::default();
+        while simulation.rounds < rounds && !self.is_bankrupt() && !self.played_all_rounds() {
+            if self.out_of_cards() {
+                self.deck = Deck::new();
+                self.phase = Phase::Dealing;
+                strategy.new_deck();
+            }
+            self.advance(Input::Deal);
+            let view = GameView::new(&self.hand, self.scoring.as_ref());
+            let choice = match strategy.choose(&view) {
+                Choice::Card(choice) => choice,
+                Choice::Quit => break,
+            };
+            self.advance(Input::Choose(choice));
+            if let Event::Revealed(result) = self.advance(Input::Reveal) {
+                strategy.observe(&self.hand, result.won);
+                simulation.rounds += 1;
+                simulation.wins += result.won as usize;
+                simulation.points += result.points;
+                simulation.chips += result.chips.unwrap_or(0);
+            }
+            self.advance(Input::Continue);
+        }
+        simulation.best_streak = self.best_streak;
+        simulation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::{GameBuilder, Speed};
+    use crate::opponent::{Opponent, Skill};
+    use crate::strategy::{
+        Choice, FirstCard, GameView, HighestVisibleSuit, Human, Random, Strategy,
+    };
+    use std::io::{self, Cursor};
+
+    #[test]
+    /// Tests simulations play every round asked for and repeat with a seed.
+    fn test_simulate() {
+        let run = |strategy: &mut dyn Strategy| {
+            let mut game = GameBuilder::new().with_seed(7).spawn().unwrap();
+            game.simulate(1000, strategy)
+        };
+        let first = run(&mut FirstCard);
+        assert_eq!(first.rounds, 1000);
+        let mut game = GameBuilder::new().speed(Speed::Slow).spawn().unwrap();
+        game.simulate(1, &mut FirstCard);
+        assert_eq!(game.speed(), Speed::Instant);
+        assert_eq!(first, run(&mut FirstCard));
+        assert_eq!(run(&mut Random::new(1)), run(&mut Random::new(1)));
+
+        let heuristic = run(&mut HighestVisibleSuit);
+        assert!(heuristic.win_rate() > first.win_rate());
+        let suit_aware = run(&mut Opponent::new(Skill::SuitAware, 1));
+        assert!(suit_aware.wins > first.wins);
+    }
+
+    #[test]
+    /// Tests a strategy written outside the built-ins can be simulated, and a
+    /// simulation stops when the strategy quits.
+    fn test_simulate_own_strategy() {
+        /// Picks the last card.
+        struct LastCard;
+
+        impl Strategy for LastCard {
+            fn choose(&mut self, view: &GameView) -> Choice {
+                Choice::Card(view.hand.len() - 1)
+            }
+        }
+
+        let mut game = GameBuilder::new().with_seed(7).spawn().unwrap();
+        assert_eq!(game.simulate(10, &mut LastCard).rounds, 10);
+
+        let mut human = Human::new(Cursor::new("0\n1\nq\n"), io::sink());
+        let mut game = GameBuilder::new().with_seed(7).spawn().unwrap();
+        assert_eq!(game.simulate(10, &mut human).rounds, 2);
+    }
+}
@@ -0,0 +1,433 @@
+//! This module provides the high card game as a state machine.
+//!
+//! A frontend feeds the game an `Input` and gets back an `Event` saying what
+//! happened, so the rules don't depend on where input comes from or how long a
+//! frontend pauses between steps. Each round runs
+//! `Dealing → AwaitingChoice → Revealing → RoundOver`, and the game ends in
+//! `GameOver` once the deck or the chips run out. Multiplayer rounds have no
+//! choice to make and reveal straight from `AwaitingChoice`.
+//!
+//! `Input::Undo` steps back through the round: from a pick back to the hand as it
+//! was dealt, and from there back to before the deal with the cards in the deck.
+
+use super::{Game, Player};
+#[cfg(feature = "audio")]
+use crate::audio::Effect;
+use crate::bank::{Bank, BankError};
+use crate::cards::{Deck, Hand};
+#[cfg(feature = "events")]
+use crate::events::Broadcast;
+use crate::opponent::Opponent;
+use rand::rngs::StdRng;
+use std::time::Duration;
+
+/// Where the game is in the current round.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Phase {
+    /// Waiting for the next hand to be dealt.
+    #[default]
+    Dealing,
+    /// A hand is dealt face down, waiting for a wager or a pick.
+    AwaitingChoice,
+    /// The player picked `choice` and the hand is about to be turned over.
+    Revealing { choice: usize },
+    /// The hand is face up and the result is in.
+    RoundOver,
+    /// The deck or the chips ran out.
+    GameOver,
+}
+
+/// Something a frontend asks the game to do.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Input {
+    /// Deal the next hand.
+    Deal,
+    /// Bet chips on the round.
+    Wager(u64),
+    /// Pick the card at an index, clamped to the last card.
+    Choose(usize),
+    /// Pick the card at an index after thinking for a while. The time is kept
+    /// in the history, and in a timed challenge earns a bonus for answering
+    /// quickly.
+    ChooseIn(usize, Duration),
+    /// Pick a card at random.
+    Random,
+    /// Turn the hand over and score the round.
+    Reveal,
+    /// Move on from a finished round.
+    Continue,
+    /// Take back the last step of the round.
+    Undo,
+}
+
+/// The result of a round.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RoundResult {
+    /// The card picked, or None in a multiplayer round.
+    pub choice: Option<usize>,
+    /// The card the computer opponent picked, if there is one.
+    pub opponent: Option<usize>,
+    /// The high card, which in a multiplayer round is also the winning player.
+    pub winner: usize,
+    pub won: bool,
+    pub points: u64,
+    /// Chips won or lost when betting.
+    pub chips: Option<i64>,
+    /// How long the player took to pick, if the frontend timed it.
+    pub decision_time: Option<Duration>,
+}
+
+/// What happened after an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A new hand is on the table.
+    Dealt,
+    /// The wager is placed.
+    Wagered(u64),
+    /// The wager couldn't be placed and the round waits for another.
+    WagerRejected(BankError),
+    /// The player picked `choice`, and on hard the hand was shuffled by `swaps`
+    /// afterwards.
+    Chose {
+        choice: usize,
+        swaps: Vec<(usize, usize)>,
+    },
+    /// The hand is face up and the round is scored.
+    Revealed(RoundResult),
+    /// Ready to deal the next round.
+    NextRound,
+    /// The deck or the chips ran out.
+    GameOver,
+    /// The round was stepped back to this phase.
+    Undone(Phase),
+    /// The input doesn't apply in this phase, so nothing changed.
+    Ignored(Phase),
+}
+
+/// Everything a round changes, saved so it can be undone.
+#[derive(Clone)]
+struct Snapshot {
+    rng: StdRng,
+    deck: Deck,
+    hand: Hand,
+    bank: Option<Bank>,
+    opponent: Option<Opponent>,
+    players: Vec<Player>,
+    games_played: usize,
+    wins: usize,
+    streak: usize,
+    best_streak: usize,
+    points: u64,
+    rounds: usize,
+}
+
+/// Snapshots of the round in progress.
+#[derive(Default)]
+pub(crate) struct UndoStack {
+    /// Before the hand was dealt.
+    round_start: Option<Snapshot>,
+    /// Just after the hand was dealt.
+    dealt: Option<Snapshot>,
+}
+
+impl Game {
+    /// Where the game is in the current round.
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// Moves the game on by one step.
+    pub fn advance(&mut self, input: Input) -> Event {
+        let event = self.step(input);
+        if let Some(mut log) = self.log.take() {
+            // A log that can't be written to any more is dropped so the game
+            // carries on without it.
+            if log.record(input, &event, self).is_ok() {
+                self.log = Some(log);
+            }
+        }
+        #[cfg(feature = "events")]
+        if let Some(spectators) = &self.spectators {
+            for broadcast in Broadcast::from_event(&event, self) {
+                spectators.send(&broadcast);
+            }
+        }
+        #[cfg(feature = "audio")]
+        if let Some(sounds) = &self.sounds {
+            sounds.play(&Effect::from_event(&event));
+        }
+        event
+    }
+
+    fn step(&mut self, input: Input) -> Event {
+        match (self.phase, input) {
+            (Phase::Dealing, Input::Deal) => {
+                if self.is_over() {
+                    self.phase = Phase::GameOver;
+                    return Event::GameOver;
+                }
+                self.undo.round_start = Some(self.snapshot());
+                self.deal_cards();
+                self.undo.dealt = Some(self.snapshot());
+                self.phase = Phase::AwaitingChoice;
+                Event::Dealt
+            }
+            (Phase::AwaitingChoice, Input::Wager(amount)) if !self.is_multiplayer() => {
+                match self.bank.as_mut().map(|bank| bank.wager(amount)) {
+                    Some(Ok(())) => Event::Wagered(amount),
+                    Some(Err(err)) => Event::WagerRejected(err),
+                    None => Event::Ignored(self.phase),
+                }
+            }
+            (Phase::AwaitingChoice, Input::Choose(choice)) if !self.is_multiplayer() => {
+                self.answer_time = None;
+                self.choose(choice.min(self.hand.len() - 1))
+            }
+            (Phase::AwaitingChoice, Input::ChooseIn(choice, elapsed)) if !self.is_multiplayer() => {
+                self.answer_time = Some(elapsed);
+                self.choose(choice.min(self.hand.len() - 1))
+            }
+            (Phase::AwaitingChoice, Input::Random) if !self.is_multiplayer() => {
+                self.answer_time = None;
+                let choice = self.random_choice();
+                self.choose(choice)
+            }
+            (Phase::AwaitingChoice, Input::Reveal) if self.is_multiplayer() => {
+                self.hand.reveal_all();
+                let winner = self.finish_round();
+                self.phase = Phase::RoundOver;
+                Event::Revealed(RoundResult {
+                    choice: None,
+                    opponent: None,
+                    winner,
+                    won: false,
+                    points: 0,
+                    chips: None,
+                    decision_time: None,
+                })
+            }
+            (Phase::Revealing { choice }, Input::Reveal) => {
+                let scoring = self.scoring.as_ref();
+                let hand = &self.hand;
+                let opponent = self
+                    .opponent
+                    .as_mut()
+                    .map(|opponent| opponent.guess(hand, scoring));
+                self.hand.reveal_all();
+                let winner = self.find_high_card();
+                if let (Some(opponent), Some(pick)) = (self.opponent.as_mut(), opponent) {
+                    opponent.record(&self.hand, pick == winner);
+                }
+                let won = choice == winner;
+                let points = self.record_guess(choice);
+                let chips = self.settle_wager(won);
+                self.phase = Phase::RoundOver;
+                Event::Revealed(RoundResult {
+                    choice: Some(choice),
+                    opponent,
+                    winner,
+                    won,
+                    points,
+                    chips,
+                    decision_time: self.answer_time,
+                })
+            }
+            (Phase::RoundOver, Input::Continue) => {
+                self.undo = UndoStack::default();
+                if self.is_over() {
+                    self.phase = Phase::GameOver;
+                    Event::GameOver
+                } else {
+                    self.phase = Phase::Dealing;
+                    Event::NextRound
+                }
+            }
+            (Phase::AwaitingChoice, Input::Undo) => match self.undo.round_start.take() {
+                Some(snapshot) => {
+                    self.restore(snapshot);
+                    self.undo.dealt = None;
+                    self.phase = Phase::Dealing;
+                    Event::Undone(self.phase)
+                }
+                None => Event::Ignored(self.phase),
+            },
+            (Phase::Revealing { .. }, Input::Undo) | (Phase::RoundOver, Input::Undo) => {
+                match self.undo.dealt.as_ref() {
+                    Some(snapshot) => {
+                        let snapshot = snapshot.clone();
+                        self.restore(snapshot);
+                        self.phase = Phase::AwaitingChoice;
+                        Event::Undone(self.phase)
+                    }
+                    None => Event::Ignored(self.phase),
+                }
+            }
+            (phase, _) => Event::Ignored(phase),
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            rng: self.rng.clone(),
+            deck: self.deck.clone(),
+            hand: self.hand.clone(),
+            bank: self.bank.clone(),
+            opponent: self.opponent.clone(),
+            players: self.players.clone(),
+            games_played: self.games_played,
+            wins: self.wins,
+            streak: self.streak,
+            best_streak: self.best_streak,
+            points: self.points,
+            rounds: self.history.len(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.rng = snapshot.rng;
+        self.deck = snapshot.deck;
+        self.hand = snapshot.hand;
+        self.bank = snapshot.bank;
+        self.opponent = snapshot.opponent;
+        self.players = snapshot.players;
+        self.games_played = snapshot.games_played;
+        self.wins = snapshot.wins;
+        self.streak = snapshot.streak;
+        self.best_streak = snapshot.best_streak;
+        self.points = snapshot.points;
+        self.history.truncate(snapshot.rounds);
+    }
+
+    fn choose(&mut self, choice: usize) -> Event {
+        let swaps = self.monte_swaps();
+        for &(first, second) in swaps.iter() {
+            self.hand.swap(first, second);
+        }
+        self.apply_forced(choice);
+        self.reveal_choice(choice);
+        self.phase = Phase::Revealing { choice };
+        Event::Chose { choice, swaps }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Event, Input, Phase};
+    use crate::bank::{Bank, BankError};
+    use crate::cards::CardState;
+    use crate::game::GameBuilder;
+
+    #[test]
+    /// Tests a whole game can be played through `advance`.
+    fn test_play_to_game_over() {
+        let mut game = GameBuilder::new().with_seed(4).spawn().unwrap();
+        assert_eq!(game.advance(Input::Reveal), Event::Ignored(Phase::Dealing));
+
+        let mut rounds = 0;
+        while game.advance(Input::Deal) == Event::Dealt {
+            assert_eq!(
+                game.advance(Input::Continue),
+                Event::Ignored(Phase::AwaitingChoice)
+            );
+            match game.advance(Input::Choose(7)) {
+                Event::Chose { choice, swaps } => {
+                    assert_eq!(choice, 2);
+                    assert!(swaps.is_empty());
+                }
+                event => panic!("unexpected {:?}", event),
+            }
+            assert!(matches!(game.advance(Input::Reveal), Event::Revealed(_)));
+            assert!(game.hand().iter().all(|card| card.state.is_face_up()));
+            assert_eq!(game.hand().cards()[2].state, CardState::Selected);
+            rounds += 1;
+            if game.advance(Input::Continue) == Event::GameOver {
+                break;
+            }
+        }
+
+        assert_eq!(rounds, 17);
+        assert_eq!(game.phase(), Phase::GameOver);
+        assert_eq!(game.history().len(), 17);
+        assert_eq!(game.advance(Input::Deal), Event::Ignored(Phase::GameOver));
+    }
+
+    #[test]
+    /// Tests undo steps back to the dealt hand and then to the full deck.
+    fn test_undo() {
+        let mut game = GameBuilder::new()
+            .with_seed(4)
+            .bank(Bank::new(10))
+            .spawn()
+            .unwrap();
+        assert_eq!(game.advance(Input::Undo), Event::Ignored(Phase::Dealing));
+        game.advance(Input::Deal);
+        let dealt = game.hand().clone();
+        game.advance(Input::Wager(10));
+        game.advance(Input::Choose(0));
+        let first = game.advance(Input::Reveal);
+
+        assert_eq!(
+            game.advance(Input::Undo),
+            Event::Undone(Phase::AwaitingChoice)
+        );
+        assert_eq!(game.hand(), &dealt);
+        assert_eq!(game.games_played(), 0);
+        assert_eq!(game.history().len(), 0);
+        assert_eq!(game.bank().unwrap().chips(), 10);
+        game.advance(Input::Wager(10));
+        game.advance(Input::Choose(0));
+        assert_eq!(game.advance(Input::Reveal), first);
+
+        game.advance(Input::Undo);
+        assert_eq!(game.advance(Input::Undo), Event::Undone(Phase::Dealing));
+        assert_eq!(game.deck().remaining(), 52);
+        assert_eq!(game.advance(Input::Undo), Event::Ignored(Phase::Dealing));
+        game.advance(Input::Deal);
+        assert_eq!(game.hand(), &dealt);
+    }
+
+    #[test]
+    /// Tests wagers are placed and settled by the round.
+    fn test_wager_round() {
+        let mut game = GameBuilder::new()
+            .with_seed(4)
+            .bank(Bank::new(10))
+            .spawn()
+            .unwrap();
+        game.advance(Input::Deal);
+        assert_eq!(
+            game.advance(Input::Wager(11)),
+            Event::WagerRejected(BankError::InsufficientChips {
+                wager: 11,
+                chips: 10
+            })
+        );
+        assert_eq!(game.advance(Input::Wager(10)), Event::Wagered(10));
+        game.advance(Input::Random);
+        match game.advance(Input::Reveal) {
+            Event::Revealed(result) => {
+                let expected = if result.won { 20 } else { -10 };
+                assert_eq!(result.chips, Some(expected));
+            }
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+
+    #[test]
+    /// Tests a multiplayer round reveals without a pick.
+    fn test_multiplayer_round() {
+        let mut game = GameBuilder::new().players(2).with_seed(4).spawn().unwrap();
+        game.advance(Input::Deal);
+        assert_eq!(
+            game.advance(Input::Choose(0)),
+            Event::Ignored(Phase::AwaitingChoice)
+        );
+        match game.advance(Input::Reveal) {
+            Event::Revealed(result) => {
+                assert_eq!(result.choice, None);
+                assert_eq!(game.players()[result.winner].wins(), 1);
+            }
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! Game modes built on top of the cards module.
+
+pub mod blackjack;
+pub mod go_fish;
+pub mod hi_lo;
+pub mod holdem;
+pub mod klondike;
+pub mod memory;
+pub mod poker;
+pub mod war;
@@ -0,0 +1,420 @@
+//! This module provides blackjack against a dealer who stands on 17.
+//!
+//! Cards are dealt from a `Shoe`, one deck by default, which is shuffled before
+//! the round after its cut card comes out.
+
+use crate::cards::card_printer::display_hand;
+use crate::cards::shoe::Shoe;
+use crate::cards::{Card, Hand, Rank};
+use crate::error::GameError;
+use crate::input::read_line;
+use crate::scoring::{BlackjackScoring, ScoringStrategy};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fmt::{self, Display, Formatter};
+
+/// The dealer stops drawing once their hand is worth this much.
+const DEALER_STANDS_ON: u32 = 17;
+
+/// Blackjack value of a hand. Aces count 11 unless that would bust the hand,
+/// in which case they count 1.
+pub fn hand_value(hand: &Hand) -> u32 {
+    let mut total = hand.total_value_with(&BlackjackScoring);
+    let mut aces = hand
+        .cards()
+        .iter()
+        .filter(|card| card.rank == Rank::Ace)
+        .count();
+
+    while total > 21 && aces > 0 {
+        total -= 10;
+        aces -= 1;
+    }
+    total
+}
+
+/// Returns true if the hand is worth more than 21.
+pub fn is_bust(hand: &Hand) -> bool {
+    hand_value(hand) > 21
+}
+
+/// Returns true if the hand is a natural 21 from the first two cards.
+pub fn is_blackjack(hand: &Hand) -> bool {
+    hand.len() == 2 && hand_value(hand) == 21
+}
+
+/// Result of a round of blackjack from the player's point of view.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Blackjack,
+    Win,
+    DealerBust,
+    Push,
+    Lose,
+    Bust,
+}
+
+impl Outcome {
+    /// Returns true if the player won the round.
+    pub fn is_win(&self) -> bool {
+        matches!(
+            self,
+            Outcome::Blackjack | Outcome::Win | Outcome::DealerBust
+        )
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let s = match self {
+            Outcome::Blackjack => "Blackjack! You win!!!",
+            Outcome::Win => "You win!!!",
+            Outcome::DealerBust => "Dealer busts. You win!!!",
+            Outcome::Push => "Push.",
+            Outcome::Lose => "You lose!",
+            Outcome::Bust => "Bust! You lose!",
+        };
+        write!(formatter, "{}", s)
+    }
+}
+
+/// A fixed rule for when to hit, so hands can be played without a player.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Policy {
+    /// Never takes a card.
+    Stand,
+    /// Hits below 17, the same as the dealer.
+    Dealer,
+    /// Hits below 12, and on 12 to 16 when the dealer shows a 7 or better.
+    Basic,
+}
+
+impl Policy {
+    /// Every policy, in the order they're listed.
+    pub const ALL: [Policy; 3] = [Policy::Stand, Policy::Dealer, Policy::Basic];
+
+    /// Looks up a policy by name.
+    pub fn named(name: &str) -> Option<Policy> {
+        Policy::ALL
+            .iter()
+            .copied()
+            .find(|policy| policy.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Policy::Stand => "stand",
+            Policy::Dealer => "dealer",
+            Policy::Basic => "basic",
+        }
+    }
+
+    /// Whether to take another card holding `player` with the dealer showing
+    /// `upcard`.
+    pub fn hits(&self, player: &Hand, upcard: &Card) -> bool {
+        let total = hand_value(player);
+        match self {
+            Policy::Stand => false,
+            Policy::Dealer => total < DEALER_STANDS_ON,
+            Policy::Basic => {
+                total < 12 || (total < DEALER_STANDS_ON && BlackjackScoring.score(upcard) >= 7)
+            }
+        }
+    }
+}
+
+/// Holds blackjack game state.
+pub struct Blackjack {
+    rng: StdRng,
+    shoe: Shoe,
+    player: Hand,
+    dealer: Hand,
+    wins: usize,
+    losses: usize,
+    pushes: usize,
+    show_count: bool,
+}
+
+impl Blackjack {
+    /// Construct a new game with a randomly seeded shuffle.
+    pub fn new() -> Blackjack {
+        Blackjack::with_rng(StdRng::from_entropy())
+    }
+
+    /// Construct a new game whose shuffles are reproducible.
+    pub fn with_seed(seed: u64) -> Blackjack {
+        Blackjack::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(rng: StdRng) -> Blackjack {
+        let shoe = Shoe::new(1).expect("a single deck shoe always has cards");
+        Blackjack {
+            rng,
+            shoe: shoe.clone(),
+            player: Hand::new(),
+            dealer: Hand::new(),
+            wins: 0,
+            losses: 0,
+            pushes: 0,
+            show_count: false,
+        }
+        .with_shoe(shoe)
+    }
+
+    /// Option to deal from `shoe`, which is shuffled straight away.
+    pub fn with_shoe(mut self, shoe: Shoe) -> Blackjack {
+        self.shoe = shoe;
+        self.shoe.shuffle(&mut self.rng);
+        self
+    }
+
+    /// Option to show the running and true count after each round in the terminal.
+    pub fn show_count(mut self, show_count: bool) -> Blackjack {
+        self.show_count = show_count;
+        self
+    }
+
+    pub fn shoe(&self) -> &Shoe {
+        &self.shoe
+    }
+
+    pub fn player(&self) -> &Hand {
+        &self.player
+    }
+
+    pub fn dealer(&self) -> &Hand {
+        &self.dealer
+    }
+
+    /// Discards the previous round, shuffles if the cut card has come out and
+    /// deals two cards each. The dealer's second card is dealt face down.
+    pub fn deal(&mut self) {
+        for card in self.player.drain().chain(self.dealer.drain()) {
+            self.shoe.discard(card);
+        }
+        if self.shoe.needs_shuffle() {
+            self.shoe.shuffle(&mut self.rng);
+        }
+
+        self.draw_to_player();
+        self.draw_to_dealer();
+        self.draw_to_player();
+        self.dealer.extend(self.shoe.deal_face_down(&mut self.rng));
+    }
+
+    fn draw_to_player(&mut self) {
+        self.player.extend(self.shoe.deal_face_up(&mut self.rng));
+    }
+
+    fn draw_to_dealer(&mut self) {
+        self.dealer.extend(self.shoe.deal_face_up(&mut self.rng));
+    }
+
+    /// Deals the player another card.
+    pub fn hit(&mut self) {
+        self.draw_to_player();
+    }
+
+    /// Reveals the dealer's hole card and draws until the dealer reaches 17.
+    pub fn dealer_play(&mut self) {
+        for card in self.dealer.iter().filter(|card| !card.state.is_face_up()) {
+            self.shoe.turn_over(card);
+        }
+        self.dealer.reveal_all();
+        if is_bust(&self.player) {
+            return;
+        }
+        while hand_value(&self.dealer) < DEALER_STANDS_ON {
+            self.draw_to_dealer();
+        }
+    }
+
+    /// Settles the round and records the result.
+    pub fn settle(&mut self) -> Outcome {
+        let player = hand_value(&self.player);
+        let dealer = hand_value(&self.dealer);
+
+        let outcome = if is_bust(&self.player) {
+            Outcome::Bust
+        } else if is_blackjack(&self.player) && !is_blackjack(&self.dealer) {
+            Outcome::Blackjack
+        } else if is_bust(&self.dealer) {
+            Outcome::DealerBust
+        } else if player > dealer {
+            Outcome::Win
+        } else if player == dealer {
+            Outcome::Push
+        } else {
+            Outcome::Lose
+        };
+
+        match outcome {
+            Outcome::Push => self.pushes += 1,
+            _ if outcome.is_win() => self.wins += 1,
+            _ => self.losses += 1,
+        }
+        outcome
+    }
+
+    /// Plays a whole round with `policy` deciding when to hit.
+    pub fn play_hand(&mut self, policy: Policy) -> Outcome {
+        self.deal();
+        while hand_value(&self.player) < 21 && policy.hits(&self.player, &self.dealer.cards()[0]) {
+            self.hit();
+        }
+        self.dealer_play();
+        self.settle()
+    }
+
+    fn show_table(&self) -> Result<(), GameError> {
+        println!("Dealer");
+        display_hand(self.dealer.cards(), false)?;
+        println!("You ({})", hand_value(&self.player));
+        display_hand(self.player.cards(), false)
+    }
+
+    /// Plays rounds in the terminal until the player quits.
+    pub async fn play(&mut self) -> Result<(), GameError> {
+        loop {
+            self.deal();
+
+            while !is_bust(&self.player) && hand_value(&self.player) < 21 {
+                self.show_table()?;
+                println!("[h]it or [s]tand?");
+
+                let input = read_line().await?;
+                if input.is_empty() {
+                    return Ok(());
+                }
+                match input.trim() {
+                    "h" | "hit" => self.hit(),
+                    "s" | "stand" => break,
+                    _ => {}
+                }
+            }
+
+            self.dealer_play();
+            self.show_table()?;
+            println!("Dealer has {}.", hand_value(&self.dealer));
+            println!("{}", self.settle());
+            println!("{}", self);
+            if self.show_count {
+                println!(
+                    "Running count {}, true count {:.1}, {} cards left in the shoe.",
+                    self.shoe.stats().running_count,
+                    self.shoe.true_count(),
+                    self.shoe.remaining()
+                );
+            }
+            println!();
+
+            println!("Press [Enter] to play again or [q] to quit.");
+            let input = read_line().await?;
+            if input.is_empty() || input.trim() == "q" {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl Default for Blackjack {
+    fn default() -> Blackjack {
+        Blackjack::new()
+    }
+}
+
+impl Display for Blackjack {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Won {}, lost {}, pushed {}.",
+            self.wins, self.losses, self.pushes
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hand_value, is_blackjack, Blackjack, Outcome, Policy, DEALER_STANDS_ON};
+    use crate::cards::shoe::Shoe;
+    use crate::cards::{Card, Hand, Rank, Suit};
+
+    fn hand(ranks: &[Rank]) -> Hand {
+        Hand::from_cards(
+            ranks
+                .iter()
+                .map(|rank| Card::new(Suit::Hearts, *rank))
+                .collect(),
+        )
+    }
+
+    #[test]
+    /// Tests aces count as 11 or 1.
+    fn test_hand_value_aces() {
+        assert_eq!(hand_value(&hand(&[Rank::Ace, Rank::King])), 21);
+        assert_eq!(hand_value(&hand(&[Rank::Ace, Rank::Ace])), 12);
+        assert_eq!(hand_value(&hand(&[Rank::Ace, Rank::Nine, Rank::Five])), 15);
+        assert_eq!(hand_value(&hand(&[Rank::Queen, Rank::Jack, Rank::Two])), 22);
+        assert!(is_blackjack(&hand(&[Rank::Ten, Rank::Ace])));
+        assert!(!is_blackjack(&hand(&[Rank::Five, Rank::Six, Rank::Ten])));
+    }
+
+    #[test]
+    /// Tests the dealer stands on 17 and rounds are settled.
+    fn test_dealer_and_settle() {
+        let mut game = Blackjack::with_seed(3);
+        for _ in 0..20 {
+            game.deal();
+            assert_eq!(game.player().len(), 2);
+            game.dealer_play();
+
+            let dealer = hand_value(game.dealer());
+            assert!(dealer >= DEALER_STANDS_ON);
+
+            let outcome = game.settle();
+            if dealer > 21 && !matches!(outcome, Outcome::Blackjack) {
+                assert_eq!(outcome, Outcome::DealerBust);
+            }
+        }
+    }
+
+    #[test]
+    /// Tests each policy decides when to hit by its own rule.
+    fn test_policy() {
+        let seven = Card::new(Suit::Clubs, Rank::Seven);
+        let six = Card::new(Suit::Clubs, Rank::Six);
+        let fifteen = hand(&[Rank::Ten, Rank::Five]);
+        assert!(!Policy::Stand.hits(&hand(&[Rank::Two, Rank::Three]), &seven));
+        assert!(Policy::Dealer.hits(&fifteen, &six));
+        assert!(Policy::Basic.hits(&fifteen, &seven));
+        assert!(!Policy::Basic.hits(&fifteen, &six));
+        assert!(Policy::Basic.hits(&hand(&[Rank::Five, Rank::Six]), &six));
+        assert_eq!(Policy::named("basic"), Some(Policy::Basic));
+
+        let mut game = Blackjack::with_seed(8);
+        for _ in 0..20 {
+            game.play_hand(Policy::Stand);
+            assert_eq!(game.player().len(), 2);
+        }
+    }
+
+    #[test]
+    /// Tests rounds are dealt from the shoe, counting the hole card once it's
+    /// turned over and shuffling once the cut card is out.
+    fn test_shoe() {
+        let shoe = Shoe::new(2).unwrap().penetration(0.5);
+        let mut game = Blackjack::with_seed(4).with_shoe(shoe);
+        assert_eq!(game.shoe().remaining(), 103);
+        game.deal();
+        let shown: usize = game.shoe().stats().seen.iter().sum();
+        assert_eq!((game.shoe().stats().dealt, shown), (4, 3));
+        game.dealer_play();
+        let shown: usize = game.shoe().stats().seen.iter().sum();
+        assert_eq!(shown, game.shoe().stats().dealt);
+
+        for _ in 0..30 {
+            game.play_hand(Policy::Dealer);
+        }
+        assert!(game.shoe().stats().shuffles > 1);
+    }
+}
@@ -0,0 +1,423 @@
+//! This module provides Go Fish between the player and the computer.
+//!
+//! Players take turns asking the other for a rank they hold. If the other
+//! player has any they hand them all over and the asker goes again, otherwise
+//! the asker draws from the pool and only goes again if they drew the rank they
+//! asked for. Four cards of a rank make a book, and whoever has the most books
+//! once they're all made wins.
+//!
+//! In hot seat mode two people share the terminal instead, and the screen is
+//! cleared between turns so neither sees the other's hand.
+
+use crate::cards::card_printer::display_hand;
+use crate::cards::{Card, Deck, Hand, Rank};
+use crate::engine::{pass_to, wants_to_quit, Flow, GameMode};
+use crate::error::GameError;
+use crate::input::read_line;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::fmt::{self, Display, Formatter};
+
+/// Cards dealt to each player.
+const HAND_SIZE: usize = 7;
+
+/// Cards of one rank that make a book.
+const BOOK_SIZE: usize = 4;
+
+/// The player at the keyboard.
+pub const PLAYER: usize = 0;
+
+/// The computer opponent, or the second player in hot seat mode.
+pub const COMPUTER: usize = 1;
+
+/// What came of asking for a rank.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Ask {
+    /// The other player handed over this many cards.
+    Took(usize),
+    /// The asker drew the rank they asked for.
+    FishedIt,
+    /// The asker drew something else, or nothing when the pool is empty.
+    GoFish,
+}
+
+/// Why a rank couldn't be asked for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GoFishError {
+    /// Players can only ask for ranks they hold.
+    NotHeld(Rank),
+    /// It's the other player's turn.
+    NotYourTurn,
+}
+
+impl Display for GoFishError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            GoFishError::NotHeld(rank) => write!(formatter, "you don't have any {}s", rank),
+            GoFishError::NotYourTurn => write!(formatter, "it isn't your turn"),
+        }
+    }
+}
+
+impl std::error::Error for GoFishError {}
+
+/// Holds Go Fish game state.
+pub struct GoFish {
+    pool: Deck,
+    hands: [Hand; 2],
+    books: [Vec<Rank>; 2],
+    turn: usize,
+    /// Ranks the player has asked for and so is known to hold.
+    asked: Vec<Rank>,
+    rng: StdRng,
+    hot_seat: bool,
+    /// The player last handed the keyboard in hot seat mode.
+    passed: Option<usize>,
+}
+
+impl GoFish {
+    /// Construct a new game with a randomly seeded shuffle.
+    pub fn new() -> GoFish {
+        GoFish::with_rng(StdRng::from_entropy())
+    }
+
+    /// Construct a new game whose shuffle and computer choices are reproducible.
+    pub fn with_seed(seed: u64) -> GoFish {
+        GoFish::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(mut rng: StdRng) -> GoFish {
+        let mut pool = Deck::new();
+        pool.shuffle(&mut rng);
+        let hands = [
+            Hand::from_cards(pool.draw(HAND_SIZE)),
+            Hand::from_cards(pool.draw(HAND_SIZE)),
+        ];
+        let mut game = GoFish {
+            pool,
+            hands,
+            books: [Vec::new(), Vec::new()],
+            turn: PLAYER,
+            asked: Vec::new(),
+            rng,
+            hot_seat: false,
+            passed: None,
+        };
+        game.lay_books(PLAYER);
+        game.lay_books(COMPUTER);
+        game
+    }
+
+    /// Option to have two people take turns at the terminal instead of playing
+    /// the computer.
+    pub fn hot_seat(mut self, hot_seat: bool) -> GoFish {
+        self.hot_seat = hot_seat;
+        self
+    }
+
+    pub fn hand(&self, player: usize) -> &Hand {
+        &self.hands[player]
+    }
+
+    /// Ranks a player has completed, in the order they were made.
+    pub fn books(&self, player: usize) -> &[Rank] {
+        &self.books[player]
+    }
+
+    /// The player whose turn it is.
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    /// Cards left to draw.
+    pub fn pool(&self) -> usize {
+        self.pool.remaining()
+    }
+
+    /// Returns true once every book is made, or nobody can ask for anything.
+    pub fn is_over(&self) -> bool {
+        let books = self.books[PLAYER].len() + self.books[COMPUTER].len();
+        books == 13 || (self.pool.is_empty() && self.hands.iter().any(Hand::is_empty))
+    }
+
+    /// The player with the most books, or None if they're level.
+    pub fn winner(&self) -> Option<usize> {
+        match self.books[PLAYER].len().cmp(&self.books[COMPUTER].len()) {
+            std::cmp::Ordering::Greater => Some(PLAYER),
+            std::cmp::Ordering::Less => Some(COMPUTER),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// The current player asks the other for `rank`. The turn passes unless the
+    /// asker took cards or fished the rank they asked for.
+    pub fn ask(&mut self, asker: usize, rank: Rank) -> Result<Ask, GoFishError> {
+        if asker != self.turn {
+            return Err(GoFishError::NotYourTurn);
+        }
+        if !self.hands[asker].iter().any(|card| card.rank == rank) {
+            return Err(GoFishError::NotHeld(rank));
+        }
+        let other = 1 - asker;
+        if asker == PLAYER && !self.asked.contains(&rank) {
+            self.asked.push(rank);
+        }
+
+        let (given, kept): (Vec<Card>, Vec<Card>) = self.hands[other]
+            .drain()
+            .partition(|card| card.rank == rank);
+        self.hands[other] = Hand::from_cards(kept);
+
+        let ask = if !given.is_empty() {
+            if other == PLAYER {
+                self.asked.retain(|&asked| asked != rank);
+            }
+            let took = given.len();
+            for card in given {
+                self.hands[asker].push(card);
+            }
+            Ask::Took(took)
+        } else {
+            match self.pool.draw(1).pop() {
+                Some(card) => {
+                    let fished = card.rank == rank;
+                    self.hands[asker].push(card);
+                    if fished {
+                        Ask::FishedIt
+                    } else {
+                        Ask::GoFish
+                    }
+                }
+                None => Ask::GoFish,
+            }
+        };
+
+        self.lay_books(asker);
+        for player in [PLAYER, COMPUTER].iter().copied() {
+            if self.hands[player].is_empty() {
+                let cards = self.pool.draw(HAND_SIZE);
+                self.hands[player] = Hand::from_cards(cards);
+                self.lay_books(player);
+            }
+        }
+        if ask == Ask::GoFish {
+            self.turn = other;
+        }
+        Ok(ask)
+    }
+
+    /// Picks the rank the computer asks for: one the player is known to hold if
+    /// it can, otherwise any rank in its hand.
+    pub fn computer_choice(&mut self) -> Rank {
+        let held: Vec<Rank> = self.hands[COMPUTER].iter().map(|card| card.rank).collect();
+        if let Some(&rank) = self.asked.iter().find(|rank| held.contains(rank)) {
+            return rank;
+        }
+        *held
+            .choose(&mut self.rng)
+            .expect("the computer always holds a card on its turn")
+    }
+
+    /// Moves any complete books out of a player's hand, leaving it sorted by rank.
+    fn lay_books(&mut self, player: usize) {
+        let mut cards: Vec<Card> = self.hands[player].drain().collect();
+        cards.sort_by(Card::cmp_by_rank);
+        let mut kept = Vec::new();
+        for rank in cards.iter().map(|card| card.rank).collect::<Vec<Rank>>() {
+            let same = cards.iter().filter(|card| card.rank == rank).count();
+            if same == BOOK_SIZE {
+                if !self.books[player].contains(&rank) {
+                    self.books[player].push(rank);
+                    self.asked.retain(|&asked| asked != rank);
+                }
+            } else if !kept.iter().any(|card: &Card| card.rank == rank) {
+                kept.extend(cards.iter().filter(|card| card.rank == rank).copied());
+            }
+        }
+        self.hands[player] = Hand::from_cards(kept);
+    }
+}
+
+impl GoFish {
+    /// How a player is referred to at the prompt.
+    fn name(&self, player: usize) -> &'static str {
+        match (self.hot_seat, player) {
+            (false, PLAYER) => "You",
+            (false, _) => "The computer",
+            (true, PLAYER) => "Player 1",
+            (true, _) => "Player 2",
+        }
+    }
+
+    /// The score as seen by `player`.
+    fn status(&self, player: usize) -> String {
+        let other = 1 - player;
+        format!(
+            "Your books {}  Their books {}  {} holds {} cards  Pool {}",
+            self.books[player].len(),
+            self.books[other].len(),
+            self.name(other),
+            self.hands[other].len(),
+            self.pool.remaining()
+        )
+    }
+}
+
+impl Default for GoFish {
+    fn default() -> GoFish {
+        GoFish::new()
+    }
+}
+
+impl Display for GoFish {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.status(PLAYER))
+    }
+}
+
+impl GameMode for GoFish {
+    fn setup(&mut self) -> Result<(), GameError> {
+        println!("Collect books of four cards of the same rank.");
+        Ok(())
+    }
+
+    async fn play_round(&mut self) -> Result<Flow, GameError> {
+        if self.turn == COMPUTER && !self.hot_seat {
+            let rank = self.computer_choice();
+            println!("The computer asks for {}s.", rank);
+            match self
+                .ask(COMPUTER, rank)
+                .expect("the computer asks on its turn")
+            {
+                Ask::Took(count) => println!("You hand over {}.", count),
+                Ask::FishedIt => println!("You say go fish, and it draws a {}.", rank),
+                Ask::GoFish => println!("You say go fish.\n"),
+            }
+            return Ok(Flow::Continue);
+        }
+
+        let player = self.turn;
+        if self.hot_seat && self.passed != Some(player) {
+            pass_to(self.name(player)).await?;
+            self.passed = Some(player);
+            println!("{}'s turn.", self.name(player));
+        }
+
+        let mut cards = self.hands[player].clone();
+        cards.reveal_all();
+        display_hand(cards.cards(), false)?;
+        println!("{}", self.status(player));
+        println!("Ask for a rank you hold (e.g. 7, J or A) or [q] to quit.");
+
+        let input = read_line().await?;
+        if input.is_empty() || wants_to_quit(&input) {
+            return Ok(Flow::Quit);
+        }
+        let rank: Rank = match input.trim().parse() {
+            Ok(rank) => rank,
+            Err(err) => {
+                println!("Sorry, {}.", err);
+                return Ok(Flow::Continue);
+            }
+        };
+        let books = self.books[player].len();
+        match self.ask(player, rank) {
+            Ok(Ask::Took(count)) => println!("{} hands over {}.", self.name(1 - player), count),
+            Ok(Ask::FishedIt) => println!("Go fish! You drew a {}, go again.", rank),
+            Ok(Ask::GoFish) => println!("Go fish!\n"),
+            Err(err) => println!("Sorry, {}.", err),
+        }
+        if self.books[player].len() > books {
+            println!("You made a book of {}s.", rank);
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn is_over(&self) -> bool {
+        GoFish::is_over(self)
+    }
+
+    fn summary(&self) -> String {
+        let (yours, theirs) = (self.books[PLAYER].len(), self.books[COMPUTER].len());
+        match self.winner() {
+            Some(PLAYER) if self.hot_seat => {
+                format!("Player 1 wins with {} books to {}!", yours, theirs)
+            }
+            Some(_) if self.hot_seat => {
+                format!("Player 2 wins with {} books to {}!", theirs, yours)
+            }
+            Some(PLAYER) => format!("You win with {} books to {}!", yours, theirs),
+            Some(_) => format!("The computer wins with {} books to {}.", theirs, yours),
+            None => format!("It's a draw at {} books each.", yours),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ask, GoFish, GoFishError, COMPUTER, PLAYER};
+    use crate::cards::{Card, Deck, Hand, Rank, Suit};
+    use crate::engine::GameMode;
+
+    fn hand(ranks: &[(Suit, Rank)]) -> Hand {
+        Hand::from_cards(
+            ranks
+                .iter()
+                .map(|&(suit, rank)| Card::new(suit, rank))
+                .collect(),
+        )
+    }
+
+    #[test]
+    /// Tests cards change hands, books are laid down and the turn passes on a miss.
+    fn test_ask() {
+        let mut game = GoFish::with_seed(2);
+        game.hands[PLAYER] = hand(&[
+            (Suit::Spades, Rank::Seven),
+            (Suit::Hearts, Rank::Seven),
+            (Suit::Clubs, Rank::Two),
+        ]);
+        game.hands[COMPUTER] = hand(&[
+            (Suit::Diamonds, Rank::Seven),
+            (Suit::Clubs, Rank::Seven),
+            (Suit::Hearts, Rank::Nine),
+        ]);
+        game.pool = Deck::from_cards(vec![Card::new(Suit::Spades, Rank::Three)]);
+
+        assert_eq!(
+            game.ask(PLAYER, Rank::Nine),
+            Err(GoFishError::NotHeld(Rank::Nine))
+        );
+        assert_eq!(game.ask(PLAYER, Rank::Seven), Ok(Ask::Took(2)));
+        assert_eq!(game.books(PLAYER), &[Rank::Seven]);
+        assert_eq!(game.hand(PLAYER).len(), 1);
+        assert_eq!(game.ask(PLAYER, Rank::Two), Ok(Ask::GoFish));
+        assert_eq!(game.turn(), COMPUTER);
+        assert_eq!(game.ask(PLAYER, Rank::Two), Err(GoFishError::NotYourTurn));
+
+        let game = game.hot_seat(true);
+        assert_eq!(game.summary(), "Player 1 wins with 1 books to 0!");
+    }
+
+    #[test]
+    /// Tests the computer asks for ranks the player has asked for.
+    fn test_computer_remembers() {
+        let mut game = GoFish::with_seed(2);
+        game.hands[PLAYER] = hand(&[(Suit::Spades, Rank::King), (Suit::Spades, Rank::Four)]);
+        game.hands[COMPUTER] = hand(&[
+            (Suit::Hearts, Rank::Two),
+            (Suit::Hearts, Rank::Three),
+            (Suit::Hearts, Rank::Four),
+            (Suit::Hearts, Rank::Five),
+        ]);
+        game.pool = Deck::from_cards(vec![Card::new(Suit::Clubs, Rank::Ace)]);
+
+        assert_eq!(game.ask(PLAYER, Rank::Four), Ok(Ask::Took(1)));
+        assert_eq!(game.ask(PLAYER, Rank::King), Ok(Ask::GoFish));
+        game.hands[COMPUTER].push(Card::new(Suit::Hearts, Rank::King));
+        assert_eq!(game.computer_choice(), Rank::King);
+        assert_eq!(game.ask(COMPUTER, Rank::King), Ok(Ask::Took(1)));
+        assert!(game.asked.iter().all(|&rank| rank == Rank::Four));
+    }
+}
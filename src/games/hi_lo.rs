@@ -0,0 +1,236 @@
+//! This module provides Hi-Lo, guessing whether the next card is higher or lower.
+//!
+//! Each right guess in a row is worth one more point than the last. A wrong
+//! guess ends the streak, and a card of the same rank neither scores nor breaks
+//! it. The game ends when the deck runs out.
+
+use crate::cards::card_printer::{display_hand, display_hand_with, RenderOptions};
+use crate::cards::{Card, CardState, Deck};
+use crate::engine::{wants_to_quit, Flow, GameMode};
+use crate::error::GameError;
+use crate::input::read_line;
+use crate::rules::Ruleset;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+/// The player's call on the next card.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Guess {
+    Higher,
+    Lower,
+}
+
+/// How a guess turned out.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The guess was right and scored this many points.
+    Right(u32),
+    Wrong,
+    /// The next card had the same rank.
+    Tie,
+}
+
+/// Holds Hi-Lo game state.
+pub struct HiLo {
+    deck: Deck,
+    current: Card,
+    rules: Ruleset,
+    streak: u32,
+    best_streak: u32,
+    score: u32,
+}
+
+impl HiLo {
+    /// Construct a new game with a randomly seeded shuffle.
+    pub fn new() -> HiLo {
+        HiLo::with_rng(StdRng::from_entropy())
+    }
+
+    /// Construct a new game whose shuffle is reproducible.
+    pub fn with_seed(seed: u64) -> HiLo {
+        HiLo::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(mut rng: StdRng) -> HiLo {
+        let mut deck = Deck::new();
+        deck.shuffle(&mut rng);
+        let mut current = deck.draw(1).remove(0);
+        current.state = CardState::Visible;
+        HiLo {
+            deck,
+            current,
+            rules: Ruleset::new(),
+            streak: 0,
+            best_streak: 0,
+            score: 0,
+        }
+    }
+
+    /// Option to change whether aces are high or low, and whether a suit decides
+    /// between cards of the same rank. By default aces are high and the same rank
+    /// is a tie.
+    pub fn rules(mut self, rules: Ruleset) -> HiLo {
+        self.rules = rules;
+        self
+    }
+
+    /// The face up card the next guess is made against.
+    pub fn current(&self) -> &Card {
+        &self.current
+    }
+
+    pub fn streak(&self) -> u32 {
+        self.streak
+    }
+
+    pub fn best_streak(&self) -> u32 {
+        self.best_streak
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    /// Returns true once there are no cards left to guess.
+    pub fn is_over(&self) -> bool {
+        self.deck.is_empty()
+    }
+
+    /// Draws the next card and scores `guess` against the current one. The drawn
+    /// card becomes the current card.
+    pub fn guess(&mut self, guess: Guess) -> Option<Outcome> {
+        let mut next = self.deck.draw(1).pop()?;
+        next.state = CardState::Visible;
+        let order = self.rules.compare(&next, &self.current);
+        let outcome = match (order, guess) {
+            (Ordering::Equal, _) => Outcome::Tie,
+            (Ordering::Greater, Guess::Higher) | (Ordering::Less, Guess::Lower) => {
+                self.streak += 1;
+                self.best_streak = self.best_streak.max(self.streak);
+                self.score += self.streak;
+                Outcome::Right(self.streak)
+            }
+            _ => {
+                self.streak = 0;
+                Outcome::Wrong
+            }
+        };
+        self.current = next;
+        Some(outcome)
+    }
+}
+
+impl Default for HiLo {
+    fn default() -> HiLo {
+        HiLo::new()
+    }
+}
+
+impl Display for HiLo {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Score {}  Streak {}  Cards left {}",
+            self.score,
+            self.streak,
+            self.deck.remaining()
+        )
+    }
+}
+
+impl GameMode for HiLo {
+    fn setup(&mut self) -> Result<(), GameError> {
+        println!("Playing with {}.", self.rules);
+        Ok(())
+    }
+
+    async fn play_round(&mut self) -> Result<Flow, GameError> {
+        display_hand(&[self.current], false)?;
+        println!("{}", self);
+        println!("Will the next card be [h]igher or [l]ower? [q] to quit.");
+
+        let input = read_line().await?;
+        if input.is_empty() || wants_to_quit(&input) {
+            return Ok(Flow::Quit);
+        }
+        let guess = match input.trim() {
+            "h" | "higher" => Guess::Higher,
+            "l" | "lower" => Guess::Lower,
+            _ => {
+                println!("Type h for higher or l for lower.");
+                return Ok(Flow::Continue);
+            }
+        };
+
+        let previous = self.current;
+        if let Some(outcome) = self.guess(guess) {
+            display_hand_with(&[previous, self.current], &RenderOptions::new())?;
+            match outcome {
+                Outcome::Right(points) => println!("Right! +{} points", points),
+                Outcome::Wrong => println!("Wrong, the streak is over."),
+                Outcome::Tie => println!("Same rank, no change."),
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn is_over(&self) -> bool {
+        HiLo::is_over(self)
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "Out of cards. You scored {} with a best streak of {}.",
+            self.score, self.best_streak
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Guess, HiLo, Outcome};
+    use crate::cards::{Card, Deck, Rank, Suit};
+    use crate::rules::{AceRule, Ruleset};
+
+    /// A game that starts on `first` then draws `rest` in order.
+    fn stacked(first: Rank, rest: &[Rank]) -> HiLo {
+        let mut game = HiLo::with_seed(1);
+        game.current = Card::new(Suit::Spades, first);
+        game.deck = Deck::from_cards(
+            rest.iter()
+                .map(|&rank| Card::new(Suit::Hearts, rank))
+                .collect(),
+        );
+        game
+    }
+
+    #[test]
+    /// Tests streaks score one more point per right guess and reset when wrong.
+    fn test_streak_scoring() {
+        let mut game = stacked(
+            Rank::Five,
+            &[Rank::Nine, Rank::Jack, Rank::Jack, Rank::Two, Rank::Three],
+        );
+        assert_eq!(game.guess(Guess::Higher), Some(Outcome::Right(1)));
+        assert_eq!(game.guess(Guess::Higher), Some(Outcome::Right(2)));
+        assert_eq!(game.guess(Guess::Lower), Some(Outcome::Tie));
+        assert_eq!(game.guess(Guess::Lower), Some(Outcome::Right(3)));
+        assert_eq!(game.guess(Guess::Lower), Some(Outcome::Wrong));
+        assert_eq!(game.guess(Guess::Lower), None);
+        assert_eq!((game.score(), game.streak(), game.best_streak()), (6, 0, 3));
+        assert!(game.is_over());
+    }
+
+    #[test]
+    /// Tests aces rank above kings or below twos depending on the rule.
+    fn test_ace_rule() {
+        let mut high = stacked(Rank::King, &[Rank::Ace]);
+        assert_eq!(high.guess(Guess::Higher), Some(Outcome::Right(1)));
+
+        let mut low = stacked(Rank::Two, &[Rank::Ace]).rules(Ruleset::new().aces(AceRule::Low));
+        assert_eq!(low.guess(Guess::Lower), Some(Outcome::Right(1)));
+        assert_eq!(AceRule::named("low"), Some(AceRule::Low));
+    }
+}
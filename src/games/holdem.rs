@@ -0,0 +1,553 @@
+//! This module provides fixed limit Texas Hold'em against computer opponents.
+//!
+//! Every seat antes, is dealt two hole cards and shares five community cards
+//! dealt over the flop, turn and river. Each street has one bet, which the other
+//! seats call or fold, and the best five card hand at the showdown takes the pot.
+//! A seat that can't cover a bet puts in what it has left and stays in for the
+//! whole pot, since there are no side pots.
+//!
+//! Each hand is kept as a hand history, which can be written out after every
+//! hand for poker tracking tools to import.
+
+use crate::bank::Bank;
+use crate::cards::card_printer::display_hand;
+use crate::cards::{Card, CardState, Deck, Hand, Rank};
+use crate::engine::{wants_to_quit, Flow, GameMode};
+use crate::error::GameError;
+use crate::input::read_line;
+use crate::poker::history::{Action, HandHistory, Variant};
+use crate::poker::{evaluate_hand, HandCategory};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt::{self, Display, Formatter};
+use std::io::{self};
+use std::path::PathBuf;
+
+/// Chips every seat starts with.
+const STARTING_CHIPS: u64 = 100;
+
+/// Chips every seat puts in before the cards are dealt.
+const ANTE: u64 = 5;
+
+/// The seat of the player at the keyboard.
+pub const PLAYER: usize = 0;
+
+/// How hand histories describe the game.
+const VARIANT: Variant = Variant::Holdem {
+    small_bet: Street::PreFlop.bet(),
+    big_bet: Street::River.bet(),
+};
+
+/// A stage of the hand and the community cards dealt at it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Street {
+    PreFlop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Street {
+    /// Every street in the order they're played.
+    pub const ALL: [Street; 4] = [Street::PreFlop, Street::Flop, Street::Turn, Street::River];
+
+    /// Community cards dealt when the street starts.
+    fn cards(self) -> usize {
+        match self {
+            Street::PreFlop => 0,
+            Street::Flop => 3,
+            Street::Turn | Street::River => 1,
+        }
+    }
+
+    /// The size of a bet on this street, doubling for the turn and river.
+    pub const fn bet(self) -> u64 {
+        match self {
+            Street::PreFlop | Street::Flop => 10,
+            Street::Turn | Street::River => 20,
+        }
+    }
+}
+
+impl Display for Street {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Street::PreFlop => "Pre-flop",
+            Street::Flop => "Flop",
+            Street::Turn => "Turn",
+            Street::River => "River",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+
+/// Holds Hold'em game state.
+pub struct Holdem {
+    rng: StdRng,
+    deck: Deck,
+    banks: Vec<Bank>,
+    hole: Vec<Hand>,
+    board: Vec<Card>,
+    /// Seats out of the current hand, by folding or by having no chips.
+    folded: Vec<bool>,
+    pot: u64,
+    history: HandHistory,
+    /// Where each finished hand's history is written.
+    export: Option<PathBuf>,
+}
+
+impl Holdem {
+    /// Construct a new game against `opponents` opponents, from 1 to 3, with a
+    /// randomly seeded shuffle.
+    pub fn new(opponents: usize) -> Holdem {
+        Holdem::with_rng(StdRng::from_entropy(), opponents)
+    }
+
+    /// Construct a new game whose shuffles and opponent choices are reproducible.
+    pub fn with_seed(seed: u64, opponents: usize) -> Holdem {
+        Holdem::with_rng(StdRng::seed_from_u64(seed), opponents)
+    }
+
+    fn with_rng(rng: StdRng, opponents: usize) -> Holdem {
+        let seats = opponents.clamp(1, 3) + 1;
+        Holdem {
+            rng,
+            deck: Deck::new(),
+            banks: vec![Bank::new(STARTING_CHIPS); seats],
+            hole: vec![Hand::new(); seats],
+            board: Vec::new(),
+            folded: vec![false; seats],
+            pot: 0,
+            history: HandHistory::new(0, VARIANT, 0, seats),
+            export: None,
+        }
+    }
+
+    /// Option to add the history of every hand played to the end of the file
+    /// at `path`.
+    pub fn hand_history<P: Into<PathBuf>>(mut self, path: P) -> Holdem {
+        self.export = Some(path.into());
+        self
+    }
+
+    /// Number of seats including the player's.
+    pub fn seats(&self) -> usize {
+        self.banks.len()
+    }
+
+    pub fn chips(&self, seat: usize) -> u64 {
+        self.banks[seat].chips()
+    }
+
+    pub fn hole(&self, seat: usize) -> &Hand {
+        &self.hole[seat]
+    }
+
+    pub fn board(&self) -> &[Card] {
+        &self.board
+    }
+
+    pub fn pot(&self) -> u64 {
+        self.pot
+    }
+
+    /// The history of the hand being played, or the last one once it's over.
+    pub fn history(&self) -> &HandHistory {
+        &self.history
+    }
+
+    /// Seats still in the current hand.
+    pub fn active(&self) -> Vec<usize> {
+        (0..self.seats())
+            .filter(|&seat| !self.folded[seat])
+            .collect()
+    }
+
+    /// Returns true once the player or every opponent is out of chips.
+    pub fn is_over(&self) -> bool {
+        self.banks[PLAYER].is_bankrupt() || self.banks[PLAYER + 1..].iter().all(Bank::is_bankrupt)
+    }
+
+    /// Shuffles, takes the antes and deals two hole cards to every seat with chips.
+    /// The opponents' cards stay hidden.
+    pub fn deal(&mut self) {
+        self.deck.reset();
+        self.deck.shuffle(&mut self.rng);
+        self.board.clear();
+        self.pot = 0;
+        self.history = HandHistory::starting_now(VARIANT, self.seats());
+        for seat in 0..self.seats() {
+            self.folded[seat] = self.banks[seat].is_bankrupt();
+            self.hole[seat] = Hand::new();
+            if self.folded[seat] {
+                continue;
+            }
+            self.history
+                .seat(seat, &Holdem::name(seat), self.banks[seat].chips());
+            let ante = self.banks[seat].pay_in(ANTE);
+            self.pot += ante;
+            self.history.act(seat, Action::Ante(ante));
+            for mut card in self.deck.draw(2) {
+                if seat == PLAYER {
+                    card.state = CardState::Visible;
+                }
+                self.hole[seat].push(card);
+            }
+        }
+        self.history.dealt(PLAYER, self.hole[PLAYER].cards());
+    }
+
+    /// Deals the community cards for `street` face up.
+    pub fn deal_street(&mut self, street: Street) {
+        let dealt = self.board.len();
+        for mut card in self.deck.draw(street.cards()) {
+            card.state = CardState::Visible;
+            self.board.push(card);
+        }
+        if self.board.len() > dealt {
+            self.history.board(&self.board[dealt..]);
+        }
+    }
+
+    /// Puts a bet for `street` into the pot from `seat` and returns the chips paid.
+    pub fn bet(&mut self, seat: usize, street: Street) -> u64 {
+        let paid = self.banks[seat].pay_in(street.bet());
+        self.pot += paid;
+        paid
+    }
+
+    pub fn fold(&mut self, seat: usize) {
+        self.folded[seat] = true;
+        self.history.act(seat, Action::Folds);
+    }
+
+    /// How good a computer seat thinks its cards are: 0 weak, 1 playable, 2 strong.
+    fn strength(&self, seat: usize) -> u8 {
+        let hole = self.hole[seat].cards();
+        if self.board.is_empty() {
+            let high = |card: &Card| card.rank >= Rank::Ten;
+            return match hole {
+                [first, second] if first.rank == second.rank => 2,
+                [first, second] if high(first) && high(second) => 1,
+                _ => 0,
+            };
+        }
+        let cards: Vec<Card> = hole.iter().chain(&self.board).copied().collect();
+        match evaluate_hand(&cards).category {
+            HandCategory::HighCard => 0,
+            HandCategory::OnePair => 1,
+            _ => 2,
+        }
+    }
+
+    /// Whether a computer seat opens the betting when nobody has bet yet.
+    pub fn opponent_bets(&mut self, seat: usize) -> bool {
+        self.strength(seat) == 2 || self.rng.gen_bool(0.1)
+    }
+
+    /// Whether a computer seat calls a bet rather than folding.
+    pub fn opponent_calls(&mut self, seat: usize) -> bool {
+        self.strength(seat) >= 1 || self.rng.gen_bool(0.3)
+    }
+
+    /// Every computer seat still in the hand, other than `bettor`, calls or
+    /// folds. Returns each seat's choice.
+    pub fn opponents_respond(&mut self, bettor: usize, street: Street) -> Vec<(usize, bool)> {
+        let mut choices = Vec::new();
+        for seat in self.active() {
+            if seat == PLAYER || seat == bettor {
+                continue;
+            }
+            let calls = self.opponent_calls(seat);
+            if calls {
+                let paid = self.bet(seat, street);
+                self.history.act(seat, Action::Calls(paid));
+            } else {
+                self.fold(seat);
+            }
+            choices.push((seat, calls));
+        }
+        choices
+    }
+
+    /// Reveals the hands still in and splits the pot between the best of them,
+    /// any odd chips going to the first winner. Returns the winning seats.
+    pub fn showdown(&mut self) -> Vec<usize> {
+        let active = self.active();
+        let ranks: Vec<_> = active
+            .iter()
+            .map(|&seat| {
+                self.hole[seat].reveal_all();
+                if active.len() > 1 {
+                    self.history.show(seat, self.hole[seat].cards());
+                }
+                let cards: Vec<Card> = self.hole[seat].iter().chain(&self.board).copied().collect();
+                evaluate_hand(&cards)
+            })
+            .collect();
+        let best = ranks.iter().max().cloned();
+        let winners: Vec<usize> = active
+            .iter()
+            .zip(&ranks)
+            .filter(|(_, rank)| Some(*rank) == best.as_ref())
+            .map(|(&seat, _)| seat)
+            .collect();
+
+        let share = self.pot / winners.len() as u64;
+        let odd = self.pot % winners.len() as u64;
+        for (idx, &seat) in winners.iter().enumerate() {
+            let chips = if idx == 0 { share + odd } else { share };
+            self.banks[seat].pay_out(chips);
+            self.history.collect(seat, chips);
+        }
+        self.pot = 0;
+        winners
+    }
+
+    /// The name a seat is shown with.
+    fn name(seat: usize) -> String {
+        match seat {
+            PLAYER => "You".to_string(),
+            seat => format!("Opponent {}", seat),
+        }
+    }
+
+    /// Asks the player to act. Returns None if they chose to quit.
+    async fn ask(&self, prompt: &str) -> io::Result<Option<String>> {
+        println!("{} [q] to quit.", prompt);
+        let input = read_line().await?;
+        if input.is_empty() || wants_to_quit(&input) {
+            return Ok(None);
+        }
+        Ok(Some(input.trim().to_lowercase()))
+    }
+
+    /// Calls or folds a bet made by `bettor`, asking the player if they're in.
+    async fn answer_bet(&mut self, bettor: usize, street: Street) -> io::Result<Flow> {
+        if bettor != PLAYER && !self.folded[PLAYER] {
+            let prompt = format!(
+                "{} bets {}. [c]all or [f]old?",
+                Holdem::name(bettor),
+                street.bet()
+            );
+            match self.ask(&prompt).await? {
+                None => return Ok(Flow::Quit),
+                Some(answer) if answer.starts_with('f') => self.fold(PLAYER),
+                Some(_) => {
+                    let paid = self.bet(PLAYER, street);
+                    self.history.act(PLAYER, Action::Calls(paid));
+                }
+            }
+        }
+        for (seat, calls) in self.opponents_respond(bettor, street) {
+            let action = if calls { "calls" } else { "folds" };
+            println!("{} {}.", Holdem::name(seat), action);
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Plays the betting on one street.
+    async fn betting_round(&mut self, street: Street) -> io::Result<Flow> {
+        if !self.folded[PLAYER] {
+            let prompt = format!("[c]heck, [b]et {} or [f]old?", street.bet());
+            match self.ask(&prompt).await? {
+                None => return Ok(Flow::Quit),
+                Some(answer) if answer.starts_with('f') => self.fold(PLAYER),
+                Some(answer) if answer.starts_with('b') => {
+                    let paid = self.bet(PLAYER, street);
+                    self.history.act(PLAYER, Action::Bets(paid));
+                    return self.answer_bet(PLAYER, street).await;
+                }
+                Some(_) => self.history.act(PLAYER, Action::Checks),
+            }
+        }
+        let mut bettor = None;
+        for seat in self.active().into_iter().filter(|&seat| seat != PLAYER) {
+            if self.opponent_bets(seat) {
+                bettor = Some(seat);
+                break;
+            }
+            self.history.act(seat, Action::Checks);
+        }
+        match bettor {
+            Some(bettor) => {
+                let paid = self.bet(bettor, street);
+                self.history.act(bettor, Action::Bets(paid));
+                self.answer_bet(bettor, street).await
+            }
+            None => Ok(Flow::Continue),
+        }
+    }
+
+    fn show_table(&self, street: Street) -> Result<(), GameError> {
+        println!("{}  Pot {}", street, self.pot);
+        for seat in 1..self.seats() {
+            let state = if self.folded[seat] { "  (out)" } else { "" };
+            println!(
+                "{}: {} chips{}",
+                Holdem::name(seat),
+                self.chips(seat),
+                state
+            );
+        }
+        if !self.board.is_empty() {
+            display_hand(&self.board, false)?;
+        }
+        println!("Your cards, {} chips", self.chips(PLAYER));
+        display_hand(self.hole[PLAYER].cards(), false)?;
+        Ok(())
+    }
+}
+
+impl Display for Holdem {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let chips: Vec<String> = (0..self.seats())
+            .map(|seat| format!("{} {}", Holdem::name(seat), self.chips(seat)))
+            .collect();
+        write!(formatter, "Chips: {}", chips.join("  "))
+    }
+}
+
+impl GameMode for Holdem {
+    fn setup(&mut self) -> Result<(), GameError> {
+        println!(
+            "Everyone starts with {} chips and antes {} a hand.",
+            STARTING_CHIPS, ANTE
+        );
+        Ok(())
+    }
+
+    async fn play_round(&mut self) -> Result<Flow, GameError> {
+        self.deal();
+        for &street in Street::ALL.iter() {
+            self.deal_street(street);
+            self.show_table(street)?;
+            if self.betting_round(street).await? == Flow::Quit {
+                return Ok(Flow::Quit);
+            }
+            if self.active().len() == 1 {
+                break;
+            }
+        }
+
+        let pot = self.pot;
+        let winners = self.showdown();
+        if let Some(path) = &self.export {
+            self.history.export(path)?;
+        }
+        for seat in self.active().into_iter().filter(|&seat| seat != PLAYER) {
+            println!("{}", Holdem::name(seat));
+            display_hand(self.hole[seat].cards(), false)?;
+        }
+        let names: Vec<String> = winners.iter().map(|&seat| Holdem::name(seat)).collect();
+        if winners.len() == 1 {
+            println!("{} won the pot of {}.", names[0], pot);
+        } else {
+            println!("{} split the pot of {}.", names.join(" and "), pot);
+        }
+        println!("{}\n", self);
+        Ok(Flow::Continue)
+    }
+
+    fn is_over(&self) -> bool {
+        Holdem::is_over(self)
+    }
+
+    fn summary(&self) -> String {
+        if self.banks[PLAYER].is_bankrupt() {
+            "You're out of chips.".to_string()
+        } else {
+            format!(
+                "You took every chip at the table, {} in all!",
+                self.chips(PLAYER)
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Holdem, Street, PLAYER};
+    use crate::cards::{Card, Hand, Rank, Suit};
+
+    fn cards(cards: &[(Suit, Rank)]) -> Vec<Card> {
+        cards
+            .iter()
+            .map(|&(suit, rank)| Card::new(suit, rank))
+            .collect()
+    }
+
+    #[test]
+    /// Tests antes and hole cards are dealt and community cards arrive by street.
+    fn test_deal() {
+        let mut game = Holdem::with_seed(3, 5);
+        assert_eq!(game.seats(), 4);
+        game.deal();
+        assert_eq!(game.pot(), 20);
+        assert!((0..4).all(|seat| game.hole(seat).len() == 2));
+        for &street in Street::ALL.iter() {
+            game.deal_street(street);
+        }
+        assert_eq!(game.board().len(), 5);
+        assert_eq!(game.bet(1, Street::River), 20);
+        assert_eq!(game.chips(1), 75);
+    }
+
+    #[test]
+    /// Tests the best hand still in takes the pot, folded seats can't win it, and
+    /// the hand history records it.
+    fn test_showdown() {
+        let mut game = Holdem::with_seed(3, 2);
+        game.deal();
+        game.board = cards(&[
+            (Suit::Hearts, Rank::Two),
+            (Suit::Clubs, Rank::Seven),
+            (Suit::Spades, Rank::Nine),
+            (Suit::Hearts, Rank::Jack),
+            (Suit::Diamonds, Rank::Four),
+        ]);
+        game.hole[PLAYER] = Hand::from_cards(cards(&[
+            (Suit::Clubs, Rank::Jack),
+            (Suit::Spades, Rank::Three),
+        ]));
+        game.hole[1] = Hand::from_cards(cards(&[
+            (Suit::Clubs, Rank::Nine),
+            (Suit::Diamonds, Rank::Nine),
+        ]));
+        game.hole[2] = Hand::from_cards(cards(&[
+            (Suit::Spades, Rank::Ace),
+            (Suit::Spades, Rank::King),
+        ]));
+
+        game.fold(1);
+        assert_eq!(game.showdown(), vec![PLAYER]);
+        assert_eq!(game.chips(PLAYER), 110);
+        assert_eq!(game.chips(1), 95);
+        let history = game.history().to_string();
+        assert!(history.contains("Opponent 1: folds"));
+        assert!(history.contains("*** SHOW DOWN ***\nYou: shows [Jc 3s]"));
+        assert!(history.contains("You collected 15 from pot"));
+        assert!(game
+            .hole(2)
+            .iter()
+            .all(|card| card.state == crate::cards::CardState::Visible));
+    }
+
+    #[test]
+    /// Tests computer seats call with a pair and the hand ends once one seat is left.
+    fn test_opponents_respond() {
+        let mut game = Holdem::with_seed(1, 1);
+        game.deal();
+        game.hole[1] = Hand::from_cards(cards(&[
+            (Suit::Clubs, Rank::Queen),
+            (Suit::Hearts, Rank::Queen),
+        ]));
+        game.bet(PLAYER, Street::PreFlop);
+        assert_eq!(
+            game.opponents_respond(PLAYER, Street::PreFlop),
+            vec![(1, true)]
+        );
+        assert_eq!(game.pot(), 30);
+        assert!(!game.is_over());
+    }
+}
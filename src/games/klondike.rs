@@ -0,0 +1,438 @@
+//! This module provides Klondike solitaire.
+//!
+//! Seven tableau piles are dealt with their top card face up. Piles build down
+//! in alternating colours and only a king can start an empty pile. The four
+//! foundations build up by suit from the ace, and the game is won when they hold
+//! every card. The stock is drawn one card at a time onto the waste, and turned
+//! back over once it runs out.
+
+use crate::cards::card_printer::{write_stacks, RenderOptions};
+use crate::cards::{Card, CardState, Deck, Rank, Suit};
+use crate::engine::{wants_to_quit, Flow, GameMode};
+use crate::error::GameError;
+use crate::input::read_line;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Write as _};
+
+/// Number of tableau piles.
+const PILES: usize = 7;
+
+/// Cards in each foundation once it's complete.
+const SUIT_SIZE: usize = 13;
+
+/// A move in the game. Piles are numbered 0 to 6 from the left.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Move {
+    /// Turns the next stock card onto the waste, or the waste back over as the
+    /// stock once it's empty.
+    Draw,
+    WasteToPile(usize),
+    WasteToFoundation,
+    PileToFoundation(usize),
+    /// Moves the face up cards of `from` that fit onto `to`.
+    PileToPile {
+        from: usize,
+        to: usize,
+    },
+}
+
+impl Move {
+    /// Parses a move typed at the prompt: `d` to draw, `w 3` or `w f` to play the
+    /// waste onto a pile or foundation, `2 f` or `2 5` to play from a pile.
+    pub fn parse(line: &str) -> Option<Move> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["d"] => Some(Move::Draw),
+            ["w", "f"] => Some(Move::WasteToFoundation),
+            ["w", pile] => pile.parse().ok().map(Move::WasteToPile),
+            [pile, "f"] => pile.parse().ok().map(Move::PileToFoundation),
+            [from, to] => match (from.parse(), to.parse()) {
+                (Ok(from), Ok(to)) => Some(Move::PileToPile { from, to }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Why a move couldn't be made.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MoveError {
+    /// There's no pile with this number.
+    NoPile(usize),
+    /// There's no card to move.
+    Empty,
+    /// The card doesn't go there.
+    Illegal,
+}
+
+impl Display for MoveError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            MoveError::NoPile(pile) => write!(formatter, "there's no pile {}", pile),
+            MoveError::Empty => write!(formatter, "there's no card to move"),
+            MoveError::Illegal => write!(formatter, "that card doesn't go there"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// Holds Klondike game state.
+pub struct Klondike {
+    stock: Vec<Card>,
+    waste: Vec<Card>,
+    foundations: [Vec<Card>; 4],
+    tableau: [Vec<Card>; PILES],
+    moves: usize,
+}
+
+impl Klondike {
+    /// Construct a new game with a randomly seeded deal.
+    pub fn new() -> Klondike {
+        Klondike::with_rng(StdRng::from_entropy())
+    }
+
+    /// Construct a new game whose deal is reproducible.
+    pub fn with_seed(seed: u64) -> Klondike {
+        Klondike::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(mut rng: StdRng) -> Klondike {
+        let mut deck = Deck::new();
+        deck.shuffle(&mut rng);
+
+        let mut tableau: [Vec<Card>; PILES] = Default::default();
+        for (idx, pile) in tableau.iter_mut().enumerate() {
+            *pile = deck.draw(idx + 1);
+            if let Some(top) = pile.last_mut() {
+                top.state = CardState::Visible;
+            }
+        }
+        Klondike {
+            stock: deck.draw(deck.remaining()),
+            waste: Vec::new(),
+            foundations: Default::default(),
+            tableau,
+            moves: 0,
+        }
+    }
+
+    /// The cards in a tableau pile, bottom card first.
+    pub fn pile(&self, pile: usize) -> &[Card] {
+        &self.tableau[pile]
+    }
+
+    /// The foundation for `suit`, ace first.
+    pub fn foundation(&self, suit: Suit) -> &[Card] {
+        &self.foundations[suit as usize - 1]
+    }
+
+    /// Cards left to draw.
+    pub fn stock(&self) -> usize {
+        self.stock.len()
+    }
+
+    /// The card that can be played from the waste.
+    pub fn waste_top(&self) -> Option<&Card> {
+        self.waste.last()
+    }
+
+    /// Moves made so far.
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    /// Returns true once every card is on a foundation.
+    pub fn is_won(&self) -> bool {
+        self.foundations.iter().all(|pile| pile.len() == SUIT_SIZE)
+    }
+
+    /// Makes `mv`, leaving the game unchanged if it isn't allowed.
+    pub fn play(&mut self, mv: Move) -> Result<(), MoveError> {
+        match mv {
+            Move::Draw => self.draw()?,
+            Move::WasteToPile(pile) => {
+                let card = *self.waste.last().ok_or(MoveError::Empty)?;
+                if !fits_pile(&card, self.tableau_pile(pile)?) {
+                    return Err(MoveError::Illegal);
+                }
+                self.waste.pop();
+                self.tableau[pile].push(card);
+            }
+            Move::WasteToFoundation => {
+                let card = *self.waste.last().ok_or(MoveError::Empty)?;
+                if !fits_foundation(&card, self.foundation(card.suit)) {
+                    return Err(MoveError::Illegal);
+                }
+                self.waste.pop();
+                self.foundations[card.suit as usize - 1].push(card);
+            }
+            Move::PileToFoundation(pile) => {
+                let card = *self.tableau_pile(pile)?.last().ok_or(MoveError::Empty)?;
+                if !fits_foundation(&card, self.foundation(card.suit)) {
+                    return Err(MoveError::Illegal);
+                }
+                self.tableau[pile].pop();
+                self.foundations[card.suit as usize - 1].push(card);
+                self.turn_over(pile);
+            }
+            Move::PileToPile { from, to } => {
+                let (source, target) = (self.tableau_pile(from)?, self.tableau_pile(to)?);
+                if from == to {
+                    return Err(MoveError::Illegal);
+                }
+                if source.is_empty() {
+                    return Err(MoveError::Empty);
+                }
+                // Every face up run in a pile is in sequence, so the run starts
+                // at whichever face up card fits onto the target.
+                let start = (0..source.len())
+                    .filter(|&idx| source[idx].state == CardState::Visible)
+                    .find(|&idx| fits_pile(&source[idx], target))
+                    .ok_or(MoveError::Illegal)?;
+                let run = self.tableau[from].split_off(start);
+                self.tableau[to].extend(run);
+                self.turn_over(from);
+            }
+        }
+        self.moves += 1;
+        Ok(())
+    }
+
+    fn draw(&mut self) -> Result<(), MoveError> {
+        match self.stock.pop() {
+            Some(mut card) => {
+                card.state = CardState::Visible;
+                self.waste.push(card);
+            }
+            None if self.waste.is_empty() => return Err(MoveError::Empty),
+            None => {
+                self.stock = self.waste.drain(..).rev().collect();
+                for card in &mut self.stock {
+                    card.state = CardState::Hidden;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn tableau_pile(&self, pile: usize) -> Result<&[Card], MoveError> {
+        self.tableau
+            .get(pile)
+            .map(Vec::as_slice)
+            .ok_or(MoveError::NoPile(pile))
+    }
+
+    /// Turns the top card of a pile face up once the cards on it are moved.
+    fn turn_over(&mut self, pile: usize) {
+        if let Some(top) = self.tableau[pile].last_mut() {
+            top.state = CardState::Visible;
+        }
+    }
+
+    /// Draws the stock, waste and foundations above the numbered tableau.
+    pub fn render(&self, options: &RenderOptions) -> String {
+        let top = |pile: &[Card]| pile.len().saturating_sub(1);
+        let mut row: Vec<&[Card]> = vec![
+            &self.stock[top(&self.stock)..],
+            &self.waste[top(&self.waste)..],
+        ];
+        row.extend(self.foundations.iter().map(|pile| &pile[top(pile)..]));
+        let tableau: Vec<&[Card]> = self.tableau.iter().map(Vec::as_slice).collect();
+
+        let mut out = String::new();
+        let gap = " ".repeat(options.theme.padding);
+        out.push_str(&format!("{:<11}{}{:<11}{}", "Stock", gap, "Waste", gap));
+        for suit in Suit::iter() {
+            out.push_str(&format!("{:<11}{}", suit.name(), gap));
+        }
+        out.push('\n');
+        write_stacks(&mut out, &row, &options.clone().show_index(false))
+            .and_then(|()| write_stacks(&mut out, &tableau, &options.clone().show_index(true)))
+            .expect("writing to a String cannot fail");
+        out
+    }
+}
+
+/// Where a rank falls in a suit, from the ace at 1 to the king at 13.
+fn ordinal(rank: Rank) -> usize {
+    match rank {
+        Rank::Ace => 1,
+        rank => rank.ordinal() as usize,
+    }
+}
+
+/// Whether `card` can go on top of `pile` in the tableau.
+fn fits_pile(card: &Card, pile: &[Card]) -> bool {
+    match pile.last() {
+        None => card.rank == Rank::King,
+        Some(top) => {
+            top.state == CardState::Visible
+                && top.suit.color() != card.suit.color()
+                && ordinal(top.rank) == ordinal(card.rank) + 1
+        }
+    }
+}
+
+/// Whether `card` is the next card for the foundation of its suit.
+fn fits_foundation(card: &Card, foundation: &[Card]) -> bool {
+    ordinal(card.rank) == foundation.len() + 1
+}
+
+impl Default for Klondike {
+    fn default() -> Klondike {
+        Klondike::new()
+    }
+}
+
+impl Display for Klondike {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let found: usize = self.foundations.iter().map(Vec::len).sum();
+        write!(
+            formatter,
+            "Moves {}  Stock {}  Foundations {} of 52",
+            self.moves,
+            self.stock.len(),
+            found
+        )
+    }
+}
+
+impl GameMode for Klondike {
+    fn setup(&mut self) -> Result<(), GameError> {
+        println!("Build the foundations up by suit from the ace.");
+        Ok(())
+    }
+
+    async fn play_round(&mut self) -> Result<Flow, GameError> {
+        let options = RenderOptions::new().resolve();
+        let mut stdout = io::stdout();
+        stdout.write_all(self.render(&options).as_bytes())?;
+        stdout.flush()?;
+        println!("{}", self);
+        println!("[d] draw  [w 3] waste to pile 3  [w f] or [2 f] to a foundation  [2 5] pile 2 to 5  [q] quit");
+
+        let input = read_line().await?;
+        if input.is_empty() || wants_to_quit(&input) {
+            return Ok(Flow::Quit);
+        }
+        match Move::parse(&input).map(|mv| self.play(mv)) {
+            Some(Ok(())) => println!(),
+            Some(Err(err)) => println!("Sorry, {}.\n", err),
+            None => println!("That isn't a move.\n"),
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn is_over(&self) -> bool {
+        self.is_won()
+    }
+
+    fn summary(&self) -> String {
+        format!("You won in {} moves!", self.moves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Klondike, Move, MoveError};
+    use crate::cards::card_printer::{ColorMode, RenderOptions};
+    use crate::cards::{Card, CardState, Rank, Suit};
+
+    fn visible(suit: Suit, rank: Rank) -> Card {
+        let mut card = Card::new(suit, rank);
+        card.state = CardState::Visible;
+        card
+    }
+
+    /// A game with nothing dealt, to lay out by hand.
+    fn empty() -> Klondike {
+        let mut game = Klondike::with_seed(1);
+        game.stock.clear();
+        for pile in game.tableau.iter_mut() {
+            pile.clear();
+        }
+        game
+    }
+
+    #[test]
+    /// Tests the deal and how the table is drawn.
+    fn test_deal() {
+        let game = Klondike::with_seed(4);
+        for idx in 0..7 {
+            let pile = game.pile(idx);
+            assert_eq!(pile.len(), idx + 1);
+            assert_eq!(pile.last().unwrap().state, CardState::Visible);
+        }
+        assert_eq!(game.stock(), 24);
+        insta::assert_snapshot!(game.render(&RenderOptions::new().color(ColorMode::Never)));
+    }
+
+    #[test]
+    /// Tests piles build down in alternating colours and foundations up by suit.
+    fn test_moves() {
+        let mut game = empty();
+        game.tableau[0] = vec![
+            Card::new(Suit::Clubs, Rank::Two),
+            visible(Suit::Hearts, Rank::Ace),
+        ];
+        game.tableau[1] = vec![
+            visible(Suit::Spades, Rank::Nine),
+            visible(Suit::Hearts, Rank::Eight),
+        ];
+        game.tableau[2] = vec![visible(Suit::Diamonds, Rank::Ten)];
+        game.tableau[3] = vec![visible(Suit::Clubs, Rank::King)];
+
+        assert_eq!(
+            game.play(Move::PileToPile { from: 2, to: 1 }),
+            Err(MoveError::Illegal)
+        );
+        assert_eq!(game.play(Move::PileToFoundation(0)), Ok(()));
+        assert_eq!(game.pile(0)[0].state, CardState::Visible);
+        assert_eq!(
+            game.play(Move::PileToFoundation(0)),
+            Err(MoveError::Illegal)
+        );
+
+        // The nine and eight move together onto the ten.
+        assert_eq!(game.play(Move::PileToPile { from: 1, to: 2 }), Ok(()));
+        assert_eq!(game.pile(2).len(), 3);
+        assert_eq!(
+            game.play(Move::PileToPile { from: 2, to: 1 }),
+            Err(MoveError::Illegal)
+        );
+        assert_eq!(game.play(Move::PileToPile { from: 3, to: 1 }), Ok(()));
+        assert_eq!(
+            game.play(Move::PileToPile { from: 9, to: 1 }),
+            Err(MoveError::NoPile(9))
+        );
+        assert_eq!(game.foundation(Suit::Hearts).len(), 1);
+        assert_eq!(game.moves(), 3);
+    }
+
+    #[test]
+    /// Tests the stock is drawn onto the waste and turned back over when empty.
+    fn test_draw() {
+        let mut game = empty();
+        game.stock = vec![
+            Card::new(Suit::Spades, Rank::Ace),
+            Card::new(Suit::Hearts, Rank::Two),
+        ];
+        assert_eq!(game.play(Move::WasteToFoundation), Err(MoveError::Empty));
+        game.play(Move::Draw).unwrap();
+        assert_eq!(game.waste_top(), Some(&visible(Suit::Hearts, Rank::Two)));
+        game.play(Move::Draw).unwrap();
+        game.play(Move::WasteToFoundation).unwrap();
+        game.play(Move::Draw).unwrap();
+        assert_eq!((game.stock(), game.waste_top()), (1, None));
+        assert_eq!(Move::parse("w 3"), Some(Move::WasteToPile(3)));
+        assert_eq!(
+            Move::parse("2 5"),
+            Some(Move::PileToPile { from: 2, to: 5 })
+        );
+        assert_eq!(Move::parse("x"), None);
+    }
+}
@@ -0,0 +1,265 @@
+//! This module provides Memory, also known as Concentration.
+//!
+//! Pairs of cards with matching ranks are laid out face down in a grid. Each
+//! attempt turns over two cards: a pair stays face up, anything else is turned
+//! back over before the next attempt.
+
+use crate::cards::card_printer::{display_grid, RenderOptions};
+use crate::cards::{Card, CardState, Deck, Rank};
+use crate::engine::{wants_to_quit, Flow, GameMode};
+use crate::error::GameError;
+use crate::input::read_line;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::fmt::{self, Display, Formatter};
+
+/// Pairs laid out in a game.
+const PAIRS: usize = 8;
+
+/// Cards in each row of the grid.
+const COLUMNS: usize = 4;
+
+/// What turning over two cards found.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Flip {
+    /// Both cards have this rank and stay face up.
+    Match(Rank),
+    /// The ranks differ.
+    Miss,
+}
+
+/// Why two cards couldn't be turned over.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MemoryError {
+    /// There's no card at this position.
+    OutOfRange(usize),
+    /// The same card was picked twice.
+    SameCard,
+    /// The card at this position is already part of a pair.
+    AlreadyMatched(usize),
+}
+
+impl Display for MemoryError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            MemoryError::OutOfRange(idx) => write!(formatter, "there's no card {}", idx),
+            MemoryError::SameCard => write!(formatter, "pick two different cards"),
+            MemoryError::AlreadyMatched(idx) => {
+                write!(formatter, "card {} is already matched", idx)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MemoryError {}
+
+/// Holds Memory game state.
+pub struct Memory {
+    grid: Vec<Card>,
+    matched: Vec<bool>,
+    attempts: usize,
+}
+
+impl Memory {
+    /// Construct a new game with a randomly seeded layout.
+    pub fn new() -> Memory {
+        Memory::with_rng(StdRng::from_entropy())
+    }
+
+    /// Construct a new game whose layout is reproducible.
+    pub fn with_seed(seed: u64) -> Memory {
+        Memory::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(mut rng: StdRng) -> Memory {
+        let mut deck = Deck::new();
+        deck.shuffle(&mut rng);
+
+        // Keep the first two cards of each rank until there are enough pairs.
+        let mut grid: Vec<Card> = Vec::new();
+        let mut ranks: Vec<Rank> = Vec::new();
+        for card in deck.draw(deck.remaining()) {
+            let kept = grid.iter().filter(|kept| kept.rank == card.rank).count();
+            if kept == 0 && ranks.len() < PAIRS {
+                ranks.push(card.rank);
+                grid.push(card);
+            } else if kept == 1 {
+                grid.push(card);
+            }
+        }
+        grid.shuffle(&mut rng);
+
+        Memory {
+            matched: vec![false; grid.len()],
+            grid,
+            attempts: 0,
+        }
+    }
+
+    /// Every card in the grid, row by row.
+    pub fn grid(&self) -> &[Card] {
+        &self.grid
+    }
+
+    /// Attempts made so far.
+    pub fn attempts(&self) -> usize {
+        self.attempts
+    }
+
+    /// Pairs found so far.
+    pub fn pairs_found(&self) -> usize {
+        self.matched.iter().filter(|&&matched| matched).count() / 2
+    }
+
+    /// Returns true once every pair is found.
+    pub fn is_over(&self) -> bool {
+        self.matched.iter().all(|&matched| matched)
+    }
+
+    /// Turns over the cards at `first` and `second`, after turning back any miss
+    /// left face up by the last attempt.
+    pub fn flip(&mut self, first: usize, second: usize) -> Result<Flip, MemoryError> {
+        for idx in [first, second].iter().copied() {
+            if idx >= self.grid.len() {
+                return Err(MemoryError::OutOfRange(idx));
+            }
+            if self.matched[idx] {
+                return Err(MemoryError::AlreadyMatched(idx));
+            }
+        }
+        if first == second {
+            return Err(MemoryError::SameCard);
+        }
+
+        for (card, &matched) in self.grid.iter_mut().zip(self.matched.iter()) {
+            if !matched {
+                card.state = CardState::Hidden;
+            }
+        }
+        self.grid[first].state = CardState::Visible;
+        self.grid[second].state = CardState::Visible;
+        self.attempts += 1;
+
+        if self.grid[first].rank == self.grid[second].rank {
+            self.matched[first] = true;
+            self.matched[second] = true;
+            Ok(Flip::Match(self.grid[first].rank))
+        } else {
+            Ok(Flip::Miss)
+        }
+    }
+}
+
+impl Default for Memory {
+    fn default() -> Memory {
+        Memory::new()
+    }
+}
+
+impl Display for Memory {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Pairs found {} of {}  Attempts {}",
+            self.pairs_found(),
+            self.grid.len() / 2,
+            self.attempts
+        )
+    }
+}
+
+impl GameMode for Memory {
+    fn setup(&mut self) -> Result<(), GameError> {
+        println!("Find every pair of cards with the same rank.");
+        Ok(())
+    }
+
+    async fn play_round(&mut self) -> Result<Flow, GameError> {
+        let render = RenderOptions::new().show_index(true);
+        display_grid(&self.grid, COLUMNS, &render)?;
+        println!("{}", self);
+        println!("Enter two cards to turn over (e.g. \"3 7\") or [q] to quit.");
+
+        let input = read_line().await?;
+        if input.is_empty() || wants_to_quit(&input) {
+            return Ok(Flow::Quit);
+        }
+        let picks: Vec<usize> = input
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter_map(|idx| idx.parse().ok())
+            .collect();
+        if picks.len() != 2 {
+            println!("Pick exactly two cards.");
+            return Ok(Flow::Continue);
+        }
+
+        match self.flip(picks[0], picks[1]) {
+            Ok(Flip::Match(rank)) => println!("A pair of {:?}s!\n", rank),
+            Ok(Flip::Miss) => println!("No match.\n"),
+            Err(err) => println!("Sorry, {}.", err),
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn is_over(&self) -> bool {
+        Memory::is_over(self)
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "Found all {} pairs in {} attempts.",
+            self.pairs_found(),
+            self.attempts
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Flip, Memory, MemoryError, PAIRS};
+    use crate::cards::CardState;
+
+    #[test]
+    /// Tests the grid holds two cards of each rank.
+    fn test_layout() {
+        let memory = Memory::with_seed(3);
+        assert_eq!(memory.grid().len(), PAIRS * 2);
+        for card in memory.grid() {
+            let same = memory
+                .grid()
+                .iter()
+                .filter(|other| other.rank == card.rank)
+                .count();
+            assert_eq!(same, 2);
+            assert_eq!(card.state, CardState::Hidden);
+        }
+    }
+
+    #[test]
+    /// Tests pairs stay face up, misses are turned back and attempts are counted.
+    fn test_flip() {
+        let mut memory = Memory::with_seed(3);
+        let rank = memory.grid()[0].rank;
+        let pair = (1..memory.grid().len())
+            .find(|&idx| memory.grid()[idx].rank == rank)
+            .unwrap();
+        let other = (1..memory.grid().len())
+            .find(|&idx| memory.grid()[idx].rank != rank)
+            .unwrap();
+
+        assert_eq!(memory.flip(0, 0), Err(MemoryError::SameCard));
+        assert_eq!(memory.flip(0, 99), Err(MemoryError::OutOfRange(99)));
+        assert_eq!(memory.flip(0, other), Ok(Flip::Miss));
+        assert_eq!(memory.grid()[other].state, CardState::Visible);
+        assert_eq!(memory.flip(0, pair), Ok(Flip::Match(rank)));
+        assert_eq!(memory.grid()[other].state, CardState::Hidden);
+        assert_eq!(
+            memory.flip(pair, other),
+            Err(MemoryError::AlreadyMatched(pair))
+        );
+        assert_eq!(memory.attempts(), 2);
+        assert_eq!(memory.pairs_found(), 1);
+        assert!(!memory.is_over());
+    }
+}
@@ -0,0 +1,321 @@
+//! This module provides Five Card Draw poker against a computer dealer.
+//!
+//! Each hand is kept as a hand history, which can be written out after every
+//! hand for poker tracking tools to import.
+
+use crate::cards::card_printer::display_hand;
+use crate::cards::{Card, CardState, Deck, Hand};
+use crate::error::GameError;
+use crate::input::read_line;
+use crate::poker::history::{Action, HandHistory, Variant};
+use crate::poker::{evaluate_hand, HandCategory, HandRank};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::path::PathBuf;
+
+/// Number of cards in a Five Card Draw hand.
+const HAND_SIZE: usize = 5;
+
+/// The player's seat and the dealer's.
+const PLAYER: usize = 0;
+const DEALER: usize = 1;
+
+/// Holds Five Card Draw game state.
+pub struct FiveCardDraw {
+    rng: StdRng,
+    deck: Deck,
+    player: Hand,
+    dealer: Hand,
+    wins: usize,
+    losses: usize,
+    ties: usize,
+    history: HandHistory,
+    /// Where each finished hand's history is written.
+    export: Option<PathBuf>,
+}
+
+impl FiveCardDraw {
+    /// Construct a new game with a randomly seeded shuffle.
+    pub fn new() -> FiveCardDraw {
+        FiveCardDraw::with_rng(StdRng::from_entropy())
+    }
+
+    /// Construct a new game whose shuffles are reproducible.
+    pub fn with_seed(seed: u64) -> FiveCardDraw {
+        FiveCardDraw::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(rng: StdRng) -> FiveCardDraw {
+        FiveCardDraw {
+            rng,
+            deck: Deck::new(),
+            player: Hand::new(),
+            dealer: Hand::new(),
+            wins: 0,
+            losses: 0,
+            ties: 0,
+            history: HandHistory::new(0, Variant::FiveCardDraw, 0, 2),
+            export: None,
+        }
+    }
+
+    /// Option to add the history of every hand played to the end of the file
+    /// at `path`.
+    pub fn hand_history<P: Into<PathBuf>>(mut self, path: P) -> FiveCardDraw {
+        self.export = Some(path.into());
+        self
+    }
+
+    pub fn player(&self) -> &Hand {
+        &self.player
+    }
+
+    pub fn dealer(&self) -> &Hand {
+        &self.dealer
+    }
+
+    /// The history of the hand being played, or the last one once it's over.
+    pub fn history(&self) -> &HandHistory {
+        &self.history
+    }
+
+    /// Shuffles a full deck and deals five cards each. The dealer's cards stay hidden.
+    pub fn deal(&mut self) {
+        self.player.drain();
+        self.dealer.drain();
+        self.deck.reset();
+        self.deck.shuffle(&mut self.rng);
+
+        for _ in 0..HAND_SIZE {
+            self.player
+                .push(draw_card(&mut self.deck, CardState::Visible));
+            self.dealer
+                .push(draw_card(&mut self.deck, CardState::Hidden));
+        }
+        self.history = HandHistory::starting_now(Variant::FiveCardDraw, 2);
+        self.history.seat(PLAYER, "You", 0);
+        self.history.seat(DEALER, "Dealer", 0);
+        self.history.dealt(PLAYER, self.player.cards());
+    }
+
+    /// Replaces the player's cards at `indices` with new cards from the deck.
+    /// Out of range and repeated indices are ignored.
+    pub fn redraw(&mut self, indices: &[usize]) {
+        let before = self.player.clone();
+        self.player = redraw_hand(&self.player, indices, &mut self.deck, CardState::Visible);
+        self.history.draw();
+        self.history.act(PLAYER, swapped(&before, &self.player));
+    }
+
+    /// The dealer keeps made hands of a straight or better, otherwise keeps any paired
+    /// cards, or just the highest card, and redraws the rest.
+    pub fn dealer_draw(&mut self) {
+        let indices = dealer_discards(&self.dealer);
+        let before = self.dealer.clone();
+        self.dealer = redraw_hand(&self.dealer, &indices, &mut self.deck, CardState::Hidden);
+        self.history.act(DEALER, swapped(&before, &self.dealer));
+    }
+
+    /// Reveals the dealer's hand and records who won.
+    pub fn showdown(&mut self) -> Ordering {
+        self.dealer.reveal_all();
+        let result = evaluate_hand(self.player.cards()).cmp(&evaluate_hand(self.dealer.cards()));
+        self.history.show(PLAYER, self.player.cards());
+        self.history.show(DEALER, self.dealer.cards());
+        match result {
+            Ordering::Greater => self.wins += 1,
+            Ordering::Less => self.losses += 1,
+            Ordering::Equal => self.ties += 1,
+        }
+        if result != Ordering::Less {
+            self.history.collect(PLAYER, 0);
+        }
+        if result != Ordering::Greater {
+            self.history.collect(DEALER, 0);
+        }
+        result
+    }
+
+    /// Plays hands in the terminal until the player quits.
+    pub async fn play(&mut self) -> Result<(), GameError> {
+        loop {
+            self.deal();
+
+            display_hand(self.player.cards(), true)?;
+            println!("You have {}.", evaluate_hand(self.player.cards()));
+            println!(
+                "Enter the cards to discard (e.g. \"0 3 4\") or press [Enter] to keep them all."
+            );
+
+            let input = read_line().await?;
+            if input.is_empty() {
+                return Ok(());
+            }
+            let indices: Vec<usize> = input
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter_map(|idx| idx.parse().ok())
+                .collect();
+
+            self.redraw(&indices);
+            self.dealer_draw();
+            let result = self.showdown();
+            if let Some(path) = &self.export {
+                self.history.export(path)?;
+            }
+
+            println!("Dealer");
+            display_hand(self.dealer.cards(), false)?;
+            println!("{}", describe(&evaluate_hand(self.dealer.cards())));
+            println!("You");
+            display_hand(self.player.cards(), false)?;
+            println!("{}", describe(&evaluate_hand(self.player.cards())));
+
+            match result {
+                Ordering::Greater => println!("You win!!!"),
+                Ordering::Less => println!("You lose!"),
+                Ordering::Equal => println!("Split pot."),
+            }
+            println!("{}\n", self);
+
+            println!("Press [Enter] to play again or [q] to quit.");
+            let input = read_line().await?;
+            if input.is_empty() || input.trim() == "q" {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl Default for FiveCardDraw {
+    fn default() -> FiveCardDraw {
+        FiveCardDraw::new()
+    }
+}
+
+impl Display for FiveCardDraw {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Won {}, lost {}, split {}.",
+            self.wins, self.losses, self.ties
+        )
+    }
+}
+
+fn describe(rank: &HandRank) -> String {
+    format!("  {}", rank)
+}
+
+fn draw_card(deck: &mut Deck, state: CardState) -> Card {
+    let mut card = deck
+        .draw(1)
+        .pop()
+        .expect("a deck has enough cards for two hands");
+    card.state = state;
+    card
+}
+
+fn redraw_hand(hand: &Hand, indices: &[usize], deck: &mut Deck, state: CardState) -> Hand {
+    let mut cards = hand.cards().to_vec();
+    for (idx, card) in cards.iter_mut().enumerate() {
+        if indices.contains(&idx) {
+            deck.discard(*card);
+            *card = draw_card(deck, state);
+        }
+    }
+    Hand::from_cards(cards)
+}
+
+/// The discard that turned `before` into `after`.
+fn swapped(before: &Hand, after: &Hand) -> Action {
+    let (discarded, drawn) = before
+        .iter()
+        .zip(after.iter())
+        .filter(|(old, new)| old != new)
+        .map(|(old, new)| (*old, *new))
+        .unzip();
+    Action::Discards { discarded, drawn }
+}
+
+fn dealer_discards(hand: &Hand) -> Vec<usize> {
+    let cards = hand.cards();
+    if evaluate_hand(cards).category >= HandCategory::Straight {
+        return Vec::new();
+    }
+
+    let paired: Vec<usize> = (0..cards.len())
+        .filter(|&idx| {
+            cards
+                .iter()
+                .filter(|card| card.rank == cards[idx].rank)
+                .count()
+                > 1
+        })
+        .collect();
+    let keep = if paired.is_empty() {
+        (0..cards.len())
+            .max_by_key(|&idx| cards[idx].rank)
+            .into_iter()
+            .collect()
+    } else {
+        paired
+    };
+
+    (0..cards.len()).filter(|idx| !keep.contains(idx)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dealer_discards, FiveCardDraw};
+    use crate::cards::{Card, CardState, Hand, Rank, Suit};
+
+    #[test]
+    /// Tests redrawing replaces only the chosen cards.
+    fn test_redraw() {
+        let mut game = FiveCardDraw::with_seed(9);
+        game.deal();
+        let before = game.player().cards().to_vec();
+
+        game.redraw(&[1, 3, 7]);
+        let after = game.player().cards();
+
+        assert_eq!(after.len(), 5);
+        assert_eq!(after[0], before[0]);
+        assert_ne!(after[1], before[1]);
+        assert_eq!(after[2], before[2]);
+        assert_ne!(after[3], before[3]);
+        assert!(after.iter().all(|card| card.state == CardState::Visible));
+    }
+
+    #[test]
+    /// Tests the dealer keeps pairs and made hands.
+    fn test_dealer_discards() {
+        let pair = Hand::from_cards(vec![
+            Card::new(Suit::Spades, Rank::Two),
+            Card::new(Suit::Hearts, Rank::Nine),
+            Card::new(Suit::Clubs, Rank::Two),
+            Card::new(Suit::Diamonds, Rank::King),
+            Card::new(Suit::Spades, Rank::Five),
+        ]);
+        assert_eq!(dealer_discards(&pair), vec![1, 3, 4]);
+
+        let nothing = Hand::from_cards(vec![
+            Card::new(Suit::Spades, Rank::Two),
+            Card::new(Suit::Hearts, Rank::Nine),
+            Card::new(Suit::Clubs, Rank::Ace),
+            Card::new(Suit::Diamonds, Rank::King),
+            Card::new(Suit::Spades, Rank::Five),
+        ]);
+        assert_eq!(dealer_discards(&nothing), vec![0, 1, 3, 4]);
+
+        let flush = Hand::from_cards(
+            [Rank::Two, Rank::Four, Rank::Six, Rank::Eight, Rank::Ten]
+                .iter()
+                .map(|rank| Card::new(Suit::Hearts, *rank))
+                .collect(),
+        );
+        assert!(dealer_discards(&flush).is_empty());
+    }
+}
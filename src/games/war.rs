@@ -0,0 +1,257 @@
+//! This module provides the card game War between two players.
+
+use crate::cards::card_printer::display_hand;
+use crate::cards::{Card, CardState, Deck, Hand};
+use crate::engine::{self};
+use crate::error::GameError;
+use crate::game::Speed;
+use crate::input::read_line;
+use crate::rules::Ruleset;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::time;
+
+/// Number of cards each player places face down when a war is declared.
+const WAR_FACE_DOWN: usize = 3;
+
+/// Games are called after this many rounds, since War can go on forever.
+const MAX_ROUNDS: usize = 5000;
+
+/// Result of a single round of War.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Round {
+    /// Every card placed on the table during the round, in the order it was played.
+    pub table: Hand,
+    /// Index of the player who took the cards.
+    pub winner: usize,
+    /// Number of wars fought before the round was decided.
+    pub wars: usize,
+}
+
+/// Holds War game state.
+pub struct War {
+    piles: [Deck; 2],
+    rounds: usize,
+    rules: Ruleset,
+    speed: Speed,
+}
+
+impl War {
+    /// Construct a new game with a randomly seeded shuffle.
+    pub fn new() -> War {
+        War::with_rng(StdRng::from_entropy())
+    }
+
+    /// Construct a new game whose shuffle is reproducible.
+    pub fn with_seed(seed: u64) -> War {
+        War::with_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(mut rng: StdRng) -> War {
+        let mut deck = Deck::new();
+        deck.shuffle(&mut rng);
+
+        let mut piles = [Deck::from_cards(Vec::new()), Deck::from_cards(Vec::new())];
+        let mut player = 0;
+        while let Some(card) = deck.draw(1).pop() {
+            piles[player].place_bottom(card);
+            player = 1 - player;
+        }
+        War {
+            piles,
+            rounds: 0,
+            rules: Ruleset::new(),
+            speed: Speed::Normal,
+        }
+    }
+
+    /// Option to change how the face up cards compare. By default aces are high
+    /// and matching ranks start a war, but with a suit tiebreak only identical
+    /// cards do.
+    pub fn rules(mut self, rules: Ruleset) -> War {
+        self.rules = rules;
+        self
+    }
+
+    /// Option to pause between automatic rounds for longer or shorter.
+    pub fn speed(mut self, speed: Speed) -> War {
+        self.speed = speed;
+        self
+    }
+
+    /// Number of cards left in a player's pile.
+    pub fn pile_size(&self, player: usize) -> usize {
+        self.piles[player].remaining()
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    /// Returns true once a player has run out of cards or the round limit is hit.
+    pub fn is_over(&self) -> bool {
+        self.piles.iter().any(|pile| pile.is_empty()) || self.rounds >= MAX_ROUNDS
+    }
+
+    /// The player holding the most cards, or None if they are level.
+    pub fn winner(&self) -> Option<usize> {
+        match self.pile_size(0).cmp(&self.pile_size(1)) {
+            std::cmp::Ordering::Greater => Some(0),
+            std::cmp::Ordering::Less => Some(1),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// Both players turn over their top card and the higher rank takes the table.
+    /// Matching ranks start a war: three cards go face down and the next face up
+    /// cards decide it. A player who can't finish a war loses the round.
+    pub fn play_round(&mut self) -> Round {
+        let mut table = Hand::new();
+        let mut wars = 0;
+
+        let winner = loop {
+            let (first, second) = match (self.flip(0), self.flip(1)) {
+                (Some(first), Some(second)) => (first, second),
+                (Some(first), None) => {
+                    table.push(first);
+                    break 0;
+                }
+                (None, Some(second)) => {
+                    table.push(second);
+                    break 1;
+                }
+                (None, None) => break 0,
+            };
+            table.push(first);
+            table.push(second);
+
+            match self.rules.compare(&first, &second) {
+                Ordering::Greater => break 0,
+                Ordering::Less => break 1,
+                Ordering::Equal => {}
+            }
+
+            wars += 1;
+            for player in 0..2 {
+                for card in self.piles[player].draw(WAR_FACE_DOWN) {
+                    table.push(card);
+                }
+            }
+        };
+
+        for mut card in table.cards().to_vec() {
+            card.state = CardState::Hidden;
+            self.piles[winner].place_bottom(card);
+        }
+        self.rounds += 1;
+
+        Round {
+            table,
+            winner,
+            wars,
+        }
+    }
+
+    fn flip(&mut self, player: usize) -> Option<Card> {
+        self.piles[player].draw(1).pop().map(|mut card| {
+            card.state = CardState::Visible;
+            card
+        })
+    }
+
+    /// Plays the game in the terminal. Interactive games wait for [Enter] between
+    /// rounds, otherwise rounds are played automatically.
+    pub async fn play(&mut self, mut interactive: bool) -> Result<(), GameError> {
+        let sleep_time = self.speed.scale(time::Duration::from_millis(250));
+
+        while !self.is_over() {
+            if interactive {
+                println!("Press [Enter] to flip or [a] to play automatically.");
+                let input = read_line().await?;
+                if input.is_empty() {
+                    return Ok(());
+                }
+                if input.trim() == "a" {
+                    interactive = false;
+                }
+            }
+
+            let round = self.play_round();
+            display_hand(round.table.cards(), false)?;
+            if round.wars > 0 {
+                println!("War! x{}", round.wars);
+            }
+            println!(
+                "Player {} takes {} cards.",
+                round.winner + 1,
+                round.table.len()
+            );
+            println!("{}\n", self);
+
+            if !interactive {
+                engine::pause(sleep_time).await?;
+            }
+        }
+
+        match self.winner() {
+            Some(player) => println!("Player {} wins the war!", player + 1),
+            None => println!("It's a draw."),
+        }
+        Ok(())
+    }
+}
+
+impl Default for War {
+    fn default() -> War {
+        War::new()
+    }
+}
+
+impl Display for War {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "Round {}: Player 1 has {} cards, Player 2 has {} cards.",
+            self.rounds,
+            self.pile_size(0),
+            self.pile_size(1)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::War;
+
+    #[test]
+    /// Tests the deck is split evenly and no cards are lost while playing.
+    fn test_cards_are_conserved() {
+        let mut game = War::with_seed(11);
+        assert_eq!(game.pile_size(0), 26);
+        assert_eq!(game.pile_size(1), 26);
+
+        while !game.is_over() {
+            let round = game.play_round();
+            assert!(round.table.len() >= 2);
+            assert_eq!(game.pile_size(0) + game.pile_size(1), 52);
+        }
+        assert!(game.rounds() > 0);
+    }
+
+    #[test]
+    /// Tests the higher face up card takes the round.
+    fn test_higher_rank_wins() {
+        let mut game = War::with_seed(5);
+        for _ in 0..50 {
+            let round = game.play_round();
+            if round.wars == 0 {
+                let cards = round.table.cards();
+                let expected = if cards[0].rank > cards[1].rank { 0 } else { 1 };
+                assert_eq!(round.winner, expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! This module provides a record of every round played in a session.
+
+use crate::cards::Card;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One round of the high card game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundRecord {
+    pub cards: Vec<Card>,
+    pub choice: usize,
+    pub winner: usize,
+    pub won: bool,
+    /// Seconds since the Unix epoch when the round was played.
+    pub timestamp: u64,
+    /// Milliseconds the player took to pick, if the pick was timed.
+    #[serde(default)]
+    pub decision_ms: Option<u64>,
+}
+
+/// Seconds since the Unix epoch.
+#[cfg(not(target_arch = "wasm32"))]
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// Seconds since the Unix epoch, from the page's clock since a bare wasm32
+/// target has none of its own.
+#[cfg(target_arch = "wasm32")]
+fn now() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+/// Every round played so far, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct History {
+    rounds: Vec<RoundRecord>,
+}
+
+impl History {
+    /// Construct an empty history.
+    pub fn new() -> History {
+        History::default()
+    }
+
+    /// Records a round played now, picked after `decision` if it was timed.
+    pub fn record(
+        &mut self,
+        cards: &[Card],
+        choice: usize,
+        winner: usize,
+        decision: Option<Duration>,
+    ) {
+        let timestamp = now();
+        self.rounds.push(RoundRecord {
+            cards: cards.to_vec(),
+            choice,
+            winner,
+            won: choice == winner,
+            timestamp,
+            decision_ms: decision.map(|took| took.as_millis() as u64),
+        });
+    }
+
+    /// Forgets every round after the first `len`.
+    pub fn truncate(&mut self, len: usize) {
+        self.rounds.truncate(len);
+    }
+
+    pub fn rounds(&self) -> &[RoundRecord] {
+        &self.rounds
+    }
+
+    pub fn len(&self) -> usize {
+        self.rounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rounds.is_empty()
+    }
+
+    /// Percentage of rounds won, or zero before any are played.
+    pub fn accuracy(&self) -> f64 {
+        if self.rounds.is_empty() {
+            return 0.0;
+        }
+        let won = self.rounds.iter().filter(|round| round.won).count();
+        won as f64 * 100.0 / self.rounds.len() as f64
+    }
+
+    /// The mean time taken over every timed pick, or None if none were timed.
+    pub fn average_decision(&self) -> Option<Duration> {
+        let times: Vec<u64> = self
+            .rounds
+            .iter()
+            .filter_map(|round| round.decision_ms)
+            .collect();
+        if times.is_empty() {
+            return None;
+        }
+        Some(Duration::from_millis(
+            times.iter().sum::<u64>() / times.len() as u64,
+        ))
+    }
+
+    /// The quickest timed pick, or None if none were timed.
+    pub fn fastest_decision(&self) -> Option<Duration> {
+        self.rounds
+            .iter()
+            .filter_map(|round| round.decision_ms)
+            .min()
+            .map(Duration::from_millis)
+    }
+}
+
+/// Time of day in UTC as `HH:MM:SS`.
+fn time_of_day(timestamp: u64) -> String {
+    let seconds = timestamp % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Milliseconds as seconds to one decimal place, as in `1.8s`.
+fn seconds(millis: u64) -> String {
+    format!("{:.1}s", millis as f64 / 1000.0)
+}
+
+/// Summary table of every round followed by the accuracy, and the average and
+/// fastest pick once any were timed.
+impl Display for History {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        writeln!(
+            formatter,
+            "{:>5}  {:<8}  {:<19}  {:<6}  {:<9}  {:<6}  Result",
+            "Round", "Time", "Cards", "Choice", "High card", "Took"
+        )?;
+        for (number, round) in self.rounds.iter().enumerate() {
+            let cards: Vec<String> = round.cards.iter().map(Card::to_string).collect();
+            let took = round.decision_ms.map_or_else(|| "-".to_string(), seconds);
+            writeln!(
+                formatter,
+                "{:>5}  {:<8}  {:<19}  {:<6}  {:<9}  {:<6}  {}",
+                number + 1,
+                time_of_day(round.timestamp),
+                cards.join(" "),
+                round.cards[round.choice],
+                round.cards[round.winner],
+                took,
+                if round.won { "Win" } else { "Loss" },
+            )?;
+        }
+        write!(
+            formatter,
+            "Accuracy {:.1}% over {} rounds",
+            self.accuracy(),
+            self.rounds.len()
+        )?;
+        if let (Some(average), Some(fastest)) = (self.average_decision(), self.fastest_decision()) {
+            write!(
+                formatter,
+                "\nAverage pick {}, fastest {}",
+                seconds(average.as_millis() as u64),
+                seconds(fastest.as_millis() as u64)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::History;
+    use crate::cards::{Card, Rank, Suit};
+    use std::time::Duration;
+
+    #[test]
+    /// Tests rounds are tabled with the accuracy underneath.
+    fn test_summary() {
+        let cards = [
+            Card::new(Suit::Hearts, Rank::Ten),
+            Card::new(Suit::Spades, Rank::Queen),
+            Card::new(Suit::Clubs, Rank::Two),
+        ];
+        let mut history = History::new();
+        history.record(&cards, 0, 0, Some(Duration::from_millis(1_840)));
+        history.record(&cards, 1, 0, None);
+        history.record(&cards, 2, 0, Some(Duration::from_millis(960)));
+        for round in history.rounds.iter_mut() {
+            round.timestamp = 45_296;
+        }
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(
+            history.average_decision(),
+            Some(Duration::from_millis(1_400))
+        );
+        assert_eq!(history.fastest_decision(), Some(Duration::from_millis(960)));
+        insta::assert_snapshot!(history.to_string());
+    }
+}
@@ -0,0 +1,333 @@
+//! This module provides keyboard and mouse input that gives up after a timeout.
+//!
+//! Terminal input goes through crossterm, which reads single key presses and
+//! waits on them with a timeout on unix and the Windows console alike. The
+//! terminal is put in raw mode while waiting so keys arrive straight away, and
+//! stays in it while `catch_interrupt` is on. Piped input isn't a terminal, so it's
+//! read a character at a time as it comes instead.
+//!
+//! Mouse events only arrive once crossterm's `EnableMouseCapture` has been sent.
+//! Resizes are only reported after `watch_resize`. Likewise Ctrl+C is only
+//! reported as `TermEvent::Interrupt` instead of ending the program while
+//! `catch_interrupt` is on.
+//!
+//! Reads are async. Each one blocks one of tokio's blocking threads instead of
+//! the runtime, so timers and other tasks keep running while the player thinks.
+
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
+use crossterm::terminal;
+use std::io::{self, IsTerminal, Read};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::task;
+
+/// Exit status for a program ended by Ctrl+C, as a shell reports `SIGINT`.
+const INTERRUPTED_STATUS: i32 = 130;
+
+/// Set while resizes are reported.
+static WATCH_RESIZE: AtomicBool = AtomicBool::new(false);
+
+/// Set while Ctrl+C is reported rather than ending the program.
+static CATCH_INTERRUPT: AtomicBool = AtomicBool::new(false);
+
+/// Set when a wait was ended by Ctrl+C, until the next read reports it.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// A key press or something done with the mouse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TermEvent {
+    Key(KeyCode),
+    Mouse(Mouse),
+    /// The terminal changed size.
+    Resize,
+    /// Ctrl+C was pressed.
+    Interrupt,
+}
+
+/// What the mouse did.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MouseKind {
+    /// The left button was pressed.
+    Click,
+    /// The pointer moved, with or without a button held.
+    Move,
+    /// Any other button or the wheel.
+    Other,
+}
+
+/// A mouse event at a cell on the screen, counted from zero at the top left.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Mouse {
+    pub kind: MouseKind,
+    pub column: usize,
+    pub row: usize,
+}
+
+/// Waits up to `timeout` for a key press. Returns None if nothing was pressed.
+/// An interrupt also ends the wait, and is left for the next
+/// `read_event_timeout` to report.
+pub async fn read_key_timeout(timeout: Duration) -> io::Result<Option<KeyCode>> {
+    match read_event_timeout(Some(timeout)).await? {
+        Some(TermEvent::Key(key)) => Ok(Some(key)),
+        Some(TermEvent::Interrupt) => {
+            INTERRUPTED.store(true, Ordering::SeqCst);
+            Ok(None)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Reports the terminal changing size as `TermEvent::Resize` from now on.
+pub fn watch_resize() {
+    WATCH_RESIZE.store(true, Ordering::SeqCst);
+}
+
+/// Reports Ctrl+C as `TermEvent::Interrupt` while `catch` is true, and goes back
+/// to ending the program on it once it's false. The terminal stays in raw mode
+/// while it's caught, so a frontend drawing meanwhile ends its lines with
+/// `\r\n`.
+pub fn catch_interrupt(catch: bool) {
+    CATCH_INTERRUPT.store(catch, Ordering::SeqCst);
+    if !io::stdin().is_terminal() {
+        return;
+    }
+    let _ = if catch {
+        terminal::enable_raw_mode()
+    } else {
+        terminal::disable_raw_mode()
+    };
+}
+
+/// Waits up to `timeout`, or for as long as it takes given None, for a key press
+/// or mouse event. Returns None if nothing happened in time.
+pub async fn read_event_timeout(timeout: Option<Duration>) -> io::Result<Option<TermEvent>> {
+    blocking(move || read_event(timeout)).await
+}
+
+/// Waits for a key press, blocking the thread. For menus drawn before the game
+/// loop starts.
+pub fn read_key() -> io::Result<KeyCode> {
+    loop {
+        match read_event(None)? {
+            Some(TermEvent::Key(key)) => return Ok(key),
+            Some(TermEvent::Interrupt) => return Ok(KeyCode::Esc),
+            _ => {}
+        }
+    }
+}
+
+/// Reads a line typed at stdin, or an empty string once input has run out.
+pub async fn read_line() -> io::Result<String> {
+    blocking(|| {
+        let mut line = String::new();
+        io::stdin().read_line(&mut line)?;
+        Ok(line)
+    })
+    .await
+}
+
+/// Runs `read` on one of tokio's blocking threads and waits for it.
+async fn blocking<T, F>(read: F) -> io::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+{
+    task::spawn_blocking(read).await.map_err(io::Error::other)?
+}
+
+/// Turns an event from crossterm into one the games handle, or None for one
+/// they don't, like a key being let go.
+pub fn term_event(event: Event) -> Option<TermEvent> {
+    match event {
+        Event::Key(KeyEvent {
+            kind: KeyEventKind::Release,
+            ..
+        }) => None,
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('c'),
+            modifiers,
+            ..
+        }) if modifiers.contains(KeyModifiers::CONTROL) => Some(TermEvent::Interrupt),
+        Event::Key(KeyEvent { code, .. }) => Some(TermEvent::Key(code)),
+        Event::Mouse(MouseEvent {
+            kind, column, row, ..
+        }) => {
+            let kind = match kind {
+                MouseEventKind::Down(MouseButton::Left) => MouseKind::Click,
+                MouseEventKind::Moved | MouseEventKind::Drag(_) => MouseKind::Move,
+                _ => MouseKind::Other,
+            };
+            Some(TermEvent::Mouse(Mouse {
+                kind,
+                column: column as usize,
+                row: row as usize,
+            }))
+        }
+        Event::Resize(..) => Some(TermEvent::Resize),
+        _ => None,
+    }
+}
+
+/// Puts the terminal in raw mode until dropped, unless it already was.
+struct RawMode {
+    enabled: bool,
+}
+
+impl RawMode {
+    fn enable() -> io::Result<RawMode> {
+        let enabled = !terminal::is_raw_mode_enabled()?;
+        if enabled {
+            terminal::enable_raw_mode()?;
+        }
+        Ok(RawMode { enabled })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if self.enabled {
+            let _ = terminal::disable_raw_mode();
+        }
+    }
+}
+
+fn read_event(timeout: Option<Duration>) -> io::Result<Option<TermEvent>> {
+    if INTERRUPTED.swap(false, Ordering::SeqCst) {
+        return Ok(Some(TermEvent::Interrupt));
+    }
+    if !io::stdin().is_terminal() {
+        return read_piped().map(Some);
+    }
+    let raw = RawMode::enable()?;
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        if let Some(deadline) = deadline {
+            if !event::poll(deadline.saturating_duration_since(Instant::now()))? {
+                return Ok(None);
+            }
+        }
+        match term_event(event::read()?) {
+            Some(TermEvent::Resize) if !WATCH_RESIZE.load(Ordering::SeqCst) => {}
+            Some(TermEvent::Interrupt) if !CATCH_INTERRUPT.load(Ordering::SeqCst) => {
+                // Raw mode turned Ctrl+C into a key, so end the program as it would have.
+                drop(raw);
+                process::exit(INTERRUPTED_STATUS);
+            }
+            Some(event) => return Ok(Some(event)),
+            None => {}
+        }
+    }
+}
+
+/// Reads a character of piped input as a key press, with a line break as
+/// [Enter]. A pipe has its input ready or has run out, so there's no waiting.
+fn read_piped() -> io::Result<TermEvent> {
+    let mut stdin = io::stdin().lock();
+    let mut bytes = [0; 4];
+    stdin.read_exact(&mut bytes[..1])?;
+    let len = match bytes[0] {
+        0xf0.. => 4,
+        0xe0.. => 3,
+        0xc0.. => 2,
+        _ => 1,
+    };
+    stdin.read_exact(&mut bytes[1..len])?;
+    let key = match std::str::from_utf8(&bytes[..len]).map(|s| s.chars().next()) {
+        Ok(Some('\n')) | Ok(Some('\r')) => KeyCode::Enter,
+        Ok(Some(c)) => KeyCode::Char(c),
+        _ => KeyCode::Null,
+    };
+    Ok(TermEvent::Key(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{term_event, Mouse, MouseKind, TermEvent};
+    use crossterm::event::{
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+        MouseEventKind,
+    };
+
+    #[test]
+    /// Tests key presses are passed on, Ctrl+C becomes an interrupt and keys
+    /// being let go are dropped.
+    fn test_term_event_key() {
+        let key = |code, modifiers| Event::Key(KeyEvent::new(code, modifiers));
+        assert_eq!(
+            term_event(key(KeyCode::Enter, KeyModifiers::NONE)),
+            Some(TermEvent::Key(KeyCode::Enter))
+        );
+        assert_eq!(
+            term_event(key(KeyCode::Char('♠'), KeyModifiers::NONE)),
+            Some(TermEvent::Key(KeyCode::Char('♠')))
+        );
+        assert_eq!(
+            term_event(key(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(TermEvent::Interrupt)
+        );
+        let release =
+            KeyEvent::new_with_kind(KeyCode::Left, KeyModifiers::NONE, KeyEventKind::Release);
+        assert_eq!(term_event(Event::Key(release)), None);
+        assert_eq!(term_event(Event::FocusGained), None);
+        assert_eq!(term_event(Event::Resize(80, 24)), Some(TermEvent::Resize));
+    }
+
+    #[test]
+    /// Tests mouse events keep their position and are sorted into clicks, moves
+    /// and everything else.
+    fn test_term_event_mouse() {
+        let mouse = |kind, column, row| {
+            term_event(Event::Mouse(MouseEvent {
+                kind,
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            }))
+        };
+        let expected = |kind, column, row| Some(TermEvent::Mouse(Mouse { kind, column, row }));
+        assert_eq!(
+            mouse(MouseEventKind::Down(MouseButton::Left), 11, 8),
+            expected(MouseKind::Click, 11, 8)
+        );
+        assert_eq!(
+            mouse(MouseEventKind::Moved, 2, 0),
+            expected(MouseKind::Move, 2, 0)
+        );
+        assert_eq!(
+            mouse(MouseEventKind::Drag(MouseButton::Left), 2, 0),
+            expected(MouseKind::Move, 2, 0)
+        );
+        assert_eq!(
+            mouse(MouseEventKind::Up(MouseButton::Left), 11, 8),
+            expected(MouseKind::Other, 11, 8)
+        );
+        assert_eq!(
+            mouse(MouseEventKind::ScrollDown, 0, 0),
+            expected(MouseKind::Other, 0, 0)
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    /// Tests a wait on the Windows console gives up once its timeout passes.
+    /// Needs the tests run from a console with nothing pressed, as CI doesn't
+    /// give them one.
+    fn test_read_event_timeout_console() {
+        use std::io::{self, IsTerminal};
+        use std::time::{Duration, Instant};
+
+        if !io::stdin().is_terminal() {
+            return;
+        }
+        let started = Instant::now();
+        assert_eq!(
+            super::read_event(Some(Duration::from_millis(200))).unwrap(),
+            None
+        );
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
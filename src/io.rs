@@ -0,0 +1,253 @@
+//! This module provides the input source and output sink a game plays through.
+//!
+//! A game loop reads typed lines and key presses from an `Io` and writes
+//! everything the player sees to it, rather than going to stdin and stdout
+//! itself. `Terminal` is the real thing. `Script` answers from a fixed list and
+//! keeps everything written to it, so a test can play a game through and check
+//! what was shown.
+//!
+//! Reading and pausing are async, so a game waiting on the player or the clock
+//! leaves the runtime free. They return a boxed `Wait` so games can hold any
+//! `Io` as a `dyn Io`.
+
+use crate::cards::card_printer::{columns_for, write_grid, ColorMode, RenderOptions};
+use crate::cards::Card;
+use crate::engine;
+use crate::input::{self, read_key_timeout};
+use console::Term;
+use crossterm::event::KeyCode;
+use std::collections::VecDeque;
+use std::future::{self, Future};
+use std::io::{self, Write};
+use std::pin::Pin;
+use std::time::Duration;
+
+/// Something an `Io` is waiting on: the player, or the clock.
+pub type Wait<'a, T> = Pin<Box<dyn Future<Output = io::Result<T>> + 'a>>;
+
+/// Where a game gets its input and sends its output.
+pub trait Io {
+    /// Reads a line of input. Callers trim it.
+    fn read_line(&mut self) -> Wait<'_, String>;
+
+    /// Waits up to `timeout` for a key press. Returns None if nothing was pressed.
+    fn read_key(&mut self, timeout: Duration) -> Wait<'_, Option<KeyCode>>;
+
+    /// Where text for the player is written.
+    fn out(&mut self) -> &mut dyn Write;
+
+    /// Whether the player is watching on a live terminal, where hands can be
+    /// animated and drawn to fit the screen.
+    fn is_terminal(&self) -> bool;
+
+    /// Waits `duration` between steps of a round.
+    fn pause(&mut self, duration: Duration) -> Wait<'_, ()>;
+
+    /// Draws `hand` with `options`, wrapping it to the width of a terminal. Only
+    /// a terminal gets colour in `ColorMode::Auto`.
+    fn show_hand(&mut self, hand: &[Card], options: &RenderOptions) -> io::Result<()> {
+        let width = if self.is_terminal() {
+            Term::stdout()
+                .size_checked()
+                .map(|(_, width)| width as usize)
+        } else {
+            None
+        };
+        let color = match options.color {
+            ColorMode::Auto if width.is_none() => ColorMode::Never,
+            color => color.resolve(),
+        };
+        let mut options = options.clone().color(color);
+        if width.is_some() {
+            options.suits = options.suits.resolve();
+        }
+        let columns = width.map_or(hand.len(), |width| columns_for(width, &options));
+
+        let mut text = String::new();
+        write_grid(&mut text, hand, columns.min(hand.len()), &options)
+            .expect("writing to a String cannot fail");
+        let out = self.out();
+        out.write_all(text.as_bytes())?;
+        out.flush()
+    }
+}
+
+impl<T: Io + ?Sized> Io for &mut T {
+    fn read_line(&mut self) -> Wait<'_, String> {
+        (**self).read_line()
+    }
+
+    fn read_key(&mut self, timeout: Duration) -> Wait<'_, Option<KeyCode>> {
+        (**self).read_key(timeout)
+    }
+
+    fn out(&mut self) -> &mut dyn Write {
+        (**self).out()
+    }
+
+    fn is_terminal(&self) -> bool {
+        (**self).is_terminal()
+    }
+
+    fn pause(&mut self, duration: Duration) -> Wait<'_, ()> {
+        (**self).pause(duration)
+    }
+}
+
+/// Stdin and stdout.
+pub struct Terminal {
+    stdout: io::Stdout,
+}
+
+impl Terminal {
+    pub fn new() -> Terminal {
+        Terminal {
+            stdout: io::stdout(),
+        }
+    }
+}
+
+impl Default for Terminal {
+    fn default() -> Terminal {
+        Terminal::new()
+    }
+}
+
+impl Io for Terminal {
+    fn read_line(&mut self) -> Wait<'_, String> {
+        Box::pin(input::read_line())
+    }
+
+    fn read_key(&mut self, timeout: Duration) -> Wait<'_, Option<KeyCode>> {
+        Box::pin(read_key_timeout(timeout))
+    }
+
+    fn out(&mut self) -> &mut dyn Write {
+        &mut self.stdout
+    }
+
+    fn is_terminal(&self) -> bool {
+        Term::stdout().features().is_attended()
+    }
+
+    /// Waits as `engine::pause` does, so a key press skips ahead.
+    fn pause(&mut self, duration: Duration) -> Wait<'_, ()> {
+        Box::pin(engine::pause(duration))
+    }
+}
+
+/// Scripted input for tests. Each line or key press read takes the next answer,
+/// and everything written is kept to be checked afterwards. Pauses are skipped.
+/// Once the answers run out a line reads as empty, as stdin does at the end of
+/// a file, and a key press is an error.
+#[derive(Debug, Clone, Default)]
+pub struct Script {
+    answers: VecDeque<String>,
+    output: Vec<u8>,
+}
+
+impl Script {
+    /// A script giving `answers` in order. A key press takes the first character
+    /// of an answer, or [Enter] for an empty one.
+    pub fn new<I, S>(answers: I) -> Script
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Script {
+            answers: answers.into_iter().map(Into::into).collect(),
+            output: Vec::new(),
+        }
+    }
+
+    /// Everything written so far.
+    pub fn output(&self) -> String {
+        String::from_utf8_lossy(&self.output).into_owned()
+    }
+
+    /// How many answers haven't been read yet.
+    pub fn remaining(&self) -> usize {
+        self.answers.len()
+    }
+
+    fn next(&mut self) -> io::Result<String> {
+        self.answers.pop_front().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the script ran out of answers",
+            )
+        })
+    }
+}
+
+impl Io for Script {
+    fn read_line(&mut self) -> Wait<'_, String> {
+        Box::pin(future::ready(Ok(self
+            .answers
+            .pop_front()
+            .unwrap_or_default())))
+    }
+
+    fn read_key(&mut self, _timeout: Duration) -> Wait<'_, Option<KeyCode>> {
+        let key = self.next().map(|answer| match answer.chars().next() {
+            Some(c) => Some(KeyCode::Char(c)),
+            None => Some(KeyCode::Enter),
+        });
+        Box::pin(future::ready(key))
+    }
+
+    fn out(&mut self) -> &mut dyn Write {
+        &mut self.output
+    }
+
+    fn is_terminal(&self) -> bool {
+        false
+    }
+
+    fn pause(&mut self, _duration: Duration) -> Wait<'_, ()> {
+        Box::pin(future::ready(Ok(())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Io, Script};
+    use crate::cards::card_printer::RenderOptions;
+    use crate::cards::{Card, Rank, Suit};
+    use crossterm::event::KeyCode;
+    use std::io::ErrorKind;
+    use std::time::Duration;
+
+    #[tokio::test]
+    /// Tests a script answers in order, keeps its output and runs out cleanly.
+    async fn test_script() {
+        let mut script = Script::new(vec!["2", "", "q"]);
+        assert_eq!(script.read_line().await.unwrap(), "2");
+        assert_eq!(
+            script.read_key(Duration::from_secs(1)).await.unwrap(),
+            Some(KeyCode::Enter)
+        );
+        assert_eq!(
+            script.read_key(Duration::from_secs(1)).await.unwrap(),
+            Some(KeyCode::Char('q'))
+        );
+        assert_eq!(script.remaining(), 0);
+        assert_eq!(script.read_line().await.unwrap(), "");
+        assert_eq!(
+            script
+                .read_key(Duration::from_secs(1))
+                .await
+                .unwrap_err()
+                .kind(),
+            ErrorKind::UnexpectedEof
+        );
+
+        writeln!(script.out(), "Find the High card.").unwrap();
+        let hand = [Card::new(Suit::Spades, Rank::Ace)];
+        script.show_hand(&hand, &RenderOptions::new()).unwrap();
+        let output = script.output();
+        assert!(output.starts_with("Find the High card.\n"));
+        assert_eq!(output.lines().count(), 1 + 7);
+        assert!(!output.contains('\u{1b}'));
+    }
+}
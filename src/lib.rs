@@ -0,0 +1,53 @@
+//! Super Card Game
+//!
+//! Cards, decks and hands, the high card game engine, and terminal rendering
+//! for building card games.
+
+pub mod analysis;
+#[cfg(feature = "audio")]
+pub mod audio;
+pub mod bank;
+pub mod cards;
+#[cfg(feature = "terminal")]
+pub mod cli;
+#[cfg(feature = "terminal")]
+pub mod config;
+pub mod daily;
+pub mod debug;
+#[cfg(feature = "terminal")]
+pub mod engine;
+pub mod error;
+#[cfg(feature = "events")]
+pub mod events;
+pub mod game;
+#[cfg(feature = "terminal")]
+pub mod games;
+pub mod history;
+#[cfg(feature = "terminal")]
+pub mod input;
+#[cfg(feature = "terminal")]
+pub mod io;
+pub mod locale;
+#[cfg(feature = "terminal")]
+pub mod menu;
+#[cfg(feature = "net")]
+pub mod net;
+pub mod opponent;
+pub mod poker;
+pub mod profile;
+pub mod rating;
+pub mod replay;
+pub mod rules;
+pub mod scoring;
+#[cfg(feature = "terminal")]
+pub mod session;
+#[cfg(feature = "terminal")]
+pub mod simulator;
+pub mod stats;
+pub mod strategy;
+#[cfg(feature = "color")]
+pub mod style;
+pub mod tournament;
+#[cfg(feature = "terminal")]
+pub mod tui;
+pub mod web;
@@ -0,0 +1,436 @@
+//! This module provides the high card game's text in each supported language.
+//!
+//! Every line the game shows is a `Message`, and each `Locale` has a catalog
+//! giving the text for every message. Text with `{}` placeholders is filled in
+//! by `Locale::format`, in order. A locale is picked by name, as in `es`, or
+//! from the environment the same way other programs pick one.
+
+use std::env;
+use std::fmt::Display;
+
+/// A language the game can be played in.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    English,
+    Spanish,
+}
+
+/// Each line of the high card game's text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Message {
+    /// Takes the seed.
+    Seed,
+    /// Takes the difficulty.
+    Difficulty,
+    Dealing,
+    FindHighCard,
+    FasterPicks,
+    /// Takes the number of seconds.
+    TimedPrompt,
+    /// Takes the number of seconds.
+    SecondsLeft,
+    TimesUp,
+    RandomOrQuit,
+    /// Takes the card numbers that can be picked.
+    NotACard,
+    /// Takes the picked card's number.
+    ConfirmPick,
+    RevealOrQuit,
+    /// Takes the winning player's name.
+    RoundWinner,
+    Shuffling,
+    Results,
+    /// Takes the winning card's number.
+    WhyWon,
+    /// Takes the winning card and the card of the same rank it beat.
+    SuitDecided,
+    /// Takes the winning card, the card it tied with and their score.
+    FirstOfTie,
+    /// Takes the card and its score.
+    Scores,
+    /// Takes the card and its rank's score.
+    ScoresForRank,
+    /// Takes the card, its suit's points, its rank's value and its score.
+    ScoresForSuitAndRank,
+    /// Takes the card, its rank's place and its score.
+    ScoresByRank,
+    /// Takes the card, its rank's place, its suit's place and its score.
+    ScoresByRankAndSuit,
+    /// Takes the points scored and the streak multiplier.
+    Win,
+    Lose,
+    /// Takes the signed change in chips.
+    Chips,
+    /// Takes the chips held and the default wager.
+    WagerPrompt,
+    /// Takes the chips wagered.
+    Wager,
+    WagerNotNumber,
+    /// Takes the reason the wager was refused.
+    WagerRejected,
+    /// Takes the card the opponent picked.
+    OpponentWon,
+    /// Takes the card the opponent picked.
+    OpponentLost,
+    NextOrRetry,
+    AnyKey,
+    AnyKeyOrRetry,
+    Controls,
+    BetControls,
+    Paused,
+    PauseControls,
+    /// Takes the card's number, the card and the percentage chance.
+    HintCard,
+    /// Takes the card's number and the percentage chance.
+    HintHidden,
+    /// Takes the unseen cards of each suit.
+    HintUnseen,
+    OutOfChips,
+    /// Takes the number of rounds.
+    AllRounds,
+    OutOfCards,
+    /// Takes the rounds won, the rounds played and the percentage won.
+    GameOverAccuracy,
+    /// Takes the points scored and the best streak.
+    GameOverStreaks,
+    PlayAgain,
+    Thanks,
+    /// Takes the player's wins and the opponent's.
+    MatchWon,
+    /// Takes the opponent's wins and the player's.
+    MatchLost,
+    /// Takes the wins each.
+    MatchDrawn,
+    TutorialWelcome,
+    TutorialDeal,
+    TutorialRanks,
+    TutorialSuits,
+    /// Takes the last card's number.
+    TutorialChoose,
+    /// Takes the last card's number.
+    TutorialNotCard,
+    TutorialValues,
+    /// Takes the card's number, the card and its value.
+    TutorialValue,
+    /// Takes the high card's number and the card.
+    TutorialWon,
+    /// Takes the high card's number and the card.
+    TutorialLost,
+    TutorialDone,
+    /// Takes the strategy's name.
+    AutoplayStart,
+    /// Takes the strategy's name and the card's number.
+    AutoplayPick,
+    /// Takes the strategy's name.
+    AutoplayFound,
+    /// Takes the strategy's name and the streak.
+    AutoplayStreak,
+    /// Takes the strategy's name and the high card.
+    AutoplayMissed,
+}
+
+impl Locale {
+    /// Looks up a locale by language, as in `es`, or by a full locale name such
+    /// as `es_ES.UTF-8`.
+    pub fn named(name: &str) -> Option<Locale> {
+        let language = name
+            .split(&['_', '-', '.'][..])
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Locale::English),
+            "es" => Some(Locale::Spanish),
+            _ => None,
+        }
+    }
+
+    /// The locale from `LC_ALL`, `LC_MESSAGES` or `LANG`, whichever is set first,
+    /// or English if it isn't one the game has.
+    pub fn from_env() -> Locale {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Locale::named(&value))
+            .unwrap_or_default()
+    }
+
+    /// The text of `message`.
+    pub fn text(self, message: Message) -> &'static str {
+        match self {
+            Locale::English => english(message),
+            Locale::Spanish => spanish(message),
+        }
+    }
+
+    /// `items` written as a list ending in "or", as in `0, 1, or 2`.
+    pub fn or_list(self, items: &[String]) -> String {
+        let (last, rest) = match items.split_last() {
+            Some(split) => split,
+            None => return String::new(),
+        };
+        if rest.is_empty() {
+            return last.clone();
+        }
+        match self {
+            Locale::English if rest.len() == 1 => format!("{} or {}", rest[0], last),
+            Locale::English => format!("{}, or {}", rest.join(", "), last),
+            Locale::Spanish => format!("{} o {}", rest.join(", "), last),
+        }
+    }
+
+    /// Whether `answer`, in lowercase, is yes in the language. An empty answer
+    /// takes a [Y/n] prompt's default of yes.
+    pub fn is_yes(self, answer: &str) -> bool {
+        let yes: &[&str] = match self {
+            Locale::English => &["", "y", "yes"],
+            Locale::Spanish => &["", "s", "si", "sí"],
+        };
+        yes.contains(&answer)
+    }
+
+    /// The text of `message` with each `{}` replaced by the next of `args`.
+    pub fn format(self, message: Message, args: &[&dyn Display]) -> String {
+        let mut parts = self.text(message).split("{}");
+        let mut out = parts.next().unwrap_or("").to_string();
+        for (idx, part) in parts.enumerate() {
+            if let Some(arg) = args.get(idx) {
+                out.push_str(&arg.to_string());
+            }
+            out.push_str(part);
+        }
+        out
+    }
+}
+
+fn english(message: Message) -> &'static str {
+    match message {
+        Message::Seed => "Seed {}",
+        Message::Difficulty => "Difficulty {}",
+        Message::Dealing => "Dealing...",
+        Message::FindHighCard => "Find the High card.",
+        Message::FasterPicks => "Faster picks score more.",
+        Message::TimedPrompt => {
+            "Press a card number within {} seconds, [Enter] for a random choice or [q] to save and quit."
+        }
+        Message::SecondsLeft => "{}s left ",
+        Message::TimesUp => "Time's up!",
+        Message::RandomOrQuit => "Press [Enter] for a random choice or [q] to save and quit.",
+        Message::NotACard => "Enter {}, or press Enter for random.",
+        Message::ConfirmPick => "Lock in card {}? [Y/n]",
+        Message::RevealOrQuit => "Press [Enter] to reveal or [q] to save and quit.",
+        Message::RoundWinner => "{} wins the round!",
+        Message::Shuffling => "Shuffling...",
+        Message::Results => "Lets see the results.",
+        Message::WhyWon => "Card {} is the high card:",
+        Message::SuitDecided => "{} and {} are the same rank, so the suit decided.",
+        Message::FirstOfTie => "{} and {} tie on {}, so the first of them is high.",
+        Message::Scores => "{} scores {}",
+        Message::ScoresForRank => "{} scores {} for its rank",
+        Message::ScoresForSuitAndRank => "{} scores {} for its suit x {} for its rank = {}",
+        Message::ScoresByRank => "{} scores {} x 4 = {}",
+        Message::ScoresByRankAndSuit => "{} scores {} x 4 + {} = {}",
+        Message::Win => "You win!!! +{} points (x{} streak)",
+        Message::Lose => "You lose!",
+        Message::Chips => "{} chips",
+        Message::WagerPrompt => {
+            "You have {} chips. Enter a wager, [Enter] for {} or [q] to save and quit."
+        }
+        Message::Wager => "Wager {} chips.",
+        Message::WagerNotNumber => "Wagers are a number of chips.",
+        Message::WagerRejected => "Sorry, {}.",
+        Message::OpponentWon => "The opponent picked card {} and won.",
+        Message::OpponentLost => "The opponent picked card {} and lost.",
+        Message::NextOrRetry => "Press [Enter] for the next round or [u] to retry this one.",
+        Message::AnyKey => "Press any key to continue.",
+        Message::AnyKeyOrRetry => "Press any key to continue or [u] to retry the round.",
+        Message::Controls => "[←/→] select  [Enter] choose  [r] random  [q] pause",
+        Message::BetControls => "[↑/↓] raise or lower the wager",
+        Message::Paused => "Paused.",
+        Message::PauseControls => "[Enter] resume  [s] save and quit  [t] stats  [q] quit",
+        Message::HintCard => "Card {}, {}, has a {}% chance of being highest.",
+        Message::HintHidden => "Card {} has a {}% chance of being highest.",
+        Message::HintUnseen => "Still unseen: {}",
+        Message::OutOfChips => "You're out of chips.",
+        Message::AllRounds => "That's all {} rounds.",
+        Message::OutOfCards => "Game over, the deck is empty.",
+        Message::GameOverAccuracy => "You found the high card {} times in {} rounds ({}%).",
+        Message::GameOverStreaks => "Points {}  Best streak {}",
+        Message::PlayAgain => {
+            "Press [p] to play again with a fresh deck, [m] for the menu or [Enter] to quit."
+        }
+        Message::Thanks => "Thanks for playing.",
+        Message::MatchWon => "You win the match {} to {}.",
+        Message::MatchLost => "The opponent wins the match {} to {}.",
+        Message::MatchDrawn => "The match is drawn at {} each.",
+        Message::TutorialWelcome => {
+            "Welcome! This round walks you through the high card game one step at a time."
+        }
+        Message::TutorialDeal => {
+            "Three cards are dealt face down. One of them is the high card, and your job is to find it."
+        }
+        Message::TutorialRanks => {
+            "Cards rank from the two up to the ten, then the Jack, Queen, King and Ace, which is highest."
+        }
+        Message::TutorialSuits => {
+            "When two cards share a rank the suit decides: clubs beat hearts, hearts beat diamonds and diamonds beat spades."
+        }
+        Message::TutorialChoose => {
+            "The number under each card is how you pick it. Type a number from 0 to {} and press [Enter]."
+        }
+        Message::TutorialNotCard => {
+            "That isn't one of the card numbers. Type a number from 0 to {}, or [q] to quit."
+        }
+        Message::TutorialValues => {
+            "Each card is worth four times its rank, counting the Jack as 11 up to the Ace as 14, plus 0 to 3 for its suit, so the suit only matters between matching ranks."
+        }
+        Message::TutorialValue => "Card {}, {}, is worth {}.",
+        Message::TutorialWon => "You found it: card {}, {}, is the high card.",
+        Message::TutorialLost => "Not this time: card {}, {}, is the high card.",
+        Message::TutorialDone => {
+            "That's the tutorial done. Run `card_game play` for a real game."
+        }
+        Message::AutoplayStart => "Autoplay: {} is playing. Press Ctrl-C to stop.",
+        Message::AutoplayPick => "{} picks card {}.",
+        Message::AutoplayFound => "{} found the high card.",
+        Message::AutoplayStreak => "{} has found the high card {} times in a row.",
+        Message::AutoplayMissed => "{} missed. The high card was {}.",
+    }
+}
+
+fn spanish(message: Message) -> &'static str {
+    match message {
+        Message::Seed => "Semilla {}",
+        Message::Difficulty => "Dificultad {}",
+        Message::Dealing => "Repartiendo...",
+        Message::FindHighCard => "Encuentra la carta más alta.",
+        Message::FasterPicks => "Cuanto antes elijas, más puntos.",
+        Message::TimedPrompt => {
+            "Pulsa el número de una carta en {} segundos, [Enter] para elegir al azar o [q] para guardar y salir."
+        }
+        Message::SecondsLeft => "quedan {}s ",
+        Message::TimesUp => "¡Se acabó el tiempo!",
+        Message::RandomOrQuit => "Pulsa [Enter] para elegir al azar o [q] para guardar y salir.",
+        Message::NotACard => "Escribe {}, o pulsa Enter para elegir al azar.",
+        Message::ConfirmPick => "¿Te quedas con la carta {}? [S/n]",
+        Message::RevealOrQuit => "Pulsa [Enter] para descubrir o [q] para guardar y salir.",
+        Message::RoundWinner => "¡{} gana la ronda!",
+        Message::Shuffling => "Barajando...",
+        Message::Results => "Veamos los resultados.",
+        Message::WhyWon => "La carta {} es la más alta:",
+        Message::SuitDecided => "{} y {} tienen el mismo valor, así que decidió el palo.",
+        Message::FirstOfTie => "{} y {} empatan a {}, así que gana la primera.",
+        Message::Scores => "{} vale {}",
+        Message::ScoresForRank => "{} vale {} por su valor",
+        Message::ScoresForSuitAndRank => "{} vale {} por su palo x {} por su valor = {}",
+        Message::ScoresByRank => "{} vale {} x 4 = {}",
+        Message::ScoresByRankAndSuit => "{} vale {} x 4 + {} = {}",
+        Message::Win => "¡¡¡Ganas!!! +{} puntos (racha x{})",
+        Message::Lose => "¡Pierdes!",
+        Message::Chips => "{} fichas",
+        Message::WagerPrompt => {
+            "Tienes {} fichas. Escribe una apuesta, [Enter] para {} o [q] para guardar y salir."
+        }
+        Message::Wager => "Apuestas {} fichas.",
+        Message::WagerNotNumber => "Las apuestas son un número de fichas.",
+        Message::WagerRejected => "Lo siento, {}.",
+        Message::OpponentWon => "El rival eligió la carta {} y ganó.",
+        Message::OpponentLost => "El rival eligió la carta {} y perdió.",
+        Message::NextOrRetry => "Pulsa [Enter] para la siguiente ronda o [u] para repetir esta.",
+        Message::AnyKey => "Pulsa cualquier tecla para seguir.",
+        Message::AnyKeyOrRetry => "Pulsa cualquier tecla para seguir o [u] para repetir la ronda.",
+        Message::Controls => "[←/→] elegir  [Enter] escoger  [r] al azar  [q] pausa",
+        Message::BetControls => "[↑/↓] subir o bajar la apuesta",
+        Message::Paused => "En pausa.",
+        Message::PauseControls => {
+            "[Enter] seguir  [s] guardar y salir  [t] estadísticas  [q] salir"
+        }
+        Message::HintCard => "La carta {}, {}, tiene un {}% de probabilidad de ser la más alta.",
+        Message::HintHidden => "La carta {} tiene un {}% de probabilidad de ser la más alta.",
+        Message::HintUnseen => "Sin ver: {}",
+        Message::OutOfChips => "Te has quedado sin fichas.",
+        Message::AllRounds => "Se acabaron las {} rondas.",
+        Message::OutOfCards => "Fin de la partida, se acabó la baraja.",
+        Message::GameOverAccuracy => {
+            "Encontraste la carta más alta {} veces en {} rondas ({}%)."
+        }
+        Message::GameOverStreaks => "Puntos {}  Mejor racha {}",
+        Message::PlayAgain => {
+            "Pulsa [p] para jugar otra vez con una baraja nueva, [m] para el menú o [Enter] para salir."
+        }
+        Message::Thanks => "Gracias por jugar.",
+        Message::MatchWon => "Ganas la partida {} a {}.",
+        Message::MatchLost => "El rival gana la partida {} a {}.",
+        Message::MatchDrawn => "La partida queda empatada a {}.",
+        Message::TutorialWelcome => {
+            "¡Bienvenido! Esta ronda te enseña el juego de la carta más alta paso a paso."
+        }
+        Message::TutorialDeal => {
+            "Se reparten tres cartas boca abajo. Una de ellas es la más alta y tienes que encontrarla."
+        }
+        Message::TutorialRanks => {
+            "Las cartas van del dos al diez, luego la J, la Q, la K y el As, que es la más alta."
+        }
+        Message::TutorialSuits => {
+            "Si dos cartas tienen el mismo valor decide el palo: tréboles ganan a corazones, corazones a diamantes y diamantes a picas."
+        }
+        Message::TutorialChoose => {
+            "El número bajo cada carta sirve para elegirla. Escribe un número del 0 al {} y pulsa [Enter]."
+        }
+        Message::TutorialNotCard => {
+            "Ese no es el número de ninguna carta. Escribe un número del 0 al {}, o [q] para salir."
+        }
+        Message::TutorialValues => {
+            "Cada carta vale cuatro veces su valor, contando la J como 11 hasta el As como 14, más de 0 a 3 por su palo, así que el palo solo cuenta entre cartas del mismo valor."
+        }
+        Message::TutorialValue => "La carta {}, {}, vale {}.",
+        Message::TutorialWon => "Lo encontraste: la carta {}, {}, es la más alta.",
+        Message::TutorialLost => "Esta vez no: la carta {}, {}, es la más alta.",
+        Message::TutorialDone => {
+            "Fin del tutorial. Ejecuta `card_game play` para una partida de verdad."
+        }
+        Message::AutoplayStart => "Juego automático: juega {}. Pulsa Ctrl-C para parar.",
+        Message::AutoplayPick => "{} elige la carta {}.",
+        Message::AutoplayFound => "{} encontró la carta más alta.",
+        Message::AutoplayStreak => "{} lleva {} aciertos seguidos.",
+        Message::AutoplayMissed => "{} falló. La más alta era {}.",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Locale, Message};
+
+    #[test]
+    /// Tests locales are found by language and text is filled in.
+    fn test_format() {
+        assert_eq!(Locale::named("es_ES.UTF-8"), Some(Locale::Spanish));
+        assert_eq!(Locale::named("en"), Some(Locale::English));
+        assert_eq!(Locale::named("C"), Some(Locale::English));
+        assert_eq!(Locale::named("fr_FR"), None);
+
+        assert_eq!(
+            Locale::English.format(Message::Win, &[&25, &3]),
+            "You win!!! +25 points (x3 streak)"
+        );
+        assert_eq!(
+            Locale::Spanish.format(Message::Win, &[&25, &3]),
+            "¡¡¡Ganas!!! +25 puntos (racha x3)"
+        );
+        assert_eq!(
+            Locale::Spanish.text(Message::FindHighCard),
+            "Encuentra la carta más alta."
+        );
+
+        let numbers: Vec<String> = (0..3).map(|number| number.to_string()).collect();
+        assert_eq!(Locale::English.or_list(&numbers), "0, 1, or 2");
+        assert_eq!(Locale::English.or_list(&numbers[..2]), "0 or 1");
+        assert_eq!(Locale::Spanish.or_list(&numbers), "0, 1 o 2");
+
+        assert!(Locale::English.is_yes("y") && Locale::English.is_yes(""));
+        assert!(!Locale::English.is_yes("s"));
+        assert!(Locale::Spanish.is_yes("sí") && !Locale::Spanish.is_yes("y"));
+    }
+}
@@ -1,170 +1,353 @@
-#![allow(dead_code)]
-
 //! Super Card Game
 
-use rand::prelude::*;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
-use std::{thread, time};
-
-mod cards;
-
-use cards::card_printer::display_hand;
-use cards::{Card, DeckBuilder};
-
-/// GameBuilder struct representing game options.
-struct GameBuilder {
-    card_count: u8,
-}
-
-/// Builds the game object using the builder pattern.
-impl GameBuilder {
-    /// GameBuilder Contsturctor.
-    fn new() -> GameBuilder {
-        GameBuilder { card_count: 3 }
-    }
+use std::env;
+use std::io::{self};
+use std::process;
+use std::time;
+
+use card_game::cards::card_printer::display_hand;
+use card_game::cards::shoe::Shoe;
+use card_game::cards::{DeckBuilder, Hand};
+use card_game::cli::{self, Command, PlayOptions};
+use card_game::daily::DailyLog;
+use card_game::engine::high_card::AfterGame;
+use card_game::engine::tutorial::Tutorial;
+use card_game::engine::{self, wants_to_quit};
+use card_game::error::GameError;
+use card_game::games::blackjack::Blackjack;
+use card_game::games::go_fish::GoFish;
+use card_game::games::hi_lo::HiLo;
+use card_game::games::holdem::Holdem;
+use card_game::games::klondike::Klondike;
+use card_game::games::memory::Memory;
+use card_game::games::poker::FiveCardDraw;
+use card_game::games::war::War;
+use card_game::input;
+use card_game::menu::{self, MenuItem};
+#[cfg(feature = "net")]
+use card_game::net;
+use card_game::replay::Replay;
+use card_game::session::{
+    self, load_config, load_profiles, load_stats, play, play_session, DAILY_SAVE, SAVE,
+};
+#[cfg(feature = "net")]
+use card_game::session::{rate_net_match, rating_of};
+use card_game::simulator::{SimGame, Simulator};
+use card_game::strategy;
+use card_game::tournament::{Format, Tournament};
+use console::Term;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let command = match cli::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, cli::USAGE);
+            process::exit(2);
+        }
+    };
 
-    /// Option to change the number of players.
-    fn max_cards(mut self, count: u8) -> GameBuilder {
-        self.card_count = match count {
-            0..=2 => panic!("Must have more than one card."),
-            3..=5 => count,
-            _ => panic!("Too many cards."),
-        };
-        self
+    if let Err(err) = run(command).await {
+        eprintln!("card_game: {}", err);
+        process::exit(1);
     }
+}
 
-    /// Creates a new Game opject.
-    fn spawn(self) -> Game {
-        Game {
-            deck: DeckBuilder::new(),
-            cards: Vec::new(),
-            games_played: 0,
-            wins: 0,
+async fn run(command: Command) -> Result<(), GameError> {
+    match command {
+        Command::Menu => {
+            if Term::stdout().features().is_attended() {
+                menu(PlayOptions::default()).await?;
+            } else {
+                play(PlayOptions::default(), load_config(), &SAVE).await?;
+            }
+        }
+        Command::Play(options) => {
+            if play_session(options.clone(), &load_config(), &SAVE).await? == Some(AfterGame::Menu)
+            {
+                menu(options).await?;
+            }
+        }
+        Command::Daily => {
+            session::daily(load_config(), DailyLog::default_path(), &DAILY_SAVE).await?
+        }
+        Command::Tutorial => tutorial().await?,
+        Command::Rules(rules) => println!("{}", rules.explain()),
+        Command::Simulate {
+            game,
+            strategies,
+            hands,
+            threads,
+            seed,
+        } => simulate(game, strategies, hands, threads, seed),
+        Command::Tournament {
+            format,
+            strategies,
+            games,
+            seed,
+        } => tournament(format, strategies, games, seed),
+        Command::Replay { path, watch } => {
+            let replay = Replay::load(&path).map_err(|err| {
+                io::Error::new(err.kind(), format!("couldn't load {}: {}", path, err))
+            })?;
+            watch_replay(&replay, watch).await?;
+        }
+        Command::Stats { player: None } => println!("{}", load_stats()),
+        Command::Stats { player: Some(name) } => match load_profiles().get(&name) {
+            Some(profile) => println!("{}\n{}", name, profile),
+            None => println!("No profile called {} yet.", name),
+        },
+        Command::Leaderboard => println!("{}", load_profiles()),
+        Command::DeckShow { decks, jokers } => show_deck(decks, jokers)?,
+        Command::Blackjack {
+            seed,
+            decks,
+            penetration,
+            count,
+        } => {
+            let blackjack = match seed {
+                Some(seed) => Blackjack::with_seed(seed),
+                None => Blackjack::new(),
+            };
+            let shoe = Shoe::new(decks)?.penetration(f64::from(penetration) / 100.0);
+            blackjack.with_shoe(shoe).show_count(count).play().await?;
         }
+        Command::Poker { seed, hand_history } => {
+            let mut poker = match seed {
+                Some(seed) => FiveCardDraw::with_seed(seed),
+                None => FiveCardDraw::new(),
+            };
+            if let Some(path) = hand_history {
+                poker = poker.hand_history(path);
+            }
+            poker.play().await?;
+        }
+        Command::War { seed, auto, rules } => {
+            let war = match seed {
+                Some(seed) => War::with_seed(seed),
+                None => War::new(),
+            };
+            war.rules(rules)
+                .speed(load_config().speed())
+                .play(!auto)
+                .await?;
+        }
+        Command::Memory { seed } => {
+            let mut memory = match seed {
+                Some(seed) => Memory::with_seed(seed),
+                None => Memory::new(),
+            };
+            engine::run(&mut memory, load_config().pause()).await?;
+        }
+        Command::HiLo { seed, rules } => {
+            let hi_lo = match seed {
+                Some(seed) => HiLo::with_seed(seed),
+                None => HiLo::new(),
+            };
+            engine::run(&mut hi_lo.rules(rules), load_config().pause()).await?;
+        }
+        Command::Solitaire { seed } => {
+            let mut klondike = match seed {
+                Some(seed) => Klondike::with_seed(seed),
+                None => Klondike::new(),
+            };
+            engine::run(&mut klondike, time::Duration::from_millis(0)).await?;
+        }
+        Command::GoFish { seed, hot_seat } => {
+            let go_fish = match seed {
+                Some(seed) => GoFish::with_seed(seed),
+                None => GoFish::new(),
+            };
+            engine::run(&mut go_fish.hot_seat(hot_seat), load_config().pause()).await?;
+        }
+        Command::Holdem {
+            seed,
+            opponents,
+            hand_history,
+        } => {
+            let mut holdem = match seed {
+                Some(seed) => Holdem::with_seed(seed, opponents),
+                None => Holdem::new(opponents),
+            };
+            if let Some(path) = hand_history {
+                holdem = holdem.hand_history(path);
+            }
+            engine::run(&mut holdem, load_config().pause()).await?;
+        }
+        #[cfg(feature = "net")]
+        Command::Host {
+            port,
+            game,
+            seed,
+            player,
+        } => {
+            let finish = net::host(port, game, seed, rating_of(player.as_deref()))?;
+            rate_net_match(player.as_deref(), finish);
+        }
+        #[cfg(feature = "net")]
+        Command::Join { addr, player } => {
+            let finish = net::join(&addr, rating_of(player.as_deref()))?;
+            rate_net_match(player.as_deref(), finish);
+        }
+        Command::Help => println!("{}", cli::USAGE),
     }
+    Ok(())
 }
 
-/// Holds game state.
-struct Game {
-    deck: Vec<Card>,
-    cards: Vec<Card>,
-    games_played: usize,
-    wins: usize,
-}
-
-impl Game {
-    /// Randomly shuffles cards
-    fn shuffle_deck(&mut self) {
-        let mut rng = thread_rng();
-        self.deck.shuffle(&mut rng);
-    }
-
-    fn deal_cards(&mut self) {
-        self.shuffle_deck();
-        self.cards = self.deck.drain(0..3).collect();
-    }
-
-    fn find_high_card(&self) -> usize {
-        let mut index = 0;
-        let mut value = 0;
-        for (idx, card) in self.cards.iter().enumerate() {
-            if card.value() > value {
-                value = card.value();
-                index = idx;
+/// Runs games picked from the menu until the player quits.
+async fn menu(mut options: PlayOptions) -> Result<(), GameError> {
+    let config = load_config();
+    loop {
+        match menu::run(&mut options, &config)? {
+            MenuItem::HighCard => match play_session(options.clone(), &config, &SAVE).await? {
+                Some(AfterGame::Menu) => continue,
+                Some(AfterGame::Quit) => return Ok(()),
+                _ => {}
+            },
+            MenuItem::Tutorial => tutorial().await?,
+            MenuItem::Blackjack => Blackjack::new().play().await?,
+            MenuItem::Poker => FiveCardDraw::new().play().await?,
+            MenuItem::War => War::new().speed(config.speed()).play(true).await?,
+            MenuItem::Memory => {
+                engine::run(&mut Memory::new(), config.pause()).await?;
+            }
+            MenuItem::HiLo => {
+                engine::run(&mut HiLo::new(), config.pause()).await?;
+            }
+            MenuItem::Solitaire => {
+                engine::run(&mut Klondike::new(), time::Duration::from_millis(0)).await?;
+            }
+            MenuItem::GoFish => {
+                engine::run(&mut GoFish::new(), config.pause()).await?;
             }
+            MenuItem::Holdem => {
+                engine::run(&mut Holdem::new(2), config.pause()).await?;
+            }
+            MenuItem::Stats => println!("{}", load_stats()),
+            MenuItem::Settings => continue,
+            MenuItem::Quit => return Ok(()),
         }
-        index
+        println!("\nPress any key to return to the menu.");
+        input::read_key()?;
     }
+}
 
-    fn inc_gamesplayed(&mut self) {
-        self.games_played += 1;
+/// Prints every card in a deck, four cards per row.
+fn show_deck(decks: usize, jokers: bool) -> Result<(), GameError> {
+    let mut builder = DeckBuilder::new().decks(decks);
+    if jokers {
+        builder = builder.with_jokers();
     }
-
-    fn inc_wins(&mut self) {
-        self.wins += 1;
+    let mut cards: Hand = builder.build()?.into_iter().collect();
+    cards.reveal_all();
+    for row in cards.cards().chunks(4) {
+        display_hand(row, false)?;
     }
+    println!("{} cards", cards.len());
+    Ok(())
+}
 
-    fn out_of_cards(&self) -> bool {
-        if self.deck.len() < 3 {
-            return true;
+/// Plays the guided first round.
+async fn tutorial() -> Result<(), GameError> {
+    let config = load_config();
+    engine::run(&mut Tutorial::new(&config), config.pause()).await?;
+    Ok(())
+}
+
+/// Simulates `hands` hands of `game` with each of `strategies`, or with every
+/// strategy the game has when none are named, and prints how often each won.
+fn simulate(
+    game: SimGame,
+    strategies: Vec<String>,
+    hands: u64,
+    threads: Option<usize>,
+    seed: Option<u64>,
+) {
+    let mut simulator = Simulator::new(game, seed.unwrap_or_else(rand::random)).hands(hands);
+    if let Some(threads) = threads {
+        simulator = simulator.threads(threads);
+    }
+    println!(
+        "Simulating {} hands of {} on {} {}, seed {}.",
+        hands,
+        game,
+        simulator.threads_used(),
+        match simulator.threads_used() {
+            1 => "thread",
+            _ => "threads",
+        },
+        simulator.seed()
+    );
+    let strategies = match strategies.is_empty() {
+        true => game
+            .strategies()
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+        false => strategies,
+    };
+    for strategy in strategies {
+        if let Some(estimate) = simulator.run(&strategy) {
+            println!("{}", estimate);
         }
-        false
     }
 }
 
-use std::fmt::{self, Display, Formatter};
-use std::io::{self};
-
-impl Display for Game {
-    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
-        write!(
-            formatter,
-            "Won {} out of {} games.\nCards Left {}",
-            self.wins,
-            self.games_played,
-            self.deck.len(),
-        )
+/// Plays a tournament in `format` between `strategies`, or between every
+/// built-in strategy when none are named, and prints the report.
+fn tournament(format: Format, strategies: Vec<String>, games: usize, seed: Option<u64>) {
+    let strategies = match strategies.is_empty() {
+        true => strategy::NAMES
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+        false => strategies,
+    };
+    let mut tournament = Tournament::new(format, seed.unwrap_or_else(rand::random)).games(games);
+    for name in strategies {
+        let built = name.clone();
+        tournament = tournament.entrant(&name, move |seed| {
+            strategy::named(&built, seed).expect("the strategy was checked to be built in")
+        });
     }
+    println!(
+        "Playing a {} of {} strategies, {} games a match, seed {}.\n",
+        tournament.format(),
+        tournament.len(),
+        games,
+        tournament.seed()
+    );
+    println!("{}", tournament.run());
 }
 
-fn main() {
-    let mut game: Game = GameBuilder::new().spawn();
-    println!("{}", game.cards.len());
-
-    while !game.out_of_cards() {
-        game.deal_cards();
-
-        let winning_card = game.find_high_card();
-        let sleep_time = time::Duration::from_secs(1);
-
-        display_hand(&game.cards, true);
-
-        println!("Find the High card.");
-        println!("Press [Enter] for a random choice.");
-
-        let mut input = String::new();
-        let mut choice: usize = rand::thread_rng().gen_range(0..=2);
-
-        match io::stdin().read_line(&mut input) {
-            Ok(_) => {
-                if let Ok(i) = input.trim().parse::<usize>() {
-                    if i > game.cards.len() - 1 {
-                        choice = game.cards.len() - 1;
-                    } else {
-                        choice = i;
-                    }
-                }
-            }
-            Err(_) => {}
-        }
-
-        game.cards[choice].toggle();
-        display_hand(&game.cards, true);
-
-        println!("Lets see the results.");
-
-        thread::sleep(sleep_time);
-
-        game.cards[choice].toggle();
-        game.cards[0].toggle();
-        game.cards[1].toggle();
-        game.cards[2].toggle();
-
-        display_hand(&game.cards, true);
-
-        if choice == winning_card {
-            game.inc_wins();
-            println!("You win!!!")
+/// Shows each round of a recorded session. With `auto` rounds advance every
+/// second, otherwise on [Enter].
+async fn watch_replay(replay: &Replay, auto: bool) -> Result<(), GameError> {
+    let speed = load_config().speed();
+    println!("Replaying seed {}.", replay.seed);
+    let mut replayer = replay.replayer()?;
+    while let Some(choice) = replayer.next_round() {
+        let game = replayer.game();
+        display_hand(game.hand().cards(), true)?;
+        let round = game
+            .history()
+            .rounds()
+            .last()
+            .expect("a round was just played");
+        let result = if round.won { "won" } else { "lost" };
+        println!("Picked card {} and {}.", choice, result);
+        println!("{}\n", game);
+
+        if auto {
+            engine::pause(speed.scale(time::Duration::from_secs(1))).await?;
         } else {
-            println!("You lose!")
+            println!("Press [Enter] for the next round or [q] to stop.");
+            let input = input::read_line().await?;
+            if wants_to_quit(&input) {
+                return Ok(());
+            }
         }
-
-        game.inc_gamesplayed();
-        println!("{}\n\n", game);
-
-        thread::sleep(sleep_time);
     }
-
-    println!("Sorry ran out of cards.");
+    println!("{}", replayer.game().history());
+    Ok(())
 }
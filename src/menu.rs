@@ -0,0 +1,261 @@
+//! This module provides the menu shown when the binary starts without a command.
+//!
+//! The menu picks a game with the arrow keys. Its settings screen changes the
+//! options the high card game is played with for the rest of the session.
+
+use crate::cards::card_printer::CardTheme;
+use crate::cli::PlayOptions;
+use crate::config::Config;
+use crate::game::Difficulty;
+use crate::input::read_key;
+use console::style;
+use crossterm::cursor::MoveTo;
+use crossterm::event::KeyCode;
+use crossterm::queue;
+use crossterm::style::Print;
+use crossterm::terminal::{Clear, ClearType};
+use std::fmt::Write;
+use std::io::{self, Write as _};
+
+/// Help line shown under the menu.
+const CONTROLS: &str = "[↑/↓] select  [Enter] choose  [q] quit";
+
+/// Help line shown under the settings.
+const SETTINGS_CONTROLS: &str = "[↑/↓] select  [←/→] change  [Enter] done";
+
+/// Built-in theme names in the order the settings cycle through them.
+const THEMES: [&str; 4] = ["classic", "rounded", "casino", "minimal"];
+
+const DIFFICULTIES: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+/// An entry in the main menu.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MenuItem {
+    HighCard,
+    Tutorial,
+    Blackjack,
+    Poker,
+    War,
+    Memory,
+    HiLo,
+    Solitaire,
+    GoFish,
+    Holdem,
+    Settings,
+    Stats,
+    Quit,
+}
+
+impl MenuItem {
+    /// Every entry, top to bottom.
+    pub const ALL: [MenuItem; 13] = [
+        MenuItem::HighCard,
+        MenuItem::Tutorial,
+        MenuItem::Blackjack,
+        MenuItem::Poker,
+        MenuItem::War,
+        MenuItem::Memory,
+        MenuItem::HiLo,
+        MenuItem::Solitaire,
+        MenuItem::GoFish,
+        MenuItem::Holdem,
+        MenuItem::Settings,
+        MenuItem::Stats,
+        MenuItem::Quit,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MenuItem::HighCard => "High card",
+            MenuItem::Tutorial => "Tutorial",
+            MenuItem::Blackjack => "Blackjack",
+            MenuItem::Poker => "Five Card Draw",
+            MenuItem::War => "War",
+            MenuItem::Memory => "Memory",
+            MenuItem::HiLo => "Hi-Lo",
+            MenuItem::Solitaire => "Solitaire",
+            MenuItem::GoFish => "Go Fish",
+            MenuItem::Holdem => "Texas Hold'em",
+            MenuItem::Settings => "Settings",
+            MenuItem::Stats => "Stats",
+            MenuItem::Quit => "Quit",
+        }
+    }
+}
+
+/// A row on the settings screen.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Setting {
+    Cards,
+    Difficulty,
+    Theme,
+}
+
+const SETTINGS: [Setting; 3] = [Setting::Cards, Setting::Difficulty, Setting::Theme];
+
+/// Shows the menu until an entry other than settings is picked. Settings change
+/// `options` in place, starting from the values in `config`.
+pub fn run(options: &mut PlayOptions, config: &Config) -> io::Result<MenuItem> {
+    let mut selected = 0;
+    loop {
+        draw(&menu_frame(selected))?;
+        match read_key()? {
+            KeyCode::Up | KeyCode::Char('k') => selected = selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                selected = (selected + 1).min(MenuItem::ALL.len() - 1)
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => match MenuItem::ALL[selected] {
+                MenuItem::Settings => settings(options, config)?,
+                item => return Ok(item),
+            },
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(MenuItem::Quit),
+            _ => {}
+        }
+    }
+}
+
+fn settings(options: &mut PlayOptions, config: &Config) -> io::Result<()> {
+    options.cards = options.cards.or(config.cards);
+    options.difficulty = options.difficulty.or(config.difficulty);
+    if options.theme.is_none() {
+        options.theme = THEMES
+            .iter()
+            .find(|name| CardTheme::named(name) == config.theme)
+            .map(|name| name.to_string());
+    }
+    let mut selected = 0;
+    loop {
+        draw(&settings_frame(options, selected))?;
+        match read_key()? {
+            KeyCode::Up | KeyCode::Char('k') => selected = selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => selected = (selected + 1).min(SETTINGS.len() - 1),
+            KeyCode::Left | KeyCode::Char('h') => change(options, SETTINGS[selected], false),
+            KeyCode::Right | KeyCode::Char('l') => change(options, SETTINGS[selected], true),
+            KeyCode::Enter | KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+/// Steps a setting to its next value, or its previous one when `forward` is false.
+fn change(options: &mut PlayOptions, setting: Setting, forward: bool) {
+    match setting {
+        Setting::Cards => {
+            let cards = options.cards.unwrap_or(3);
+            options.cards = Some(if forward {
+                (cards + 1).min(5)
+            } else {
+                cards.saturating_sub(1).max(3)
+            });
+        }
+        Setting::Difficulty => {
+            let difficulty = options.difficulty.unwrap_or_default();
+            let idx = DIFFICULTIES
+                .iter()
+                .position(|&d| d == difficulty)
+                .unwrap_or(1);
+            options.difficulty = Some(DIFFICULTIES[step(idx, DIFFICULTIES.len(), forward)]);
+        }
+        Setting::Theme => {
+            let theme = options.theme.as_deref().unwrap_or(THEMES[0]);
+            let idx = THEMES.iter().position(|&t| t == theme).unwrap_or(0);
+            options.theme = Some(THEMES[step(idx, THEMES.len(), forward)].to_string());
+        }
+    }
+}
+
+/// The index after `idx` in a list of `len` values, wrapping at either end.
+fn step(idx: usize, len: usize, forward: bool) -> usize {
+    if forward {
+        (idx + 1) % len
+    } else {
+        (idx + len - 1) % len
+    }
+}
+
+fn draw(frame: &str) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    queue!(out, Clear(ClearType::All), MoveTo(0, 0), Print(frame))?;
+    out.flush()
+}
+
+/// Renders the main menu with the `selected` entry highlighted.
+fn menu_frame(selected: usize) -> String {
+    let mut out = String::new();
+    writeln!(out, "{}\n", style("Super Card Game").bold()).unwrap();
+    for (idx, item) in MenuItem::ALL.iter().enumerate() {
+        if idx == selected {
+            writeln!(
+                out,
+                "{}",
+                style(format!("> {}", item.label())).yellow().bold()
+            )
+            .unwrap();
+        } else {
+            writeln!(out, "  {}", item.label()).unwrap();
+        }
+    }
+    writeln!(out, "\n{}", style(CONTROLS).dim()).unwrap();
+    out
+}
+
+/// Renders the settings with the `selected` row highlighted.
+fn settings_frame(options: &PlayOptions, selected: usize) -> String {
+    let mut out = String::new();
+    writeln!(out, "{}\n", style("Settings").bold()).unwrap();
+    for (idx, setting) in SETTINGS.iter().enumerate() {
+        let row = match setting {
+            Setting::Cards => format!("Cards       < {} >", options.cards.unwrap_or(3)),
+            Setting::Difficulty => {
+                format!("Difficulty  < {} >", options.difficulty.unwrap_or_default())
+            }
+            Setting::Theme => format!(
+                "Theme       < {} >",
+                options.theme.as_deref().unwrap_or(THEMES[0])
+            ),
+        };
+        if idx == selected {
+            writeln!(out, "{}", style(format!("> {}", row)).yellow().bold()).unwrap();
+        } else {
+            writeln!(out, "  {}", row).unwrap();
+        }
+    }
+    writeln!(out, "\n{}", style(SETTINGS_CONTROLS).dim()).unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{change, menu_frame, settings_frame, Setting, THEMES};
+    use crate::cards::card_printer::CardTheme;
+    use crate::cli::PlayOptions;
+    use crate::game::Difficulty;
+
+    #[test]
+    /// Tests the menu with the second entry selected.
+    fn test_menu_frame() {
+        insta::assert_snapshot!(menu_frame(1));
+    }
+
+    #[test]
+    /// Tests settings step through their values and stay in range.
+    fn test_change_settings() {
+        let mut options = PlayOptions::default();
+        change(&mut options, Setting::Cards, false);
+        assert_eq!(options.cards, Some(3));
+        change(&mut options, Setting::Cards, true);
+        change(&mut options, Setting::Cards, true);
+        change(&mut options, Setting::Cards, true);
+        assert_eq!(options.cards, Some(5));
+
+        change(&mut options, Setting::Difficulty, true);
+        assert_eq!(options.difficulty, Some(Difficulty::Hard));
+        change(&mut options, Setting::Difficulty, true);
+        assert_eq!(options.difficulty, Some(Difficulty::Easy));
+
+        assert!(THEMES.iter().all(|name| CardTheme::named(name).is_some()));
+        change(&mut options, Setting::Theme, false);
+        assert_eq!(options.theme.as_deref(), Some("minimal"));
+        insta::assert_snapshot!(settings_frame(&options, 2));
+    }
+}
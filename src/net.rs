@@ -0,0 +1,536 @@
+//! This module provides head-to-head games over TCP.
+//!
+//! One instance hosts and the other joins. Messages are JSON objects, one per
+//! line, tagged with their `type`. The host shuffles and deals every card and
+//! only sends cards once they're face up, so the guest never learns more than
+//! the board shows. Seat 0 is the host and seat 1 the guest. Players with a
+//! profile tell each other their rating as they meet, so the match can be rated.
+
+use crate::cards::card_printer::display_hand;
+use crate::cards::{Card, Deck, Hand, Rank, Suit};
+use crate::engine::wants_to_quit;
+use crate::error::GameError;
+use crate::games::war::{Round, War};
+use crate::rules::Ruleset;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+
+/// Port used when none is given.
+pub const DEFAULT_PORT: u16 = 7777;
+
+/// Cards dealt each round of high card.
+const HAND_SIZE: usize = 3;
+
+/// The seat of whoever is hosting.
+pub const HOST: usize = 0;
+
+/// The seat of whoever joined.
+pub const GUEST: usize = 1;
+
+/// Which game a match plays.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NetGame {
+    /// Both players pick a card from the same hand and the higher pick wins.
+    #[default]
+    HighCard,
+    War,
+}
+
+impl NetGame {
+    /// Looks up a game by name.
+    pub fn named(name: &str) -> Option<NetGame> {
+        match name {
+            "high-card" => Some(NetGame::HighCard),
+            "war" => Some(NetGame::War),
+            _ => None,
+        }
+    }
+}
+
+impl Display for NetGame {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            NetGame::HighCard => write!(formatter, "high card"),
+            NetGame::War => write!(formatter, "War"),
+        }
+    }
+}
+
+/// A line sent between host and guest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// Sent by the host once the guest connects, with the host's rating if
+    /// they're playing with a profile.
+    Hello {
+        game: NetGame,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rating: Option<i32>,
+    },
+    /// The guest's answer to `Hello`, with their rating if they have a profile.
+    Joined {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rating: Option<i32>,
+    },
+    /// A high card hand of `cards` face down cards is on the table.
+    Deal { round: usize, cards: usize },
+    /// The guest's pick from the hand.
+    Pick { choice: usize },
+    /// The hand turned over, both picks and the winning seat if it wasn't a draw.
+    Reveal {
+        cards: Vec<Card>,
+        picks: [usize; 2],
+        winner: Option<usize>,
+        score: [usize; 2],
+    },
+    /// The host is ready to flip the next War round.
+    Flip { round: usize },
+    /// The guest is ready to flip.
+    Ready,
+    /// A round of War and the pile sizes after it.
+    WarRound { round: Round, piles: [usize; 2] },
+    /// The match is over.
+    GameOver { winner: Option<usize> },
+    /// The other player left.
+    Quit,
+}
+
+/// Why a match couldn't carry on.
+#[derive(Debug)]
+pub enum NetError {
+    Io(io::Error),
+    /// A line wasn't a message.
+    Json(serde_json::Error),
+    /// The other side closed the connection.
+    Disconnected,
+    /// A message arrived that doesn't fit at this point of the game.
+    Unexpected(Message),
+}
+
+impl Display for NetError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            NetError::Io(err) => write!(formatter, "{}", err),
+            NetError::Json(err) => write!(formatter, "bad message: {}", err),
+            NetError::Disconnected => write!(formatter, "the other player disconnected"),
+            NetError::Unexpected(message) => write!(formatter, "unexpected message {:?}", message),
+        }
+    }
+}
+
+impl std::error::Error for NetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NetError::Io(err) => Some(err),
+            NetError::Json(err) => Some(err),
+            NetError::Disconnected | NetError::Unexpected(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for NetError {
+    fn from(err: io::Error) -> NetError {
+        NetError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for NetError {
+    fn from(err: serde_json::Error) -> NetError {
+        NetError::Json(err)
+    }
+}
+
+/// A connection sending and receiving one message per line.
+pub struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Connection {
+    pub fn new(stream: TcpStream) -> Result<Connection, NetError> {
+        Ok(Connection {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        })
+    }
+
+    pub fn send(&mut self, message: &Message) -> Result<(), NetError> {
+        let mut line = serde_json::to_string(message)?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Waits for the next message.
+    pub fn recv(&mut self) -> Result<Message, NetError> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(NetError::Disconnected);
+        }
+        Ok(serde_json::from_str(&line)?)
+    }
+}
+
+/// The seat whose pick scores higher, or None if they picked equal cards.
+pub fn judge(cards: &[Card], picks: [usize; 2]) -> Option<usize> {
+    match Ruleset::high_card().compare(&cards[picks[HOST]], &cards[picks[GUEST]]) {
+        Ordering::Greater => Some(HOST),
+        Ordering::Less => Some(GUEST),
+        Ordering::Equal => None,
+    }
+}
+
+/// How a match played to the end went.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Finish {
+    /// How this player did against the other, e.g. `Greater` for a win.
+    pub result: Ordering,
+    /// The other player's rating, if they played with a profile.
+    pub opponent_rating: Option<i32>,
+}
+
+/// Waits on `port` for a player to join, then plays `game` as the host,
+/// telling the guest `rating` if there is one. None if either player left
+/// before the end.
+pub fn host(
+    port: u16,
+    game: NetGame,
+    seed: Option<u64>,
+    rating: Option<i32>,
+) -> Result<Option<Finish>, GameError> {
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    println!("Waiting for a player to join on port {}...", port);
+    let (stream, addr) = listener.accept()?;
+    println!("{} joined.", addr);
+
+    let mut connection = Connection::new(stream)?;
+    connection.send(&Message::Hello { game, rating })?;
+    let opponent_rating = match connection.recv()? {
+        Message::Joined { rating } => rating,
+        message => return Err(NetError::Unexpected(message).into()),
+    };
+    let result = match (game, seed) {
+        (NetGame::HighCard, Some(seed)) => {
+            host_high_card(&mut connection, StdRng::seed_from_u64(seed))
+        }
+        (NetGame::HighCard, None) => host_high_card(&mut connection, StdRng::from_entropy()),
+        (NetGame::War, Some(seed)) => host_war(&mut connection, War::with_seed(seed)),
+        (NetGame::War, None) => host_war(&mut connection, War::new()),
+    }?;
+    Ok(result.map(|result| Finish {
+        result,
+        opponent_rating,
+    }))
+}
+
+/// Joins a game hosted at `addr`, e.g. `localhost:7777`, telling the host
+/// `rating` if there is one. None if either player left before the end.
+pub fn join(addr: &str, rating: Option<i32>) -> Result<Option<Finish>, GameError> {
+    let mut connection = Connection::new(TcpStream::connect(addr)?)?;
+    let opponent_rating = match connection.recv()? {
+        Message::Hello { game, rating } => {
+            println!("Joined a game of {}.", game);
+            rating
+        }
+        message => return Err(NetError::Unexpected(message).into()),
+    };
+    connection.send(&Message::Joined { rating })?;
+    loop {
+        match connection.recv()? {
+            Message::Deal { round, cards } => {
+                println!("Round {}", round);
+                display_hand(&vec![Card::new(Suit::Spades, Rank::Ace); cards], true)?;
+                match read_pick(cards)? {
+                    Some(choice) => connection.send(&Message::Pick { choice })?,
+                    None => return leave(&mut connection),
+                }
+                println!("Waiting for the host...");
+            }
+            Message::Reveal {
+                cards,
+                picks,
+                winner,
+                score,
+            } => show_reveal(&cards, picks, winner, score, GUEST)?,
+            Message::Flip { round } => {
+                println!("Round {}. Press [Enter] to flip or [q] to quit.", round);
+                if read_line()?.is_none_or(|input| wants_to_quit(&input)) {
+                    return leave(&mut connection);
+                }
+                connection.send(&Message::Ready)?;
+            }
+            Message::WarRound { round, piles } => show_war_round(&round, piles, GUEST)?,
+            Message::GameOver { winner } => {
+                println!("{}", verdict(winner, GUEST));
+                return Ok(Some(Finish {
+                    result: outcome(winner, GUEST),
+                    opponent_rating,
+                }));
+            }
+            Message::Quit => {
+                println!("The host left the game.");
+                return Ok(None);
+            }
+            message => return Err(NetError::Unexpected(message).into()),
+        }
+    }
+}
+
+/// Plays high card as the host. How the host did, or None if someone left.
+fn host_high_card(
+    connection: &mut Connection,
+    mut rng: StdRng,
+) -> Result<Option<Ordering>, GameError> {
+    let mut deck = Deck::new();
+    deck.shuffle(&mut rng);
+    let mut score = [0, 0];
+    let mut round = 1;
+    while deck.remaining() >= HAND_SIZE {
+        let mut hand = Hand::from_cards(deck.draw(HAND_SIZE));
+        connection.send(&Message::Deal {
+            round,
+            cards: HAND_SIZE,
+        })?;
+        println!("Round {}", round);
+        display_hand(hand.cards(), true)?;
+
+        let host_pick = match read_pick(HAND_SIZE)? {
+            Some(choice) => choice,
+            None => return leave(connection),
+        };
+        println!("Waiting for the guest...");
+        let guest_pick = match connection.recv()? {
+            Message::Pick { choice } => choice.min(HAND_SIZE - 1),
+            Message::Quit => {
+                println!("The guest left the game.");
+                return Ok(None);
+            }
+            message => return Err(NetError::Unexpected(message).into()),
+        };
+
+        hand.reveal_all();
+        let picks = [host_pick, guest_pick];
+        let winner = judge(hand.cards(), picks);
+        if let Some(seat) = winner {
+            score[seat] += 1;
+        }
+        connection.send(&Message::Reveal {
+            cards: hand.cards().to_vec(),
+            picks,
+            winner,
+            score,
+        })?;
+        show_reveal(hand.cards(), picks, winner, score, HOST)?;
+        round += 1;
+    }
+
+    let winner = match score[HOST].cmp(&score[GUEST]) {
+        Ordering::Greater => Some(HOST),
+        Ordering::Less => Some(GUEST),
+        Ordering::Equal => None,
+    };
+    connection.send(&Message::GameOver { winner })?;
+    println!("{}", verdict(winner, HOST));
+    Ok(Some(outcome(winner, HOST)))
+}
+
+/// Plays War as the host. How the host did, or None if someone left.
+fn host_war(connection: &mut Connection, mut war: War) -> Result<Option<Ordering>, GameError> {
+    while !war.is_over() {
+        let round = war.rounds() + 1;
+        connection.send(&Message::Flip { round })?;
+        println!("Round {}. Press [Enter] to flip or [q] to quit.", round);
+        if read_line()?.is_none_or(|input| wants_to_quit(&input)) {
+            return leave(connection);
+        }
+        println!("Waiting for the guest...");
+        match connection.recv()? {
+            Message::Ready => {}
+            Message::Quit => {
+                println!("The guest left the game.");
+                return Ok(None);
+            }
+            message => return Err(NetError::Unexpected(message).into()),
+        }
+
+        let played = war.play_round();
+        let piles = [war.pile_size(HOST), war.pile_size(GUEST)];
+        connection.send(&Message::WarRound {
+            round: played.clone(),
+            piles,
+        })?;
+        show_war_round(&played, piles, HOST)?;
+    }
+    let winner = war.winner();
+    connection.send(&Message::GameOver { winner })?;
+    println!("{}", verdict(winner, HOST));
+    Ok(Some(outcome(winner, HOST)))
+}
+
+/// Tells the other player we're leaving.
+fn leave<T>(connection: &mut Connection) -> Result<Option<T>, GameError> {
+    connection.send(&Message::Quit)?;
+    Ok(None)
+}
+
+/// A line from stdin, or None at the end of input.
+fn read_line() -> io::Result<Option<String>> {
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(input))
+}
+
+/// Asks for a card out of `cards`. Returns None if the player quits.
+fn read_pick(cards: usize) -> io::Result<Option<usize>> {
+    loop {
+        println!("Pick the high card, 0 to {}, or [q] to quit.", cards - 1);
+        let input = match read_line()? {
+            Some(input) if !wants_to_quit(&input) => input,
+            _ => return Ok(None),
+        };
+        match input.trim().parse::<usize>() {
+            Ok(choice) if choice < cards => return Ok(Some(choice)),
+            _ => println!("That isn't one of the cards."),
+        }
+    }
+}
+
+/// Who a seat is to the player in `seat`.
+fn name(seat: usize, me: usize) -> &'static str {
+    match (seat == me, seat) {
+        (true, _) => "You",
+        (false, HOST) => "The host",
+        (false, _) => "The guest",
+    }
+}
+
+/// How the match went for the player in `me`.
+fn outcome(winner: Option<usize>, me: usize) -> Ordering {
+    match winner {
+        Some(seat) if seat == me => Ordering::Greater,
+        Some(_) => Ordering::Less,
+        None => Ordering::Equal,
+    }
+}
+
+fn verdict(winner: Option<usize>, me: usize) -> String {
+    match winner {
+        Some(seat) if seat == me => "You win the match!".to_string(),
+        Some(seat) => format!("{} wins the match.", name(seat, me)),
+        None => "The match is a draw.".to_string(),
+    }
+}
+
+fn show_reveal(
+    cards: &[Card],
+    picks: [usize; 2],
+    winner: Option<usize>,
+    score: [usize; 2],
+    me: usize,
+) -> Result<(), GameError> {
+    display_hand(cards, true)?;
+    let other = 1 - me;
+    println!(
+        "You picked {}, {} picked {}.",
+        picks[me],
+        name(other, me).to_lowercase(),
+        picks[other]
+    );
+    match winner {
+        Some(seat) if seat == me => println!("You win the round!"),
+        Some(seat) => println!("{} wins the round.", name(seat, me)),
+        None => println!("It's a draw."),
+    }
+    println!("Score: you {}, them {}\n", score[me], score[other]);
+    Ok(())
+}
+
+fn show_war_round(round: &Round, piles: [usize; 2], me: usize) -> Result<(), GameError> {
+    display_hand(round.table.cards(), false)?;
+    if round.wars > 0 {
+        println!("War! x{}", round.wars);
+    }
+    println!(
+        "{} takes {} cards.",
+        name(round.winner, me),
+        round.table.len()
+    );
+    println!(
+        "You have {} cards, they have {}.\n",
+        piles[me],
+        piles[1 - me]
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{judge, Connection, Message, NetError, NetGame, GUEST, HOST};
+    use crate::cards::{Card, Rank, Suit};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+
+    #[test]
+    /// Tests messages are sent as tagged JSON lines and arrive intact.
+    fn test_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let guest = thread::spawn(move || {
+            let mut connection = Connection::new(TcpStream::connect(addr).unwrap()).unwrap();
+            let hello = connection.recv().unwrap();
+            connection.send(&Message::Pick { choice: 2 }).unwrap();
+            hello
+        });
+
+        let (stream, _) = listener.accept().unwrap();
+        let mut connection = Connection::new(stream).unwrap();
+        connection
+            .send(&Message::Hello {
+                game: NetGame::War,
+                rating: Some(1250),
+            })
+            .unwrap();
+        assert_eq!(connection.recv().unwrap(), Message::Pick { choice: 2 });
+        assert_eq!(
+            guest.join().unwrap(),
+            Message::Hello {
+                game: NetGame::War,
+                rating: Some(1250)
+            }
+        );
+        assert!(matches!(connection.recv(), Err(NetError::Disconnected)));
+        assert_eq!(
+            serde_json::to_string(&Message::Hello {
+                game: NetGame::HighCard,
+                rating: None
+            })
+            .unwrap(),
+            r#"{"type":"hello","game":"high-card"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Message>(r#"{"type":"joined"}"#).unwrap(),
+            Message::Joined { rating: None }
+        );
+    }
+
+    #[test]
+    /// Tests the higher pick wins and equal picks draw.
+    fn test_judge() {
+        let cards = vec![
+            Card::new(Suit::Spades, Rank::King),
+            Card::new(Suit::Clubs, Rank::Jack),
+        ];
+        assert_eq!(judge(&cards, [0, 1]), Some(HOST));
+        assert_eq!(judge(&cards, [1, 0]), Some(GUEST));
+        assert_eq!(judge(&cards, [1, 1]), None);
+    }
+}
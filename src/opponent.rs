@@ -0,0 +1,168 @@
+//! This module provides a computer opponent for the single player high card game.
+//!
+//! The opponent guesses the same hand as the player each round. Face down cards
+//! only show it their suit, never their rank, so how well it does depends on how
+//! much it makes of the suits and of the cards it has already seen turned over.
+//! Each skill plays one of the built-in strategies in `strategy`.
+
+use crate::cards::Hand;
+use crate::scoring::ScoringStrategy;
+use crate::strategy::{CardCounter, Choice, GameView, HighestVisibleSuit, Random, Strategy};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// How the opponent picks a card.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Skill {
+    /// Picks any card, see `strategy::Random`.
+    #[default]
+    Random,
+    /// Picks the card whose suit scores best across a full deck, see
+    /// `HighestVisibleSuit`.
+    SuitAware,
+    /// Picks the card whose suit scores best across the cards it hasn't seen yet,
+    /// see `CardCounter`.
+    CardCounting,
+}
+
+impl Skill {
+    /// Looks up a skill by name.
+    pub fn named(name: &str) -> Option<Skill> {
+        match name {
+            "random" => Some(Skill::Random),
+            "suit" | "suit-aware" => Some(Skill::SuitAware),
+            "counting" | "card-counting" => Some(Skill::CardCounting),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Skill {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Skill::Random => "Random",
+            Skill::SuitAware => "Suit aware",
+            Skill::CardCounting => "Card counting",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+
+/// A computer player guessing alongside the player.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Opponent {
+    skill: Skill,
+    wins: usize,
+    /// Cards turned face up in earlier rounds.
+    #[serde(default, rename = "seen")]
+    counter: CardCounter,
+    #[serde(skip, default = "Random::from_entropy")]
+    random: Random,
+}
+
+impl Opponent {
+    /// Construct an opponent whose random picks come from `seed`.
+    pub fn new(skill: Skill, seed: u64) -> Opponent {
+        Opponent {
+            skill,
+            wins: 0,
+            counter: CardCounter::new(),
+            random: Random::new(seed),
+        }
+    }
+
+    pub fn skill(&self) -> Skill {
+        self.skill
+    }
+
+    /// Rounds the opponent has guessed right.
+    pub fn wins(&self) -> usize {
+        self.wins
+    }
+
+    /// Picks a card in `hand` with the strategy its skill plays.
+    pub fn guess(&mut self, hand: &Hand, scoring: &dyn ScoringStrategy) -> usize {
+        let view = GameView::new(hand, scoring);
+        let choice = match self.skill {
+            Skill::Random => self.random.choose(&view),
+            Skill::SuitAware => HighestVisibleSuit.choose(&view),
+            Skill::CardCounting => self.counter.choose(&view),
+        };
+        match choice {
+            Choice::Card(idx) => idx,
+            // None of the built-in strategies quit.
+            Choice::Quit => 0,
+        }
+    }
+
+    /// Scores a guess once the hand is turned over and remembers the cards seen.
+    pub fn record(&mut self, hand: &Hand, won: bool) {
+        if won {
+            self.wins += 1;
+        }
+        self.counter.observe(hand, won);
+    }
+
+    /// Forgets the cards seen so far, for when a fresh deck comes in.
+    pub fn forget(&mut self) {
+        self.counter.new_deck();
+    }
+}
+
+/// The computer opponent can stand in as a strategy at any of its skills.
+impl Strategy for Opponent {
+    fn choose(&mut self, view: &GameView) -> Choice {
+        Choice::Card(self.guess(view.hand, view.scoring))
+    }
+
+    fn observe(&mut self, hand: &Hand, won: bool) {
+        self.record(hand, won);
+    }
+
+    fn new_deck(&mut self) {
+        self.forget();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Opponent, Skill};
+    use crate::cards::{Card, CardState, Hand, Rank, Suit};
+    use crate::scoring::SuitWeightedScoring;
+
+    #[test]
+    /// Tests skilled opponents read the suits and use what they've seen.
+    fn test_guess() {
+        let hand = Hand::from_cards(vec![
+            Card::new(Suit::Diamonds, Rank::Two),
+            Card::new(Suit::Clubs, Rank::Two),
+            Card::new(Suit::Hearts, Rank::Two),
+        ]);
+        let mut suit_aware = Opponent::new(Skill::SuitAware, 1);
+        assert_eq!(suit_aware.guess(&hand, &SuitWeightedScoring), 1);
+
+        // Once every high club is gone a heart is the better bet.
+        let mut counting = Opponent::new(Skill::CardCounting, 1);
+        let clubs: Vec<Card> = [
+            Rank::Ace,
+            Rank::King,
+            Rank::Queen,
+            Rank::Jack,
+            Rank::Ten,
+            Rank::Nine,
+            Rank::Eight,
+        ]
+        .iter()
+        .map(|&rank| Card::new(Suit::Clubs, rank))
+        .collect();
+        counting.record(&Hand::from_cards(clubs), false);
+        assert_eq!(counting.guess(&hand, &SuitWeightedScoring), 2);
+
+        let mut shown = hand.clone();
+        shown.reveal(0);
+        let mut random = Opponent::new(Skill::Random, 1);
+        assert!(random.guess(&shown, &SuitWeightedScoring) < 3);
+        assert_eq!(shown.cards()[0].state, CardState::Visible);
+        assert_eq!(Skill::named("counting"), Some(Skill::CardCounting));
+    }
+}
@@ -0,0 +1,421 @@
+//! This module provides poker hand evaluation and comparison.
+
+use crate::cards::{Card, Rank};
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+pub mod history;
+
+/// Poker hand categories from weakest to strongest.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HandCategory {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+/// Displays the name of the hand category.
+impl Display for HandCategory {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let s = match self {
+            HandCategory::HighCard => "High Card",
+            HandCategory::OnePair => "One Pair",
+            HandCategory::TwoPair => "Two Pair",
+            HandCategory::ThreeOfAKind => "Three of a Kind",
+            HandCategory::Straight => "Straight",
+            HandCategory::Flush => "Flush",
+            HandCategory::FullHouse => "Full House",
+            HandCategory::FourOfAKind => "Four of a Kind",
+            HandCategory::StraightFlush => "Straight Flush",
+        };
+        write!(formatter, "{}", s)
+    }
+}
+
+/// An evaluated poker hand. Hands compare by category first, then by the tiebreak
+/// ranks from most to least significant.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HandRank {
+    pub category: HandCategory,
+    pub tiebreak: Vec<Rank>,
+}
+
+impl Display for HandRank {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self.tiebreak.first() {
+            Some(rank) => write!(formatter, "{} ({} high)", self.category, rank),
+            None => write!(formatter, "{}", self.category),
+        }
+    }
+}
+
+/// Evaluates the best poker hand that can be made from `cards`.
+///
+/// Hands of more than five cards (e.g. Hold'em hole and community cards) are scored
+/// by their best five card combination. Straights and flushes need five cards.
+///
+pub fn evaluate_hand(cards: &[Card]) -> HandRank {
+    if cards.len() <= 5 {
+        return evaluate_cards(cards);
+    }
+
+    let mut best: Option<HandRank> = None;
+    let mut hand = [cards[0]; 5];
+    for combination in combinations(cards.len(), 5) {
+        for (slot, &idx) in hand.iter_mut().zip(combination.iter()) {
+            *slot = cards[idx];
+        }
+        let rank = evaluate_cards(&hand);
+        if best.as_ref().is_none_or(|best| rank > *best) {
+            best = Some(rank);
+        }
+    }
+    best.expect("a hand of more than five cards has combinations")
+}
+
+/// Compares two hands, returning `Greater` if the first hand wins.
+pub fn compare_hands(first: &[Card], second: &[Card]) -> Ordering {
+    evaluate_hand(first).cmp(&evaluate_hand(second))
+}
+
+/// Every way to choose `k` indices out of `n`, in lexicographic order.
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    let mut result = Vec::new();
+    let mut current: Vec<usize> = (0..k).collect();
+    if k > n {
+        return result;
+    }
+    loop {
+        result.push(current.clone());
+
+        let mut i = k;
+        while i > 0 && current[i - 1] == n - k + i - 1 {
+            i -= 1;
+        }
+        if i == 0 {
+            return result;
+        }
+        current[i - 1] += 1;
+        for j in i..k {
+            current[j] = current[j - 1] + 1;
+        }
+    }
+}
+
+fn evaluate_cards(cards: &[Card]) -> HandRank {
+    // Group ranks by how often they appear, biggest groups then highest ranks first.
+    let mut groups: Vec<(usize, Rank)> = Vec::with_capacity(cards.len());
+    for card in cards {
+        match groups.iter_mut().find(|(_, rank)| *rank == card.rank) {
+            Some(group) => group.0 += 1,
+            None => groups.push((1, card.rank)),
+        }
+    }
+    groups.sort_by(|a, b| b.cmp(a));
+
+    let mut counts = [0; 5];
+    for (count, (group, _)) in counts.iter_mut().zip(groups.iter()) {
+        *count = *group;
+    }
+    let counts = &counts[..groups.len().min(5)];
+    let ranks: Vec<Rank> = groups.iter().map(|(_, rank)| *rank).collect();
+
+    let is_flush = cards.len() == 5 && cards.iter().all(|card| card.suit == cards[0].suit);
+    let straight_high = if cards.len() == 5 && counts.len() == 5 {
+        straight_high_card(&ranks)
+    } else {
+        None
+    };
+
+    let (category, tiebreak) = match (straight_high, is_flush, counts) {
+        (Some(high), true, _) => (HandCategory::StraightFlush, vec![high]),
+        (_, _, [4, ..]) => (HandCategory::FourOfAKind, ranks),
+        (_, _, [3, 2]) => (HandCategory::FullHouse, ranks),
+        (_, true, _) => (HandCategory::Flush, ranks),
+        (Some(high), _, _) => (HandCategory::Straight, vec![high]),
+        (_, _, [3, ..]) => (HandCategory::ThreeOfAKind, ranks),
+        (_, _, [2, 2, ..]) => (HandCategory::TwoPair, ranks),
+        (_, _, [2, ..]) => (HandCategory::OnePair, ranks),
+        _ => (HandCategory::HighCard, ranks),
+    };
+
+    HandRank { category, tiebreak }
+}
+
+/// High card of a straight given five distinct ranks sorted high to low. The wheel
+/// (A-2-3-4-5) plays the ace low.
+fn straight_high_card(ranks: &[Rank]) -> Option<Rank> {
+    if ranks == [Rank::Ace, Rank::Five, Rank::Four, Rank::Three, Rank::Two] {
+        return Some(Rank::Five);
+    }
+    if ranks
+        .windows(2)
+        .all(|pair| pair[0] as usize == pair[1] as usize + 1)
+    {
+        Some(ranks[0])
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{combinations, compare_hands, evaluate_hand, HandCategory};
+    use crate::cards::{Card, Rank, Suit};
+    use std::cmp::Ordering;
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card::new(suit, rank)
+    }
+
+    fn category(cards: &[Card]) -> HandCategory {
+        evaluate_hand(cards).category
+    }
+
+    use Rank::*;
+    use Suit::*;
+
+    #[test]
+    /// Tests every hand category is recognised, including a flush holding the same
+    /// card twice as a shoe of several decks can deal.
+    fn test_categories() {
+        let cases = vec![
+            (
+                vec![
+                    card(Two, Spades),
+                    card(Five, Hearts),
+                    card(Nine, Clubs),
+                    card(Jack, Diamonds),
+                    card(King, Spades),
+                ],
+                HandCategory::HighCard,
+            ),
+            (
+                vec![
+                    card(Two, Spades),
+                    card(Two, Hearts),
+                    card(Nine, Clubs),
+                    card(Jack, Diamonds),
+                    card(King, Spades),
+                ],
+                HandCategory::OnePair,
+            ),
+            (
+                vec![
+                    card(Two, Spades),
+                    card(Two, Hearts),
+                    card(Nine, Clubs),
+                    card(Nine, Diamonds),
+                    card(King, Spades),
+                ],
+                HandCategory::TwoPair,
+            ),
+            (
+                vec![
+                    card(Two, Spades),
+                    card(Two, Hearts),
+                    card(Two, Clubs),
+                    card(Jack, Diamonds),
+                    card(King, Spades),
+                ],
+                HandCategory::ThreeOfAKind,
+            ),
+            (
+                vec![
+                    card(Six, Spades),
+                    card(Seven, Hearts),
+                    card(Eight, Clubs),
+                    card(Nine, Diamonds),
+                    card(Ten, Spades),
+                ],
+                HandCategory::Straight,
+            ),
+            (
+                vec![
+                    card(Two, Hearts),
+                    card(Five, Hearts),
+                    card(Nine, Hearts),
+                    card(Jack, Hearts),
+                    card(King, Hearts),
+                ],
+                HandCategory::Flush,
+            ),
+            (
+                vec![
+                    card(Two, Hearts),
+                    card(Two, Hearts),
+                    card(Nine, Hearts),
+                    card(Jack, Hearts),
+                    card(King, Hearts),
+                ],
+                HandCategory::Flush,
+            ),
+            (
+                vec![
+                    card(Two, Spades),
+                    card(Two, Hearts),
+                    card(Two, Clubs),
+                    card(King, Diamonds),
+                    card(King, Spades),
+                ],
+                HandCategory::FullHouse,
+            ),
+            (
+                vec![
+                    card(Two, Spades),
+                    card(Two, Hearts),
+                    card(Two, Clubs),
+                    card(Two, Diamonds),
+                    card(King, Spades),
+                ],
+                HandCategory::FourOfAKind,
+            ),
+            (
+                vec![
+                    card(Ten, Clubs),
+                    card(Jack, Clubs),
+                    card(Queen, Clubs),
+                    card(King, Clubs),
+                    card(Ace, Clubs),
+                ],
+                HandCategory::StraightFlush,
+            ),
+        ];
+
+        for (cards, expected) in cases {
+            assert_eq!(category(&cards), expected, "{:?}", cards);
+        }
+    }
+
+    #[test]
+    /// Tests the ace plays low in a wheel straight and doesn't wrap around.
+    fn test_wheel_straight() {
+        let wheel = vec![
+            card(Ace, Spades),
+            card(Two, Hearts),
+            card(Three, Clubs),
+            card(Four, Diamonds),
+            card(Five, Spades),
+        ];
+        let rank = evaluate_hand(&wheel);
+        assert_eq!(rank.category, HandCategory::Straight);
+        assert_eq!(rank.tiebreak, vec![Five]);
+
+        let six_high = vec![
+            card(Two, Hearts),
+            card(Three, Clubs),
+            card(Four, Diamonds),
+            card(Five, Spades),
+            card(Six, Spades),
+        ];
+        assert_eq!(compare_hands(&six_high, &wheel), Ordering::Greater);
+
+        let wrap = vec![
+            card(Queen, Spades),
+            card(King, Hearts),
+            card(Ace, Clubs),
+            card(Two, Diamonds),
+            card(Three, Spades),
+        ];
+        assert_eq!(category(&wrap), HandCategory::HighCard);
+    }
+
+    #[test]
+    /// Tests hands of the same category are decided by their ranks then kickers.
+    fn test_tiebreaks() {
+        let kings_up = vec![
+            card(King, Spades),
+            card(King, Hearts),
+            card(Four, Clubs),
+            card(Four, Diamonds),
+            card(Two, Spades),
+        ];
+        let queens_up = vec![
+            card(Queen, Spades),
+            card(Queen, Hearts),
+            card(Jack, Clubs),
+            card(Jack, Diamonds),
+            card(Ace, Spades),
+        ];
+        assert_eq!(compare_hands(&kings_up, &queens_up), Ordering::Greater);
+
+        let pair_ace_kicker = vec![
+            card(Nine, Spades),
+            card(Nine, Hearts),
+            card(Ace, Clubs),
+            card(Four, Diamonds),
+            card(Two, Spades),
+        ];
+        let pair_king_kicker = vec![
+            card(Nine, Clubs),
+            card(Nine, Diamonds),
+            card(King, Clubs),
+            card(Queen, Diamonds),
+            card(Jack, Spades),
+        ];
+        assert_eq!(
+            compare_hands(&pair_ace_kicker, &pair_king_kicker),
+            Ordering::Greater
+        );
+
+        let same = vec![
+            card(Nine, Clubs),
+            card(Nine, Diamonds),
+            card(Ace, Hearts),
+            card(Four, Spades),
+            card(Two, Clubs),
+        ];
+        assert_eq!(compare_hands(&pair_ace_kicker, &same), Ordering::Equal);
+
+        let full_house = vec![
+            card(Three, Spades),
+            card(Three, Hearts),
+            card(Three, Clubs),
+            card(Two, Diamonds),
+            card(Two, Spades),
+        ];
+        let flush = vec![
+            card(Two, Hearts),
+            card(Five, Hearts),
+            card(Nine, Hearts),
+            card(Jack, Hearts),
+            card(Ace, Hearts),
+        ];
+        assert_eq!(compare_hands(&full_house, &flush), Ordering::Greater);
+    }
+
+    #[test]
+    /// Tests the best five cards are picked out of seven.
+    fn test_best_of_seven() {
+        let cards = vec![
+            card(Two, Hearts),
+            card(King, Spades),
+            card(Five, Hearts),
+            card(Nine, Hearts),
+            card(King, Clubs),
+            card(Jack, Hearts),
+            card(Ace, Hearts),
+        ];
+        let rank = evaluate_hand(&cards);
+        assert_eq!(rank.category, HandCategory::Flush);
+        assert_eq!(rank.tiebreak, vec![Ace, Jack, Nine, Five, Two]);
+        assert_eq!(combinations(7, 5).len(), 21);
+    }
+
+    #[test]
+    /// Tests short hands can still make pairs.
+    fn test_short_hands() {
+        assert_eq!(
+            category(&[card(Ace, Spades), card(Ace, Hearts)]),
+            HandCategory::OnePair
+        );
+        assert_eq!(category(&[card(Ace, Spades)]), HandCategory::HighCard);
+        assert_eq!(category(&[]), HandCategory::HighCard);
+    }
+}
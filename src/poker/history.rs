@@ -0,0 +1,622 @@
+//! This module provides hand histories for the poker games, written in the
+//! text format PokerStars uses, which most poker tracking and analysis tools
+//! import.
+//!
+//! A game starts a `HandHistory` when it deals, adds each step of the hand to
+//! it as it's played, and once the hand is over can write it to the end of a
+//! file. Cards are written as a rank and a lowercase suit letter, `Th` for the
+//! ten of hearts:
+//!
+//! ```text
+//! PokerStars Hand #1700000000000: Hold'em Limit (10/20) - 2023/11/14 22:13:20 UTC
+//! Table 'Card Game' 3-max Seat #3 is the button
+//! Seat 1: You (100 in chips)
+//! Seat 2: Opponent 1 (100 in chips)
+//! You: posts the ante 5
+//! *** HOLE CARDS ***
+//! Dealt to You [Ah Kd]
+//! You: bets 10
+//! Opponent 1: folds
+//! ```
+
+use super::evaluate_hand;
+use crate::cards::Card;
+use crate::daily::Day;
+use std::fmt::{self, Display, Formatter};
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The table every hand is played at.
+const TABLE: &str = "Card Game";
+
+/// Which poker game a hand was.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Variant {
+    /// Fixed limit Hold'em with bets of `small_bet` on the early streets and
+    /// `big_bet` on the late ones.
+    Holdem {
+        small_bet: u64,
+        big_bet: u64,
+    },
+    FiveCardDraw,
+}
+
+impl Display for Variant {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Variant::Holdem { small_bet, big_bet } => {
+                write!(formatter, "Hold'em Limit ({}/{})", small_bet, big_bet)
+            }
+            Variant::FiveCardDraw => write!(formatter, "5 Card Draw Limit (0/0)"),
+        }
+    }
+}
+
+/// Something a seat did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Ante(u64),
+    Checks,
+    Bets(u64),
+    Calls(u64),
+    Folds,
+    /// Threw away `discarded` and was dealt `drawn` in their place.
+    Discards {
+        discarded: Vec<Card>,
+        drawn: Vec<Card>,
+    },
+}
+
+/// One step of a hand, in the order they happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+    /// The cards were dealt.
+    Deal,
+    Act {
+        seat: usize,
+        action: Action,
+    },
+    /// Community cards were dealt.
+    Board(Vec<Card>),
+    /// The draw in Five Card Draw began.
+    Draw,
+    /// A seat turned its cards over at the showdown.
+    Show {
+        seat: usize,
+        cards: Vec<Card>,
+    },
+    /// A seat took chips from the pot.
+    Collect {
+        seat: usize,
+        chips: u64,
+    },
+}
+
+/// A seat at the table when the hand was dealt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seat {
+    /// The seat's position, counting from 0.
+    pub seat: usize,
+    pub name: String,
+    /// Chips in front of the seat before the antes.
+    pub chips: u64,
+}
+
+/// Everything that happened in one hand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandHistory {
+    id: u64,
+    variant: Variant,
+    /// Seconds since 1970-01-01 in UTC.
+    started: u64,
+    max_seats: usize,
+    seats: Vec<Seat>,
+    /// The seat whose cards were dealt face up, and those cards.
+    hero: Option<(usize, Vec<Card>)>,
+    entries: Vec<Entry>,
+}
+
+impl HandHistory {
+    /// Construct the history of hand number `id` of `variant`, dealt `started`
+    /// seconds after 1970-01-01 at a table of `max_seats` seats.
+    pub fn new(id: u64, variant: Variant, started: u64, max_seats: usize) -> HandHistory {
+        HandHistory {
+            id,
+            variant,
+            started,
+            max_seats,
+            seats: Vec::new(),
+            hero: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Construct the history of a hand of `variant` dealt now, numbered by the
+    /// milliseconds since 1970-01-01 so hands from different sessions don't
+    /// share a number.
+    pub fn starting_now(variant: Variant, max_seats: usize) -> HandHistory {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        HandHistory::new(now.as_millis() as u64, variant, now.as_secs(), max_seats)
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Sits `name` at `seat` with `chips` in front of them.
+    pub fn seat(&mut self, seat: usize, name: &str, chips: u64) {
+        self.seats.push(Seat {
+            seat,
+            name: name.to_string(),
+            chips,
+        });
+    }
+
+    /// Records the deal, with the cards dealt to the player at `seat`, whose
+    /// side of the table the history is written from.
+    pub fn dealt(&mut self, seat: usize, cards: &[Card]) {
+        self.hero = Some((seat, cards.to_vec()));
+        self.entries.push(Entry::Deal);
+    }
+
+    pub fn act(&mut self, seat: usize, action: Action) {
+        self.entries.push(Entry::Act { seat, action });
+    }
+
+    pub fn board(&mut self, cards: &[Card]) {
+        self.entries.push(Entry::Board(cards.to_vec()));
+    }
+
+    pub fn draw(&mut self) {
+        self.entries.push(Entry::Draw);
+    }
+
+    pub fn show(&mut self, seat: usize, cards: &[Card]) {
+        self.entries.push(Entry::Show {
+            seat,
+            cards: cards.to_vec(),
+        });
+    }
+
+    pub fn collect(&mut self, seat: usize, chips: u64) {
+        self.entries.push(Entry::Collect { seat, chips });
+    }
+
+    /// Writes the hand to the end of the file at `path`, creating it if it
+    /// isn't there, with blank lines between it and the hand before.
+    pub fn export<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        write!(file, "{}\n\n\n", self)
+    }
+
+    fn name(&self, seat: usize) -> &str {
+        self.seats
+            .iter()
+            .find(|entry| entry.seat == seat)
+            .map_or("", |entry| entry.name.as_str())
+    }
+
+    /// Every community card dealt.
+    fn full_board(&self) -> Vec<Card> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match entry {
+                Entry::Board(cards) => Some(cards.clone()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// The chips put into the pot, antes, bets and calls.
+    fn pot(&self) -> u64 {
+        self.entries
+            .iter()
+            .map(|entry| match entry {
+                Entry::Act {
+                    action: Action::Ante(chips),
+                    ..
+                }
+                | Entry::Act {
+                    action: Action::Bets(chips),
+                    ..
+                }
+                | Entry::Act {
+                    action: Action::Calls(chips),
+                    ..
+                } => *chips,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// How the hand ended for `seat`, for the summary.
+    fn outcome(&self, seat: usize) -> String {
+        let folded = self.entries.iter().position(|entry| {
+            matches!(entry, Entry::Act { seat: folder, action: Action::Folds } if *folder == seat)
+        });
+        if let Some(position) = folded {
+            return format!("folded {}", self.folded_on(position));
+        }
+        let collected = self.entries.iter().find_map(|entry| match entry {
+            Entry::Collect {
+                seat: collector,
+                chips,
+            } if *collector == seat => Some(*chips),
+            _ => None,
+        });
+        let shown = self.entries.iter().find_map(|entry| match entry {
+            Entry::Show {
+                seat: shower,
+                cards,
+            } if *shower == seat => Some(cards),
+            _ => None,
+        });
+        match (shown, collected) {
+            (Some(cards), Some(chips)) => format!(
+                "showed [{}] and won ({}) with {}",
+                notation(cards),
+                chips,
+                self.made_hand(cards)
+            ),
+            (Some(cards), None) => format!(
+                "showed [{}] and lost with {}",
+                notation(cards),
+                self.made_hand(cards)
+            ),
+            (None, Some(chips)) => format!("collected ({})", chips),
+            (None, None) => "mucked".to_string(),
+        }
+    }
+
+    /// The street the fold at `position` in the entries came on.
+    fn folded_on(&self, position: usize) -> &'static str {
+        if matches!(self.variant, Variant::FiveCardDraw) {
+            let drawn = self.entries[..position]
+                .iter()
+                .any(|entry| matches!(entry, Entry::Draw));
+            return if drawn {
+                "after the Draw"
+            } else {
+                "before the Draw"
+            };
+        }
+        let boards = self.entries[..position]
+            .iter()
+            .filter(|entry| matches!(entry, Entry::Board(_)))
+            .count();
+        match boards {
+            0 => "before Flop",
+            1 => "on the Flop",
+            2 => "on the Turn",
+            _ => "on the River",
+        }
+    }
+
+    /// The best hand `cards` make with the board.
+    fn made_hand(&self, cards: &[Card]) -> String {
+        let mut all = cards.to_vec();
+        all.extend(self.full_board());
+        evaluate_hand(&all).to_string()
+    }
+
+    fn write_header(&self, formatter: &mut Formatter) -> fmt::Result {
+        let day = Day(self.started / 86_400);
+        let (year, month, date) = day.date();
+        let secs = self.started % 86_400;
+        writeln!(
+            formatter,
+            "PokerStars Hand #{}: {} - {}/{:02}/{:02} {:02}:{:02}:{:02} UTC",
+            self.id,
+            self.variant,
+            year,
+            month,
+            date,
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )?;
+        writeln!(
+            formatter,
+            "Table '{}' {}-max Seat #{} is the button",
+            TABLE, self.max_seats, self.max_seats
+        )?;
+        for seat in &self.seats {
+            writeln!(
+                formatter,
+                "Seat {}: {} ({} in chips)",
+                seat.seat + 1,
+                seat.name,
+                seat.chips
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the lines for `entry`. `board` holds the community cards dealt
+    /// before it and `hand` the hero's cards so far, and both are kept up to
+    /// date.
+    fn write_entry(
+        &self,
+        formatter: &mut Formatter,
+        entry: &Entry,
+        board: &mut Vec<Card>,
+        hand: &mut Vec<Card>,
+    ) -> fmt::Result {
+        match entry {
+            Entry::Deal => {
+                let section = match self.variant {
+                    Variant::Holdem { .. } => "HOLE CARDS",
+                    Variant::FiveCardDraw => "DEALING HANDS",
+                };
+                writeln!(formatter, "*** {} ***", section)?;
+                if let Some((seat, cards)) = &self.hero {
+                    *hand = cards.clone();
+                    writeln!(
+                        formatter,
+                        "Dealt to {} [{}]",
+                        self.name(*seat),
+                        notation(hand)
+                    )?;
+                }
+                Ok(())
+            }
+            Entry::Act { seat, action } => self.write_action(formatter, *seat, action, hand),
+            Entry::Board(cards) => {
+                let street = match board.len() {
+                    0 => "FLOP",
+                    3 => "TURN",
+                    _ => "RIVER",
+                };
+                match board.is_empty() {
+                    true => writeln!(formatter, "*** {} *** [{}]", street, notation(cards))?,
+                    false => writeln!(
+                        formatter,
+                        "*** {} *** [{}] [{}]",
+                        street,
+                        notation(board),
+                        notation(cards)
+                    )?,
+                }
+                board.extend_from_slice(cards);
+                Ok(())
+            }
+            Entry::Draw => writeln!(formatter, "*** FIRST DRAW ***"),
+            Entry::Show { seat, cards } => writeln!(
+                formatter,
+                "{}: shows [{}] ({})",
+                self.name(*seat),
+                notation(cards),
+                self.made_hand(cards)
+            ),
+            Entry::Collect { seat, chips } => writeln!(
+                formatter,
+                "{} collected {} from pot",
+                self.name(*seat),
+                chips
+            ),
+        }
+    }
+
+    fn write_action(
+        &self,
+        formatter: &mut Formatter,
+        seat: usize,
+        action: &Action,
+        hand: &mut Vec<Card>,
+    ) -> fmt::Result {
+        let name = self.name(seat);
+        match action {
+            Action::Ante(chips) => writeln!(formatter, "{}: posts the ante {}", name, chips),
+            Action::Checks => writeln!(formatter, "{}: checks", name),
+            Action::Bets(chips) => writeln!(formatter, "{}: bets {}", name, chips),
+            Action::Calls(chips) => writeln!(formatter, "{}: calls {}", name, chips),
+            Action::Folds => writeln!(formatter, "{}: folds", name),
+            Action::Discards { discarded, .. } if discarded.is_empty() => {
+                writeln!(formatter, "{}: stands pat", name)
+            }
+            Action::Discards { discarded, drawn } => {
+                let count = match discarded.len() {
+                    1 => "1 card".to_string(),
+                    count => format!("{} cards", count),
+                };
+                if self.hero.as_ref().map(|(hero, _)| *hero) != Some(seat) {
+                    return writeln!(formatter, "{}: discards {}", name, count);
+                }
+                writeln!(
+                    formatter,
+                    "{}: discards {} [{}]",
+                    name,
+                    count,
+                    notation(discarded)
+                )?;
+                hand.retain(|card| !discarded.contains(card));
+                writeln!(
+                    formatter,
+                    "Dealt to {} [{}] [{}]",
+                    name,
+                    notation(hand),
+                    notation(drawn)
+                )?;
+                hand.extend_from_slice(drawn);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Displays the hand in PokerStars' hand history format.
+impl Display for HandHistory {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        self.write_header(formatter)?;
+        let (mut board, mut hand) = (Vec::new(), Vec::new());
+        let mut shown = false;
+        for entry in &self.entries {
+            if matches!(entry, Entry::Show { .. }) && !shown {
+                shown = true;
+                writeln!(formatter, "*** SHOW DOWN ***")?;
+            }
+            self.write_entry(formatter, entry, &mut board, &mut hand)?;
+        }
+
+        writeln!(formatter, "*** SUMMARY ***")?;
+        writeln!(formatter, "Total pot {} | Rake 0", self.pot())?;
+        if !board.is_empty() {
+            writeln!(formatter, "Board [{}]", notation(&board))?;
+        }
+        let summary: Vec<String> = self
+            .seats
+            .iter()
+            .map(|seat| {
+                format!(
+                    "Seat {}: {} {}",
+                    seat.seat + 1,
+                    seat.name,
+                    self.outcome(seat.seat)
+                )
+            })
+            .collect();
+        write!(formatter, "{}", summary.join("\n"))
+    }
+}
+
+/// The cards in hand history notation, separated by spaces, e.g. `Ah Td`.
+pub fn notation(cards: &[Card]) -> String {
+    let cards: Vec<String> = cards.iter().map(Card::ascii).collect();
+    cards.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{notation, Action, HandHistory, Variant};
+    use crate::cards::{Card, Rank, Suit};
+
+    /// 2023-11-14 22:13:20 UTC.
+    const STARTED: u64 = 1_700_000_000;
+
+    fn cards(cards: &[(Suit, Rank)]) -> Vec<Card> {
+        cards
+            .iter()
+            .map(|&(suit, rank)| Card::new(suit, rank))
+            .collect()
+    }
+
+    #[test]
+    /// Tests cards are written as a rank and a lowercase suit letter.
+    fn test_notation() {
+        let hand = cards(&[(Suit::Hearts, Rank::Ace), (Suit::Clubs, Rank::Two)]);
+        assert_eq!(notation(&hand), "Ah 2c");
+    }
+
+    #[test]
+    /// Tests a Hold'em hand played to the river and shown down.
+    fn test_holdem() {
+        let variant = Variant::Holdem {
+            small_bet: 10,
+            big_bet: 20,
+        };
+        let mut history = HandHistory::new(7, variant, STARTED, 3);
+        history.seat(0, "You", 100);
+        history.seat(1, "Opponent 1", 100);
+        history.seat(2, "Opponent 2", 35);
+        for seat in 0..3 {
+            history.act(seat, Action::Ante(5));
+        }
+        history.dealt(
+            0,
+            &cards(&[(Suit::Clubs, Rank::Jack), (Suit::Spades, Rank::Three)]),
+        );
+        history.act(0, Action::Bets(10));
+        history.act(1, Action::Calls(10));
+        history.act(2, Action::Folds);
+        history.board(&cards(&[
+            (Suit::Hearts, Rank::Two),
+            (Suit::Clubs, Rank::Seven),
+            (Suit::Spades, Rank::Nine),
+        ]));
+        history.act(0, Action::Checks);
+        history.act(1, Action::Checks);
+        history.board(&cards(&[(Suit::Hearts, Rank::Jack)]));
+        history.act(0, Action::Bets(20));
+        history.act(1, Action::Calls(20));
+        history.board(&cards(&[(Suit::Diamonds, Rank::Four)]));
+        history.act(0, Action::Checks);
+        history.act(1, Action::Checks);
+        history.show(
+            0,
+            &cards(&[(Suit::Clubs, Rank::Jack), (Suit::Spades, Rank::Three)]),
+        );
+        history.show(
+            1,
+            &cards(&[(Suit::Spades, Rank::Ace), (Suit::Spades, Rank::King)]),
+        );
+        history.collect(0, 75);
+        insta::assert_snapshot!(history.to_string());
+    }
+
+    #[test]
+    /// Tests a Five Card Draw hand shows the player's draw but only how many
+    /// cards the dealer drew.
+    fn test_five_card_draw() {
+        let mut history = HandHistory::new(8, Variant::FiveCardDraw, STARTED, 2);
+        history.seat(0, "You", 0);
+        history.seat(1, "Dealer", 0);
+        let dealt = cards(&[
+            (Suit::Spades, Rank::King),
+            (Suit::Hearts, Rank::Nine),
+            (Suit::Diamonds, Rank::King),
+            (Suit::Clubs, Rank::Four),
+            (Suit::Spades, Rank::Two),
+        ]);
+        history.dealt(0, &dealt);
+        history.draw();
+        history.act(
+            0,
+            Action::Discards {
+                discarded: vec![dealt[1], dealt[3], dealt[4]],
+                drawn: cards(&[
+                    (Suit::Hearts, Rank::King),
+                    (Suit::Clubs, Rank::Six),
+                    (Suit::Hearts, Rank::Three),
+                ]),
+            },
+        );
+        history.act(
+            1,
+            Action::Discards {
+                discarded: Vec::new(),
+                drawn: Vec::new(),
+            },
+        );
+        history.show(
+            0,
+            &cards(&[
+                (Suit::Spades, Rank::King),
+                (Suit::Hearts, Rank::King),
+                (Suit::Diamonds, Rank::King),
+                (Suit::Clubs, Rank::Six),
+                (Suit::Hearts, Rank::Three),
+            ]),
+        );
+        history.show(
+            1,
+            &cards(&[
+                (Suit::Spades, Rank::Five),
+                (Suit::Spades, Rank::Six),
+                (Suit::Spades, Rank::Seven),
+                (Suit::Hearts, Rank::Eight),
+                (Suit::Spades, Rank::Nine),
+            ]),
+        );
+        history.collect(1, 0);
+        insta::assert_snapshot!(history.to_string());
+    }
+}
@@ -0,0 +1,294 @@
+//! This module provides named player profiles and the leaderboard ranking them.
+//!
+//! Every profile keeps its own lifetime stats, the achievements it has unlocked
+//! and the chips left over from its last betting session. All of them are kept
+//! in one file next to the shared stats.
+
+use crate::rating::Rating;
+use crate::stats::Stats;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self};
+use std::path::{Path, PathBuf};
+
+/// A milestone a player unlocks once.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Achievement {
+    FirstWin,
+    HotStreak,
+    OnFire,
+    Regular,
+    HighRoller,
+}
+
+impl Achievement {
+    /// Every achievement, in the order they're listed.
+    pub const ALL: [Achievement; 5] = [
+        Achievement::FirstWin,
+        Achievement::HotStreak,
+        Achievement::OnFire,
+        Achievement::Regular,
+        Achievement::HighRoller,
+    ];
+
+    /// What it takes to unlock.
+    pub fn description(self) -> &'static str {
+        match self {
+            Achievement::FirstWin => "find the high card",
+            Achievement::HotStreak => "win 5 rounds in a row",
+            Achievement::OnFire => "win 10 rounds in a row",
+            Achievement::Regular => "play 100 rounds",
+            Achievement::HighRoller => "hold 1000 chips",
+        }
+    }
+
+    /// Whether `profile` has done what it takes.
+    fn earned(self, profile: &Profile) -> bool {
+        let stats = &profile.stats;
+        match self {
+            Achievement::FirstWin => stats.wins > 0,
+            Achievement::HotStreak => stats.longest_streak >= 5,
+            Achievement::OnFire => stats.longest_streak >= 10,
+            Achievement::Regular => stats.games >= 100,
+            Achievement::HighRoller => profile.chips.unwrap_or(0) >= 1000,
+        }
+    }
+}
+
+impl Display for Achievement {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Achievement::FirstWin => "First Win",
+            Achievement::HotStreak => "Hot Streak",
+            Achievement::OnFire => "On Fire",
+            Achievement::Regular => "Regular",
+            Achievement::HighRoller => "High Roller",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+
+/// One player's record.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    pub stats: Stats,
+    pub achievements: BTreeSet<Achievement>,
+    /// Chips left when the player last stopped betting, carried into their next
+    /// game. None once they've gone bust or never bet.
+    pub chips: Option<u64>,
+    /// Elo rating from matches against the computer opponent and over the
+    /// network.
+    #[serde(default)]
+    pub rating: Rating,
+}
+
+impl Profile {
+    /// Unlocks every achievement newly earned, returning them.
+    pub fn unlock(&mut self) -> Vec<Achievement> {
+        let earned: Vec<Achievement> = Achievement::ALL
+            .iter()
+            .copied()
+            .filter(|achievement| !self.achievements.contains(achievement))
+            .filter(|achievement| achievement.earned(self))
+            .collect();
+        self.achievements.extend(&earned);
+        earned
+    }
+}
+
+/// Displays the profile's stats and achievements.
+impl Display for Profile {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        writeln!(formatter, "{}", self.stats)?;
+        if let Some(chips) = self.chips {
+            writeln!(formatter, "  Chips           {}", chips)?;
+        }
+        if self.rating.matches > 0 {
+            writeln!(formatter, "  Rating          {}", self.rating)?;
+        }
+        write!(formatter, "  Achievements")?;
+        for achievement in Achievement::ALL.iter() {
+            let mark = if self.achievements.contains(achievement) {
+                'x'
+            } else {
+                ' '
+            };
+            write!(
+                formatter,
+                "\n    [{}] {} - {}",
+                mark,
+                achievement,
+                achievement.description()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Every named profile.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profiles {
+    players: BTreeMap<String, Profile>,
+}
+
+impl Profiles {
+    /// Construct an empty set of profiles.
+    pub fn new() -> Profiles {
+        Profiles::default()
+    }
+
+    /// Where profiles are kept: `profiles.json` next to the lifetime stats.
+    pub fn default_path() -> Option<PathBuf> {
+        let data_home = match env::var_os("XDG_DATA_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".local/share"),
+        };
+        Some(data_home.join("card_game").join("profiles.json"))
+    }
+
+    /// Loads profiles from `path`. A missing file means nobody has played yet.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Profiles> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Profiles::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Saves profiles to `path`, creating its directory if needed.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, json)
+    }
+
+    /// The profile called `name`, if there is one.
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.players.get(name)
+    }
+
+    /// The profile called `name`, created empty if it's new.
+    pub fn profile(&mut self, name: &str) -> &mut Profile {
+        self.players.entry(name.to_string()).or_default()
+    }
+
+    /// Every profile, best win rate first. Ties go to the longer best streak,
+    /// then to the name.
+    pub fn ranked(&self) -> Vec<(&str, &Profile)> {
+        let mut ranked: Vec<(&str, &Profile)> = self
+            .players
+            .iter()
+            .map(|(name, profile)| (name.as_str(), profile))
+            .collect();
+        ranked.sort_by(|(_, a), (_, b)| {
+            b.stats
+                .win_rate()
+                .partial_cmp(&a.stats.win_rate())
+                .unwrap_or(Ordering::Equal)
+                .then(b.stats.longest_streak.cmp(&a.stats.longest_streak))
+        });
+        ranked
+    }
+}
+
+/// Displays the leaderboard screen.
+impl Display for Profiles {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        writeln!(formatter, "*-------------- Leaderboard --------------*")?;
+        writeln!(
+            formatter,
+            "  #  {:<12} {:>8} {:>7} {:>6}",
+            "Player", "Win rate", "Streak", "Games"
+        )?;
+        for (place, (name, profile)) in self.ranked().into_iter().enumerate() {
+            let stats = &profile.stats;
+            writeln!(
+                formatter,
+                "  {:<2} {:<12} {:>7.1}% {:>7} {:>6}",
+                place + 1,
+                name,
+                stats.win_rate(),
+                stats.longest_streak,
+                stats.games
+            )?;
+        }
+        if self.players.is_empty() {
+            writeln!(formatter, "  No profiles yet. Play with --player NAME.")?;
+        }
+        write!(formatter, "*-----------------------------------------*")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Achievement, Profiles};
+    use std::cmp::Ordering;
+
+    #[test]
+    /// Tests achievements unlock once each.
+    fn test_unlock() {
+        let mut profiles = Profiles::new();
+        let profile = profiles.profile("alice");
+        assert!(profile.unlock().is_empty());
+
+        for _ in 0..5 {
+            profile.stats.record(true);
+        }
+        profile.chips = Some(1200);
+        assert_eq!(
+            profile.unlock(),
+            vec![
+                Achievement::FirstWin,
+                Achievement::HotStreak,
+                Achievement::HighRoller
+            ]
+        );
+        profile.chips = None;
+        assert!(profile.unlock().is_empty());
+        assert_eq!(profile.achievements.len(), 3);
+
+        assert!(!profile.to_string().contains("Rating"));
+        profile.rating.record(1000, Ordering::Less, 32);
+        assert!(profile
+            .to_string()
+            .contains("Rating          1176 from 1 match"));
+    }
+
+    #[test]
+    /// Tests the leaderboard ranks by win rate, then by best streak.
+    fn test_leaderboard() {
+        let mut profiles = Profiles::new();
+        for (name, results) in [
+            ("alice", &[true, false, false, false][..]),
+            ("bob", &[true, true, false, true, true, true, false][..]),
+            ("carol", &[false, true, false, true][..]),
+            ("dave", &[][..]),
+        ]
+        .iter()
+        {
+            let profile = profiles.profile(name);
+            for won in results.iter() {
+                profile.stats.record(*won);
+            }
+        }
+
+        let names: Vec<&str> = profiles.ranked().iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["bob", "carol", "alice", "dave"]);
+        insta::assert_snapshot!(profiles.to_string());
+
+        let path =
+            std::env::temp_dir().join(format!("card_game_profiles_{}.json", std::process::id()));
+        profiles.save(&path).unwrap();
+        assert_eq!(Profiles::load(&path).unwrap(), profiles);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
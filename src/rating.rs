@@ -0,0 +1,116 @@
+//! This module provides Elo ratings for player profiles.
+//!
+//! A rating goes up after a win and down after a loss, by more the less the
+//! result was expected: beating someone rated far higher gains a lot, beating
+//! someone rated far lower hardly anything. The K-factor is the most one match
+//! can move a rating. Each of the computer opponent's skills has a fixed rating,
+//! so playing it only moves the player's.
+
+use crate::opponent::Skill;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+/// Every player's rating before their first match.
+pub const INITIAL: i32 = 1200;
+
+/// The K-factor used unless another is configured.
+pub const DEFAULT_K_FACTOR: u32 = 32;
+
+/// A player's rating and how many rated matches it comes from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rating {
+    pub value: i32,
+    pub matches: usize,
+}
+
+impl Default for Rating {
+    fn default() -> Rating {
+        Rating {
+            value: INITIAL,
+            matches: 0,
+        }
+    }
+}
+
+impl Rating {
+    /// Construct the rating of a player who hasn't played a rated match.
+    pub fn new() -> Rating {
+        Rating::default()
+    }
+
+    /// The chance of beating a player rated `opponent`, from 0 to 1, counting a
+    /// draw as half a win.
+    pub fn expected(&self, opponent: i32) -> f64 {
+        1.0 / (1.0 + 10f64.powf((opponent - self.value) as f64 / 400.0))
+    }
+
+    /// Updates the rating after a match against a player rated `opponent`, with
+    /// `result` how this player did, e.g. `Greater` for a win. Returns how far
+    /// the rating moved.
+    pub fn record(&mut self, opponent: i32, result: Ordering, k_factor: u32) -> i32 {
+        let score = match result {
+            Ordering::Greater => 1.0,
+            Ordering::Equal => 0.5,
+            Ordering::Less => 0.0,
+        };
+        let change = (k_factor as f64 * (score - self.expected(opponent))).round() as i32;
+        self.value += change;
+        self.matches += 1;
+        change
+    }
+}
+
+impl Display for Rating {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self.matches {
+            1 => write!(formatter, "{} from 1 match", self.value),
+            matches => write!(formatter, "{} from {} matches", self.value, matches),
+        }
+    }
+}
+
+/// The fixed rating of the computer opponent playing with `skill`.
+pub fn skill_rating(skill: Skill) -> i32 {
+    match skill {
+        Skill::Random => 1000,
+        Skill::SuitAware => 1200,
+        Skill::CardCounting => 1400,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{skill_rating, Rating, DEFAULT_K_FACTOR, INITIAL};
+    use crate::opponent::Skill;
+    use std::cmp::Ordering;
+
+    #[test]
+    /// Tests ratings move by how surprising the result was, scaled by K.
+    fn test_record() {
+        let mut rating = Rating::new();
+        assert_eq!(rating.expected(INITIAL), 0.5);
+        assert!((rating.expected(INITIAL + 400) - 1.0 / 11.0).abs() < 1e-9);
+
+        assert_eq!(
+            rating.record(INITIAL, Ordering::Greater, DEFAULT_K_FACTOR),
+            16
+        );
+        assert_eq!(rating.value, 1216);
+        let mut level = Rating::new();
+        assert_eq!(level.record(INITIAL, Ordering::Equal, DEFAULT_K_FACTOR), 0);
+
+        // Losing to a far weaker player costs nearly all of K.
+        let mut favourite = Rating::new();
+        assert_eq!(favourite.record(800, Ordering::Less, 16), -15);
+        assert_eq!(favourite.to_string(), "1185 from 1 match");
+        assert_eq!(rating.matches + level.matches, 2);
+    }
+
+    #[test]
+    /// Tests the computer's skills are rated weakest first.
+    fn test_skill_rating() {
+        assert!(skill_rating(Skill::Random) < skill_rating(Skill::SuitAware));
+        assert!(skill_rating(Skill::SuitAware) < skill_rating(Skill::CardCounting));
+    }
+}
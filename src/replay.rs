@@ -0,0 +1,184 @@
+//! This module provides recording and replaying of whole high card sessions.
+//!
+//! A replay only holds the game settings, the seed and the card picked each round.
+//! The settings are everything that changes which cards are dealt or who wins:
+//! the card count, difficulty, opponent, rules, round limit and any deck given
+//! to deal from in order. Replaying deals the same cards from the seed and makes
+//! the same picks. Wagers aren't recorded, multiplayer rounds have no pick to
+//! record, and a session resumed from a save can't be replayed because loading
+//! a game reseeds its generator.
+//!
+//! Sessions are saved as JSON, or in the text notation of `notation` for files
+//! ending `.hcn`, which suits pasting into a bug report.
+
+use crate::cards::{Card, Deck};
+use crate::error::GameConfigError;
+use crate::game::state::{Event, Input};
+use crate::game::{Difficulty, Game, GameBuilder, RoundLimit};
+use crate::opponent::{Opponent, Skill};
+use crate::rules::Ruleset;
+use notation::Notation;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{self};
+use std::path::Path;
+
+pub mod notation;
+
+/// A recorded session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub card_count: usize,
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub opponent: Option<Skill>,
+    #[serde(default = "Ruleset::high_card")]
+    pub rules: Ruleset,
+    #[serde(default)]
+    pub rounds: RoundLimit,
+    /// The deck dealt from in order, top card first, if the game was given one.
+    #[serde(default)]
+    pub deck: Option<Vec<Card>>,
+    pub choices: Vec<usize>,
+}
+
+impl Replay {
+    /// Records the session played so far in `game`.
+    pub fn from_game(game: &Game) -> Replay {
+        Replay {
+            seed: game.seed(),
+            card_count: game.card_count(),
+            difficulty: game.difficulty(),
+            opponent: game.opponent().map(Opponent::skill),
+            rules: game.scoring().rules().unwrap_or_else(Ruleset::high_card),
+            rounds: game.round_limit(),
+            deck: game.given_deck().map(<[Card]>::to_vec),
+            choices: game
+                .history()
+                .rounds()
+                .iter()
+                .map(|round| round.choice)
+                .collect(),
+        }
+    }
+
+    /// Saves the replay to a JSON file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, json)
+    }
+
+    /// Saves the session played so far in `game`, in the notation if `path`
+    /// ends `.hcn` and as JSON otherwise.
+    pub fn record<P: AsRef<Path>>(game: &Game, path: P) -> io::Result<()> {
+        match path.as_ref().extension() == Some(OsStr::new(notation::EXTENSION)) {
+            true => Notation::from_game(game).save(path),
+            false => Replay::from_game(game).save(path),
+        }
+    }
+
+    /// Loads a replay saved with `save` or `record`. Notation must replay the
+    /// way it's written.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Replay> {
+        let text = fs::read_to_string(path)?;
+        let invalid = |err| io::Error::new(io::ErrorKind::InvalidData, err);
+        if text.trim_start().starts_with('[') {
+            let notation: Notation = text.parse().map_err(invalid)?;
+            notation.verify().map_err(invalid)?;
+            return Ok(notation.replay);
+        }
+        serde_json::from_str(&text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Starts replaying the session from a freshly dealt game. Fails if the file
+    /// holds settings no game could be played with.
+    pub fn replayer(&self) -> Result<Replayer, GameConfigError> {
+        let mut builder = GameBuilder::new()
+            .max_cards(self.card_count.min(u8::MAX as usize) as u8)
+            .difficulty(self.difficulty)
+            .rules(self.rules)
+            .rounds(self.rounds)
+            .with_seed(self.seed);
+        if let Some(skill) = self.opponent {
+            builder = builder.opponent(skill);
+        }
+        if let Some(cards) = &self.deck {
+            builder = builder.deck(Deck::from_cards(cards.clone()));
+        }
+        let game = builder.spawn()?;
+        Ok(Replayer {
+            game,
+            choices: self.choices.clone().into_iter(),
+        })
+    }
+}
+
+/// Steps through a replay one round at a time.
+pub struct Replayer {
+    game: Game,
+    choices: std::vec::IntoIter<usize>,
+}
+
+impl Replayer {
+    /// Deals the next round and makes the recorded pick, leaving the hand face
+    /// up. Returns the pick, or None once every round has been replayed.
+    pub fn next_round(&mut self) -> Option<usize> {
+        let choice = self.choices.next()?;
+        self.game.advance(Input::Continue);
+        if self.game.advance(Input::Deal) != Event::Dealt {
+            return None;
+        }
+        self.game.advance(Input::Choose(choice));
+        self.game.advance(Input::Reveal);
+        Some(choice)
+    }
+
+    /// The game being replayed.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Replay;
+    use crate::game::state::{Event, Input};
+    use crate::game::{Difficulty, GameBuilder};
+
+    #[test]
+    /// Tests a replay deals the same cards and reaches the same score.
+    fn test_replay_repeats_session() {
+        let mut game = GameBuilder::new()
+            .difficulty(Difficulty::Hard)
+            .with_seed(21)
+            .spawn()
+            .unwrap();
+        while game.advance(Input::Deal) == Event::Dealt {
+            game.advance(Input::Random);
+            game.advance(Input::Reveal);
+            game.advance(Input::Continue);
+        }
+
+        let path = std::env::temp_dir().join("card_game_test_replay.json");
+        Replay::from_game(&game).save(&path).unwrap();
+        let replay = Replay::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut replayer = replay.replayer().unwrap();
+        while replayer.next_round().is_some() {}
+        assert_eq!(replayer.game().history().len(), 17);
+        assert_eq!(replayer.game().wins(), game.wins());
+        for (replayed, played) in replayer
+            .game()
+            .history()
+            .rounds()
+            .iter()
+            .zip(game.history().rounds())
+        {
+            assert_eq!(replayed.cards, played.cards);
+        }
+    }
+}
@@ -0,0 +1,436 @@
+//! This module provides a compact text notation for high card sessions,
+//! modelled on the portable game notation chess players share games in.
+//!
+//! Tag pairs in square brackets give what the session was dealt with, and each
+//! line after them is a round: its number, the cards dealt, the card picked
+//! counting from 0, and `W` for a win or `L` for a loss. Anything after a `;` is
+//! a comment. Tags other than the ones below are kept out of the way rather
+//! than rejected, so notation can carry notes of its own:
+//!
+//! ```text
+//! [Game "High Card"]
+//! [Seed "21"]
+//! [Cards "3"]
+//! [Difficulty "hard"]
+//! [Opponent "card-counting"]
+//! [Aces "low"]
+//! [Tiebreak "bridge"]
+//! [Rounds "2"]
+//! [Result "1-1"]
+//!
+//! 1. Th Qs 2c 1 W
+//! 2. 9d Ac 4h 2 L ; the ace was right there
+//! ```
+//!
+//! Aces, Tiebreak, Rounds and Deck, the cards dealt in order when the game was
+//! given a deck, are only written when they aren't the defaults.
+//!
+//! The seed and the picks are enough to replay the session. The cards and
+//! results are written out so a reader can follow the game without running
+//! it, and are checked against the replay when the notation is read back.
+
+use super::Replay;
+use crate::cards::Card;
+use crate::error::GameConfigError;
+use crate::game::{Difficulty, Game, RoundLimit};
+use crate::opponent::Skill;
+use crate::rules::{AceRule, Ruleset, SuitTiebreak};
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self};
+use std::path::Path;
+use std::str::FromStr;
+
+/// The file extension notation is saved under.
+pub const EXTENSION: &str = "hcn";
+
+/// One round as the notation gives it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotatedRound {
+    pub cards: Vec<Card>,
+    pub choice: usize,
+    pub won: bool,
+}
+
+/// A session written out in the notation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notation {
+    pub replay: Replay,
+    pub rounds: Vec<NotatedRound>,
+}
+
+/// Why notation couldn't be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotationError {
+    /// Line `line`, counting from 1, isn't a tag or a round.
+    Line { line: usize, reason: String },
+    /// A tag the notation needs isn't there.
+    MissingTag(&'static str),
+    /// Replaying the session didn't deal or score round `round` the way the
+    /// notation says.
+    Mismatch { round: usize },
+    /// The tags give settings no game could be played with.
+    Config(GameConfigError),
+}
+
+impl Display for NotationError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            NotationError::Line { line, reason } => write!(formatter, "line {}: {}", line, reason),
+            NotationError::MissingTag(tag) => write!(formatter, "there's no {} tag", tag),
+            NotationError::Mismatch { round } => write!(
+                formatter,
+                "round {} doesn't replay the way it's written",
+                round
+            ),
+            NotationError::Config(err) => write!(formatter, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for NotationError {}
+
+impl From<GameConfigError> for NotationError {
+    fn from(err: GameConfigError) -> NotationError {
+        NotationError::Config(err)
+    }
+}
+
+impl Notation {
+    /// Writes out the session played so far in `game`. The cards are kept face
+    /// down, as they read back.
+    pub fn from_game(game: &Game) -> Notation {
+        Notation {
+            replay: Replay::from_game(game),
+            rounds: game
+                .history()
+                .rounds()
+                .iter()
+                .map(|round| NotatedRound {
+                    cards: round
+                        .cards
+                        .iter()
+                        .map(|card| Card::new(card.suit, card.rank))
+                        .collect(),
+                    choice: round.choice,
+                    won: round.won,
+                })
+                .collect(),
+        }
+    }
+
+    /// Saves the notation to a text file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// Replays the session and checks every round deals the cards and ends the
+    /// way the notation says.
+    pub fn verify(&self) -> Result<(), NotationError> {
+        let mut replayer = self.replay.replayer()?;
+        for (idx, round) in self.rounds.iter().enumerate() {
+            let mismatch = NotationError::Mismatch { round: idx + 1 };
+            replayer.next_round().ok_or_else(|| mismatch.clone())?;
+            let played = replayer
+                .game()
+                .history()
+                .rounds()
+                .last()
+                .ok_or_else(|| mismatch.clone())?;
+            let ids = |cards: &[Card]| cards.iter().map(Card::id).collect::<Vec<u8>>();
+            if ids(&played.cards) != ids(&round.cards) || played.won != round.won {
+                return Err(mismatch);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Displays the session in the notation, tags first.
+impl Display for Notation {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let replay = &self.replay;
+        let wins = self.rounds.iter().filter(|round| round.won).count();
+        writeln!(formatter, "[Game \"High Card\"]")?;
+        writeln!(formatter, "[Seed \"{}\"]", replay.seed)?;
+        writeln!(formatter, "[Cards \"{}\"]", replay.card_count)?;
+        writeln!(
+            formatter,
+            "[Difficulty \"{}\"]",
+            tag_name(&replay.difficulty.to_string())
+        )?;
+        if let Some(skill) = replay.opponent {
+            writeln!(formatter, "[Opponent \"{}\"]", tag_name(&skill.to_string()))?;
+        }
+        let rules = Ruleset::high_card();
+        if replay.rules.aces != rules.aces {
+            writeln!(formatter, "[Aces \"{}\"]", replay.rules.aces.name())?;
+        }
+        if replay.rules.tiebreak != rules.tiebreak {
+            writeln!(formatter, "[Tiebreak \"{}\"]", replay.rules.tiebreak.name())?;
+        }
+        match replay.rounds {
+            RoundLimit::DeckEmpty => {}
+            RoundLimit::Rounds(rounds) => writeln!(formatter, "[Rounds \"{}\"]", rounds)?,
+            RoundLimit::Endless => writeln!(formatter, "[Rounds \"endless\"]")?,
+        }
+        if let Some(deck) = &replay.deck {
+            let cards: Vec<String> = deck.iter().map(Card::ascii).collect();
+            writeln!(formatter, "[Deck \"{}\"]", cards.join(" "))?;
+        }
+        writeln!(
+            formatter,
+            "[Result \"{}-{}\"]",
+            wins,
+            self.rounds.len() - wins
+        )?;
+        writeln!(formatter)?;
+        for (idx, round) in self.rounds.iter().enumerate() {
+            let cards: Vec<String> = round.cards.iter().map(Card::ascii).collect();
+            let result = if round.won { "W" } else { "L" };
+            writeln!(
+                formatter,
+                "{}. {} {} {}",
+                idx + 1,
+                cards.join(" "),
+                round.choice,
+                result
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads notation written by `Display`, or by hand. The Seed tag is needed,
+/// and the others default to a normal game of three cards.
+impl FromStr for Notation {
+    type Err = NotationError;
+
+    fn from_str(input: &str) -> Result<Notation, NotationError> {
+        let mut replay = Replay {
+            seed: 0,
+            card_count: 3,
+            difficulty: Difficulty::Normal,
+            opponent: None,
+            rules: Ruleset::high_card(),
+            rounds: RoundLimit::DeckEmpty,
+            deck: None,
+            choices: Vec::new(),
+        };
+        let mut seeded = false;
+        let mut rounds = Vec::new();
+        for (idx, line) in input.lines().enumerate() {
+            let line = line.split(';').next().unwrap_or("").trim();
+            let fail = |reason: &str| NotationError::Line {
+                line: idx + 1,
+                reason: reason.to_string(),
+            };
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with('[') {
+                let (tag, value) = parse_tag(line).ok_or_else(|| fail("isn't a tag"))?;
+                match tag {
+                    "Seed" => {
+                        replay.seed = value.parse().map_err(|_| fail("the seed isn't a number"))?;
+                        seeded = true;
+                    }
+                    "Cards" => {
+                        replay.card_count = value
+                            .parse()
+                            .map_err(|_| fail("the cards aren't a number"))?;
+                    }
+                    "Difficulty" => {
+                        replay.difficulty = Difficulty::named(&tag_name(value))
+                            .ok_or_else(|| fail("there's no such difficulty"))?;
+                    }
+                    "Opponent" => {
+                        replay.opponent = Some(
+                            Skill::named(&tag_name(value))
+                                .ok_or_else(|| fail("there's no such opponent"))?,
+                        );
+                    }
+                    "Aces" => {
+                        replay.rules.aces = AceRule::named(&tag_name(value))
+                            .ok_or_else(|| fail("aces are high or low"))?;
+                    }
+                    "Tiebreak" => {
+                        replay.rules.tiebreak = SuitTiebreak::named(&tag_name(value))
+                            .ok_or_else(|| fail("there's no such tiebreak"))?;
+                    }
+                    "Rounds" => {
+                        replay.rounds = RoundLimit::named(&tag_name(value))
+                            .ok_or_else(|| fail("the rounds aren't a number"))?;
+                    }
+                    "Deck" => {
+                        let cards = value
+                            .split_whitespace()
+                            .map(|code| code.parse())
+                            .collect::<Result<Vec<Card>, _>>()
+                            .map_err(|err| fail(&err.to_string()))?;
+                        replay.deck = Some(cards);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            let round = parse_round(line, rounds.len() + 1).map_err(|reason| fail(&reason))?;
+            replay.choices.push(round.choice);
+            rounds.push(round);
+        }
+        if !seeded {
+            return Err(NotationError::MissingTag("Seed"));
+        }
+        Ok(Notation { replay, rounds })
+    }
+}
+
+/// A name as tags write it, in lowercase with dashes for spaces, e.g.
+/// `card-counting`.
+fn tag_name(name: &str) -> String {
+    name.trim().to_lowercase().replace(' ', "-")
+}
+
+/// The name and value of a `[Name "value"]` tag pair.
+fn parse_tag(line: &str) -> Option<(&str, &str)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (tag, value) = inner.split_once(' ')?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((tag, value))
+}
+
+/// Reads round `number`, e.g. `3. Th Qs 2c 1 W`.
+fn parse_round(line: &str, number: usize) -> Result<NotatedRound, String> {
+    let mut tokens: Vec<&str> = line.split_whitespace().collect();
+    if tokens.first() != Some(&format!("{}.", number).as_str()) {
+        return Err(format!("expected round {}", number));
+    }
+    let won = match tokens.pop() {
+        Some("W") => true,
+        Some("L") => false,
+        _ => return Err("a round ends with W or L".to_string()),
+    };
+    let choice: usize = tokens
+        .pop()
+        .and_then(|choice| choice.parse().ok())
+        .ok_or("the pick isn't a number")?;
+    let cards = tokens[1..]
+        .iter()
+        .map(|code| code.parse())
+        .collect::<Result<Vec<Card>, _>>()
+        .map_err(|err| err.to_string())?;
+    if choice >= cards.len() {
+        return Err(format!("there's no card {} to pick", choice));
+    }
+    Ok(NotatedRound { cards, choice, won })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Notation, NotationError};
+    use crate::cards::{Card, Deck};
+    use crate::game::state::{Event, Input};
+    use crate::game::{Difficulty, Game, GameBuilder, RoundLimit};
+    use crate::opponent::Skill;
+    use crate::rules::{AceRule, Ruleset, SuitTiebreak};
+
+    fn played() -> Game {
+        let mut game = GameBuilder::new()
+            .difficulty(Difficulty::Hard)
+            .opponent(Skill::SuitAware)
+            .with_seed(21)
+            .spawn()
+            .unwrap();
+        for _ in 0..4 {
+            game.advance(Input::Deal);
+            game.advance(Input::Random);
+            game.advance(Input::Reveal);
+            game.advance(Input::Continue);
+        }
+        game
+    }
+
+    #[test]
+    /// Tests a session is written with its tags and a line per round.
+    fn test_display() {
+        insta::assert_snapshot!(Notation::from_game(&played()).to_string());
+    }
+
+    #[test]
+    /// Tests written notation reads back into the same session and replays.
+    fn test_round_trip() {
+        let notation = Notation::from_game(&played());
+        let text = notation.to_string();
+        let parsed: Notation = text.parse().unwrap();
+        assert_eq!(parsed, notation);
+        assert_eq!(parsed.verify(), Ok(()));
+
+        // Comments, blank lines and unknown tags are skipped.
+        let annotated = text.replace("\n1.", "\n[Reported \"by a friend\"]\n\n1.") + "; gg\n";
+        assert_eq!(annotated.parse::<Notation>().unwrap(), notation);
+
+        let altered = text.replacen(" W\n", " L\n", 1).replacen(" L\n", " W\n", 1);
+        let altered: Notation = altered.parse().unwrap();
+        assert!(matches!(
+            altered.verify(),
+            Err(NotationError::Mismatch { .. })
+        ));
+    }
+
+    #[test]
+    /// Tests a session under other rules, a round limit and a given deck is
+    /// tagged with them and replays.
+    fn test_settings_round_trip() {
+        let cards: Vec<Card> = Deck::new().iter().rev().copied().collect();
+        let mut game = GameBuilder::new()
+            .rules(Ruleset::new().aces(AceRule::Low))
+            .rounds(RoundLimit::Rounds(20))
+            .deck(Deck::from_cards(cards))
+            .with_seed(5)
+            .spawn()
+            .unwrap();
+        while game.advance(Input::Deal) == Event::Dealt {
+            game.advance(Input::Random);
+            game.advance(Input::Reveal);
+            game.advance(Input::Continue);
+        }
+
+        let notation = Notation::from_game(&game);
+        let text = notation.to_string();
+        assert!(text.contains("[Aces \"low\"]\n[Tiebreak \"none\"]\n[Rounds \"20\"]\n"));
+        assert!(text.contains("[Deck \"Ac Ah Ad As Kc"));
+        let parsed: Notation = text.parse().unwrap();
+        assert_eq!(parsed, notation);
+        assert_eq!(parsed.verify(), Ok(()));
+
+        let tagged = "[Seed \"1\"]\n[Tiebreak \"bridge\"]\n[Rounds \"endless\"]";
+        let replay = tagged.parse::<Notation>().unwrap().replay;
+        assert_eq!(replay.rules.tiebreak, SuitTiebreak::Bridge);
+        assert_eq!(replay.rounds, RoundLimit::Endless);
+    }
+
+    #[test]
+    /// Tests notation that can't be read says which line is wrong.
+    fn test_errors() {
+        let error = |text: &str| text.parse::<Notation>().unwrap_err().to_string();
+        assert_eq!(error("1. 2c 3d 4h 0 W"), "there's no Seed tag");
+        assert_eq!(error("[Seed \"x\"]"), "line 1: the seed isn't a number");
+        assert_eq!(
+            error("[Seed \"1\"]\n2. 2c 3d 4h 0 W"),
+            "line 2: expected round 1"
+        );
+        assert_eq!(
+            error("[Seed \"1\"]\n1. 2c 3d 4h 3 W"),
+            "line 2: there's no card 3 to pick"
+        );
+        assert_eq!(
+            error("[Seed \"1\"]\n1. 2c 3d 4h 0 D"),
+            "line 2: a round ends with W or L"
+        );
+        assert_eq!(
+            error("[Difficulty \"brutal\"]"),
+            "line 1: there's no such difficulty"
+        );
+        assert_eq!(error("[Aces \"wild\"]"), "line 1: aces are high or low");
+    }
+}
@@ -0,0 +1,319 @@
+//! This module provides the rules for comparing two cards.
+//!
+//! A `Ruleset` says whether aces rank high or low and which suit wins between
+//! two cards of the same rank. Game modes each start from their own ruleset and
+//! let the player change it, and since a ruleset is also a `ScoringStrategy` it
+//! can decide the high card of a hand.
+
+use crate::cards::{Card, Rank, Suit};
+use crate::locale::{Locale, Message};
+use crate::scoring::ScoringStrategy;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+/// Whether an ace ranks above the king or below the two.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AceRule {
+    #[default]
+    High,
+    Low,
+}
+
+impl AceRule {
+    /// Looks up an ace rule by name.
+    pub fn named(name: &str) -> Option<AceRule> {
+        match name {
+            "high" => Some(AceRule::High),
+            "low" => Some(AceRule::Low),
+            _ => None,
+        }
+    }
+
+    /// The name `named` looks the rule up by.
+    pub fn name(self) -> &'static str {
+        match self {
+            AceRule::High => "high",
+            AceRule::Low => "low",
+        }
+    }
+
+    /// Where `rank` falls from lowest to highest under this rule.
+    pub fn order(self, rank: Rank) -> u32 {
+        match (self, rank) {
+            (AceRule::Low, Rank::Ace) => 1,
+            (_, rank) => rank.ordinal(),
+        }
+    }
+}
+
+/// Which suit wins between two cards of the same rank.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SuitTiebreak {
+    /// Neither, so the cards tie.
+    #[default]
+    None,
+    /// The suits' point order: ♠ lowest, then ♦, ♥ and ♣ highest.
+    Points,
+    /// Bridge order: ♣ lowest, then ♦, ♥ and ♠ highest.
+    Bridge,
+    /// Red and black in turn: ♦ lowest, then ♣, ♥ and ♠ highest.
+    AlternatingColors,
+}
+
+impl SuitTiebreak {
+    /// Looks up a tiebreak by name, as in `bridge`.
+    pub fn named(name: &str) -> Option<SuitTiebreak> {
+        match name {
+            "none" => Some(SuitTiebreak::None),
+            "points" => Some(SuitTiebreak::Points),
+            "bridge" => Some(SuitTiebreak::Bridge),
+            "alternating" | "alternating-colors" => Some(SuitTiebreak::AlternatingColors),
+            _ => None,
+        }
+    }
+
+    /// The name `named` looks the tiebreak up by.
+    pub fn name(self) -> &'static str {
+        match self {
+            SuitTiebreak::None => "none",
+            SuitTiebreak::Points => "points",
+            SuitTiebreak::Bridge => "bridge",
+            SuitTiebreak::AlternatingColors => "alternating",
+        }
+    }
+
+    /// Where `suit` falls from 0 for the lowest to 3 for the highest.
+    pub fn rank(self, suit: Suit) -> u32 {
+        let order = match self {
+            SuitTiebreak::None => return 0,
+            SuitTiebreak::Points => Suit::ALL,
+            SuitTiebreak::Bridge => [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades],
+            SuitTiebreak::AlternatingColors => {
+                [Suit::Diamonds, Suit::Clubs, Suit::Hearts, Suit::Spades]
+            }
+        };
+        order.iter().position(|&s| s == suit).unwrap_or(0) as u32
+    }
+}
+
+/// How two cards compare: by rank with aces high or low, then by suit.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ruleset {
+    pub aces: AceRule,
+    pub tiebreak: SuitTiebreak,
+}
+
+impl Ruleset {
+    /// Aces high, and cards of the same rank tie.
+    pub fn new() -> Ruleset {
+        Ruleset::default()
+    }
+
+    /// The high card game's rules: aces high, and suits in point order break
+    /// ties.
+    pub fn high_card() -> Ruleset {
+        Ruleset::new().tiebreak(SuitTiebreak::Points)
+    }
+
+    /// Option to set whether aces are high or low.
+    pub fn aces(mut self, aces: AceRule) -> Ruleset {
+        self.aces = aces;
+        self
+    }
+
+    /// Option to set which suit wins between cards of the same rank.
+    pub fn tiebreak(mut self, tiebreak: SuitTiebreak) -> Ruleset {
+        self.tiebreak = tiebreak;
+        self
+    }
+
+    /// A few lines explaining how cards are ranked and scored under the rules,
+    /// ending with the highest card's score worked out.
+    pub fn explain(&self) -> String {
+        let mut ranks = Rank::ALL;
+        ranks.sort_by_key(|&rank| self.aces.order(rank));
+        let mut suits = Suit::ALL;
+        suits.sort_by_key(|&suit| self.tiebreak.rank(suit));
+        let join = |codes: Vec<String>| codes.join(" ");
+
+        let mut out = format!(
+            "Cards are compared by rank, then by suit when the ranks match.\n\
+             Ranks from lowest: {}\n",
+            join(ranks.iter().map(|rank| rank.to_string()).collect())
+        );
+        if self.tiebreak == SuitTiebreak::None {
+            out.push_str("Suits don't break ties: the first of two matching ranks is high.\n");
+        } else {
+            out.push_str(&format!(
+                "Suits from lowest: {}\n",
+                join(suits.iter().map(|suit| suit.symbol().to_string()).collect())
+            ));
+        }
+        let top = Card::new(suits[3], ranks[ranks.len() - 1]);
+        let order = self.aces.order(top.rank);
+        if self.tiebreak == SuitTiebreak::None {
+            out.push_str(&format!(
+                "A card scores its rank's place times 4, so {} scores {} x 4 = {}.",
+                top.rank,
+                order,
+                self.score(&top)
+            ));
+        } else {
+            out.push_str(&format!(
+                "A card scores its rank's place times 4 plus its suit's place from 0 to 3,\n\
+                 so the best card, {}, scores {} x 4 + {} = {}.",
+                top,
+                order,
+                self.tiebreak.rank(top.suit),
+                self.score(&top)
+            ));
+        }
+        out
+    }
+
+    /// Compares the ranks of two cards alone.
+    pub fn compare_ranks(&self, a: &Card, b: &Card) -> Ordering {
+        self.aces.order(a.rank).cmp(&self.aces.order(b.rank))
+    }
+
+    /// Compares two cards by rank, then by suit when the ranks match.
+    pub fn compare(&self, a: &Card, b: &Card) -> Ordering {
+        self.compare_ranks(a, b)
+            .then_with(|| self.tiebreak.rank(a.suit).cmp(&self.tiebreak.rank(b.suit)))
+    }
+}
+
+/// Scores a card so higher scores win under the rules: rank first, then suit.
+impl ScoringStrategy for Ruleset {
+    fn score(&self, card: &Card) -> u32 {
+        self.aces.order(card.rank) * 4 + self.tiebreak.rank(card.suit)
+    }
+
+    /// The sum from `explain`'s last line for `card`, leaving out the suit's
+    /// place when suits don't break ties.
+    fn explain_score(&self, card: &Card, locale: Locale) -> String {
+        let (order, score) = (self.aces.order(card.rank), self.score(card));
+        match self.tiebreak {
+            SuitTiebreak::None => locale.format(Message::ScoresByRank, &[card, &order, &score]),
+            tiebreak => locale.format(
+                Message::ScoresByRankAndSuit,
+                &[card, &order, &tiebreak.rank(card.suit), &score],
+            ),
+        }
+    }
+
+    fn rules(&self) -> Option<Ruleset> {
+        Some(*self)
+    }
+}
+
+/// Displays the rules, as in `aces high, bridge tiebreak`.
+impl Display for Ruleset {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let aces = match self.aces {
+            AceRule::High => "aces high",
+            AceRule::Low => "aces low",
+        };
+        let tiebreak = match self.tiebreak {
+            SuitTiebreak::None => "no tiebreak",
+            SuitTiebreak::Points => "points tiebreak",
+            SuitTiebreak::Bridge => "bridge tiebreak",
+            SuitTiebreak::AlternatingColors => "alternating colors tiebreak",
+        };
+        write!(formatter, "{}, {}", aces, tiebreak)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AceRule, Ruleset, SuitTiebreak};
+    use crate::cards::{Card, Rank, Suit};
+    use crate::locale::Locale;
+    use crate::scoring::ScoringStrategy;
+    use std::cmp::Ordering;
+
+    #[test]
+    /// Tests cards compare by rank, then by the tiebreak's suit order.
+    fn test_compare() {
+        let king = Card::new(Suit::Spades, Rank::King);
+        let jack = Card::new(Suit::Clubs, Rank::Jack);
+        let other_king = Card::new(Suit::Hearts, Rank::King);
+        let ace = Card::new(Suit::Diamonds, Rank::Ace);
+
+        let rules = Ruleset::new();
+        assert_eq!(rules.compare(&king, &jack), Ordering::Greater);
+        assert_eq!(rules.compare(&king, &other_king), Ordering::Equal);
+        assert_eq!(rules.compare(&ace, &king), Ordering::Greater);
+        assert_eq!(
+            rules.aces(AceRule::Low).compare(&ace, &jack),
+            Ordering::Less
+        );
+
+        let compare = |tiebreak| {
+            Ruleset::new()
+                .tiebreak(tiebreak)
+                .compare(&king, &other_king)
+        };
+        assert_eq!(compare(SuitTiebreak::Points), Ordering::Less);
+        assert_eq!(compare(SuitTiebreak::Bridge), Ordering::Greater);
+        assert_eq!(compare(SuitTiebreak::AlternatingColors), Ordering::Greater);
+        assert_eq!(
+            Ruleset::new()
+                .tiebreak(SuitTiebreak::AlternatingColors)
+                .compare(
+                    &Card::new(Suit::Diamonds, Rank::Two),
+                    &Card::new(Suit::Clubs, Rank::Two)
+                ),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    /// Tests scores agree with comparing cards, and rules are looked up by name.
+    fn test_score() {
+        let rules = Ruleset::high_card().aces(AceRule::Low);
+        let cards = [
+            Card::new(Suit::Clubs, Rank::Ace),
+            Card::new(Suit::Spades, Rank::Two),
+            Card::new(Suit::Clubs, Rank::Two),
+            Card::new(Suit::Hearts, Rank::King),
+        ];
+        for a in cards.iter() {
+            for b in cards.iter() {
+                assert_eq!(rules.score(a).cmp(&rules.score(b)), rules.compare(a, b));
+            }
+        }
+        assert_eq!(SuitTiebreak::named("bridge"), Some(SuitTiebreak::Bridge));
+        assert_eq!(SuitTiebreak::named("suits"), None);
+        assert_eq!(rules.to_string(), "aces low, points tiebreak");
+    }
+
+    #[test]
+    /// Tests the explanation follows the ace rule and suit order.
+    fn test_explain() {
+        let explained = Ruleset::high_card().explain();
+        assert!(explained.contains("Ranks from lowest: 2 3 4 5 6 7 8 9 10 J Q K A\n"));
+        assert!(explained.contains("Suits from lowest: ♠ ♦ ♥ ♣\n"));
+        assert!(explained.ends_with("the best card, A♣, scores 14 x 4 + 3 = 59."));
+
+        let explained = Ruleset::new().aces(AceRule::Low).explain();
+        assert!(explained.contains("Ranks from lowest: A 2 3"));
+        assert!(explained.contains("Suits don't break ties"));
+        assert!(explained.ends_with("so K scores 13 x 4 = 52."));
+
+        let king = Card::new(Suit::Hearts, Rank::King);
+        assert_eq!(
+            Ruleset::high_card().explain_score(&king, Locale::English),
+            "K♥ scores 13 x 4 + 2 = 54"
+        );
+        assert_eq!(
+            Ruleset::new().explain_score(&king, Locale::English),
+            "K♥ scores 13 x 4 = 52"
+        );
+        assert_eq!(
+            Ruleset::new().explain_score(&king, Locale::Spanish),
+            "K♥ vale 13 x 4 = 52"
+        );
+    }
+}
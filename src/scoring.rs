@@ -0,0 +1,124 @@
+//! This module provides pluggable rules for scoring cards.
+
+use crate::cards::{Card, Rank};
+use crate::locale::{Locale, Message};
+use crate::rules::Ruleset;
+
+/// Decides how many points a card is worth. Games compare cards by their score.
+pub trait ScoringStrategy {
+    fn score(&self, card: &Card) -> u32;
+
+    /// How `card`'s score is worked out, as in `K♠ scores 13 x 4 + 0 = 52`, so
+    /// players can see why one card beat another, in `locale`'s language. By
+    /// default just the score.
+    fn explain_score(&self, card: &Card, locale: Locale) -> String {
+        locale.format(Message::Scores, &[card, &self.score(card)])
+    }
+
+    /// The ruleset this strategy scores by, if it is one, so a saved game can
+    /// be loaded with the same rules.
+    fn rules(&self) -> Option<Ruleset> {
+        None
+    }
+}
+
+/// Scores a card as its suit point number times its rank value, see `Card::value`.
+/// Face cards of a suit score the same as its ten.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct SuitWeightedScoring;
+
+impl ScoringStrategy for SuitWeightedScoring {
+    fn score(&self, card: &Card) -> u32 {
+        card.value()
+    }
+
+    fn explain_score(&self, card: &Card, locale: Locale) -> String {
+        locale.format(
+            Message::ScoresForSuitAndRank,
+            &[
+                card,
+                &(card.suit as u32),
+                &card.rank.value(),
+                &self.score(card),
+            ],
+        )
+    }
+}
+
+/// Scores a card by rank alone from 2 up to 14 for an ace, so a King beats a Jack
+/// and suits are ignored.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct HighCardScoring;
+
+impl ScoringStrategy for HighCardScoring {
+    fn score(&self, card: &Card) -> u32 {
+        card.rank.ordinal()
+    }
+
+    fn explain_score(&self, card: &Card, locale: Locale) -> String {
+        locale.format(Message::ScoresForRank, &[card, &self.score(card)])
+    }
+}
+
+/// Scores a card the way blackjack counts it: number cards at face value, face
+/// cards 10 and aces 11.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct BlackjackScoring;
+
+impl ScoringStrategy for BlackjackScoring {
+    fn score(&self, card: &Card) -> u32 {
+        match card.rank {
+            Rank::Ace => 11,
+            rank => rank.value(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlackjackScoring, HighCardScoring, ScoringStrategy, SuitWeightedScoring};
+    use crate::cards::{Card, Rank, Suit};
+    use crate::locale::Locale;
+
+    #[test]
+    /// Tests each strategy scores cards by its own rules.
+    fn test_strategies() {
+        let king = Card::new(Suit::Spades, Rank::King);
+        let jack = Card::new(Suit::Clubs, Rank::Jack);
+        let ace = Card::new(Suit::Hearts, Rank::Ace);
+
+        assert_eq!(SuitWeightedScoring.score(&king), 10);
+        assert_eq!(SuitWeightedScoring.score(&jack), 40);
+        assert_eq!(SuitWeightedScoring.score(&ace), 33);
+
+        assert_eq!(HighCardScoring.score(&king), 13);
+        assert_eq!(HighCardScoring.score(&jack), 11);
+        assert_eq!(HighCardScoring.score(&ace), 14);
+
+        assert_eq!(BlackjackScoring.score(&king), 10);
+        assert_eq!(BlackjackScoring.score(&jack), 10);
+        assert_eq!(BlackjackScoring.score(&ace), 11);
+    }
+
+    #[test]
+    /// Tests each strategy explains a score in its own terms.
+    fn test_explain_score() {
+        let king = Card::new(Suit::Spades, Rank::King);
+        assert_eq!(
+            SuitWeightedScoring.explain_score(&king, Locale::English),
+            "K♠ scores 1 for its suit x 10 for its rank = 10"
+        );
+        assert_eq!(
+            HighCardScoring.explain_score(&king, Locale::English),
+            "K♠ scores 13 for its rank"
+        );
+        assert_eq!(
+            BlackjackScoring.explain_score(&king, Locale::English),
+            "K♠ scores 10"
+        );
+        assert_eq!(
+            HighCardScoring.explain_score(&king, Locale::Spanish),
+            "K♠ vale 13 por su valor"
+        );
+    }
+}
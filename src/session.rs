@@ -0,0 +1,633 @@
+//! This module provides the high card sessions the binary plays.
+//!
+//! A session merges the options given on the command line over the config
+//! file, starts a new game or resumes a saved one, and plays it full screen or
+//! at the prompt. Once it ends the stats, profile and rating of whoever played
+//! are updated, and a game quit part way is saved to its `SaveSlot`. The daily
+//! challenge is a session everyone plays with the same rules, once a day.
+
+use crate::bank::Bank;
+use crate::cards::card_printer::CardTheme;
+use crate::cards::{Deck, DeckBuilder};
+use crate::cli::PlayOptions;
+use crate::config::Config;
+use crate::daily::{DailyLog, DailyResult, Day};
+use crate::engine::high_card::{self, AfterGame, HighCard};
+use crate::engine::{self};
+use crate::error::GameError;
+use crate::game::log::EventLog;
+use crate::game::{Difficulty, Game, GameBuilder};
+use crate::io::Terminal;
+#[cfg(feature = "net")]
+use crate::net;
+use crate::profile::Profiles;
+#[cfg(feature = "net")]
+use crate::rating::Rating;
+use crate::rating::{self};
+use crate::replay::Replay;
+use crate::stats::Stats;
+use crate::strategy;
+use crate::tui;
+use console::Term;
+use std::cmp::Ordering;
+use std::fs;
+use std::io::{self};
+use std::path::PathBuf;
+use std::time;
+
+/// Where a game in progress is saved when the player quits, and how they pick
+/// it up again.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SaveSlot<'a> {
+    pub path: &'a str,
+    pub resume: &'a str,
+}
+
+/// The regular game's save.
+pub const SAVE: SaveSlot<'static> = SaveSlot {
+    path: "card_game_save.json",
+    resume: "Run with --resume to continue.",
+};
+
+/// The daily challenge's save, kept apart so it never touches the regular one.
+pub const DAILY_SAVE: SaveSlot<'static> = SaveSlot {
+    path: "card_game_daily.json",
+    resume: "Run daily again to finish it.",
+};
+
+/// Loads lifetime stats, starting fresh if the file can't be read.
+pub fn load_stats() -> Stats {
+    match Stats::default_path() {
+        Some(path) => Stats::load(path).unwrap_or_else(|err| {
+            println!("Couldn't read stats: {}", err);
+            Stats::new()
+        }),
+        None => Stats::new(),
+    }
+}
+
+/// Loads the config file, using the built-in defaults if it's missing or broken.
+pub fn load_config() -> Config {
+    let path = match Config::default_path() {
+        Some(path) => path,
+        None => return Config::new(),
+    };
+    Config::load(&path).unwrap_or_else(|err| {
+        println!("Ignoring {}: {}", path.display(), err);
+        Config::new()
+    })
+}
+
+/// Loads every player profile, starting fresh if the file can't be read.
+pub fn load_profiles() -> Profiles {
+    match Profiles::default_path() {
+        Some(path) => Profiles::load(path).unwrap_or_else(|err| {
+            println!("Couldn't read profiles: {}", err);
+            Profiles::new()
+        }),
+        None => Profiles::new(),
+    }
+}
+
+#[cfg(feature = "net")]
+/// The rating of the profile called `name`, if a name was given.
+pub fn rating_of(name: Option<&str>) -> Option<i32> {
+    let name = name?;
+    let rating = load_profiles()
+        .get(name)
+        .map_or_else(Rating::new, |profile| profile.rating);
+    Some(rating.value)
+}
+
+/// Updates the rating of the profile called `name` after a match against a
+/// player rated `opponent`, with `result` how they did, and shows the change.
+fn rate(name: &str, opponent: i32, result: Ordering) {
+    let mut profiles = load_profiles();
+    let rating = &mut profiles.profile(name).rating;
+    let change = rating.record(opponent, result, load_config().k_factor());
+    println!("Rating: {} ({:+})", rating.value, change);
+    if let Some(path) = Profiles::default_path() {
+        if let Err(err) = profiles.save(path) {
+            println!("Couldn't save profiles: {}", err);
+        }
+    }
+}
+
+#[cfg(feature = "net")]
+/// Rates a networked match that was played to the end, when both players
+/// have a profile.
+pub fn rate_net_match(name: Option<&str>, finish: Option<net::Finish>) {
+    if let (Some(name), Some(finish)) = (name, finish) {
+        if let Some(opponent) = finish.opponent_rating {
+            rate(name, opponent, finish.result);
+        }
+    }
+}
+
+/// Stores `stats` and what's left in `bank` in the profile called `name`, and
+/// announces any achievements it unlocked.
+fn save_profile(name: &str, stats: &Stats, bank: Option<&Bank>) {
+    let mut profiles = load_profiles();
+    let profile = profiles.profile(name);
+    profile.stats = stats.clone();
+    if let Some(bank) = bank {
+        profile.chips = Some(bank.chips()).filter(|&chips| chips > 0);
+    }
+    for achievement in profile.unlock() {
+        println!(
+            "Achievement unlocked: {} ({})",
+            achievement,
+            achievement.description()
+        );
+    }
+    if let Some(path) = Profiles::default_path() {
+        if let Err(err) = profiles.save(path) {
+            println!("Couldn't save profiles: {}", err);
+        }
+    }
+}
+
+fn save_stats(stats: &Stats) {
+    if let Some(path) = Stats::default_path() {
+        if let Err(err) = stats.save(path) {
+            println!("Couldn't save stats: {}", err);
+        }
+    }
+}
+
+/// `config` with the options given on the command line in place of its own.
+pub fn configure(mut config: Config, options: &PlayOptions) -> Config {
+    if options.cards.is_some() {
+        config.cards = options.cards;
+    }
+    if let Some(name) = &options.theme {
+        config.theme = CardTheme::named(name);
+    }
+    if options.color.is_some() {
+        config.color = options.color;
+    }
+    if options.suits.is_some() {
+        config.suits = options.suits;
+    }
+    if options.difficulty.is_some() {
+        config.difficulty = options.difficulty;
+    }
+    if options.speed.is_some() {
+        config.speed = options.speed;
+    }
+    if options.no_animation {
+        config.animation = Some(false);
+    }
+    if options.screen_reader {
+        config.screen_reader = Some(true);
+    }
+    if options.locale.is_some() {
+        config.locale = options.locale;
+    }
+    if options.mute {
+        config.mute = Some(true);
+    }
+    if options.hints {
+        config.hints = Some(true);
+    }
+    if options.values {
+        config.values = Some(true);
+    }
+    if options.confirm {
+        config.confirm = Some(true);
+    }
+    config
+}
+
+/// Reads a deck spec for `--deck-file`.
+fn load_deck(path: &str) -> io::Result<Deck> {
+    let spec = fs::read_to_string(path)
+        .map_err(|err| io::Error::new(err.kind(), format!("couldn't open {}: {}", path, err)))?;
+    DeckBuilder::from_spec(&spec).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("couldn't use {}: {}", path, err),
+        )
+    })
+}
+
+/// Plays the high card game with `config` and returns it once it ends, saving it
+/// to `save` if the player quits part way. Options given on the command line
+/// override the config.
+pub async fn play(
+    options: PlayOptions,
+    config: Config,
+    save: &SaveSlot<'_>,
+) -> Result<Game, GameError> {
+    // Multiplayer rounds have no pick to record, so there'd be nothing to replay.
+    if options.record.is_some() && options.players.is_some_and(|players| players > 1) {
+        let err = "--record only works with one player, multiplayer rounds can't be replayed";
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, err).into());
+    }
+    let config = configure(config, &options);
+
+    let mut game: Game = if options.resume {
+        let mut game = Game::load(save.path).map_err(|err| {
+            io::Error::new(err.kind(), format!("couldn't load {}: {}", save.path, err))
+        })?;
+        game.set_speed(config.speed());
+        println!("Resuming saved game.\n{}\n", game);
+        game
+    } else {
+        let mut builder = GameBuilder::new().speed(config.speed());
+        if let Some(cards) = config.cards {
+            builder = builder.max_cards(cards);
+        }
+        if let Some(seed) = options.seed {
+            builder = builder.with_seed(seed);
+        }
+        if let Some(players) = options.players {
+            builder = builder.players(players);
+        }
+        // A player who's bet before picks up with the chips they had left.
+        let carried = options
+            .player
+            .as_deref()
+            .and_then(|name| load_profiles().get(name).and_then(|profile| profile.chips));
+        if let Some(chips) = options.chips.or(carried) {
+            let payout = options.payout.unwrap_or(2);
+            builder = builder.bank(Bank::new(chips).payout(payout));
+        }
+        if let Some(difficulty) = config.difficulty {
+            builder = builder.difficulty(difficulty);
+        }
+        if let Some(skill) = options.opponent {
+            builder = builder.opponent(skill);
+        }
+        if let Some(rules) = options.rules {
+            builder = builder.rules(rules);
+        }
+        if let Some(rounds) = options.rounds {
+            builder = builder.rounds(rounds);
+        }
+        if let Some(seconds) = options.timeout {
+            builder = builder.challenge(time::Duration::from_secs(seconds));
+        }
+        if let Some(path) = &options.deck_file {
+            builder = builder.deck(load_deck(path)?);
+        }
+        builder.spawn()?
+    };
+    if let Some(path) = &options.log_file {
+        let log = EventLog::create(path).map_err(|err| {
+            io::Error::new(err.kind(), format!("couldn't open {}: {}", path, err))
+        })?;
+        game.log_events(log);
+    }
+    #[cfg(feature = "events")]
+    if let Some(port) = options.spectate {
+        let addr = if options.spectate_public {
+            std::net::Ipv4Addr::UNSPECIFIED
+        } else {
+            std::net::Ipv4Addr::LOCALHOST
+        };
+        let spectators = crate::events::Spectators::bind(addr.into(), port)?;
+        println!(
+            "Spectators can watch at http://localhost:{}/",
+            spectators.port()
+        );
+        game.spectate(spectators);
+    }
+    #[cfg(feature = "audio")]
+    if !config.muted() {
+        if let Some(sounds) = crate::audio::Sounds::detect() {
+            game.play_sounds(sounds);
+        }
+    }
+    let mut stats = match &options.player {
+        Some(name) => {
+            println!("Playing as {}.", name);
+            load_profiles()
+                .get(name)
+                .map(|profile| profile.stats.clone())
+                .unwrap_or_default()
+        }
+        None => load_stats(),
+    };
+    println!("{}\n", stats);
+    let timeout = options.timeout.map(time::Duration::from_secs);
+    let use_tui = Term::stdout().features().is_attended()
+        && !options.plain
+        && !options.debug
+        && !config.screen_reader()
+        && !game.is_multiplayer()
+        && !options.autoplay;
+    let outcome = if use_tui {
+        tui::run(&mut game, &mut stats, &config, timeout, options.practice)
+            .await
+            .map_err(GameError::from)
+    } else {
+        let seed = !game.seed();
+        let mut mode = HighCard::new(&mut game, &mut stats, &config, timeout)
+            .practice(options.practice)
+            .debug(options.debug);
+        if options.autoplay {
+            let name = options.strategy.as_deref().unwrap_or("counting");
+            let chooser =
+                strategy::named(name, seed).expect("the strategy was checked to be built in");
+            mode = mode.autoplay(name, chooser);
+        }
+        engine::run(&mut mode, config.pause()).await
+    };
+    // The computer's picks aren't the player's to keep.
+    match &options.player {
+        _ if options.autoplay => {}
+        Some(name) => save_profile(name, &stats, game.bank()),
+        None => save_stats(&stats),
+    }
+    // A match against the computer counts once at least one round was played.
+    if let (Some(name), Some(opponent), Some(result), false) = (
+        &options.player,
+        game.opponent(),
+        game.match_result(),
+        options.autoplay,
+    ) {
+        if !game.history().rounds().is_empty() {
+            rate(name, rating::skill_rating(opponent.skill()), result);
+        }
+    }
+
+    if let Some(path) = options.record {
+        Replay::record(&game, &path)?;
+        println!("Session recorded to {}.", path);
+    }
+
+    let quit = match outcome {
+        Ok(quit) => quit,
+        Err(err) => {
+            // Keep the game so it can be resumed once the problem is sorted.
+            game.save(save.path)?;
+            return Err(err);
+        }
+    };
+    if quit {
+        game.save(save.path)?;
+        println!("Game saved. {}", save.resume);
+        return Ok(game);
+    }
+
+    match fs::remove_file(save.path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+        _ => {}
+    }
+    if use_tui {
+        println!("{}", high_card::summary(&game, config.locale()));
+    }
+    Ok(game)
+}
+
+/// Plays high card games until the player stops. Whenever the deck runs out
+/// on a terminal they're shown their results and asked whether to play again
+/// with a freshly shuffled deck, which is returned unless they do. Each game
+/// is played with `config` and saved to `save` as `play` does.
+pub async fn play_session(
+    mut options: PlayOptions,
+    config: &Config,
+    save: &SaveSlot<'_>,
+) -> Result<Option<AfterGame>, GameError> {
+    loop {
+        let game = play(options.clone(), config.clone(), save).await?;
+        if !game.out_of_cards() || !Term::stdout().features().is_attended() {
+            return Ok(None);
+        }
+        let locale = options.locale.unwrap_or_else(|| config.locale());
+        match high_card::play_again(&mut Terminal::new(), locale).await? {
+            AfterGame::PlayAgain => {
+                options.seed = None;
+                options.resume = false;
+            }
+            after => return Ok(Some(after)),
+        }
+    }
+}
+
+/// Plays today's daily challenge, unless it's already been played. A daily
+/// left part way is kept in its own save and picked up again next time, so
+/// quitting can't be used to get a fresh start, and only a finished daily is
+/// logged in the log at `path`, if there is one. The daily is played with
+/// `config`, apart from the rules everyone shares.
+pub async fn daily(
+    config: Config,
+    path: Option<PathBuf>,
+    save: &SaveSlot<'_>,
+) -> Result<(), GameError> {
+    let today = Day::today();
+    let mut log = match &path {
+        Some(path) => DailyLog::load(path)?,
+        None => DailyLog::new(),
+    };
+    if let Some(result) = log.result(today) {
+        println!(
+            "You've already played the daily challenge for {}: {}.\nCome back tomorrow!",
+            today, result
+        );
+        return Ok(());
+    }
+
+    // A daily saved on an earlier day is stale and is started over.
+    let resume = Game::load(save.path).is_ok_and(|game| game.seed() == today.seed());
+    println!("Daily challenge for {}.", today);
+    let game = play(daily_options(today, resume), config, save).await?;
+    if !game.is_over() {
+        // Quitting for good from the full screen menu drops the save, but the
+        // daily still has to be finished.
+        game.save(save.path)?;
+        return Ok(());
+    }
+    let result = DailyResult::from_game(&game);
+    log.record(today, result);
+    if let Some(path) = &path {
+        log.save(path)?;
+    }
+    println!("Today's score: {}.", result);
+    Ok(())
+}
+
+/// The options every player's daily for `today` is played with, whatever their
+/// config file says.
+fn daily_options(today: Day, resume: bool) -> PlayOptions {
+    PlayOptions {
+        cards: Some(3),
+        seed: Some(today.seed()),
+        difficulty: Some(Difficulty::Normal),
+        resume,
+        ..PlayOptions::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{configure, daily, daily_options, play, play_session, SaveSlot};
+    use crate::cards::card_printer::CardTheme;
+    use crate::cli::PlayOptions;
+    use crate::config::Config;
+    use crate::daily::{DailyLog, DailyResult, Day};
+    use crate::game::{GameBuilder, RoundLimit, Speed};
+    use std::env;
+    use std::path::Path;
+
+    /// A save slot's path in the temp directory, named after `test`.
+    fn save_path(test: &str) -> String {
+        let path = env::temp_dir().join(format!("card_game_session_{}.json", test));
+        path.to_string_lossy().into_owned()
+    }
+
+    /// Options for a game the computer plays through by itself at once.
+    fn autoplay(rounds: usize) -> PlayOptions {
+        PlayOptions {
+            seed: Some(7),
+            speed: Some(Speed::Instant),
+            rounds: Some(RoundLimit::Rounds(rounds)),
+            autoplay: true,
+            ..PlayOptions::default()
+        }
+    }
+
+    #[test]
+    /// Tests options given on the command line win over the config file, which
+    /// keeps whatever they leave out.
+    fn test_configure() {
+        let config = Config {
+            cards: Some(4),
+            difficulty: None,
+            hints: Some(false),
+            ..Config::new()
+        };
+        assert_eq!(configure(config.clone(), &PlayOptions::default()), config);
+
+        let options = PlayOptions {
+            cards: Some(5),
+            theme: Some("casino".to_string()),
+            hints: true,
+            no_animation: true,
+            ..PlayOptions::default()
+        };
+        let merged = configure(config, &options);
+        assert_eq!(merged.cards, Some(5));
+        assert_eq!(merged.theme, CardTheme::named("casino"));
+        assert!(merged.hints());
+        assert!(!merged.animation());
+        assert_eq!(merged.difficulty, None);
+    }
+
+    #[tokio::test]
+    /// Tests a game played to the end is returned and its old save removed.
+    async fn test_play() {
+        let path = save_path("play");
+        let save = SaveSlot {
+            path: &path,
+            resume: "",
+        };
+        std::fs::write(save.path, "{}").unwrap();
+        let game = play(autoplay(3), Config::new(), &save).await.unwrap();
+        assert!(game.is_over());
+        assert_eq!(game.history().rounds().len(), 3);
+        assert_eq!(game.seed(), 7);
+        assert!(!Path::new(save.path).exists());
+    }
+
+    #[tokio::test]
+    /// Tests a saved game is picked up where it was left with --resume.
+    async fn test_play_resume() {
+        let path = save_path("resume");
+        let save = SaveSlot {
+            path: &path,
+            resume: "",
+        };
+        let saved = GameBuilder::new()
+            .with_seed(11)
+            .rounds(RoundLimit::Rounds(2))
+            .spawn()
+            .unwrap();
+        saved.save(save.path).unwrap();
+
+        let options = PlayOptions {
+            resume: true,
+            ..autoplay(5)
+        };
+        let game = play(options, Config::new(), &save).await.unwrap();
+        assert_eq!(game.seed(), 11);
+        assert_eq!(game.history().rounds().len(), 2);
+        assert!(!Path::new(save.path).exists());
+
+        let missing = PlayOptions {
+            resume: true,
+            ..autoplay(5)
+        };
+        let err = play(missing, Config::new(), &save).await.err().unwrap();
+        assert!(err.to_string().contains("couldn't load"));
+    }
+
+    #[tokio::test]
+    /// Tests multiplayer games can't be recorded, as there's no pick to replay.
+    async fn test_play_record_multiplayer() {
+        let options = PlayOptions {
+            players: Some(2),
+            record: Some("replay.json".to_string()),
+            ..autoplay(1)
+        };
+        let path = save_path("record");
+        let save = SaveSlot {
+            path: &path,
+            resume: "",
+        };
+        let err = play(options, Config::new(), &save).await.err().unwrap();
+        assert!(err
+            .to_string()
+            .contains("--record only works with one player"));
+    }
+
+    #[tokio::test]
+    /// Tests a session ends after one game that stopped before the deck ran out.
+    async fn test_play_session() {
+        let path = save_path("session");
+        let save = SaveSlot {
+            path: &path,
+            resume: "",
+        };
+        let after = play_session(autoplay(2), &Config::new(), &save)
+            .await
+            .unwrap();
+        assert_eq!(after, None);
+    }
+
+    #[test]
+    /// Tests everyone's daily is dealt the same way whatever their config says.
+    fn test_daily_options() {
+        let today = Day::today();
+        let options = daily_options(today, true);
+        assert_eq!(options.seed, Some(today.seed()));
+        assert_eq!(options.cards, Some(3));
+        assert!(options.resume);
+        assert!(!options.autoplay);
+    }
+
+    #[tokio::test]
+    /// Tests a daily already logged for today isn't played again.
+    async fn test_daily_played() {
+        let log_path = env::temp_dir().join(format!(
+            "card_game_session_daily_{}.json",
+            std::process::id()
+        ));
+        let mut log = DailyLog::new();
+        let game = GameBuilder::new().spawn().unwrap();
+        log.record(Day::today(), DailyResult::from_game(&game));
+        log.save(&log_path).unwrap();
+
+        let path = save_path("daily");
+        let save = SaveSlot {
+            path: &path,
+            resume: "",
+        };
+        daily(Config::new(), Some(log_path.clone()), &save)
+            .await
+            .unwrap();
+        assert!(!Path::new(save.path).exists());
+        std::fs::remove_file(log_path).unwrap();
+    }
+}
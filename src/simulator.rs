@@ -0,0 +1,273 @@
+//! This module provides Monte Carlo simulation of strategies across threads.
+//!
+//! Hands are played in batches, each dealt from its own seed worked out from the
+//! simulation's seed and the batch's number. The batches are shared out by a
+//! `rayon` thread pool sized for the run, so the totals depend on the seed alone
+//! and not on how many threads shared the work.
+
+use crate::game::GameBuilder;
+use crate::games::blackjack::{Blackjack, Policy};
+use crate::strategy::{self, Strategy};
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use std::fmt::{self, Display, Formatter};
+
+/// Hands played from each batch's seed.
+pub const BATCH: u64 = 10_000;
+
+/// The z score of a 95% confidence interval.
+const Z: f64 = 1.96;
+
+/// Which game is simulated.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum SimGame {
+    #[default]
+    HighCard,
+    Blackjack,
+}
+
+impl SimGame {
+    /// Looks up a game by name.
+    pub fn named(name: &str) -> Option<SimGame> {
+        match name {
+            "high-card" => Some(SimGame::HighCard),
+            "blackjack" => Some(SimGame::Blackjack),
+            _ => None,
+        }
+    }
+
+    /// The names of the strategies the game can be simulated with.
+    pub fn strategies(&self) -> &'static [&'static str] {
+        match self {
+            SimGame::HighCard => &strategy::NAMES,
+            SimGame::Blackjack => &["stand", "dealer", "basic"],
+        }
+    }
+}
+
+impl Display for SimGame {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            SimGame::HighCard => write!(formatter, "high card"),
+            SimGame::Blackjack => write!(formatter, "blackjack"),
+        }
+    }
+}
+
+/// How often a strategy won over the hands simulated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Estimate {
+    pub strategy: String,
+    pub hands: u64,
+    pub wins: u64,
+}
+
+impl Estimate {
+    /// The share of hands won, from 0 to 1.
+    pub fn win_rate(&self) -> f64 {
+        if self.hands == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.hands as f64
+    }
+
+    /// The 95% Wilson score interval around the win rate, lowest first.
+    pub fn interval(&self) -> (f64, f64) {
+        if self.hands == 0 {
+            return (0.0, 1.0);
+        }
+        let (n, p) = (self.hands as f64, self.win_rate());
+        let spread = Z * Z / n;
+        let centre = (p + spread / 2.0) / (1.0 + spread);
+        let margin = Z * (p * (1.0 - p) / n + spread / (4.0 * n)).sqrt() / (1.0 + spread);
+        (centre - margin, centre + margin)
+    }
+}
+
+impl Display for Estimate {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let (low, high) = self.interval();
+        write!(
+            formatter,
+            "{:<10} {:>6.2}% won, 95% between {:.2}% and {:.2}%",
+            self.strategy,
+            self.win_rate() * 100.0,
+            low * 100.0,
+            high * 100.0
+        )
+    }
+}
+
+/// Plays many hands of a game with a strategy and adds up the wins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulator {
+    game: SimGame,
+    seed: u64,
+    hands: u64,
+    threads: usize,
+}
+
+impl Simulator {
+    /// Construct a simulator of a million hands of `game` dealt from `seed`, on
+    /// as many threads as the machine runs at once.
+    pub fn new(game: SimGame, seed: u64) -> Simulator {
+        Simulator {
+            game,
+            seed,
+            hands: 1_000_000,
+            threads: rayon::current_num_threads(),
+        }
+    }
+
+    /// Option to play `hands` hands per strategy.
+    pub fn hands(mut self, hands: u64) -> Simulator {
+        self.hands = hands;
+        self
+    }
+
+    /// Option to share the work among `threads` threads.
+    pub fn threads(mut self, threads: usize) -> Simulator {
+        self.threads = threads.max(1);
+        self
+    }
+
+    pub fn game(&self) -> SimGame {
+        self.game
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn threads_used(&self) -> usize {
+        self.threads
+    }
+
+    /// Plays every hand with the built-in strategy called `strategy`, or returns
+    /// None if the game has no strategy of that name.
+    pub fn run(&self, strategy: &str) -> Option<Estimate> {
+        if !self.game.strategies().contains(&strategy) {
+            return None;
+        }
+        let estimate = match self.game {
+            SimGame::HighCard => self.tally(strategy, |seed, hands| {
+                let mut chooser = strategy::named(strategy, !seed)
+                    .expect("the strategy was checked to be built in");
+                play_high_card(seed, hands, chooser.as_mut())
+            }),
+            SimGame::Blackjack => {
+                let policy = Policy::named(strategy).expect("the policy was checked to exist");
+                self.tally(strategy, |seed, hands| {
+                    let mut blackjack = Blackjack::with_seed(seed);
+                    (0..hands)
+                        .filter(|_| blackjack.play_hand(policy).is_win())
+                        .count() as u64
+                })
+            }
+        };
+        Some(estimate)
+    }
+
+    /// Plays every hand of the high card game with strategies built by `make`,
+    /// one for each batch from that batch's seed, whichever game the simulator
+    /// was made for. This is how a strategy of your own is measured against the
+    /// built-in ones.
+    pub fn run_with<S, F>(&self, name: &str, make: F) -> Estimate
+    where
+        S: Strategy,
+        F: Fn(u64) -> S + Sync,
+    {
+        self.tally(name, |seed, hands| {
+            play_high_card(seed, hands, &mut make(!seed))
+        })
+    }
+
+    /// Adds up the wins `play` finds in every batch, given each batch's seed and
+    /// how many hands it plays.
+    fn tally<F>(&self, name: &str, play: F) -> Estimate
+    where
+        F: Fn(u64, u64) -> u64 + Sync,
+    {
+        let batches = self.hands.div_ceil(BATCH);
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(self.threads)
+            .build()
+            .expect("the simulation's threads couldn't be started");
+        let wins = pool.install(|| {
+            (0..batches)
+                .into_par_iter()
+                .map(|batch| {
+                    let hands = BATCH.min(self.hands - batch * BATCH);
+                    let seed = self
+                        .seed
+                        .wrapping_add(batch.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+                    play(seed, hands)
+                })
+                .sum()
+        });
+        Estimate {
+            strategy: name.to_string(),
+            hands: self.hands,
+            wins,
+        }
+    }
+}
+
+/// Wins from `hands` rounds of a high card game dealt from `seed`.
+fn play_high_card(seed: u64, hands: u64, strategy: &mut dyn Strategy) -> u64 {
+    let mut game = GameBuilder::new()
+        .with_seed(seed)
+        .spawn()
+        .expect("the default options are always in range");
+    game.simulate(hands as usize, strategy).wins as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Estimate, SimGame, Simulator};
+    use crate::strategy::{HighestVisibleSuit, Random};
+
+    #[test]
+    /// Tests results depend on the seed and not on the number of threads.
+    fn test_simulator() {
+        let simulator = Simulator::new(SimGame::HighCard, 3).hands(25_000);
+        let one = simulator.clone().threads(1).run("suit").unwrap();
+        let four = simulator.clone().threads(4).run("suit").unwrap();
+        assert_eq!(one, four);
+        assert_eq!(one.hands, 25_000);
+        assert_eq!(simulator.run_with("suit", |_| HighestVisibleSuit), one);
+        assert_eq!(
+            simulator.run_with("random", Random::new),
+            simulator.run("random").unwrap()
+        );
+
+        let first = simulator.run("first").unwrap();
+        let counting = simulator.run("counting").unwrap();
+        assert!(counting.interval().0 > first.interval().1);
+        assert_eq!(simulator.run("basic"), None);
+
+        let blackjack = Simulator::new(SimGame::Blackjack, 3).hands(20_000);
+        let basic = blackjack.run("basic").unwrap();
+        let stand = blackjack.run("stand").unwrap();
+        assert!(basic.win_rate() > stand.win_rate());
+    }
+
+    #[test]
+    /// Tests the confidence interval narrows around the win rate.
+    fn test_interval() {
+        let estimate = |hands, wins| Estimate {
+            strategy: "first".to_string(),
+            hands,
+            wins,
+        };
+        let (low, high) = estimate(100, 50).interval();
+        assert!((low - 0.404).abs() < 0.001 && (high - 0.596).abs() < 0.001);
+        let (low, high) = estimate(1_000_000, 500_000).interval();
+        assert!(low < 0.5 && high > 0.5 && high - low < 0.002);
+        assert_eq!(estimate(0, 0).interval(), (0.0, 1.0));
+        assert_eq!(
+            estimate(200, 50).to_string(),
+            "first       25.00% won, 95% between 19.51% and 31.43%"
+        );
+    }
+}
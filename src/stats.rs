@@ -0,0 +1,185 @@
+//! This module provides lifetime statistics kept between sessions.
+
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::{self};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Cumulative results across every session played.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub games: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    /// Picks whose time was taken, and the milliseconds they took altogether.
+    #[serde(default)]
+    pub timed_picks: usize,
+    #[serde(default)]
+    pub total_pick_ms: u64,
+    #[serde(default)]
+    pub fastest_pick_ms: Option<u64>,
+}
+
+impl Stats {
+    /// Construct empty stats.
+    pub fn new() -> Stats {
+        Stats::default()
+    }
+
+    /// Where stats are kept: `$XDG_DATA_HOME/card_game/stats.json`, falling back to
+    /// `~/.local/share/card_game/stats.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        let data_home = match env::var_os("XDG_DATA_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".local/share"),
+        };
+        Some(data_home.join("card_game").join("stats.json"))
+    }
+
+    /// Loads stats from `path`. A missing file means nothing has been played yet.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Stats> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Stats::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Saves stats to `path`, creating its directory if needed.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, json)
+    }
+
+    /// Records the result of a game.
+    pub fn record(&mut self, won: bool) {
+        self.games += 1;
+        if won {
+            self.wins += 1;
+            self.current_streak += 1;
+            self.longest_streak = self.longest_streak.max(self.current_streak);
+        } else {
+            self.losses += 1;
+            self.current_streak = 0;
+        }
+    }
+
+    /// Records how long a pick took.
+    pub fn record_pick(&mut self, took: Duration) {
+        let millis = took.as_millis() as u64;
+        self.timed_picks += 1;
+        self.total_pick_ms += millis;
+        self.fastest_pick_ms = Some(
+            self.fastest_pick_ms
+                .map_or(millis, |fastest| fastest.min(millis)),
+        );
+    }
+
+    /// The mean time taken to pick, or None before any picks were timed.
+    pub fn average_pick(&self) -> Option<Duration> {
+        if self.timed_picks == 0 {
+            return None;
+        }
+        Some(Duration::from_millis(
+            self.total_pick_ms / self.timed_picks as u64,
+        ))
+    }
+
+    /// Percentage of games won.
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.wins as f64 * 100.0 / self.games as f64
+    }
+}
+
+/// Displays the stats summary screen.
+impl Display for Stats {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        writeln!(formatter, "*------- Lifetime Stats -------*")?;
+        writeln!(formatter, "  Games played    {}", self.games)?;
+        writeln!(formatter, "  Wins            {}", self.wins)?;
+        writeln!(formatter, "  Losses          {}", self.losses)?;
+        writeln!(formatter, "  Win rate        {:.1}%", self.win_rate())?;
+        writeln!(formatter, "  Current streak  {}", self.current_streak)?;
+        writeln!(formatter, "  Longest streak  {}", self.longest_streak)?;
+        if let (Some(average), Some(fastest)) = (self.average_pick(), self.fastest_pick_ms) {
+            let average = average.as_millis() as f64 / 1000.0;
+            writeln!(formatter, "  Average pick    {:.1}s", average)?;
+            writeln!(
+                formatter,
+                "  Fastest pick    {:.1}s",
+                fastest as f64 / 1000.0
+            )?;
+        }
+        write!(formatter, "*------------------------------*")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stats;
+    use std::time::Duration;
+
+    #[test]
+    /// Tests streaks and win rate are tracked.
+    fn test_record() {
+        let mut stats = Stats::new();
+        for won in [true, true, false, true, true, true, false].iter() {
+            stats.record(*won);
+        }
+
+        assert_eq!(stats.games, 7);
+        assert_eq!(stats.wins, 5);
+        assert_eq!(stats.losses, 2);
+        assert_eq!(stats.current_streak, 0);
+        assert_eq!(stats.longest_streak, 3);
+        assert!((stats.win_rate() - 71.4).abs() < 0.1);
+        insta::assert_snapshot!(stats.to_string());
+    }
+
+    #[test]
+    /// Tests timed picks are averaged and the fastest kept.
+    fn test_record_pick() {
+        let mut stats = Stats::new();
+        assert_eq!(stats.average_pick(), None);
+        stats.record_pick(Duration::from_millis(2_500));
+        stats.record_pick(Duration::from_millis(700));
+        stats.record_pick(Duration::from_millis(1_200));
+
+        assert_eq!(stats.average_pick(), Some(Duration::from_millis(1_466)));
+        assert_eq!(stats.fastest_pick_ms, Some(700));
+        assert!(stats
+            .to_string()
+            .contains("  Average pick    1.5s\n  Fastest pick    0.7s\n"));
+    }
+
+    #[test]
+    /// Tests stats are saved and missing files load as empty stats.
+    fn test_save_and_load() {
+        let dir = std::env::temp_dir().join("card_game_test_stats");
+        let path = dir.join("nested").join("stats.json");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(Stats::load(&path).unwrap(), Stats::new());
+
+        let mut stats = Stats::new();
+        stats.record(true);
+        stats.save(&path).unwrap();
+        assert_eq!(Stats::load(&path).unwrap(), stats);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,329 @@
+//! This module provides the strategies that pick a card in the high card game.
+//!
+//! A `Strategy` is shown a `GameView` of the hand, where face down cards only
+//! give away their suit, and answers with a `Choice`. The same strategies play
+//! for the computer opponent and in simulations, so one written outside the
+//! crate can be measured against the built-in ones with `Game::simulate` or the
+//! simulator.
+
+use crate::cards::set::CardSet;
+use crate::cards::{Card, Hand, Suit};
+use crate::scoring::ScoringStrategy;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Write};
+
+/// The names `named` knows, weakest first.
+pub const NAMES: [&str; 4] = ["first", "random", "suit", "counting"];
+
+/// What a strategy can see of a round: the hand, face down cards showing only
+/// their suit, and how the cards will be scored.
+#[derive(Copy, Clone)]
+pub struct GameView<'a> {
+    pub hand: &'a Hand,
+    pub scoring: &'a dyn ScoringStrategy,
+}
+
+impl<'a> GameView<'a> {
+    pub fn new(hand: &'a Hand, scoring: &'a dyn ScoringStrategy) -> GameView<'a> {
+        GameView { hand, scoring }
+    }
+}
+
+/// What a strategy decided.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Choice {
+    /// Picks the card at this position in the hand.
+    Card(usize),
+    /// Stops playing.
+    Quit,
+}
+
+/// Decides which card in a hand to pick.
+pub trait Strategy {
+    /// Picks a card in the hand `view` shows.
+    fn choose(&mut self, view: &GameView) -> Choice;
+
+    /// Called with the revealed hand after each round, for strategies that learn
+    /// from the cards they've seen.
+    fn observe(&mut self, _hand: &Hand, _won: bool) {}
+
+    /// Called when a fresh deck is brought in, so nothing seen before is left
+    /// to count.
+    fn new_deck(&mut self) {}
+}
+
+/// A boxed strategy, such as one from `named`, plays as the strategy inside.
+impl<S: Strategy + ?Sized> Strategy for Box<S> {
+    fn choose(&mut self, view: &GameView) -> Choice {
+        (**self).choose(view)
+    }
+
+    fn observe(&mut self, hand: &Hand, won: bool) {
+        (**self).observe(hand, won)
+    }
+
+    fn new_deck(&mut self) {
+        (**self).new_deck()
+    }
+}
+
+/// Builds the built-in strategy called `name`, with any random picks coming from
+/// `seed`. See `NAMES`.
+pub fn named(name: &str, seed: u64) -> Option<Box<dyn Strategy + Send>> {
+    match name {
+        "first" => Some(Box::new(FirstCard)),
+        "random" => Some(Box::new(Random::new(seed))),
+        "suit" => Some(Box::new(HighestVisibleSuit)),
+        "counting" => Some(Box::new(CardCounter::new())),
+        _ => None,
+    }
+}
+
+/// Picks any card.
+#[derive(Debug, Clone)]
+pub struct Random {
+    rng: StdRng,
+}
+
+impl Random {
+    /// Construct a strategy whose picks come from `seed`.
+    pub fn new(seed: u64) -> Random {
+        Random {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Construct a strategy with a randomly seeded generator.
+    pub fn from_entropy() -> Random {
+        Random {
+            rng: StdRng::from_entropy(),
+        }
+    }
+}
+
+impl Strategy for Random {
+    fn choose(&mut self, view: &GameView) -> Choice {
+        Choice::Card(self.rng.gen_range(0..view.hand.len()))
+    }
+}
+
+/// Always picks the first card.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct FirstCard;
+
+impl Strategy for FirstCard {
+    fn choose(&mut self, _view: &GameView) -> Choice {
+        Choice::Card(0)
+    }
+}
+
+/// Picks the face up card with the best score, or the face down card whose suit
+/// scores best on average across a full deck, whichever is worth more. Ties go
+/// to the first card.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct HighestVisibleSuit;
+
+impl Strategy for HighestVisibleSuit {
+    fn choose(&mut self, view: &GameView) -> Choice {
+        best(view, CardSet::standard())
+    }
+}
+
+/// Picks the same way as `HighestVisibleSuit`, but judges face down cards by
+/// the cards it hasn't seen turned over since the deck was new.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CardCounter {
+    seen: CardSet,
+}
+
+impl CardCounter {
+    pub fn new() -> CardCounter {
+        CardCounter::default()
+    }
+
+    /// Returns true if `card` has been turned over since the deck was new.
+    pub fn has_seen(&self, card: &Card) -> bool {
+        self.seen.contains(card)
+    }
+}
+
+impl Strategy for CardCounter {
+    fn choose(&mut self, view: &GameView) -> Choice {
+        best(view, CardSet::standard().difference(self.seen))
+    }
+
+    fn observe(&mut self, hand: &Hand, _won: bool) {
+        self.seen.extend(hand.iter().copied());
+    }
+
+    fn new_deck(&mut self) {
+        self.seen = CardSet::new();
+    }
+}
+
+/// Asks a person for each pick, one line at a time.
+pub struct Human {
+    input: Box<dyn BufRead + Send>,
+    output: Box<dyn Write + Send>,
+}
+
+impl Human {
+    /// Construct a player reading picks from `input` and asking on `output`.
+    pub fn new<R, W>(input: R, output: W) -> Human
+    where
+        R: BufRead + Send + 'static,
+        W: Write + Send + 'static,
+    {
+        Human {
+            input: Box::new(input),
+            output: Box::new(output),
+        }
+    }
+
+    /// Construct a player at the terminal.
+    pub fn stdio() -> Human {
+        Human::new(BufReader::new(io::stdin()), io::stdout())
+    }
+
+    /// Shows the hand and reads a pick, asking again until one is given. None
+    /// if the player quits or the input runs out.
+    fn ask(&mut self, hand: &Hand) -> io::Result<Option<usize>> {
+        let shown: Vec<String> = hand
+            .iter()
+            .map(|card| match card.state.is_face_up() {
+                true => card.to_string(),
+                false => format!("?{}", card.suit.symbol()),
+            })
+            .collect();
+        loop {
+            writeln!(self.output, "{}", shown.join(", "))?;
+            write!(self.output, "Pick a card from 0 to {}: ", hand.len() - 1)?;
+            self.output.flush()?;
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 || line.trim() == "q" {
+                return Ok(None);
+            }
+            match line.trim().parse::<usize>() {
+                Ok(choice) if choice < hand.len() => return Ok(Some(choice)),
+                _ => writeln!(self.output, "{} isn't one of the cards.", line.trim())?,
+            }
+        }
+    }
+}
+
+impl Strategy for Human {
+    fn choose(&mut self, view: &GameView) -> Choice {
+        match self.ask(view.hand) {
+            Ok(Some(choice)) => Choice::Card(choice),
+            _ => Choice::Quit,
+        }
+    }
+}
+
+/// Picks the card worth most, face up cards by their score and face down ones
+/// by the average score of the cards of their suit in `unseen`.
+fn best(view: &GameView, unseen: CardSet) -> Choice {
+    let worth: Vec<f64> = view
+        .hand
+        .iter()
+        .map(|card| match card.state.is_face_up() {
+            true => view.scoring.score(card) as f64,
+            false => expected_score(unseen, card.suit, view.scoring),
+        })
+        .collect();
+    let mut best = 0;
+    for (idx, &value) in worth.iter().enumerate() {
+        if value > worth[best] {
+            best = idx;
+        }
+    }
+    Choice::Card(best)
+}
+
+/// Average score of the cards in `cards` of `suit`, or zero if there are none.
+fn expected_score(cards: CardSet, suit: Suit, scoring: &dyn ScoringStrategy) -> f64 {
+    let of_suit = cards.intersection(CardSet::of_suit(suit));
+    if of_suit.is_empty() {
+        return 0.0;
+    }
+    let total: u32 = of_suit.iter().map(|card| scoring.score(&card)).sum();
+    total as f64 / of_suit.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        named, CardCounter, Choice, FirstCard, GameView, HighestVisibleSuit, Human, Random,
+        Strategy, NAMES,
+    };
+    use crate::cards::{Card, Hand, Rank, Suit};
+    use crate::scoring::SuitWeightedScoring;
+    use std::io::{self, Cursor};
+
+    fn twos() -> Hand {
+        Hand::from_cards(vec![
+            Card::new(Suit::Diamonds, Rank::Two),
+            Card::new(Suit::Clubs, Rank::Two),
+            Card::new(Suit::Hearts, Rank::Two),
+        ])
+    }
+
+    #[test]
+    /// Tests the suit strategies favour clubs, trust face up cards and count.
+    fn test_built_in_strategies() {
+        let mut hand = Hand::from_cards(vec![
+            Card::new(Suit::Diamonds, Rank::Ace),
+            Card::new(Suit::Clubs, Rank::Two),
+            Card::new(Suit::Spades, Rank::Two),
+        ]);
+        let choose =
+            |hand: &Hand| HighestVisibleSuit.choose(&GameView::new(hand, &SuitWeightedScoring));
+        assert_eq!(choose(&hand), Choice::Card(1));
+        hand.reveal(0);
+        assert_eq!(choose(&hand), Choice::Card(1));
+        hand.reveal(1);
+        assert_eq!(choose(&hand), Choice::Card(0));
+
+        // Once every high club is gone a heart is the better bet.
+        let hand = twos();
+        let view = GameView::new(&hand, &SuitWeightedScoring);
+        let mut counter = CardCounter::new();
+        assert_eq!(counter.choose(&view), Choice::Card(1));
+        let clubs = [
+            Rank::Ace,
+            Rank::King,
+            Rank::Queen,
+            Rank::Jack,
+            Rank::Ten,
+            Rank::Nine,
+            Rank::Eight,
+        ]
+        .iter()
+        .map(|&rank| Card::new(Suit::Clubs, rank))
+        .collect();
+        counter.observe(&Hand::from_cards(clubs), false);
+        assert!(counter.has_seen(&Card::new(Suit::Clubs, Rank::Ace)));
+        assert_eq!(counter.choose(&view), Choice::Card(2));
+        counter.new_deck();
+        assert_eq!(counter.choose(&view), Choice::Card(1));
+
+        assert_eq!(FirstCard.choose(&view), Choice::Card(0));
+        assert!(matches!(Random::new(1).choose(&view), Choice::Card(idx) if idx < 3));
+        assert!(NAMES.iter().all(|name| named(name, 1).is_some()));
+        assert!(named("human", 1).is_none());
+    }
+
+    #[test]
+    /// Tests a person is asked again until they pick a card, and can quit.
+    fn test_human() {
+        let hand = twos();
+        let view = GameView::new(&hand, &SuitWeightedScoring);
+        let mut human = Human::new(Cursor::new("7\n2\nq\n"), io::sink());
+        assert_eq!(human.choose(&view), Choice::Card(2));
+        assert_eq!(human.choose(&view), Choice::Quit);
+        assert_eq!(human.choose(&view), Choice::Quit);
+    }
+}
@@ -0,0 +1,211 @@
+//! This module provides the colours and text styles cards are painted with.
+//!
+//! Everything that writes ANSI escape codes goes through `Color` and `Style`, so
+//! the renderer doesn't depend on a particular colour crate. They're backed by
+//! `nu-ansi-term`, which can also turn on escape codes in Windows consoles.
+//!
+//! Windows consoles need switching on at runtime, both for escape codes and for
+//! the UTF-8 suit symbols, so `enable_ansi` and `enable_unicode` are asked before
+//! either is written. When a console refuses, the renderer falls back to plain
+//! text and suit letters.
+
+use nu_ansi_term::{Color as Backend, Style as BackendStyle};
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// One of the eight basic terminal colours.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Purple,
+    Cyan,
+    White,
+}
+
+impl Color {
+    /// Looks up a colour by name, as in `red`. `magenta` is another name for
+    /// `purple`.
+    pub fn named(name: &str) -> Option<Color> {
+        match name {
+            "black" => Some(Color::Black),
+            "red" => Some(Color::Red),
+            "green" => Some(Color::Green),
+            "yellow" => Some(Color::Yellow),
+            "blue" => Some(Color::Blue),
+            "purple" | "magenta" => Some(Color::Purple),
+            "cyan" => Some(Color::Cyan),
+            "white" => Some(Color::White),
+            _ => None,
+        }
+    }
+
+    /// `text` in this colour.
+    pub fn paint<T: Display>(self, text: T) -> String {
+        Style::new().fg(self).paint(text)
+    }
+
+    /// This colour in bold.
+    pub fn bold(self) -> Style {
+        Style::new().fg(self).bold()
+    }
+
+    /// This colour underlined.
+    pub fn underline(self) -> Style {
+        Style::new().fg(self).underline()
+    }
+
+    /// The escape code that switches to this colour.
+    pub fn prefix(self) -> String {
+        Style::new().fg(self).prefix()
+    }
+
+    fn backend(self) -> Backend {
+        match self {
+            Color::Black => Backend::Black,
+            Color::Red => Backend::Red,
+            Color::Green => Backend::Green,
+            Color::Yellow => Backend::Yellow,
+            Color::Blue => Backend::Blue,
+            Color::Purple => Backend::Purple,
+            Color::Cyan => Backend::Cyan,
+            Color::White => Backend::White,
+        }
+    }
+}
+
+/// A foreground colour with bold and underline, all optional. The default
+/// style writes text unchanged.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Style {
+    foreground: Option<Color>,
+    bold: bool,
+    underline: bool,
+}
+
+impl Style {
+    /// A style that writes text unchanged.
+    pub fn new() -> Style {
+        Style::default()
+    }
+
+    /// The same style in `color`.
+    pub fn fg(mut self, color: Color) -> Style {
+        self.foreground = Some(color);
+        self
+    }
+
+    /// The same style in bold.
+    pub fn bold(mut self) -> Style {
+        self.bold = true;
+        self
+    }
+
+    /// The same style underlined.
+    pub fn underline(mut self) -> Style {
+        self.underline = true;
+        self
+    }
+
+    /// `text` in this style, followed by a reset when the style changes anything.
+    pub fn paint<T: Display>(self, text: T) -> String {
+        self.backend().paint(text.to_string()).to_string()
+    }
+
+    /// The escape codes that switch to this style.
+    pub fn prefix(self) -> String {
+        self.backend().prefix().to_string()
+    }
+
+    fn backend(self) -> BackendStyle {
+        let mut style = BackendStyle::new();
+        if let Some(color) = self.foreground {
+            style = style.fg(color.backend());
+        }
+        if self.bold {
+            style = style.bold();
+        }
+        if self.underline {
+            style = style.underline();
+        }
+        style
+    }
+}
+
+/// Makes sure the console understands escape codes, returning whether it does.
+/// Windows consoles need virtual terminal processing switched on first, which
+/// is tried once; everywhere else this is always true.
+pub fn enable_ansi() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        #[cfg(windows)]
+        {
+            nu_ansi_term::enable_ansi_support().is_ok()
+        }
+        #[cfg(not(windows))]
+        {
+            true
+        }
+    })
+}
+
+/// Makes sure the console shows UTF-8 text such as the suit symbols, returning
+/// whether it does. A Windows console on another code page is switched to UTF-8
+/// once, and keeps its code page if it refuses. Everywhere else this is always
+/// true and the locale decides instead.
+pub fn enable_unicode() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        #[cfg(windows)]
+        {
+            windows::use_utf8()
+        }
+        #[cfg(not(windows))]
+        {
+            true
+        }
+    })
+}
+
+#[cfg(windows)]
+mod windows {
+    /// The UTF-8 code page.
+    const CP_UTF8: u32 = 65001;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetConsoleOutputCP() -> u32;
+        fn SetConsoleOutputCP(code_page: u32) -> i32;
+    }
+
+    /// Switches console output to UTF-8, returning whether it's UTF-8 now.
+    pub fn use_utf8() -> bool {
+        unsafe { GetConsoleOutputCP() == CP_UTF8 || SetConsoleOutputCP(CP_UTF8) != 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Color, Style};
+
+    #[test]
+    /// Tests styles write the same escape codes as the backend.
+    fn test_paint() {
+        assert_eq!(Color::Red.paint("♥"), "\x1b[31m♥\x1b[0m");
+        assert_eq!(Color::Yellow.bold().prefix(), "\x1b[1;33m");
+        assert_eq!(Color::Green.underline().paint("A"), "\x1b[4;32mA\x1b[0m");
+        assert_eq!(Style::new().bold().paint("K"), "\x1b[1mK\x1b[0m");
+        assert_eq!(Style::new().paint("Q"), "Q");
+    }
+
+    #[test]
+    /// Tests colours are looked up by name.
+    fn test_named() {
+        assert_eq!(Color::named("cyan"), Some(Color::Cyan));
+        assert_eq!(Color::named("magenta"), Some(Color::Purple));
+        assert_eq!(Color::named("pink"), None);
+    }
+}
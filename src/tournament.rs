@@ -0,0 +1,456 @@
+//! This module provides tournaments between strategies in the high card game.
+//!
+//! Each match is a number of games, and in each game both strategies play the
+//! same deal from the same seed, so neither gets the better cards. Whoever wins
+//! more rounds takes the game, and whoever takes more games takes the match. In
+//! a round robin every entrant meets every other once and the standings decide
+//! the champion; in a knockout the loser of each match is out and the last one
+//! left is the champion.
+
+use crate::game::GameBuilder;
+use crate::strategy::Strategy;
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+/// Rounds in each game, as many as one deck deals three cards at a time.
+pub const ROUNDS: usize = 17;
+
+/// How entrants are drawn against one another.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Everyone meets everyone else once.
+    #[default]
+    RoundRobin,
+    /// Entrants meet in pairs, in the order they entered, and losers are out.
+    Knockout,
+}
+
+impl Format {
+    /// Looks up a format by name.
+    pub fn named(name: &str) -> Option<Format> {
+        match name {
+            "round-robin" => Some(Format::RoundRobin),
+            "knockout" => Some(Format::Knockout),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Format::RoundRobin => write!(formatter, "round robin"),
+            Format::Knockout => write!(formatter, "knockout"),
+        }
+    }
+}
+
+/// The rounds of a round robin between `entrants` entrants, each a list of the
+/// pairs who meet in it by their place in the entry order. With an odd number
+/// of entrants one sits out each round.
+pub fn round_robin(entrants: usize) -> Vec<Vec<(usize, usize)>> {
+    if entrants < 2 {
+        return Vec::new();
+    }
+    // An odd field is made even with a stand-in, and whoever meets it rests.
+    let slots = entrants + entrants % 2;
+    let mut order: Vec<usize> = (0..slots).collect();
+    let mut rounds = Vec::new();
+    for _ in 1..slots {
+        let round = (0..slots / 2)
+            .map(|idx| (order[idx], order[slots - 1 - idx]))
+            .filter(|&(first, second)| first < entrants && second < entrants)
+            .collect();
+        rounds.push(round);
+        order[1..].rotate_right(1);
+    }
+    rounds
+}
+
+/// How a match between two entrants went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchResult {
+    /// The round of the tournament the match was played in, from 1.
+    pub round: usize,
+    pub first: String,
+    pub second: String,
+    pub first_games: usize,
+    pub second_games: usize,
+    /// Games where both won as many rounds.
+    pub drawn: usize,
+    pub first_rounds: usize,
+    pub second_rounds: usize,
+}
+
+impl MatchResult {
+    /// How the first entrant did against the second, on games and then on
+    /// rounds won.
+    pub fn outcome(&self) -> Ordering {
+        self.first_games
+            .cmp(&self.second_games)
+            .then(self.first_rounds.cmp(&self.second_rounds))
+    }
+
+    /// The name of the entrant who took the match, or None if it was level.
+    pub fn winner(&self) -> Option<&str> {
+        match self.outcome() {
+            Ordering::Greater => Some(&self.first),
+            Ordering::Less => Some(&self.second),
+            Ordering::Equal => None,
+        }
+    }
+}
+
+impl Display for MatchResult {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{:<10} {}-{} {}",
+            self.first, self.first_games, self.second_games, self.second
+        )?;
+        if self.drawn > 0 {
+            write!(formatter, " ({} drawn)", self.drawn)?;
+        }
+        Ok(())
+    }
+}
+
+/// An entrant's record over the tournament.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Standing {
+    pub name: String,
+    pub played: usize,
+    pub won: usize,
+    pub drawn: usize,
+    pub lost: usize,
+    /// Rounds won across every game, which separates entrants level on points.
+    pub rounds: usize,
+}
+
+impl Standing {
+    /// Three points for each match won and one for each drawn.
+    pub fn points(&self) -> usize {
+        self.won * 3 + self.drawn
+    }
+}
+
+/// Everything a tournament played out to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub format: Format,
+    /// Games played in each match.
+    pub games: usize,
+    /// Every match in the order it was played.
+    pub matches: Vec<MatchResult>,
+    /// Every entrant, best first.
+    pub standings: Vec<Standing>,
+}
+
+impl Report {
+    /// The entrant who won: the last one standing in a knockout, or the top of
+    /// the standings in a round robin. None without any entrants.
+    pub fn champion(&self) -> Option<&str> {
+        match (self.format, self.matches.last()) {
+            (Format::Knockout, Some(last)) => Some(last.winner().unwrap_or(last.first.as_str())),
+            _ => self
+                .standings
+                .first()
+                .map(|standing| standing.name.as_str()),
+        }
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        let mut round = 0;
+        for result in &self.matches {
+            if result.round != round {
+                round = result.round;
+                writeln!(formatter, "Round {}", round)?;
+            }
+            writeln!(formatter, "  {}", result)?;
+        }
+        writeln!(
+            formatter,
+            "\n{:<10} {:>6} {:>4} {:>5} {:>4} {:>6} {:>6}",
+            "Entrant", "Played", "Won", "Drawn", "Lost", "Points", "Rounds"
+        )?;
+        for standing in &self.standings {
+            writeln!(
+                formatter,
+                "{:<10} {:>6} {:>4} {:>5} {:>4} {:>6} {:>6}",
+                standing.name,
+                standing.played,
+                standing.won,
+                standing.drawn,
+                standing.lost,
+                standing.points(),
+                standing.rounds
+            )?;
+        }
+        match self.champion() {
+            Some(champion) => write!(formatter, "\nChampion: {}", champion),
+            None => write!(formatter, "\nNobody entered."),
+        }
+    }
+}
+
+/// Builds an entrant's strategy afresh for each game from a seed.
+type Make = Box<dyn Fn(u64) -> Box<dyn Strategy>>;
+
+struct Entrant {
+    name: String,
+    make: Make,
+}
+
+/// Strategies entered to play one another.
+pub struct Tournament {
+    format: Format,
+    seed: u64,
+    games: usize,
+    entrants: Vec<Entrant>,
+}
+
+impl Tournament {
+    /// Construct a tournament in `format` without any entrants, ten games a
+    /// match and every deal coming from `seed`.
+    pub fn new(format: Format, seed: u64) -> Tournament {
+        Tournament {
+            format,
+            seed,
+            games: 10,
+            entrants: Vec::new(),
+        }
+    }
+
+    /// Option to play `games` games in each match.
+    pub fn games(mut self, games: usize) -> Tournament {
+        self.games = games.max(1);
+        self
+    }
+
+    /// Option to enter a strategy called `name`, which `make` builds for each
+    /// game from a seed for any random picks.
+    pub fn entrant<F>(mut self, name: &str, make: F) -> Tournament
+    where
+        F: Fn(u64) -> Box<dyn Strategy> + 'static,
+    {
+        self.entrants.push(Entrant {
+            name: name.to_string(),
+            make: Box::new(make),
+        });
+        self
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Number of strategies entered.
+    pub fn len(&self) -> usize {
+        self.entrants.len()
+    }
+
+    /// Returns true if nothing has been entered.
+    pub fn is_empty(&self) -> bool {
+        self.entrants.is_empty()
+    }
+
+    /// Plays every match and adds up the standings.
+    pub fn run(&self) -> Report {
+        let mut matches = Vec::new();
+        match self.format {
+            Format::RoundRobin => {
+                for (round, pairs) in round_robin(self.len()).into_iter().enumerate() {
+                    for (first, second) in pairs {
+                        matches.push(self.play_match(round + 1, first, second, matches.len()));
+                    }
+                }
+            }
+            Format::Knockout => {
+                let mut left: Vec<usize> = (0..self.len()).collect();
+                let mut round = 1;
+                while left.len() > 1 {
+                    let mut through = Vec::new();
+                    for pair in left.chunks(2) {
+                        match *pair {
+                            [first, second] => {
+                                let result = self.play_match(round, first, second, matches.len());
+                                // A level match goes to whoever entered first.
+                                through.push(match result.outcome() {
+                                    Ordering::Less => second,
+                                    _ => first,
+                                });
+                                matches.push(result);
+                            }
+                            // The odd one out goes through without playing.
+                            _ => through.extend_from_slice(pair),
+                        }
+                    }
+                    left = through;
+                    round += 1;
+                }
+            }
+        }
+        Report {
+            format: self.format,
+            games: self.games,
+            standings: self.standings(&matches),
+            matches,
+        }
+    }
+
+    /// Plays the `number`th match of the tournament between two entrants.
+    fn play_match(&self, round: usize, first: usize, second: usize, number: usize) -> MatchResult {
+        let mut result = MatchResult {
+            round,
+            first: self.entrants[first].name.clone(),
+            second: self.entrants[second].name.clone(),
+            first_games: 0,
+            second_games: 0,
+            drawn: 0,
+            first_rounds: 0,
+            second_rounds: 0,
+        };
+        for game in 0..self.games {
+            let deal = (number * self.games + game) as u64;
+            let seed = self
+                .seed
+                .wrapping_add(deal.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+            let first_rounds = self.play_game(first, seed);
+            let second_rounds = self.play_game(second, seed);
+            result.first_rounds += first_rounds;
+            result.second_rounds += second_rounds;
+            match first_rounds.cmp(&second_rounds) {
+                Ordering::Greater => result.first_games += 1,
+                Ordering::Less => result.second_games += 1,
+                Ordering::Equal => result.drawn += 1,
+            }
+        }
+        result
+    }
+
+    /// Rounds an entrant wins in a game dealt from `seed`.
+    fn play_game(&self, entrant: usize, seed: u64) -> usize {
+        let mut game = GameBuilder::new()
+            .with_seed(seed)
+            .spawn()
+            .expect("the default options are always in range");
+        let mut strategy = (self.entrants[entrant].make)(!seed);
+        game.simulate(ROUNDS, strategy.as_mut()).wins
+    }
+
+    /// Every entrant's record from `matches`, best first: on points, then rounds
+    /// won, then the order they entered.
+    fn standings(&self, matches: &[MatchResult]) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .entrants
+            .iter()
+            .map(|entrant| Standing {
+                name: entrant.name.clone(),
+                ..Standing::default()
+            })
+            .collect();
+        for result in matches {
+            let sides = [
+                (&result.first, result.first_rounds, result.outcome()),
+                (
+                    &result.second,
+                    result.second_rounds,
+                    result.outcome().reverse(),
+                ),
+            ];
+            for (name, rounds, outcome) in sides {
+                // Names are looked up from the front, so an entrant entered twice
+                // under one name shares a single line.
+                if let Some(standing) = standings.iter_mut().find(|s| &s.name == name) {
+                    standing.played += 1;
+                    standing.rounds += rounds;
+                    match outcome {
+                        Ordering::Greater => standing.won += 1,
+                        Ordering::Equal => standing.drawn += 1,
+                        Ordering::Less => standing.lost += 1,
+                    }
+                }
+            }
+        }
+        standings.sort_by(|a, b| b.points().cmp(&a.points()).then(b.rounds.cmp(&a.rounds)));
+        standings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{round_robin, Format, Tournament};
+    use crate::strategy::{self, FirstCard};
+    use std::collections::HashSet;
+
+    fn tournament(format: Format, names: &[&'static str]) -> Tournament {
+        names
+            .iter()
+            .fold(Tournament::new(format, 11).games(4), |tournament, &name| {
+                tournament.entrant(name, move |seed| strategy::named(name, seed).unwrap())
+            })
+    }
+
+    #[test]
+    /// Tests every pair meets exactly once and nobody plays twice in a round.
+    fn test_round_robin() {
+        for entrants in 2..=7 {
+            let rounds = round_robin(entrants);
+            assert_eq!(rounds.len(), entrants - 1 + entrants % 2);
+            let mut met = HashSet::new();
+            for round in &rounds {
+                let mut playing = HashSet::new();
+                for &(first, second) in round {
+                    assert!(playing.insert(first) && playing.insert(second));
+                    assert!(met.insert((first.min(second), first.max(second))));
+                }
+            }
+            assert_eq!(met.len(), entrants * (entrants - 1) / 2);
+        }
+        assert!(round_robin(1).is_empty());
+    }
+
+    #[test]
+    /// Tests a round robin plays every pairing and ranks the entrants.
+    fn test_tournament() {
+        let league = tournament(Format::RoundRobin, &strategy::NAMES);
+        let report = league.run();
+        assert_eq!(report.matches.len(), 6);
+        assert!(report.standings.iter().all(|standing| standing.played == 3));
+        assert!(report
+            .standings
+            .windows(2)
+            .all(|pair| pair[0].points() >= pair[1].points()));
+        assert_eq!(report.champion(), Some(report.standings[0].name.as_str()));
+        assert_eq!(report.champion(), Some("counting"));
+        assert_eq!(league.run(), report);
+        assert!(report.to_string().contains("Round 3\n"));
+
+        // Both sides of a game are dealt the same cards, so a strategy playing
+        // itself draws every game.
+        let mirror = Tournament::new(Format::RoundRobin, 4)
+            .entrant("one", |_| Box::new(FirstCard))
+            .entrant("two", |_| Box::new(FirstCard))
+            .run();
+        assert_eq!(mirror.matches[0].drawn, 10);
+        assert_eq!(mirror.matches[0].winner(), None);
+    }
+
+    #[test]
+    /// Tests a knockout gives the odd one out a bye and ends with one champion.
+    fn test_knockout() {
+        let cup = tournament(Format::Knockout, &["counting", "first", "suit"]).run();
+        let rounds: Vec<usize> = cup.matches.iter().map(|result| result.round).collect();
+        assert_eq!(rounds, vec![1, 2]);
+        assert_eq!(cup.matches[1].second, "suit");
+        assert_eq!(cup.champion(), cup.matches[1].winner());
+        assert_eq!(Format::named("knockout"), Some(Format::Knockout));
+        assert_eq!(Tournament::new(Format::Knockout, 1).run().champion(), None);
+    }
+}
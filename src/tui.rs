@@ -0,0 +1,603 @@
+//! This module provides a full screen terminal frontend for the high card game.
+//!
+//! Each frame is rendered to a string and drawn in one write so the screen doesn't
+//! flicker. Cards are picked with the arrow keys instead of typing an index, or
+//! by pointing at one with the mouse and clicking it. When the terminal is
+//! resized the screen is drawn again at the new width, wrapping the hand onto
+//! more rows if it no longer fits.
+//!
+//! [q], [Esc] or Ctrl+C pauses the game with a menu to resume, save and quit,
+//! look at the stats or quit. However the frontend exits the terminal is put
+//! back the way it was found.
+
+use crate::cards::card_printer::animation::{self, flip_steps};
+use crate::cards::card_printer::{card_at, columns_for, write_hand_with, RenderOptions};
+use crate::cards::Hand;
+use crate::config::Config;
+use crate::engine::high_card::{hint, opponent_result, values};
+use crate::engine::{self};
+use crate::game::state::{Event, Input, Phase};
+use crate::game::Game;
+use crate::input::{
+    catch_interrupt, read_event_timeout, watch_resize, Mouse, MouseKind, TermEvent,
+};
+use crate::locale::{Locale, Message};
+use crate::stats::Stats;
+use console::style;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture, KeyCode};
+use crossterm::style::{Print, ResetColor};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::{execute, queue};
+use std::fmt::Write;
+use std::io::{self, Write as _};
+use std::time::{Duration, Instant};
+use tokio::time;
+
+/// Chips the wager goes up or down by per key press.
+const WAGER_STEP: u64 = 5;
+
+/// How often the countdown is redrawn while waiting for a timed pick.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Cells in the countdown bar.
+const COUNTDOWN_WIDTH: usize = 20;
+
+/// What the player picked from the pause menu.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Pause {
+    Resume,
+    Save,
+    Quit,
+}
+
+/// Resets colours, shows the cursor again, stops mouse reporting and lets Ctrl+C
+/// end the program again when the frontend exits, even on errors.
+struct CursorGuard;
+
+impl Drop for CursorGuard {
+    fn drop(&mut self) {
+        catch_interrupt(false);
+        let _ = execute!(io::stdout(), ResetColor, DisableMouseCapture, Show);
+    }
+}
+
+/// Plays the high card game full screen until the deck runs out, drawing cards
+/// with the configured theme. With a `timeout` the random choice is made for the
+/// player once it passes. With `practice` a round can be retried with [u] once
+/// its result is in.
+/// Returns true if the player saved and quit. Quitting from the pause menu ends
+/// the game as if it were over.
+pub async fn run(
+    game: &mut Game,
+    stats: &mut Stats,
+    config: &Config,
+    timeout: Option<Duration>,
+    practice: bool,
+) -> io::Result<bool> {
+    execute!(io::stdout(), Hide, EnableMouseCapture)?;
+    let _guard = CursorGuard;
+    watch_resize();
+    catch_interrupt(true);
+    let render = config.render_options();
+    let render = render.clone().suits(render.suits.resolve());
+    let locale = config.locale();
+    let speed = game.speed();
+    let animation = config.animation() && speed.animates();
+
+    let mut wager = 10;
+
+    loop {
+        if game.phase() != Phase::AwaitingChoice {
+            if game.advance(Input::Deal) != Event::Dealt {
+                break;
+            }
+            if animation {
+                let cards = game.hand().cards();
+                for dealt in 1..cards.len() {
+                    let hand = Hand::from_cards(cards[..dealt].to_vec());
+                    let message = locale.text(Message::Dealing);
+                    draw(&frame(&hand, game, &render, None, message, locale, width()))?;
+                    time::sleep(speed.scale(animation::FLIP_FRAME)).await;
+                }
+            }
+        }
+        let mut selected = 0;
+        let mut started = Instant::now();
+        let mut deadline = timeout.map(|timeout| started + timeout);
+
+        let (choice, pick) = loop {
+            let prompt = match (timeout, deadline) {
+                (Some(timeout), Some(deadline)) => format!(
+                    "{} {}\n{}",
+                    locale.text(Message::FindHighCard),
+                    locale.text(Message::FasterPicks),
+                    countdown(deadline.saturating_duration_since(Instant::now()), timeout)
+                ),
+                _ => locale.text(Message::FindHighCard).to_string(),
+            };
+            let prompt = if config.hints() {
+                format!("{}\n{}", prompt, hint(game, locale))
+            } else {
+                prompt
+            };
+            let message = match game.bank() {
+                Some(bank) => {
+                    wager = wager.clamp(1, bank.chips());
+                    format!(
+                        "{} {}\n{}",
+                        prompt,
+                        locale.format(Message::Wager, &[&wager]),
+                        style(locale.text(Message::BetControls)).dim()
+                    )
+                }
+                None => prompt,
+            };
+            draw(&frame(
+                game.hand(),
+                game,
+                &render,
+                Some(selected),
+                &message,
+                locale,
+                width(),
+            ))?;
+            let event = match deadline {
+                Some(deadline) => {
+                    let left = deadline.saturating_duration_since(Instant::now());
+                    match read_event_timeout(Some(left.min(TICK))).await? {
+                        Some(event) => event,
+                        None if left > TICK => continue,
+                        None => {
+                            let choice = game.random_choice();
+                            break (choice, Input::Choose(choice));
+                        }
+                    }
+                }
+                None => match read_event_timeout(None).await? {
+                    Some(event) => event,
+                    None => continue,
+                },
+            };
+            let key = match event {
+                TermEvent::Key(key) => key,
+                TermEvent::Interrupt => KeyCode::Esc,
+                TermEvent::Resize => continue,
+                TermEvent::Mouse(mouse) => {
+                    match (card_under(game, &render, mouse, width()), mouse.kind) {
+                        (Some(idx), MouseKind::Click) => {
+                            selected = idx;
+                            if !config.confirm() || confirm_pick(game, &render, idx, locale).await?
+                            {
+                                break (idx, Input::ChooseIn(idx, started.elapsed()));
+                            }
+                        }
+                        (Some(idx), MouseKind::Move) => selected = idx,
+                        _ => {}
+                    }
+                    continue;
+                }
+            };
+            match key {
+                KeyCode::Left | KeyCode::Char('h') => selected = selected.saturating_sub(1),
+                KeyCode::Right | KeyCode::Char('l') => {
+                    selected = (selected + 1).min(game.hand().len() - 1)
+                }
+                KeyCode::Char(c) if c.is_ascii_digit() => {
+                    let idx = c.to_digit(10).unwrap() as usize;
+                    if idx < game.hand().len() {
+                        selected = idx;
+                    }
+                }
+                KeyCode::Up => wager += WAGER_STEP,
+                KeyCode::Down => wager = wager.saturating_sub(WAGER_STEP),
+                KeyCode::Enter | KeyCode::Char(' ')
+                    if !config.confirm()
+                        || confirm_pick(game, &render, selected, locale).await? =>
+                {
+                    break (selected, Input::ChooseIn(selected, started.elapsed()))
+                }
+                KeyCode::Char('r') => {
+                    let choice = game.random_choice();
+                    break (choice, Input::Choose(choice));
+                }
+                KeyCode::Char('q') | KeyCode::Esc => {
+                    let paused = Instant::now();
+                    match pause_menu(stats, locale).await? {
+                        // Time spent paused doesn't count against a timed pick.
+                        Pause::Resume => {
+                            started += paused.elapsed();
+                            deadline = deadline.map(|deadline| deadline + paused.elapsed());
+                        }
+                        Pause::Save => return Ok(true),
+                        Pause::Quit => return Ok(false),
+                    }
+                }
+                _ => {}
+            }
+        };
+
+        if game.bank().is_some() {
+            game.advance(Input::Wager(wager));
+        }
+
+        let dealt = game.hand().clone();
+        if let Event::Chose { swaps, .. } = game.advance(pick) {
+            let mut shuffled = dealt;
+            for (first, second) in swaps {
+                shuffled.swap(first, second);
+                let message = locale.text(Message::Shuffling);
+                draw(&frame(
+                    &shuffled,
+                    game,
+                    &render,
+                    Some(choice),
+                    message,
+                    locale,
+                    width(),
+                ))?;
+                time::sleep(speed.scale(Duration::from_millis(300))).await;
+            }
+        }
+
+        let message = locale.text(Message::Results);
+        draw(&frame(
+            game.hand(),
+            game,
+            &render,
+            Some(choice),
+            message,
+            locale,
+            width(),
+        ))?;
+        engine::pause(speed.scale(config.sleep())).await?;
+
+        let before = stats.clone();
+        let hidden = game.hand().clone();
+        let result = match game.advance(Input::Reveal) {
+            Event::Revealed(result) => result,
+            event => unreachable!("a pick is always revealed, got {:?}", event),
+        };
+        if animation {
+            let steps = flip_steps(hidden.cards(), game.hand().cards());
+            for cards in &steps[..steps.len().saturating_sub(1)] {
+                let hand = Hand::from_cards(cards.clone());
+                let screen = frame(&hand, game, &render, Some(choice), message, locale, width());
+                draw(&screen)?;
+                time::sleep(speed.scale(animation::FLIP_FRAME)).await;
+            }
+        }
+        stats.record(result.won);
+        if let Some(took) = result.decision_time {
+            stats.record_pick(took);
+        }
+
+        let mut message = if result.won {
+            let (points, multiplier) = (result.points, game.multiplier());
+            locale.format(Message::Win, &[&points, &multiplier])
+        } else {
+            locale.text(Message::Lose).to_string()
+        };
+        if let Some(change) = result.chips {
+            let change = format!("{:+}", change);
+            message = format!("{} {}.", message, locale.format(Message::Chips, &[&change]));
+        }
+        if let Some(pick) = result.opponent {
+            let opponent = opponent_result(pick, result.winner, locale);
+            message = format!("{}\n{}", message, opponent);
+        }
+        let next = if practice {
+            Message::AnyKeyOrRetry
+        } else {
+            Message::AnyKey
+        };
+        let mut message = format!("{} {}", message, locale.text(next));
+        if config.values() {
+            message = format!("{}{}", values(game, &render), message);
+        }
+        let result_screen = || {
+            let screen = frame(
+                game.hand(),
+                game,
+                &render,
+                Some(choice),
+                &message,
+                locale,
+                width(),
+            );
+            draw(&screen)
+        };
+        let key = loop {
+            result_screen()?;
+            match read_press(result_screen).await? {
+                KeyCode::Char('q') | KeyCode::Esc => match pause_menu(stats, locale).await? {
+                    Pause::Resume => continue,
+                    Pause::Save => return Ok(true),
+                    Pause::Quit => return Ok(false),
+                },
+                key => break key,
+            }
+        };
+        match key {
+            KeyCode::Char('u') if practice => {
+                game.advance(Input::Undo);
+                *stats = before;
+                continue;
+            }
+            _ => {}
+        }
+        game.advance(Input::Continue);
+    }
+    Ok(false)
+}
+
+/// The card of the hand shown in a `frame` of `game` under the mouse, if any.
+fn card_under(game: &Game, render: &RenderOptions, mouse: Mouse, width: usize) -> Option<usize> {
+    // The scoreboard and a blank line sit above the hand.
+    let top = game.to_string().lines().count() + 1;
+    let cards = game.hand().len();
+    // Each row of cards has a line of markers under it, laid out like an index.
+    card_at(
+        cards,
+        columns_for(width, render),
+        &render.clone().show_index(true),
+        mouse.column,
+        mouse.row.checked_sub(top)?,
+    )
+}
+
+/// Waits for a key press, counting a click as [Enter] and Ctrl+C as [Esc],
+/// ignoring the mouse moving and calling `redraw` when the terminal is resized.
+async fn read_press(mut redraw: impl FnMut() -> io::Result<()>) -> io::Result<KeyCode> {
+    loop {
+        match read_event_timeout(None).await? {
+            Some(TermEvent::Key(key)) => return Ok(key),
+            Some(TermEvent::Mouse(Mouse {
+                kind: MouseKind::Click,
+                ..
+            })) => return Ok(KeyCode::Enter),
+            Some(TermEvent::Interrupt) => return Ok(KeyCode::Esc),
+            Some(TermEvent::Resize) => redraw()?,
+            _ => {}
+        }
+    }
+}
+
+/// Shows the card at `pick` selected and asks to lock it in, for `--confirm`.
+/// [Enter], a click or the locale's yes does. Returns false if the player would
+/// rather pick again.
+async fn confirm_pick(
+    game: &Game,
+    render: &RenderOptions,
+    pick: usize,
+    locale: Locale,
+) -> io::Result<bool> {
+    let message = locale.format(Message::ConfirmPick, &[&pick]);
+    let screen = || {
+        let screen = frame(
+            game.hand(),
+            game,
+            render,
+            Some(pick),
+            &message,
+            locale,
+            width(),
+        );
+        draw(&screen)
+    };
+    screen()?;
+    Ok(match read_press(screen).await? {
+        KeyCode::Enter => true,
+        KeyCode::Char(c) => locale.is_yes(&c.to_lowercase().to_string()),
+        _ => false,
+    })
+}
+
+/// Shows the pause menu until the player picks from it, toggling the stats on
+/// [t].
+async fn pause_menu(stats: &Stats, locale: Locale) -> io::Result<Pause> {
+    let mut show_stats = false;
+    loop {
+        draw(&pause_screen(show_stats.then_some(stats), locale))?;
+        match read_event_timeout(None).await? {
+            Some(TermEvent::Key(key)) => match key {
+                KeyCode::Enter | KeyCode::Esc | KeyCode::Char('r') => return Ok(Pause::Resume),
+                KeyCode::Char('s') => return Ok(Pause::Save),
+                KeyCode::Char('t') => show_stats = !show_stats,
+                KeyCode::Char('q') => return Ok(Pause::Quit),
+                _ => {}
+            },
+            // A second Ctrl+C leaves, but keeps the game to resume later.
+            Some(TermEvent::Interrupt) => return Ok(Pause::Save),
+            _ => {}
+        }
+    }
+}
+
+/// Renders the pause menu, with `stats` above the controls if given.
+fn pause_screen(stats: Option<&Stats>, locale: Locale) -> String {
+    let mut out = String::new();
+    writeln!(out, "{}\n", locale.text(Message::Paused)).unwrap();
+    if let Some(stats) = stats {
+        writeln!(out, "{}", stats).unwrap();
+    }
+    writeln!(out, "{}", style(locale.text(Message::PauseControls)).dim()).unwrap();
+    out
+}
+
+/// Columns across the terminal.
+fn width() -> usize {
+    terminal::size().map_or(80, |(columns, _)| columns as usize)
+}
+
+/// The time left for a pick as seconds and a bar that empties as it runs out.
+fn countdown(left: Duration, limit: Duration) -> String {
+    let filled = (COUNTDOWN_WIDTH as f64 * left.as_secs_f64() / limit.as_secs_f64().max(0.001))
+        .ceil() as usize;
+    let filled = filled.min(COUNTDOWN_WIDTH);
+    let bar = format!(
+        "{}{}",
+        "█".repeat(filled),
+        "░".repeat(COUNTDOWN_WIDTH - filled)
+    );
+    let bar = if left.as_secs_f64() <= limit.as_secs_f64() / 4.0 {
+        style(bar).red()
+    } else {
+        style(bar).green()
+    };
+    format!("{} {:.1}s", bar, left.as_secs_f64())
+}
+
+/// Clears the screen and draws `frame` over it. The terminal is in raw mode while
+/// the frontend runs, so lines are ended with `\r\n`.
+fn draw(frame: &str) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    queue!(
+        out,
+        Clear(ClearType::All),
+        MoveTo(0, 0),
+        Print(frame.replace('\n', "\r\n"))
+    )?;
+    out.flush()
+}
+
+/// Renders a whole screen `width` columns across: scoreboard, hand, selection
+/// marker, message and controls. A hand too wide for the screen wraps onto more
+/// rows, each with its markers underneath, and scoreboard lines are cut short
+/// rather than wrapping.
+fn frame(
+    hand: &Hand,
+    game: &Game,
+    render: &RenderOptions,
+    selected: Option<usize>,
+    message: &str,
+    locale: Locale,
+    width: usize,
+) -> String {
+    let mut out = String::new();
+    let cards = hand.cards();
+    let gap = " ".repeat(render.theme.padding);
+    let columns = columns_for(width, render);
+
+    for line in game.to_string().lines() {
+        writeln!(out, "{}", line.chars().take(width).collect::<String>()).unwrap();
+    }
+    writeln!(out).unwrap();
+    for (row, chunk) in cards.chunks(columns).enumerate() {
+        write_hand_with(&mut out, chunk, render).unwrap();
+        for idx in row * columns..row * columns + chunk.len() {
+            if Some(idx) == selected {
+                write!(out, "{}{}", style("  ^^^^^^^  ").yellow().bold(), gap).unwrap();
+            } else {
+                write!(out, "    [{}]    {}", idx, gap).unwrap();
+            }
+        }
+        writeln!(out).unwrap();
+    }
+    writeln!(out).unwrap();
+    writeln!(out, "{}", message).unwrap();
+    writeln!(out, "{}", style(locale.text(Message::Controls)).dim()).unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{card_under, countdown, frame, pause_screen};
+    use crate::cards::card_printer::RenderOptions;
+    use crate::game::state::Input;
+    use crate::game::GameBuilder;
+    use crate::input::{Mouse, MouseKind};
+    use crate::locale::Locale;
+    use crate::stats::Stats;
+    use std::time::Duration;
+
+    #[test]
+    /// Tests a frame with the middle card selected.
+    fn test_frame() {
+        let mut game = GameBuilder::new().with_seed(1).spawn().unwrap();
+        game.advance(Input::Deal);
+
+        insta::assert_snapshot!(frame(
+            game.hand(),
+            &game,
+            &RenderOptions::new(),
+            Some(1),
+            "Find the High card.",
+            Locale::English,
+            80
+        ));
+    }
+
+    #[test]
+    /// Tests a hand wider than the screen wraps with markers under each row.
+    fn test_frame_wrapped() {
+        let mut game = GameBuilder::new().with_seed(1).spawn().unwrap();
+        game.advance(Input::Deal);
+
+        insta::assert_snapshot!(frame(
+            game.hand(),
+            &game,
+            &RenderOptions::new(),
+            Some(2),
+            "Find the High card.",
+            Locale::English,
+            24
+        ));
+    }
+
+    #[test]
+    /// Tests the mouse finds the card box it's over in a frame.
+    fn test_card_under() {
+        let mut game = GameBuilder::new().with_seed(1).spawn().unwrap();
+        game.advance(Input::Deal);
+        let render = RenderOptions::new();
+        let at = |column, row, width| {
+            card_under(
+                &game,
+                &render,
+                Mouse {
+                    kind: MouseKind::Move,
+                    column,
+                    row,
+                },
+                width,
+            )
+        };
+
+        assert_eq!(at(0, 4, 80), Some(0));
+        assert_eq!(at(15, 10, 80), Some(1));
+        assert_eq!(at(11, 6, 80), None);
+        assert_eq!(at(30, 2, 80), None);
+        assert_eq!(at(30, 11, 80), None);
+
+        // Two cards a row, with the third under the first.
+        assert_eq!(at(0, 12, 30), Some(2));
+        assert_eq!(at(0, 11, 30), None);
+        assert_eq!(at(24, 4, 30), None);
+    }
+
+    #[test]
+    /// Tests the pause menu lists its controls and shows the stats when asked.
+    fn test_pause_screen() {
+        let mut stats = Stats::new();
+        stats.record(true);
+        let menu = pause_screen(None, Locale::English);
+        assert!(menu.starts_with("Paused.\n"));
+        assert!(menu.contains("[s] save and quit"));
+        assert!(!menu.contains("Lifetime Stats"));
+        let with_stats = pause_screen(Some(&stats), Locale::English);
+        assert!(with_stats.contains(&stats.to_string()));
+        assert!(pause_screen(None, Locale::Spanish).starts_with("En pausa."));
+    }
+
+    #[test]
+    /// Tests the countdown bar empties as time runs out.
+    fn test_countdown() {
+        let limit = Duration::from_secs(10);
+        let half = countdown(Duration::from_secs(5), limit);
+        assert!(half.contains(&format!("{}{}", "█".repeat(10), "░".repeat(10))));
+        assert!(half.ends_with(" 5.0s"));
+        let gone = countdown(Duration::ZERO, limit);
+        assert!(gone.contains(&"░".repeat(20)) && !gone.contains('█'));
+    }
+}
@@ -0,0 +1,172 @@
+//! This module provides a small facade for embedding the high card game in a web
+//! page.
+//!
+//! `WebGame` wraps the state machine in four calls: `new_game`, `deal`, `choose`
+//! and `state_json`. A page renders whatever `state_json` describes, so nothing
+//! here prints, reads input or sleeps.
+//!
+//! Built for `wasm32` the game is exported with `wasm-bindgen` as the JavaScript
+//! class `Game`, with `deal`, `choose` and `stateJson` methods, and `wasm-bindgen`
+//! generates the glue a page imports:
+//!
+//! ```sh
+//! cargo rustc --lib --release --no-default-features \
+//!     --target wasm32-unknown-unknown --crate-type cdylib
+//! wasm-bindgen --target web --out-dir pkg \
+//!     target/wasm32-unknown-unknown/release/card_game.wasm
+//! ```
+//!
+//! Seeds come from the page, since a bare wasm32 target has no source of entropy.
+
+use crate::cards::Card;
+use crate::error::GameConfigError;
+use crate::game::state::{Event, Input, Phase};
+use crate::game::{Game, GameBuilder};
+use serde::Serialize;
+
+/// A high card game driven from a web page.
+pub struct WebGame {
+    game: Game,
+}
+
+/// Everything a page needs to draw the table.
+#[derive(Serialize)]
+struct State<'a> {
+    /// One of `dealing`, `choosing`, `round_over` or `game_over`.
+    phase: &'static str,
+    hand: &'a [Card],
+    /// The high card, once the hand is face up.
+    winner: Option<usize>,
+    rounds: usize,
+    wins: usize,
+    points: u64,
+    streak: usize,
+    cards_left: usize,
+}
+
+impl WebGame {
+    /// Starts a game dealing `cards` cards a round from a deck shuffled by `seed`.
+    pub fn new_game(seed: u64, cards: u8) -> Result<WebGame, GameConfigError> {
+        let game = GameBuilder::new()
+            .max_cards(cards)
+            .with_seed(seed)
+            .spawn()?;
+        Ok(WebGame { game })
+    }
+
+    /// Deals the next hand, moving on from a finished round first. Returns false
+    /// once the deck has run out.
+    pub fn deal(&mut self) -> bool {
+        if self.game.phase() == Phase::RoundOver {
+            self.game.advance(Input::Continue);
+        }
+        self.game.advance(Input::Deal);
+        self.game.phase() == Phase::AwaitingChoice
+    }
+
+    /// Picks the card at `index` and turns the hand over. Returns whether the pick
+    /// was the high card, or None if no hand is waiting for a pick.
+    pub fn choose(&mut self, index: usize) -> Option<bool> {
+        if self.game.phase() != Phase::AwaitingChoice {
+            return None;
+        }
+        self.game.advance(Input::Choose(index));
+        match self.game.advance(Input::Reveal) {
+            Event::Revealed(result) => Some(result.won),
+            _ => None,
+        }
+    }
+
+    /// The table as JSON.
+    pub fn state_json(&self) -> String {
+        let game = &self.game;
+        let (phase, winner) = match game.phase() {
+            Phase::Dealing => ("dealing", None),
+            Phase::AwaitingChoice | Phase::Revealing { .. } => ("choosing", None),
+            Phase::RoundOver => ("round_over", Some(game.find_high_card())),
+            Phase::GameOver => ("game_over", None),
+        };
+        let state = State {
+            phase,
+            hand: game.hand().cards(),
+            winner,
+            rounds: game.games_played(),
+            wins: game.wins(),
+            points: game.points(),
+            streak: game.streak(),
+            cards_left: game.deck().remaining(),
+        };
+        serde_json::to_string(&state).expect("the table always serializes")
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod exports {
+    use super::WebGame;
+    use wasm_bindgen::prelude::*;
+
+    fn no_entropy(_: &mut [u8]) -> Result<(), getrandom::Error> {
+        Err(getrandom::Error::UNSUPPORTED)
+    }
+
+    getrandom::register_custom_getrandom!(no_entropy);
+
+    /// `WebGame` as the JavaScript class `Game`.
+    #[wasm_bindgen(js_name = Game)]
+    pub struct JsGame(WebGame);
+
+    #[wasm_bindgen(js_class = Game)]
+    impl JsGame {
+        /// `new Game(seed, cards)` starts a game, throwing if `cards` isn't 3 to 5.
+        #[wasm_bindgen(constructor)]
+        pub fn new(seed: u32, cards: u8) -> Result<JsGame, JsError> {
+            WebGame::new_game(seed as u64, cards)
+                .map(JsGame)
+                .map_err(|err| JsError::new(&err.to_string()))
+        }
+
+        /// Returns false once the deck runs out.
+        pub fn deal(&mut self) -> bool {
+            self.0.deal()
+        }
+
+        /// Returns whether the pick won, or undefined if there was nothing to pick.
+        pub fn choose(&mut self, index: usize) -> Option<bool> {
+            self.0.choose(index)
+        }
+
+        #[wasm_bindgen(js_name = stateJson)]
+        pub fn state_json(&self) -> String {
+            self.0.state_json()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WebGame;
+
+    #[test]
+    /// Tests a page can play rounds and read the table back.
+    fn test_web_game() {
+        let mut game = WebGame::new_game(5, 3).unwrap();
+        assert_eq!(game.choose(0), None);
+        assert!(game
+            .state_json()
+            .starts_with(r#"{"phase":"dealing","hand":[]"#));
+
+        assert!(game.deal());
+        assert!(game.state_json().contains(r#""phase":"choosing""#));
+        assert!(game.choose(1).is_some());
+        let state: serde_json::Value = serde_json::from_str(&game.state_json()).unwrap();
+        assert_eq!(state["phase"], "round_over");
+        assert_eq!(state["rounds"], 1);
+        assert!(state["winner"].as_u64().unwrap() < 3);
+
+        while game.deal() {
+            game.choose(0);
+        }
+        assert!(game.state_json().contains(r#""phase":"game_over""#));
+        assert!(WebGame::new_game(5, 9).is_err());
+    }
+}